@@ -1,6 +1,25 @@
 //! バイオーム検索アルゴリズム
 //! 
 //! Minecraft 1.18+ のマルチノイズバイオーム生成の簡易近似
+//! （1.18より前のバージョンはレイヤー方式の近似を使う）
+
+use std::cell::Cell;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::legacy_biome::get_legacy_biome_at;
+use super::noise::noise_2d;
+use crate::budget;
+use crate::coords::{check_search_area, BlockPos, Dimension};
+use crate::error::SearchError;
+use crate::gamedata::game_data;
+use crate::i18n::is_english;
+use crate::progress;
+use crate::version::{McVersion, LATEST_VERSION};
+
+/// バイオーム近似の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 4;
 
 /// バイオームタイプ
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -21,10 +40,148 @@ pub enum BiomeType {
     Beach,
     River,
     Mountain,       // Extreme Hills / Windswept Hills
+    MangroveSwamp,
+    CherryGrove,
+    #[allow(dead_code)] // 配色の表等で網羅するための値で、生成の結果にはならない
     Unknown,
 }
 
+/// マルチノイズ生成が始まったバージョン（より前はレイヤー方式）
+pub const MULTI_NOISE_SINCE_VERSION: McVersion = McVersion(1, 18, 0);
+
 impl BiomeType {
+    /// 検索対象にできるすべてのバイオーム
+    pub const ALL: [BiomeType; 18] = [
+        BiomeType::Plains,
+        BiomeType::Forest,
+        BiomeType::Jungle,
+        BiomeType::Desert,
+        BiomeType::Mesa,
+        BiomeType::Mushroom,
+        BiomeType::IceSpikes,
+        BiomeType::Swamp,
+        BiomeType::Savanna,
+        BiomeType::Taiga,
+        BiomeType::SnowyTaiga,
+        BiomeType::Ocean,
+        BiomeType::DeepOcean,
+        BiomeType::Beach,
+        BiomeType::River,
+        BiomeType::Mountain,
+        BiomeType::MangroveSwamp,
+        BiomeType::CherryGrove,
+    ];
+
+    /// バイオームの識別子を取得（-t オプションで使う名前）
+    pub fn id(&self) -> &'static str {
+        match self {
+            BiomeType::Plains => "plains",
+            BiomeType::Forest => "forest",
+            BiomeType::Jungle => "jungle",
+            BiomeType::Desert => "desert",
+            BiomeType::Mesa => "mesa",
+            BiomeType::Mushroom => "mushroom",
+            BiomeType::IceSpikes => "ice_spikes",
+            BiomeType::Swamp => "swamp",
+            BiomeType::Savanna => "savanna",
+            BiomeType::Taiga => "taiga",
+            BiomeType::SnowyTaiga => "snowy_taiga",
+            BiomeType::Ocean => "ocean",
+            BiomeType::DeepOcean => "deep_ocean",
+            BiomeType::Beach => "beach",
+            BiomeType::River => "river",
+            BiomeType::Mountain => "mountain",
+            BiomeType::MangroveSwamp => "mangrove_swamp",
+            BiomeType::CherryGrove => "cherry_grove",
+            BiomeType::Unknown => "unknown",
+        }
+    }
+
+    /// バイオームの表示名を取得（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                BiomeType::Plains => "Plains",
+                BiomeType::Forest => "Forest",
+                BiomeType::Jungle => "Jungle",
+                BiomeType::Desert => "Desert",
+                BiomeType::Mesa => "Badlands",
+                BiomeType::Mushroom => "Mushroom Fields",
+                BiomeType::IceSpikes => "Ice Spikes",
+                BiomeType::Swamp => "Swamp",
+                BiomeType::Savanna => "Savanna",
+                BiomeType::Taiga => "Taiga",
+                BiomeType::SnowyTaiga => "Snowy Taiga",
+                BiomeType::Ocean => "Ocean",
+                BiomeType::DeepOcean => "Deep Ocean",
+                BiomeType::Beach => "Beach",
+                BiomeType::River => "River",
+                BiomeType::Mountain => "Windswept Hills",
+                BiomeType::MangroveSwamp => "Mangrove Swamp",
+                BiomeType::CherryGrove => "Cherry Grove",
+                BiomeType::Unknown => "Unknown",
+            };
+        }
+        match self {
+            BiomeType::Plains => "平原",
+            BiomeType::Forest => "森林",
+            BiomeType::Jungle => "ジャングル",
+            BiomeType::Desert => "砂漠",
+            BiomeType::Mesa => "荒野（メサ）",
+            BiomeType::Mushroom => "キノコ島",
+            BiomeType::IceSpikes => "樹氷",
+            BiomeType::Swamp => "沼地",
+            BiomeType::Savanna => "サバンナ",
+            BiomeType::Taiga => "タイガ",
+            BiomeType::SnowyTaiga => "雪のタイガ",
+            BiomeType::Ocean => "海",
+            BiomeType::DeepOcean => "深海",
+            BiomeType::Beach => "砂浜",
+            BiomeType::River => "川",
+            BiomeType::Mountain => "山岳",
+            BiomeType::MangroveSwamp => "マングローブの沼地",
+            BiomeType::CherryGrove => "サクラの林",
+            BiomeType::Unknown => "不明",
+        }
+    }
+
+    /// 追加されたBedrock Editionのバージョン
+    pub fn since_version(&self) -> &'static str {
+        match self {
+            BiomeType::MangroveSwamp => "1.19.0",
+            BiomeType::CherryGrove => "1.20.0",
+            _ => "1.0.0",
+        }
+    }
+
+    /// 正式な追加より前に実験的機能として生成されるようになったバージョン（None は実験的機能の期間がない）
+    pub fn experimental_since(&self) -> Option<&'static str> {
+        match self {
+            BiomeType::MangroveSwamp => Some("1.18.30"),
+            BiomeType::CherryGrove => Some("1.19.80"),
+            _ => None,
+        }
+    }
+
+    /// 対象バージョン（`--experimental` を含む）で生成されるか
+    pub fn is_available(&self, version: McVersion) -> bool {
+        version.supports(self.since_version(), self.experimental_since())
+    }
+
+    /// 海（浅い海と深海）か
+    pub fn is_ocean(&self) -> bool {
+        matches!(self, BiomeType::Ocean | BiomeType::DeepOcean)
+    }
+
+    /// 検索時のサンプリング間隔（ブロック）。希少なバイオームほど細かく調べる
+    pub fn sampling_step(&self) -> i32 {
+        match self.rarity() {
+            r if r > 0.8 => 64,
+            r if r > 0.5 => 128,
+            _ => 256,
+        }
+    }
+
     /// 文字列からバイオームタイプを取得
     pub fn from_str(s: &str) -> Option<BiomeType> {
         match s.to_lowercase().as_str() {
@@ -44,6 +201,8 @@ impl BiomeType {
             "beach" => Some(BiomeType::Beach),
             "river" => Some(BiomeType::River),
             "mountain" | "extreme_hills" => Some(BiomeType::Mountain),
+            "mangrove_swamp" | "mangrove" => Some(BiomeType::MangroveSwamp),
+            "cherry_grove" | "cherry" => Some(BiomeType::CherryGrove),
             _ => None,
         }
     }
@@ -67,33 +226,102 @@ impl BiomeType {
             BiomeType::Beach => 0.2,
             BiomeType::River => 0.2,
             BiomeType::Mountain => 0.4,
+            BiomeType::MangroveSwamp => 0.6,
+            BiomeType::CherryGrove => 0.8,
             BiomeType::Unknown => 1.0,
         }
     }
 }
 
-/// 簡易パーリンノイズ（1D）
-fn noise_1d(seed: i64, x: i32) -> f64 {
-    let n = x.wrapping_mul(374761393)
-        .wrapping_add((seed as i32).wrapping_mul(668265263));
-    let n = (n ^ (n >> 13)).wrapping_mul(1274126177);
-    (n as f64) / i32::MAX as f64
+/// バージョンごとのバイオーム生成のパラメータ
+///
+/// 気候の閾値と、そのバージョンで追加されたバイオームの生成条件をまとめる。
+/// 表は生成データ（`data/biome_params.json`、`--data-dir` で差し替え可能）から読み込む。
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BiomeParams {
+    /// このパラメータを使う最初のバージョン
+    pub since: McVersion,
+    /// 実験的機能としてこのパラメータを使う最初のバージョン（None は実験的機能の期間がない）
+    #[serde(default)]
+    pub experimental_since: Option<McVersion>,
+    /// 大陸性がこれ未満は海
+    pub ocean: f64,
+    /// 大陸性がこれ未満は深海
+    pub deep_ocean: f64,
+    /// 大陸性がこれ未満は川・砂浜
+    pub shore: f64,
+    /// 温度がこれ未満は寒冷
+    pub cold: f64,
+    /// 温度がこれ未満は温暖（以上は熱帯・乾燥）
+    pub warm: f64,
+    /// 温暖な沼地がマングローブの沼地になる温度の下限（None はマングローブの沼地がない）
+    #[serde(default)]
+    pub mangrove_temperature: Option<f64>,
+    /// 内陸の森林がサクラの林になるノイズの下限（None はサクラの林がない）
+    #[serde(default)]
+    pub cherry_chance: Option<f64>,
+    /// 地下にディープダークがあるか
+    pub deep_dark: bool,
 }
 
-/// 簡易パーリンノイズ（2D）
-fn noise_2d(seed: i64, x: i32, z: i32) -> f64 {
-    let n1 = noise_1d(seed, x);
-    let n2 = noise_1d(seed.wrapping_add(12345), z);
-    let n3 = noise_1d(seed.wrapping_add(67890), x.wrapping_add(z));
-    
-    (n1 + n2 + n3) / 3.0
+/// バイオーム生成の方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BiomeModel {
+    /// 1.18より前のレイヤー方式
+    Layered,
+    /// 1.18以降のマルチノイズ生成
+    MultiNoise(BiomeParams),
+}
+
+/// バージョンのバイオーム生成の方式
+pub fn biome_model(version: McVersion, experimental: bool) -> BiomeModel {
+    if version < MULTI_NOISE_SINCE_VERSION {
+        BiomeModel::Layered
+    } else {
+        BiomeModel::MultiNoise(biome_params(version, experimental))
+    }
+}
+
+/// バージョンのマルチノイズ生成のパラメータ（1.18より前のバージョンは1.18のもの）
+///
+/// experimental が真の場合は、実験的機能として先に使えるパラメータも対象にする。
+pub fn biome_params(version: McVersion, experimental: bool) -> BiomeParams {
+    let data = game_data();
+    let table = &data.biome_params;
+    table
+        .iter()
+        .rev()
+        .find(|p| p.since <= version || (experimental && p.experimental_since.is_some_and(|e| e <= version)))
+        .copied()
+        .unwrap_or(table[0])
+}
+
+thread_local! {
+    static MODEL: Cell<BiomeModel> = Cell::new(biome_model(LATEST_VERSION, false));
+}
+
+/// 実行するコマンドの対象バージョン（と実験的機能の有無）の生成方式を使うよう設定
+pub fn set_version(version: McVersion, experimental: bool) {
+    MODEL.with(|m| m.set(biome_model(version, experimental)));
+}
+
+/// 現在のバイオーム生成の方式
+pub fn model() -> BiomeModel {
+    MODEL.with(Cell::get)
+}
+
+/// バイオーム生成の方式を直接設定（`model` で取得した呼び出し側のスレッドの方式を引き継ぐ場合）
+#[cfg_attr(not(feature = "tui"), allow(dead_code))] // バックグラウンド検索（task）で使う
+pub fn set_model(model: BiomeModel) {
+    MODEL.with(|m| m.set(model));
 }
 
 /// 温度ノイズを取得
-fn get_temperature(seed: i64, x: i32, z: i32) -> f64 {
+fn get_temperature(seed: i64, pos: BlockPos) -> f64 {
     let scale = 256.0;
-    let nx = x as f64 / scale;
-    let nz = z as f64 / scale;
+    let nx = pos.x as f64 / scale;
+    let nz = pos.z as f64 / scale;
     
     // 複数のオクターブで合成
     let mut temp = 0.0;
@@ -111,10 +339,10 @@ fn get_temperature(seed: i64, x: i32, z: i32) -> f64 {
 }
 
 /// 湿度ノイズを取得
-fn get_humidity(seed: i64, x: i32, z: i32) -> f64 {
+fn get_humidity(seed: i64, pos: BlockPos) -> f64 {
     let scale = 256.0;
-    let nx = x as f64 / scale;
-    let nz = z as f64 / scale;
+    let nx = pos.x as f64 / scale;
+    let nz = pos.z as f64 / scale;
     
     let mut humidity = 0.0;
     let mut amplitude = 1.0;
@@ -129,31 +357,134 @@ fn get_humidity(seed: i64, x: i32, z: i32) -> f64 {
     (humidity + 1.0) / 2.0
 }
 
-/// 大陸性ノイズを取得
-fn get_continentalness(seed: i64, x: i32, z: i32) -> f64 {
+/// 大陸性ノイズを取得（ワールドの座標）
+pub fn get_continentalness(seed: i64, pos: BlockPos) -> f64 {
+    continentalness(seed, pos.to_generation(Dimension::Overworld))
+}
+
+/// 生成の座標の大陸性ノイズ
+fn continentalness(seed: i64, pos: BlockPos) -> f64 {
     let scale = 512.0;
-    let nx = x as f64 / scale;
-    let nz = z as f64 / scale;
+    let nx = pos.x as f64 / scale;
+    let nz = pos.z as f64 / scale;
     
     noise_2d(seed + 100000, (nx) as i32, (nz) as i32)
 }
 
-/// 座標のバイオームを近似計算
-pub fn get_biome_at(seed: i64, x: i32, z: i32) -> BiomeType {
-    let temp = get_temperature(seed, x, z);
-    let humidity = get_humidity(seed, x, z);
-    let cont = get_continentalness(seed, x, z);
+/// 侵食ノイズを取得（低いほど起伏の大きい地形）
+fn erosion(seed: i64, pos: BlockPos) -> f64 {
+    let scale = 384.0;
+    let nx = pos.x as f64 / scale;
+    let nz = pos.z as f64 / scale;
+
+    noise_2d(seed + 700000, nx as i32, nz as i32)
+}
+
+/// 地下深く（Y=0 より下）がディープダークになるか
+///
+/// ディープダークは内陸で侵食の小さい（山や高地の）地下にできる。
+pub fn is_deep_dark(seed: i64, pos: BlockPos) -> bool {
+    deep_dark(seed, pos.to_generation(Dimension::Overworld))
+}
+
+/// 生成の座標の地下深くがディープダークになるか
+fn deep_dark(seed: i64, pos: BlockPos) -> bool {
+    continentalness(seed, pos) > 0.2 && erosion(seed, pos) < -0.2
+}
+
+/// 洞窟のバイオーム（地下にだけできる3次元のバイオーム）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaveBiome {
+    LushCaves,
+    DripstoneCaves,
+    DeepDark,
+}
+
+impl CaveBiome {
+    /// 識別子
+    pub fn id(&self) -> &'static str {
+        match self {
+            CaveBiome::LushCaves => "lush_caves",
+            CaveBiome::DripstoneCaves => "dripstone_caves",
+            CaveBiome::DeepDark => "deep_dark",
+        }
+    }
+
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                CaveBiome::LushCaves => "Lush Caves",
+                CaveBiome::DripstoneCaves => "Dripstone Caves",
+                CaveBiome::DeepDark => "Deep Dark",
+            };
+        }
+        match self {
+            CaveBiome::LushCaves => "繁茂した洞窟",
+            CaveBiome::DripstoneCaves => "鍾乳洞",
+            CaveBiome::DeepDark => "ディープダーク",
+        }
+    }
+}
+
+/// 洞窟のバイオームのノイズ（高さ32ブロックごとに別の値になる）
+fn get_cave_noise(seed: i64, pos: BlockPos, y: i32) -> f64 {
+    let scale = 192.0;
+    let nx = pos.x as f64 / scale;
+    let nz = pos.z as f64 / scale;
+
+    noise_2d(seed + 900000 + y.div_euclid(32) as i64 * 1000, nx as i32, nz as i32)
+}
+
+/// 位置 `pos` の高さ `y` の洞窟のバイオームを近似計算（洞窟のバイオームでない場合は None）
+///
+/// 地表より下かどうかは判定しないため、呼び出し側で地表の高さと比べる。
+pub fn get_cave_biome_at(seed: i64, pos: BlockPos, y: i32) -> Option<CaveBiome> {
+    let BiomeModel::MultiNoise(params) = model() else {
+        // 洞窟のバイオームはレイヤー方式の生成にはない
+        return None;
+    };
+    let pos = pos.to_generation(Dimension::Overworld);
+    if y < 0 && params.deep_dark && deep_dark(seed, pos) {
+        return Some(CaveBiome::DeepDark);
+    }
+    let cave = get_cave_noise(seed, pos, y);
+    if cave > 0.45 {
+        return Some(CaveBiome::LushCaves);
+    }
+    if cave < -0.4 && continentalness(seed, pos) > 0.3 {
+        return Some(CaveBiome::DripstoneCaves);
+    }
+    None
+}
+
+/// 座標のバイオームを近似計算（対象バージョンの生成方式を使う）
+pub fn get_biome_at(seed: i64, pos: BlockPos) -> BiomeType {
+    let pos = pos.to_generation(Dimension::Overworld);
+    match model() {
+        BiomeModel::Layered => get_legacy_biome_at(seed, pos.x, pos.z),
+        BiomeModel::MultiNoise(params) => biome_with(&params, seed, pos),
+    }
+}
+
+/// 指定したパラメータで生成の座標のバイオームを近似計算
+fn biome_with(params: &BiomeParams, seed: i64, pos: BlockPos) -> BiomeType {
+    let temp = get_temperature(seed, pos);
+    let humidity = get_humidity(seed, pos);
+    let cont = continentalness(seed, pos);
+    let BlockPos { x, z } = pos;
     
     // 海判定
-    if cont < -0.2 {
-        if cont < -0.5 {
+    if cont < params.ocean {
+        if cont < params.deep_ocean {
             return BiomeType::DeepOcean;
         }
         return BiomeType::Ocean;
     }
     
     // 川/ビーチ判定
-    if cont < 0.0 {
+    if cont < params.shore {
         if humidity > 0.7 {
             return BiomeType::River;
         }
@@ -162,8 +493,8 @@ pub fn get_biome_at(seed: i64, x: i32, z: i32) -> BiomeType {
     
     // 陸地バイオーム
     
-    // 寒冷バイオーム（温度 < 0.2）
-    if temp < 0.2 {
+    // 寒冷バイオーム
+    if temp < params.cold {
         if humidity < 0.3 {
             // 希少バイオーム判定
             let rare_chance = noise_2d(seed + 200000, x / 256, z / 256);
@@ -175,12 +506,21 @@ pub fn get_biome_at(seed: i64, x: i32, z: i32) -> BiomeType {
         return BiomeType::Taiga;
     }
     
-    // 温暖バイオーム（温度 0.2-0.6）
-    if temp < 0.6 {
+    // 温暖バイオーム
+    if temp < params.warm {
         if humidity > 0.7 {
+            if params.mangrove_temperature.is_some_and(|t| temp >= t) {
+                return BiomeType::MangroveSwamp;
+            }
             return BiomeType::Swamp;
         }
         if humidity > 0.4 {
+            // サクラの林判定（希少、内陸）
+            if let Some(chance) = params.cherry_chance {
+                if cont > 0.3 && noise_2d(seed + 800000, x / 512, z / 512) > chance {
+                    return BiomeType::CherryGrove;
+                }
+            }
             return BiomeType::Forest;
         }
         if cont > 0.5 {
@@ -189,7 +529,7 @@ pub fn get_biome_at(seed: i64, x: i32, z: i32) -> BiomeType {
         return BiomeType::Plains;
     }
     
-    // 熱帯/乾燥バイオーム（温度 > 0.6）
+    // 熱帯/乾燥バイオーム
     if humidity > 0.6 {
         // ジャングル判定（希少）
         let jungle_chance = noise_2d(seed + 300000, x / 512, z / 512);
@@ -219,80 +559,148 @@ pub fn get_biome_at(seed: i64, x: i32, z: i32) -> BiomeType {
     BiomeType::Savanna
 }
 
-/// 最寄りのバイオームを検索
+/// 最寄りのバイオームを検索（accept が偽を返す座標は候補にしない）
+///
+/// 範囲内に見つからない場合は `Ok(None)`。バイオーム名や半径が不正な場合はエラー。
 pub fn find_nearest_biome(
     seed: i64,
-    center_x: i32,
-    center_z: i32,
+    center: BlockPos,
     radius: i32,
     target_biome: &str,
-) -> Option<(i32, i32, f64)> {
-    let target = match BiomeType::from_str(target_biome) {
-        Some(b) => b,
-        None => return None,
-    };
+    accept: impl Fn(BlockPos) -> bool,
+) -> Result<Option<(BlockPos, f64)>, SearchError> {
+    let target = BiomeType::from_str(target_biome).ok_or_else(|| SearchError::UnknownBiome(target_biome.to_string()))?;
+    check_search_area(center.x, center.z, radius)?;
     
-    let mut best: Option<(i32, i32, f64)> = None;
+    let mut best: Option<(BlockPos, f64)> = None;
     
-    // サンプリング間隔（バイオームの希少度に応じて調整）
-    let step = match target.rarity() {
-        r if r > 0.8 => 64,   // 希少バイオームは細かくサンプリング
-        r if r > 0.5 => 128,
-        _ => 256,
-    };
+    // サンプリング間隔（バイオームの希少度に応じて調整し、狭い範囲では1辺16点以上を調べる）
+    let step = target.sampling_step().min((radius / 16).max(4));
     
     let samples_per_axis = (radius * 2 / step).max(1);
+    let bar = progress::bar((samples_per_axis as u64).pow(2), &format!("{} サンプル", target.id()));
+    let (mut evaluated, mut out_of_range, mut other_biome) = (0u64, 0u64, 0u64);
     
-    for i in 0..samples_per_axis {
+    for i in budget::outward(0, samples_per_axis - 1, radius / step) {
+        if budget::expired() {
+            break;
+        }
+        bar.inc(samples_per_axis as u64);
         for j in 0..samples_per_axis {
-            let x = center_x - radius + i * step;
-            let z = center_z - radius + j * step;
+            let pos = BlockPos::new(center.x - radius + i * step, center.z - radius + j * step);
             
             // 範囲内かチェック
-            let dist_sq = ((x - center_x) as i64).pow(2) + ((z - center_z) as i64).pow(2);
-            if dist_sq > (radius as i64).pow(2) {
+            let dist_sq = pos.distance_sq(center);
+            if dist_sq > (radius as i64).pow(2) || !accept(pos) {
+                out_of_range += 1;
                 continue;
             }
             
-            let biome = get_biome_at(seed, x, z);
+            let biome = get_biome_at(seed, pos);
+            evaluated += 1;
             
-            if biome == target {
+            if biome != target {
+                tracing::trace!(x = pos.x, z = pos.z, biome = biome.id(), "別のバイオーム");
+                other_biome += 1;
+            } else {
                 let distance = (dist_sq as f64).sqrt();
                 
+                // 同じ距離なら座標の小さい方（走査の順序によらない）
                 match &best {
-                    Some((_, _, best_dist)) if *best_dist <= distance => {}
+                    Some((best_pos, best_dist)) if (*best_dist, best_pos.x, best_pos.z) <= (distance, pos.x, pos.z) => {}
                     _ => {
-                        best = Some((x, z, distance));
+                        best = Some((pos, distance));
                     }
                 }
             }
         }
     }
+    bar.finish_and_clear();
+
+    tracing::info!(biome = target.id(), step, evaluated, found = best.is_some(), "バイオームをサンプリング");
+    tracing::debug!(biome = target.id(), out_of_range, other_biome, "除外したサンプル");
     
-    best
+    Ok(best)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_cave_biomes() {
+        let mut found = Vec::new();
+        for x in (-4000..4000).step_by(200) {
+            for z in (-4000..4000).step_by(200) {
+                for y in [-48, -16, 16, 40] {
+                    if let Some(biome) = get_cave_biome_at(12345, BlockPos::new(x, z), y) {
+                        if biome == CaveBiome::DeepDark {
+                            assert!(y < 0 && is_deep_dark(12345, BlockPos::new(x, z)));
+                        }
+                        found.push(biome);
+                    }
+                }
+            }
+        }
+        assert!(found.contains(&CaveBiome::LushCaves));
+        assert!(found.contains(&CaveBiome::DripstoneCaves));
+        assert!(found.contains(&CaveBiome::DeepDark));
+    }
+
+    #[test]
+    fn test_biome_params() {
+        assert_eq!(biome_params(McVersion(1, 16, 0), false), game_data().biome_params[0]);
+        assert_eq!(biome_params(McVersion(1, 19, 2), false).since, McVersion(1, 19, 0));
+        assert_eq!(biome_params(LATEST_VERSION, false).since, McVersion(1, 20, 0));
+        assert_eq!(biome_model(McVersion(1, 17, 0), true), BiomeModel::Layered);
+        assert_eq!(biome_model(McVersion(1, 18, 0), false), BiomeModel::MultiNoise(game_data().biome_params[0]));
+        // 実験的機能を対象にすると正式な追加より前のバージョンでも使う
+        assert_eq!(biome_params(McVersion(1, 18, 30), false).since, McVersion(1, 18, 0));
+        assert_eq!(biome_params(McVersion(1, 18, 30), true).since, McVersion(1, 19, 0));
+        assert_eq!(biome_params(McVersion(1, 19, 80), true).since, McVersion(1, 20, 0));
+
+        // 追加されたバイオームは以前のバージョンでは生成されない
+        let sample = |params: &BiomeParams| {
+            let mut found = Vec::new();
+            for x in (-20000..20000).step_by(256) {
+                for z in (-20000..20000).step_by(256) {
+                    found.push(biome_with(params, 12345, BlockPos::new(x, z)));
+                }
+            }
+            found
+        };
+        let old = sample(&biome_params(McVersion(1, 18, 0), false));
+        let new = sample(&biome_params(LATEST_VERSION, false));
+        assert!(!old.contains(&BiomeType::MangroveSwamp) && !old.contains(&BiomeType::CherryGrove));
+        assert!(new.contains(&BiomeType::MangroveSwamp) && new.contains(&BiomeType::CherryGrove));
+    }
+
     #[test]
     fn test_get_biome() {
         let seed = 12345;
-        let biome = get_biome_at(seed, 0, 0);
+        let biome = get_biome_at(seed, BlockPos::new(0, 0));
         println!("Biome at (0, 0): {:?}", biome);
     }
 
     #[test]
     fn test_find_jungle() {
         let seed = 12345;
-        match find_nearest_biome(seed, 0, 0, 10000, "jungle") {
-            Some((x, z, dist)) => {
-                println!("Found jungle at X={}, Z={} (distance: {:.0})", x, z, dist);
+        match find_nearest_biome(seed, BlockPos::new(0, 0), 10000, "jungle", |_| true).unwrap() {
+            Some((pos, dist)) => {
+                println!("Found jungle at X={}, Z={} (distance: {:.0})", pos.x, pos.z, dist);
             }
             None => {
                 println!("Jungle not found within range");
             }
         }
     }
+
+    #[test]
+    fn test_find_nearest_biome_errors() {
+        assert_eq!(
+            find_nearest_biome(12345, BlockPos::new(0, 0), 1000, "moon", |_| true),
+            Err(SearchError::UnknownBiome("moon".to_string()))
+        );
+        assert_eq!(find_nearest_biome(12345, BlockPos::new(0, 0), 0, "plains", |_| true), Err(SearchError::InvalidRadius(0)));
+    }
 }
@@ -0,0 +1,86 @@
+//! バイオームの縦の断面アルゴリズム
+//!
+//! 1つのX/Z座標で Y=-64〜320 を一定間隔で調べ、地表より上は地表のバイオーム、
+//! 地下は洞窟のバイオーム（ない場合は地表のバイオーム）として、同じバイオームが続く高さの帯にまとめる。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::biome::{get_biome_at, get_cave_biome_at};
+use super::height::estimate_terrain_y;
+use crate::coords::BlockPos;
+
+/// 調べる高さの範囲
+pub const COLUMN_MIN_Y: i32 = -64;
+pub const COLUMN_MAX_Y: i32 = 320;
+
+/// 調べる間隔（ブロック）
+const COLUMN_STEP: i32 = 8;
+
+/// 地表からこの深さより下で洞窟のバイオームを調べる
+const CAVE_BIOME_MIN_DEPTH: i32 = 16;
+
+/// 同じバイオームが続く高さの帯
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ColumnBand {
+    pub min_y: i32,
+    pub max_y: i32,
+    /// バイオームの識別子（洞窟のバイオームは lush_caves, dripstone_caves, deep_dark）
+    pub biome: String,
+    pub name: String,
+    /// 洞窟のバイオームか
+    pub cave: bool,
+}
+
+/// 座標の縦の断面（地表の推定の高さと、下から順の帯）
+pub fn biome_column(seed: i64, x: i32, z: i32) -> (i32, Vec<ColumnBand>) {
+    let surface = estimate_terrain_y(seed, x, z);
+    let surface_biome = get_biome_at(seed, BlockPos::new(x, z));
+
+    let mut bands: Vec<ColumnBand> = Vec::new();
+    for y in (COLUMN_MIN_Y..=COLUMN_MAX_Y).step_by(COLUMN_STEP as usize) {
+        let cave = (y <= surface - CAVE_BIOME_MIN_DEPTH).then(|| get_cave_biome_at(seed, BlockPos::new(x, z), y)).flatten();
+        let (biome, name) = match cave {
+            Some(cave) => (cave.id(), cave.display_name()),
+            None => (surface_biome.id(), surface_biome.display_name()),
+        };
+        let max_y = (y + COLUMN_STEP - 1).min(COLUMN_MAX_Y);
+        match bands.last_mut() {
+            Some(band) if band.biome == biome => band.max_y = max_y,
+            _ => bands.push(ColumnBand {
+                min_y: y,
+                max_y,
+                biome: biome.to_string(),
+                name: name.to_string(),
+                cave: cave.is_some(),
+            }),
+        }
+    }
+    (surface, bands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::biome::is_deep_dark;
+
+    #[test]
+    fn test_biome_column() {
+        let (surface, bands) = biome_column(12345, 300, -200);
+        assert_eq!(bands.first().unwrap().min_y, COLUMN_MIN_Y);
+        assert_eq!(bands.last().unwrap().max_y, COLUMN_MAX_Y);
+        assert!(bands.windows(2).all(|w| w[0].max_y + 1 == w[1].min_y && w[0].biome != w[1].biome));
+        // 地表より上は地表のバイオーム
+        assert!(!bands.last().unwrap().cave);
+        assert!(bands.iter().filter(|b| b.cave).all(|b| b.min_y <= surface - CAVE_BIOME_MIN_DEPTH));
+
+        // ディープダークの地域では Y=0 より下がディープダーク
+        let (x, z) = (-4000..4000)
+            .step_by(64)
+            .flat_map(|x| (-4000..4000).step_by(64).map(move |z| (x, z)))
+            .find(|(x, z)| is_deep_dark(12345, BlockPos::new(*x, *z)))
+            .unwrap();
+        let (_, bands) = biome_column(12345, x, z);
+        assert_eq!(bands[0].biome, "deep_dark");
+    }
+}
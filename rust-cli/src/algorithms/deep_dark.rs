@@ -0,0 +1,171 @@
+//! ディープダークと古代都市の予測アルゴリズム
+//!
+//! 検索範囲を格子状に調べてディープダークの地域（つながった格子のまとまり）を求め、
+//! 古代都市の予測座標を重ねる。古代都市はディープダークにしか生成されないため、
+//! 地域の外の候補は実際には生成されない可能性が高い。
+//! ディープダークは山や高地の地下にできやすく、地表が高い場所の下の都市ほど確実に見つかる。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::biome::is_deep_dark;
+use super::height::estimate_terrain_y;
+use crate::coords::{check_search_area, BlockPos};
+use crate::error::SearchError;
+use crate::structures::{find_structures, StructureType};
+
+/// ディープダーク予測の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 1;
+
+/// ディープダークを調べる格子の間隔（ブロック）
+const GRID_STEP: i32 = 64;
+
+/// 地表がこの高さ以上の場所を高地とみなす
+const HIGHLANDS_Y: i32 = 90;
+
+/// ディープダークの地域
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DeepDarkRegion {
+    /// 地域の中心（格子点の平均）
+    pub x: i32,
+    pub z: i32,
+    /// おおよその面積（平方ブロック）
+    pub area: i64,
+    /// 地表が高地の格子点の割合（0.0〜1.0）
+    pub highlands: f64,
+    pub distance: f64,
+}
+
+/// 古代都市の候補
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AncientCityCandidate {
+    pub x: i32,
+    pub z: i32,
+    /// ディープダークの中にあるか（false の場合は生成されない可能性が高い）
+    pub in_deep_dark: bool,
+    /// 高地の下にあるか（ディープダークが最もできやすい）
+    pub under_highlands: bool,
+    /// 最寄りの格子点が属するディープダークの地域（regions の添字）
+    pub region: Option<usize>,
+    pub distance: f64,
+}
+
+/// ディープダークの地域と古代都市の候補
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DeepDarkReport {
+    /// 面積の大きい順
+    pub regions: Vec<DeepDarkRegion>,
+    /// 検索中心からの距離順
+    pub cities: Vec<AncientCityCandidate>,
+}
+
+/// 範囲内のディープダークの地域と古代都市の候補を求める
+pub fn find_deep_dark(seed: i64, center_x: i32, center_z: i32, radius: i32) -> Result<DeepDarkReport, SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let cells = radius / GRID_STEP;
+    let side = (cells * 2 + 1) as usize;
+    let to_block = |i: usize| (i as i32 - cells) * GRID_STEP;
+    let deep_dark: Vec<Vec<bool>> = (0..side)
+        .map(|i| (0..side).map(|j| is_deep_dark(seed, BlockPos::new(center_x + to_block(i), center_z + to_block(j)))).collect())
+        .collect();
+
+    // つながった格子点を地域にまとめる
+    let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; side]; side];
+    let mut regions = Vec::new();
+    for i in 0..side {
+        for j in 0..side {
+            if !deep_dark[i][j] || labels[i][j].is_some() {
+                continue;
+            }
+            let label = regions.len();
+            let mut stack = vec![(i, j)];
+            let mut members = Vec::new();
+            labels[i][j] = Some(label);
+            while let Some((ci, cj)) = stack.pop() {
+                members.push((ci, cj));
+                let neighbors = [(ci.wrapping_sub(1), cj), (ci + 1, cj), (ci, cj.wrapping_sub(1)), (ci, cj + 1)];
+                for (ni, nj) in neighbors {
+                    if ni < side && nj < side && deep_dark[ni][nj] && labels[ni][nj].is_none() {
+                        labels[ni][nj] = Some(label);
+                        stack.push((ni, nj));
+                    }
+                }
+            }
+
+            let n = members.len() as i64;
+            let x = center_x + (members.iter().map(|(i, _)| to_block(*i) as i64).sum::<i64>() / n) as i32;
+            let z = center_z + (members.iter().map(|(_, j)| to_block(*j) as i64).sum::<i64>() / n) as i32;
+            let highlands = members
+                .iter()
+                .filter(|(i, j)| estimate_terrain_y(seed, center_x + to_block(*i), center_z + to_block(*j)) >= HIGHLANDS_Y)
+                .count();
+            regions.push(DeepDarkRegion {
+                x,
+                z,
+                area: n * (GRID_STEP as i64).pow(2),
+                highlands: highlands as f64 / n as f64,
+                distance: (((x - center_x) as f64).powi(2) + ((z - center_z) as f64).powi(2)).sqrt(),
+            });
+        }
+    }
+
+    // 面積の大きい順に並べ替え、格子点の地域の添字を付け替える
+    let mut order: Vec<usize> = (0..regions.len()).collect();
+    order.sort_by(|a, b| regions[*b].area.cmp(&regions[*a].area));
+    let mut renumber = vec![0; regions.len()];
+    for (new, old) in order.iter().enumerate() {
+        renumber[*old] = new;
+    }
+    let regions: Vec<DeepDarkRegion> = order.iter().map(|i| regions[*i].clone()).collect();
+
+    let cell_index = |offset: i32| {
+        let i = (offset as f64 / GRID_STEP as f64).round() as i32 + cells;
+        (0..side as i32).contains(&i).then_some(i as usize)
+    };
+    let mut cities: Vec<AncientCityCandidate> = find_structures(seed, BlockPos::new(center_x, center_z), radius, StructureType::AncientCity)?
+        .into_iter()
+        .map(|hit| {
+            let BlockPos { x, z } = hit.pos;
+            let region = match (cell_index(x - center_x), cell_index(z - center_z)) {
+                (Some(i), Some(j)) => labels[i][j].map(|label| renumber[label]),
+                _ => None,
+            };
+            AncientCityCandidate {
+                x,
+                z,
+                in_deep_dark: is_deep_dark(seed, hit.pos),
+                under_highlands: estimate_terrain_y(seed, x, z) >= HIGHLANDS_Y,
+                region,
+                distance: (((x - center_x) as f64).powi(2) + ((z - center_z) as f64).powi(2)).sqrt(),
+            }
+        })
+        .collect();
+    cities.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+
+    tracing::info!(grid = side * side, regions = regions.len(), cities = cities.len(), "ディープダークを走査");
+    Ok(DeepDarkReport { regions, cities })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_deep_dark() {
+        let report = find_deep_dark(12345, 0, 0, 3000).unwrap();
+        assert!(!report.regions.is_empty());
+        assert!(report.regions.windows(2).all(|w| w[0].area >= w[1].area));
+        assert!(report.regions.iter().all(|r| (0.0..=1.0).contains(&r.highlands)));
+
+        assert!(!report.cities.is_empty());
+        assert!(report.cities.windows(2).all(|w| w[0].distance <= w[1].distance));
+        for city in &report.cities {
+            assert_eq!(city.in_deep_dark, is_deep_dark(12345, BlockPos::new(city.x, city.z)));
+            if let Some(region) = city.region {
+                assert!(region < report.regions.len());
+            }
+        }
+        assert!(report.cities.iter().any(|c| c.in_deep_dark));
+        assert!(report.cities.iter().any(|c| !c.in_deep_dark));
+    }
+}
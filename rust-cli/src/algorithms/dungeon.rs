@@ -0,0 +1,149 @@
+//! ダンジョン（モンスタールーム）予測アルゴリズム
+//!
+//! チャンクごとの装飾の乱数（ワールドシードとチャンク座標から作る）で
+//! ダンジョンの有無と位置を決め、同じ乱数の続きでスポナーのモブを決める。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::coords::{block_to_chunk, check_search_area, chunk_to_block, chunk_to_generation, Dimension};
+use crate::error::SearchError;
+use crate::i18n::is_english;
+use crate::rng::{get_structure_seed, next_int};
+
+/// ダンジョン予測の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 1;
+
+/// 装飾の乱数のソルト
+const DUNGEON_SALT: i64 = 20003;
+
+/// チャンクにダンジョンが生成される確率（%。部屋を置ける空洞が見つかる割合を含む）
+const DUNGEON_CHUNK_PERCENT: i32 = 8;
+
+/// ダンジョンが生成される高さの範囲
+const DUNGEON_MIN_Y: i32 = -58;
+const DUNGEON_MAX_Y: i32 = 100;
+
+/// スポナーのモブ（ゾンビ 50%、スケルトン 25%、クモ 25%）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnerMob {
+    Zombie,
+    Skeleton,
+    Spider,
+}
+
+impl SpawnerMob {
+    /// 識別子（--mob オプションで使う名前）
+    pub fn id(&self) -> &'static str {
+        match self {
+            SpawnerMob::Zombie => "zombie",
+            SpawnerMob::Skeleton => "skeleton",
+            SpawnerMob::Spider => "spider",
+        }
+    }
+
+    /// 識別子からモブを取得
+    pub fn from_id(id: &str) -> Option<SpawnerMob> {
+        [SpawnerMob::Zombie, SpawnerMob::Skeleton, SpawnerMob::Spider]
+            .into_iter()
+            .find(|mob| mob.id() == id)
+    }
+
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                SpawnerMob::Zombie => "🧟 Zombie",
+                SpawnerMob::Skeleton => "💀 Skeleton",
+                SpawnerMob::Spider => "🕷️ Spider",
+            };
+        }
+        match self {
+            SpawnerMob::Zombie => "🧟 ゾンビ",
+            SpawnerMob::Skeleton => "💀 スケルトン",
+            SpawnerMob::Spider => "🕷️ クモ",
+        }
+    }
+}
+
+/// 予測したダンジョン
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Dungeon {
+    /// スポナーのブロック座標
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub mob: SpawnerMob,
+    pub distance: f64,
+}
+
+/// チャンクのダンジョンを予測（生成されない場合は None）
+fn chunk_dungeon(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<(i32, i32, i32, SpawnerMob)> {
+    let (gen_x, gen_z) = chunk_to_generation(Dimension::Overworld, chunk_x, chunk_z);
+    let mut rng = get_structure_seed(seed, gen_x, gen_z, DUNGEON_SALT);
+    if next_int(&mut rng, 100) >= DUNGEON_CHUNK_PERCENT {
+        return None;
+    }
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+    let y = DUNGEON_MIN_Y + next_int(&mut rng, DUNGEON_MAX_Y - DUNGEON_MIN_Y + 1);
+    let mob = match next_int(&mut rng, 4) {
+        0 => SpawnerMob::Skeleton,
+        1 => SpawnerMob::Spider,
+        _ => SpawnerMob::Zombie,
+    };
+    Some((x, y, z, mob))
+}
+
+/// 範囲内のダンジョンを検索（距離順）
+pub fn find_dungeons(seed: i64, center_x: i32, center_z: i32, radius: i32) -> Result<Vec<Dungeon>, SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let min_cx = block_to_chunk(center_x - radius);
+    let max_cx = block_to_chunk(center_x + radius);
+    let min_cz = block_to_chunk(center_z - radius);
+    let max_cz = block_to_chunk(center_z + radius);
+
+    let mut results = Vec::new();
+    for chunk_x in min_cx..=max_cx {
+        for chunk_z in min_cz..=max_cz {
+            let Some((x, y, z, mob)) = chunk_dungeon(seed, chunk_x, chunk_z) else {
+                continue;
+            };
+            let dist_sq = (x as i64 - center_x as i64).pow(2) + (z as i64 - center_z as i64).pow(2);
+            if dist_sq > (radius as i64).pow(2) {
+                continue;
+            }
+            results.push(Dungeon {
+                x,
+                y,
+                z,
+                mob,
+                distance: (dist_sq as f64).sqrt(),
+            });
+        }
+    }
+
+    tracing::info!(chunks = (max_cx - min_cx + 1) as u64 * (max_cz - min_cz + 1) as u64, hits = results.len(), "チャンクを走査");
+    results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_dungeons() {
+        let dungeons = find_dungeons(12345, 0, 0, 500).unwrap();
+        assert!(find_dungeons(12345, i32::MAX, 0, 500).is_err());
+        assert!(!dungeons.is_empty());
+        assert!(dungeons.windows(2).all(|w| w[0].distance <= w[1].distance));
+        assert!(dungeons.iter().all(|d| (DUNGEON_MIN_Y..=DUNGEON_MAX_Y).contains(&d.y)));
+
+        // スポナーのモブはおおよそ 2:1:1
+        let skeletons = dungeons.iter().filter(|d| d.mob == SpawnerMob::Skeleton).count();
+        let zombies = dungeons.iter().filter(|d| d.mob == SpawnerMob::Zombie).count();
+        assert!(skeletons > 0 && zombies > skeletons, "skeletons = {}, zombies = {}", skeletons, zombies);
+    }
+}
@@ -0,0 +1,137 @@
+//! 地表高度推定アルゴリズム
+//!
+//! バイオーム近似と大陸性ノイズから地表のY座標を大まかに推定する
+
+use super::biome::{get_biome_at, get_continentalness, BiomeType};
+use super::noise::noise_2d;
+use crate::coords::{to_generation, BlockPos, Dimension};
+
+/// 海面の高さ
+pub const SEA_LEVEL: i32 = 63;
+
+/// 座標の地表Y座標を推定（水面下の場合は海底の高さ）
+pub fn estimate_terrain_y(seed: i64, x: i32, z: i32) -> i32 {
+    let cont = get_continentalness(seed, BlockPos::new(x, z));
+
+    // 起伏ノイズ（-1.0〜1.0程度）
+    let (gx, gz) = to_generation(Dimension::Overworld, x, z);
+    let hills = noise_2d(seed + 600000, gx / 64, gz / 64);
+
+    let base = match get_biome_at(seed, BlockPos::new(x, z)) {
+        BiomeType::DeepOcean => SEA_LEVEL - 30,
+        BiomeType::Ocean => SEA_LEVEL - 15,
+        BiomeType::River => SEA_LEVEL - 5,
+        BiomeType::Beach => SEA_LEVEL + 1,
+        BiomeType::Swamp | BiomeType::MangroveSwamp => SEA_LEVEL,
+        BiomeType::Mountain => SEA_LEVEL + 40 + (cont * 60.0) as i32,
+        BiomeType::Mesa => SEA_LEVEL + 20,
+        _ => SEA_LEVEL + 5 + (cont * 20.0) as i32,
+    };
+
+    base + (hills * 6.0) as i32
+}
+
+/// 立つことができる高さを推定（海の場合は水面）
+pub fn estimate_surface_y(seed: i64, x: i32, z: i32) -> i32 {
+    estimate_terrain_y(seed, x, z).max(SEA_LEVEL) + 1
+}
+
+/// 範囲の地表の高さを格子状に推定したもの
+pub struct HeightGrid {
+    /// 北西の角の座標
+    pub min_x: i32,
+    pub min_z: i32,
+    /// 1マスあたりのブロック数
+    pub step: i32,
+    pub width: usize,
+    pub height: usize,
+    /// 北の行から順に、各行は西から順
+    pub heights: Vec<i32>,
+}
+
+impl HeightGrid {
+    /// 格子の高さ（列、行）
+    pub fn get(&self, col: usize, row: usize) -> i32 {
+        self.heights[row * self.width + col]
+    }
+
+    /// 列のX座標
+    pub fn x_at(&self, col: usize) -> i32 {
+        self.min_x + col as i32 * self.step
+    }
+
+    /// 行のZ座標
+    pub fn z_at(&self, row: usize) -> i32 {
+        self.min_z + row as i32 * self.step
+    }
+
+    /// 最低と最高の高さ
+    pub fn range(&self) -> (i32, i32) {
+        let min = self.heights.iter().copied().min().unwrap_or(SEA_LEVEL);
+        let max = self.heights.iter().copied().max().unwrap_or(SEA_LEVEL);
+        (min, max)
+    }
+
+    /// 高さの標準偏差（起伏の大きさ）
+    pub fn stddev(&self) -> f64 {
+        let n = self.heights.len().max(1) as f64;
+        let mean = self.heights.iter().map(|&y| y as f64).sum::<f64>() / n;
+        (self.heights.iter().map(|&y| (y as f64 - mean).powi(2)).sum::<f64>() / n).sqrt()
+    }
+}
+
+/// 中心から半径の正方形の範囲の地表の高さを step ブロックごとに推定
+pub fn sample_heights(seed: i64, center_x: i32, center_z: i32, radius: i32, step: i32) -> HeightGrid {
+    let step = step.max(1);
+    let size = ((radius.max(0) as i64 * 2 / step as i64) as usize).max(1);
+    let min_x = center_x - radius;
+    let min_z = center_z - radius;
+
+    let mut heights = Vec::with_capacity(size * size);
+    for row in 0..size {
+        for col in 0..size {
+            let x = min_x + col as i32 * step;
+            let z = min_z + row as i32 * step;
+            heights.push(estimate_terrain_y(seed, x, z));
+        }
+    }
+
+    HeightGrid {
+        min_x,
+        min_z,
+        step,
+        width: size,
+        height: size,
+        heights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_not_below_sea_level() {
+        let seed = 12345;
+        for x in (-2000..2000).step_by(250) {
+            let y = estimate_surface_y(seed, x, 0);
+            assert!(y > SEA_LEVEL, "Y={} at X={}", y, x);
+        }
+    }
+
+    #[test]
+    fn test_sample_heights() {
+        let grid = sample_heights(12345, 100, -50, 64, 8);
+        assert_eq!((grid.width, grid.height), (16, 16));
+        assert_eq!(grid.heights.len(), 256);
+        assert_eq!((grid.x_at(0), grid.z_at(0)), (36, -114));
+        assert_eq!(grid.get(3, 5), estimate_terrain_y(12345, grid.x_at(3), grid.z_at(5)));
+
+        let (min, max) = grid.range();
+        assert!(min <= max);
+        assert!(grid.stddev() <= (max - min) as f64);
+
+        let flat = HeightGrid { min_x: 0, min_z: 0, step: 1, width: 2, height: 1, heights: vec![70, 70] };
+        assert_eq!(flat.stddev(), 0.0);
+    }
+}
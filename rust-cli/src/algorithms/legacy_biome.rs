@@ -0,0 +1,228 @@
+//! 1.18より前のバイオーム生成（レイヤー方式）の近似
+//!
+//! 旧バージョンのバイオームは、大陸・気候・バイオーム・川の各レイヤーを
+//! 乱数で選んで拡大（ズーム）しながら重ねて決める。ここでは4ブロック単位の格子から
+//! 各レイヤーの区画まで拡大の乱数をさかのぼり、座標ごとにバイオームを計算する。
+
+use super::biome::BiomeType;
+
+/// 拡大の段数ごとの区画（4ブロック格子からの段数。2段で16ブロック、8段で1024ブロック）
+const LEVELS: usize = 8;
+
+/// 大陸を決める区画の段数（1024ブロック）
+const CONTINENT_LEVEL: usize = 8;
+
+/// バイオームを決める区画の段数（256ブロック）
+const BIOME_LEVEL: usize = 6;
+
+/// 川の区画の段数（256ブロック）
+const RIVER_LEVEL: usize = 6;
+
+/// レイヤーごとのソルト
+const ZOOM_SALT: i64 = 1000;
+const RIVER_ZOOM_SALT: i64 = 2000;
+const LAND_SALT: i64 = 1;
+const ISLAND_SALT: i64 = 2;
+const CLIMATE_SALT: i64 = 3;
+const BIOME_SALT: i64 = 4;
+const MUSHROOM_SALT: i64 = 5;
+const RIVER_SALT: i64 = 7;
+
+/// 気候の区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Climate {
+    Warm,
+    Temperate,
+    Cold,
+    Freezing,
+}
+
+/// レイヤーの乱数（Java Editionの旧生成と同じ混ぜ方の線形合同法）
+struct LayerRng {
+    layer_seed: i64,
+    state: i64,
+}
+
+impl LayerRng {
+    /// ワールドシードとソルトから作ったレイヤーの乱数を区画 (x, z) で初期化
+    fn at(seed: i64, salt: i64, x: i32, z: i32) -> LayerRng {
+        let mut base = salt;
+        for _ in 0..3 {
+            base = mix(base, salt);
+        }
+        let mut layer_seed = seed;
+        for _ in 0..3 {
+            layer_seed = mix(layer_seed, base);
+        }
+        let mut state = layer_seed;
+        for _ in 0..2 {
+            state = mix(state, x as i64);
+            state = mix(state, z as i64);
+        }
+        LayerRng { layer_seed, state }
+    }
+
+    /// 0以上 bound 未満の整数
+    fn next(&mut self, bound: i32) -> i32 {
+        let value = (self.state >> 24).rem_euclid(bound as i64) as i32;
+        self.state = mix(self.state, self.layer_seed);
+        value
+    }
+}
+
+fn mix(state: i64, add: i64) -> i64 {
+    state
+        .wrapping_mul(state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407))
+        .wrapping_add(add)
+}
+
+/// 4ブロック格子の点から、拡大の乱数をさかのぼった各段の区画
+///
+/// 拡大では奇数番目の点が隣り合う2つの区画のどちらかを乱数で引き継ぐため、区画の境目が不規則になる。
+fn ancestors(seed: i64, salt: i64, qx: i32, qz: i32) -> [(i32, i32); LEVELS + 1] {
+    let mut cells = [(qx, qz); LEVELS + 1];
+    for level in 1..=LEVELS {
+        let (cx, cz) = cells[level - 1];
+        let (mut px, mut pz) = (cx >> 1, cz >> 1);
+        let mut rng = LayerRng::at(seed, salt + level as i64, px, pz);
+        if cx & 1 != 0 {
+            px += rng.next(2);
+        }
+        if cz & 1 != 0 {
+            pz += rng.next(2);
+        }
+        cells[level] = (px, pz);
+    }
+    cells
+}
+
+/// 大陸の区画が陸か（区画の約3割）
+fn is_land_cell(seed: i64, cx: i32, cz: i32) -> bool {
+    LayerRng::at(seed, LAND_SALT, cx, cz).next(10) < 3
+}
+
+/// 4ブロック格子の点が陸か（大陸に加えて512ブロック区画の小島）
+fn is_land(seed: i64, qx: i32, qz: i32) -> bool {
+    let cells = ancestors(seed, ZOOM_SALT, qx, qz);
+    let (cx, cz) = cells[CONTINENT_LEVEL];
+    let (ix, iz) = cells[CONTINENT_LEVEL - 1];
+    is_land_cell(seed, cx, cz) || LayerRng::at(seed, ISLAND_SALT, ix, iz).next(12) == 0
+}
+
+/// 大陸の区画の気候と特殊バイオームの印（約13区画に1つ）
+fn climate(seed: i64, cx: i32, cz: i32) -> (Climate, bool) {
+    let mut rng = LayerRng::at(seed, CLIMATE_SALT, cx, cz);
+    let climate = match rng.next(6) {
+        0 => Climate::Freezing,
+        1 => Climate::Cold,
+        2 | 3 => Climate::Temperate,
+        _ => Climate::Warm,
+    };
+    (climate, rng.next(13) == 0)
+}
+
+/// バイオームの区画の陸のバイオーム
+fn land_biome(seed: i64, cells: &[(i32, i32); LEVELS + 1]) -> BiomeType {
+    let (cx, cz) = cells[CONTINENT_LEVEL];
+    let (climate, special) = climate(seed, cx, cz);
+    let (bx, bz) = cells[BIOME_LEVEL];
+    let mut rng = LayerRng::at(seed, BIOME_SALT, bx, bz);
+    match climate {
+        Climate::Warm if special => BiomeType::Mesa,
+        Climate::Warm => [
+            BiomeType::Desert,
+            BiomeType::Desert,
+            BiomeType::Desert,
+            BiomeType::Savanna,
+            BiomeType::Savanna,
+            BiomeType::Plains,
+        ][rng.next(6) as usize],
+        Climate::Temperate if special => BiomeType::Jungle,
+        Climate::Temperate => [
+            BiomeType::Forest,
+            BiomeType::Forest,
+            BiomeType::Mountain,
+            BiomeType::Plains,
+            BiomeType::Forest,
+            BiomeType::Swamp,
+        ][rng.next(6) as usize],
+        Climate::Cold => [BiomeType::Forest, BiomeType::Mountain, BiomeType::Taiga, BiomeType::Plains][rng.next(4) as usize],
+        Climate::Freezing if rng.next(8) == 0 => BiomeType::IceSpikes,
+        Climate::Freezing => BiomeType::SnowyTaiga,
+    }
+}
+
+/// 4ブロック格子の点が川か（川のレイヤーの区画の境目）
+fn is_river(seed: i64, qx: i32, qz: i32) -> bool {
+    let class = |qx: i32, qz: i32| {
+        let (cx, cz) = ancestors(seed, RIVER_ZOOM_SALT, qx, qz)[RIVER_LEVEL];
+        LayerRng::at(seed, RIVER_SALT, cx, cz).next(2)
+    };
+    let here = class(qx, qz);
+    here != class(qx + 1, qz) || here != class(qx, qz + 1)
+}
+
+/// 座標のバイオームをレイヤー方式で近似計算
+pub fn get_legacy_biome_at(seed: i64, x: i32, z: i32) -> BiomeType {
+    let (qx, qz) = (x >> 2, z >> 2);
+    let cells = ancestors(seed, ZOOM_SALT, qx, qz);
+
+    if !is_land(seed, qx, qz) {
+        // 周りの大陸の区画もすべて海なら深海、まれにキノコ島
+        let (cx, cz) = cells[CONTINENT_LEVEL];
+        let open = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .iter()
+            .all(|(dx, dz)| !is_land_cell(seed, cx + dx, cz + dz));
+        if !open {
+            return BiomeType::Ocean;
+        }
+        if LayerRng::at(seed, MUSHROOM_SALT, cx, cz).next(100) == 0 {
+            return BiomeType::Mushroom;
+        }
+        return BiomeType::DeepOcean;
+    }
+
+    let biome = land_biome(seed, &cells);
+    if is_river(seed, qx, qz) {
+        return BiomeType::River;
+    }
+    // 海から16ブロック以内は砂浜（山岳と沼地は除く）
+    let shore = [(-4, 0), (4, 0), (0, -4), (0, 4)]
+        .iter()
+        .any(|(dx, dz)| !is_land(seed, qx + dx, qz + dz));
+    if shore && !matches!(biome, BiomeType::Mountain | BiomeType::Swamp) {
+        return BiomeType::Beach;
+    }
+    biome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_biomes() {
+        let mut found = Vec::new();
+        for x in (-30000..30000).step_by(400) {
+            for z in (-30000..30000).step_by(400) {
+                let biome = get_legacy_biome_at(12345, x, z);
+                assert_eq!(biome, get_legacy_biome_at(12345, x, z));
+                if !found.contains(&biome) {
+                    found.push(biome);
+                }
+            }
+        }
+        for biome in [
+            BiomeType::Ocean,
+            BiomeType::DeepOcean,
+            BiomeType::Beach,
+            BiomeType::River,
+            BiomeType::Desert,
+            BiomeType::Forest,
+            BiomeType::SnowyTaiga,
+        ] {
+            assert!(found.contains(&biome), "{:?}", biome);
+        }
+        assert!(!found.contains(&BiomeType::MangroveSwamp) && !found.contains(&BiomeType::CherryGrove));
+    }
+}
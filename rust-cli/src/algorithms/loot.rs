@@ -0,0 +1,1263 @@
+//! ルート（チェストの中身）予測アルゴリズム
+//!
+//! ワールドシードとチェストの座標からルートシードを作り、
+//! 構造物ごとのルートテーブルを抽選してチェストの中身を予測する。
+//! チェスト内のスロット配置は予測せず、アイテムごとの合計個数を返す。
+//!
+//! ルートシードの導出は [`LootContext`] にまとめてあり、構造物ごとの予測はすべてこれを通す。
+//! 新しい構造物の予測を作るときは、[`LootContext::chest_seed`] でチェストのシードを、
+//! [`LootContext::layout_seed`] で形や配置の抽選用のシードを作り、[`roll_loot_table`] で中身を抽選する。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::height::{estimate_surface_y, estimate_terrain_y};
+use crate::coords::world_offset;
+use crate::custom::find_custom_structure;
+use crate::structures::{StructureKind, StructureType};
+
+/// ルート予測の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 2;
+
+/// 目当てにされることが多いアイテム（結果で強調する）
+const NOTABLE_ITEMS: &[&str] = &[
+    "heart_of_the_sea",
+    "enchanted_golden_apple",
+    "buried_treasure_map",
+    "elytra",
+    "spire_armor_trim_smithing_template",
+    "music_disc_pigstep",
+    "netherite_upgrade_smithing_template",
+    "heavy_core",
+    "golden_apple",
+    "music_disc_13",
+    "music_disc_cat",
+];
+
+/// バスティオン全体で見つかる確率を求めるアイテム
+const BASTION_ODDS_ITEMS: &[&str] = &["music_disc_pigstep", "netherite_upgrade_smithing_template"];
+
+/// 試練の間の宝物庫で見つかる確率を求めるアイテム
+const TRIAL_CHAMBER_ODDS_ITEMS: &[&str] = &["heavy_core", "enchanted_golden_apple", "trident"];
+
+/// アイテムに付くエンチャント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Enchantment {
+    pub id: String,
+    pub level: u32,
+}
+
+/// チェストに入るアイテム
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ItemStack {
+    /// アイテムID（minecraft: を除いたもの）
+    pub item: String,
+    pub count: u32,
+    /// ランダムなエンチャント（エンチャントされていない場合は空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enchantments: Vec<Enchantment>,
+}
+
+/// 1つのチェスト（または額縁等の入れ物）の予測
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ChestLoot {
+    /// ルートテーブルの名前（額縁等の固定の中身は入れ物の名前）
+    pub chest: String,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub items: Vec<ItemStack>,
+    /// 含まれる目当てのアイテム（ハートオブザシー、エンチャントされた金のリンゴ等）
+    pub notable: Vec<String>,
+    /// 中身がワールド生成時ではなく開けたときに抽選される（試練の間の宝物庫等）。
+    /// 開けたプレイヤーと時点の乱数で決まるため予測できず、items は空
+    pub rolled_on_open: bool,
+}
+
+/// 構造物の予測（形とチェストの中身）
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StructureLoot {
+    /// 構造物の形（難破船: full, bow, stern。逆さまの場合は _upside_down が付く。エンドシティ: with_ship, no_ship。
+    /// バスティオン: housing, stables, treasure, bridge。試練の間: 宝物庫と不吉な宝物庫の数 vaults_N_ominous_M）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub chests: Vec<ChestLoot>,
+    /// 構造物のいずれかのチェストに入る確率（ピグステップ等、対象のアイテムを含みうる構造物のみ。
+    /// 試練の間は各宝物庫を1回ずつ開けた場合）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub odds: Vec<ItemOdds>,
+}
+
+/// アイテムが入る確率（ルートテーブルから計算した値で、予測した中身によらない）
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ItemOdds {
+    pub item: String,
+    /// 0.0〜1.0
+    pub probability: f64,
+}
+
+/// ルートテーブルの抽選候補（item が None の場合は何も入らない）
+pub struct LootEntry {
+    item: Option<&'static str>,
+    weight: u32,
+    min: u32,
+    max: u32,
+    /// ランダムに付くエンチャントの候補（識別子と最大レベル）
+    enchantments: &'static [(&'static str, u32)],
+    /// 付くエンチャントの数の範囲
+    enchantment_rolls: (u32, u32),
+}
+
+pub const fn item(name: &'static str, weight: u32, min: u32, max: u32) -> LootEntry {
+    LootEntry {
+        item: Some(name),
+        weight,
+        min,
+        max,
+        enchantments: &[],
+        enchantment_rolls: (0, 0),
+    }
+}
+
+/// ランダムに1つエンチャントされる装備（1個）
+pub const fn enchanted(name: &'static str, weight: u32, enchantments: &'static [(&'static str, u32)]) -> LootEntry {
+    LootEntry {
+        item: Some(name),
+        weight,
+        min: 1,
+        max: 1,
+        enchantments,
+        enchantment_rolls: (1, 1),
+    }
+}
+
+/// 経験値レベルを指定してエンチャントされる装備（1個。複数のエンチャントが付くことがある）
+pub const fn enchanted_with_levels(
+    name: &'static str,
+    weight: u32,
+    enchantments: &'static [(&'static str, u32)],
+) -> LootEntry {
+    LootEntry {
+        item: Some(name),
+        weight,
+        min: 1,
+        max: 1,
+        enchantments,
+        enchantment_rolls: (1, 3),
+    }
+}
+
+pub const fn empty(weight: u32) -> LootEntry {
+    LootEntry {
+        item: None,
+        weight,
+        min: 0,
+        max: 0,
+        enchantments: &[],
+        enchantment_rolls: (0, 0),
+    }
+}
+
+/// 剣のエンチャント
+const WEAPON_ENCHANTMENTS: &[(&str, u32)] = &[
+    ("sharpness", 5),
+    ("smite", 5),
+    ("bane_of_arthropods", 5),
+    ("knockback", 2),
+    ("fire_aspect", 2),
+    ("looting", 3),
+    ("unbreaking", 3),
+    ("mending", 1),
+    ("curse_of_vanishing", 1),
+];
+
+/// ツールのエンチャント
+const TOOL_ENCHANTMENTS: &[(&str, u32)] = &[
+    ("efficiency", 5),
+    ("silk_touch", 1),
+    ("fortune", 3),
+    ("unbreaking", 3),
+    ("mending", 1),
+    ("curse_of_vanishing", 1),
+];
+
+/// クロスボウのエンチャント
+const CROSSBOW_ENCHANTMENTS: &[(&str, u32)] = &[
+    ("quick_charge", 3),
+    ("multishot", 1),
+    ("piercing", 4),
+    ("unbreaking", 3),
+    ("mending", 1),
+];
+
+/// ソウルスピード
+const SOUL_SPEED: &[(&str, u32)] = &[("soul_speed", 3)];
+
+/// 防具のエンチャント
+const ARMOR_ENCHANTMENTS: &[(&str, u32)] = &[
+    ("protection", 4),
+    ("fire_protection", 4),
+    ("blast_protection", 4),
+    ("projectile_protection", 4),
+    ("thorns", 3),
+    ("unbreaking", 3),
+    ("mending", 1),
+    ("curse_of_binding", 1),
+    ("curse_of_vanishing", 1),
+];
+
+/// 抽選回数の範囲と候補
+pub struct LootPool {
+    /// 抽選回数の範囲
+    pub rolls: (u32, u32),
+    pub entries: &'static [LootEntry],
+}
+
+/// 埋蔵金のチェスト
+const BURIED_TREASURE: &[LootPool] = &[
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("heart_of_the_sea", 1, 1, 1)],
+    },
+    LootPool {
+        rolls: (5, 8),
+        entries: &[item("iron_ingot", 20, 1, 4), item("gold_ingot", 10, 1, 4), item("tnt", 5, 1, 2)],
+    },
+    LootPool {
+        rolls: (1, 3),
+        entries: &[item("emerald", 5, 4, 8), item("diamond", 5, 1, 2), item("prismarine_crystals", 5, 1, 5)],
+    },
+    LootPool {
+        rolls: (0, 1),
+        entries: &[item("leather_chestplate", 1, 1, 1), item("iron_sword", 1, 1, 1)],
+    },
+    LootPool {
+        rolls: (2, 2),
+        entries: &[item("cooked_cod", 1, 2, 4), item("cooked_salmon", 1, 2, 4)],
+    },
+    LootPool {
+        rolls: (0, 2),
+        entries: &[item("potion_water_breathing", 1, 1, 1), empty(1)],
+    },
+];
+
+/// 難破船の補給チェスト
+const SHIPWRECK_SUPPLY: &[LootPool] = &[LootPool {
+    rolls: (3, 10),
+    entries: &[
+        item("paper", 8, 1, 12),
+        item("potato", 7, 2, 6),
+        item("moss_block", 7, 1, 4),
+        item("poisonous_potato", 7, 2, 6),
+        item("carrot", 7, 4, 8),
+        item("wheat", 7, 8, 21),
+        item("pumpkin", 2, 1, 3),
+        item("suspicious_stew", 10, 1, 1),
+        item("coal", 6, 2, 8),
+        item("rotten_flesh", 5, 5, 24),
+        item("bamboo", 2, 1, 3),
+        item("gunpowder", 3, 1, 5),
+        item("tnt", 1, 1, 2),
+        item("leather_helmet", 3, 1, 1),
+        item("leather_chestplate", 3, 1, 1),
+        item("leather_leggings", 3, 1, 1),
+        item("leather_boots", 3, 1, 1),
+    ],
+}];
+
+/// 難破船の地図チェスト
+const SHIPWRECK_MAP: &[LootPool] = &[
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("buried_treasure_map", 1, 1, 1)],
+    },
+    LootPool {
+        rolls: (3, 3),
+        entries: &[
+            item("compass", 1, 1, 1),
+            item("empty_map", 1, 1, 1),
+            item("clock", 1, 1, 1),
+            item("paper", 20, 1, 10),
+            item("feather", 10, 1, 5),
+            item("book", 5, 1, 5),
+        ],
+    },
+];
+
+/// 難破船の宝箱
+const SHIPWRECK_TREASURE: &[LootPool] = &[
+    LootPool {
+        rolls: (3, 6),
+        entries: &[
+            item("iron_ingot", 90, 1, 5),
+            item("gold_ingot", 10, 1, 5),
+            item("emerald", 40, 1, 5),
+            item("diamond", 5, 1, 1),
+            item("experience_bottle", 5, 1, 1),
+        ],
+    },
+    LootPool {
+        rolls: (2, 5),
+        entries: &[item("iron_nugget", 50, 1, 10), item("gold_nugget", 10, 1, 10), item("lapis_lazuli", 20, 1, 10)],
+    },
+];
+
+/// 荒廃したポータルのチェスト
+const RUINED_PORTAL: &[LootPool] = &[LootPool {
+    rolls: (4, 8),
+    entries: &[
+        item("obsidian", 40, 1, 2),
+        item("flint", 40, 1, 4),
+        item("iron_nugget", 40, 9, 18),
+        item("flint_and_steel", 40, 1, 1),
+        item("fire_charge", 40, 1, 1),
+        item("golden_apple", 15, 1, 1),
+        item("gold_nugget", 15, 4, 24),
+        enchanted("golden_sword", 15, WEAPON_ENCHANTMENTS),
+        enchanted("golden_axe", 15, TOOL_ENCHANTMENTS),
+        enchanted("golden_hoe", 15, TOOL_ENCHANTMENTS),
+        enchanted("golden_shovel", 15, TOOL_ENCHANTMENTS),
+        enchanted("golden_pickaxe", 15, TOOL_ENCHANTMENTS),
+        enchanted("golden_boots", 15, ARMOR_ENCHANTMENTS),
+        enchanted("golden_chestplate", 15, ARMOR_ENCHANTMENTS),
+        enchanted("golden_helmet", 15, ARMOR_ENCHANTMENTS),
+        enchanted("golden_leggings", 15, ARMOR_ENCHANTMENTS),
+        item("glistering_melon_slice", 5, 4, 12),
+        item("golden_carrot", 5, 4, 12),
+        item("gold_ingot", 5, 2, 8),
+        item("clock", 5, 1, 1),
+        item("light_weighted_pressure_plate", 5, 1, 1),
+        item("golden_horse_armor", 5, 1, 1),
+        item("gold_block", 1, 1, 2),
+        item("bell", 1, 1, 1),
+        item("enchanted_golden_apple", 1, 1, 1),
+        item("lodestone", 2, 1, 2),
+    ],
+}];
+
+/// エンドシティのチェスト
+const END_CITY_TREASURE: &[LootPool] = &[
+    LootPool {
+        rolls: (2, 6),
+        entries: &[
+            item("diamond", 5, 2, 7),
+            item("iron_ingot", 10, 4, 8),
+            item("gold_ingot", 15, 2, 7),
+            item("emerald", 2, 2, 6),
+            item("beetroot_seeds", 5, 1, 10),
+            item("saddle", 3, 1, 1),
+            item("iron_horse_armor", 1, 1, 1),
+            item("golden_horse_armor", 1, 1, 1),
+            item("diamond_horse_armor", 1, 1, 1),
+            enchanted_with_levels("diamond_sword", 3, WEAPON_ENCHANTMENTS),
+            enchanted_with_levels("diamond_boots", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_chestplate", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_leggings", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_helmet", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_pickaxe", 3, TOOL_ENCHANTMENTS),
+            enchanted_with_levels("diamond_shovel", 3, TOOL_ENCHANTMENTS),
+            enchanted_with_levels("iron_sword", 3, WEAPON_ENCHANTMENTS),
+            enchanted_with_levels("iron_boots", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("iron_chestplate", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("iron_leggings", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("iron_helmet", 3, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("iron_pickaxe", 3, TOOL_ENCHANTMENTS),
+            enchanted_with_levels("iron_shovel", 3, TOOL_ENCHANTMENTS),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("spire_armor_trim_smithing_template", 1, 1, 1), empty(14)],
+    },
+];
+
+/// ネザー要塞のチェスト
+const NETHER_BRIDGE: &[LootPool] = &[LootPool {
+    rolls: (2, 4),
+    entries: &[
+        item("diamond", 5, 1, 3),
+        item("iron_ingot", 5, 1, 5),
+        item("gold_ingot", 15, 1, 3),
+        item("golden_sword", 5, 1, 1),
+        item("golden_chestplate", 5, 1, 1),
+        item("flint_and_steel", 5, 1, 1),
+        item("nether_wart", 5, 3, 7),
+        item("saddle", 10, 1, 1),
+        item("golden_horse_armor", 8, 1, 1),
+        item("iron_horse_armor", 5, 1, 1),
+        item("diamond_horse_armor", 3, 1, 1),
+        item("obsidian", 2, 2, 4),
+    ],
+}];
+
+/// バスティオンの一般的なチェスト
+const BASTION_OTHER: &[LootPool] = &[
+    LootPool {
+        rolls: (1, 1),
+        entries: &[
+            enchanted("diamond_pickaxe", 6, TOOL_ENCHANTMENTS),
+            enchanted("diamond_shovel", 6, TOOL_ENCHANTMENTS),
+            enchanted("crossbow", 6, CROSSBOW_ENCHANTMENTS),
+            item("ancient_debris", 12, 1, 1),
+            item("netherite_scrap", 4, 1, 1),
+            item("spectral_arrow", 10, 10, 22),
+            item("piglin_banner_pattern", 9, 1, 1),
+            item("music_disc_pigstep", 5, 1, 1),
+            item("golden_carrot", 12, 6, 17),
+            item("golden_apple", 9, 1, 1),
+            enchanted("enchanted_book", 10, SOUL_SPEED),
+        ],
+    },
+    LootPool {
+        rolls: (2, 2),
+        entries: &[
+            item("iron_sword", 2, 1, 1),
+            item("iron_block", 2, 1, 1),
+            enchanted("golden_boots", 1, SOUL_SPEED),
+            item("golden_axe", 1, 1, 1),
+            item("gold_block", 2, 1, 1),
+            item("crossbow", 1, 1, 1),
+            item("gold_ingot", 2, 1, 6),
+            item("golden_sword", 1, 1, 1),
+            item("golden_chestplate", 1, 1, 1),
+            item("golden_helmet", 1, 1, 1),
+            item("golden_leggings", 1, 1, 1),
+            item("crying_obsidian", 2, 1, 5),
+        ],
+    },
+    LootPool {
+        rolls: (3, 4),
+        entries: &[
+            item("string", 1, 4, 6),
+            item("leather", 1, 1, 3),
+            item("arrow", 1, 5, 17),
+            item("iron_nugget", 1, 2, 6),
+            item("gold_nugget", 1, 2, 6),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("netherite_upgrade_smithing_template", 1, 1, 1), empty(9)],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("snout_armor_trim_smithing_template", 1, 1, 1), empty(11)],
+    },
+];
+
+/// バスティオンの宝物庫のチェスト
+const BASTION_TREASURE: &[LootPool] = &[
+    LootPool {
+        rolls: (3, 3),
+        entries: &[
+            item("netherite_ingot", 15, 1, 1),
+            item("ancient_debris", 10, 1, 2),
+            item("netherite_scrap", 8, 1, 1),
+            enchanted_with_levels("diamond_sword", 6, WEAPON_ENCHANTMENTS),
+            enchanted_with_levels("diamond_chestplate", 6, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_helmet", 6, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_leggings", 6, ARMOR_ENCHANTMENTS),
+            enchanted_with_levels("diamond_boots", 6, ARMOR_ENCHANTMENTS),
+            item("diamond", 5, 2, 6),
+            item("enchanted_golden_apple", 2, 1, 1),
+        ],
+    },
+    LootPool {
+        rolls: (3, 4),
+        entries: &[
+            item("spectral_arrow", 1, 12, 25),
+            item("gold_block", 1, 2, 5),
+            item("iron_block", 1, 2, 5),
+            item("gold_ingot", 1, 3, 9),
+            item("iron_ingot", 1, 3, 9),
+            item("crying_obsidian", 1, 3, 5),
+            item("quartz", 1, 8, 23),
+            item("gilded_blackstone", 1, 5, 15),
+            item("magma_cream", 1, 3, 8),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("netherite_upgrade_smithing_template", 1, 1, 1)],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[item("snout_armor_trim_smithing_template", 1, 1, 1)],
+    },
+];
+
+/// バスティオンの種類と、一般的なチェストの数の範囲（宝物庫は別に宝物庫のチェストが1個）
+const BASTION_TYPES: [(&str, (u32, u32)); 4] =
+    [("housing", (4, 6)), ("stables", (3, 5)), ("treasure", (2, 3)), ("bridge", (2, 3))];
+
+/// 試練の間の補給チェスト
+const TRIAL_CHAMBERS_SUPPLY: &[LootPool] = &[LootPool {
+    rolls: (3, 5),
+    entries: &[
+        item("arrow", 2, 4, 14),
+        item("tipped_arrow_poison", 1, 4, 8),
+        item("tipped_arrow_slowness", 1, 4, 8),
+        item("baked_potato", 2, 2, 4),
+        item("glow_berries", 2, 2, 10),
+        item("moss_block", 1, 2, 2),
+        item("bone_meal", 1, 2, 5),
+        item("tuff", 1, 5, 10),
+        item("torch", 1, 3, 6),
+        item("potion_regeneration", 1, 1, 2),
+        item("potion_strength", 1, 1, 1),
+        item("stone_pickaxe", 2, 1, 1),
+        item("milk_bucket", 1, 1, 1),
+    ],
+}];
+
+/// 試練の間の宝物庫（開けたときに抽選される）
+const VAULT_REWARD: &[LootPool] = &[
+    LootPool {
+        rolls: (1, 3),
+        entries: &[
+            item("emerald", 5, 2, 4),
+            item("arrow", 4, 2, 8),
+            item("tipped_arrow_poison", 3, 2, 8),
+            item("iron_ingot", 3, 1, 2),
+            item("wind_charge", 3, 1, 3),
+            item("honey_bottle", 3, 1, 2),
+            item("ominous_bottle", 2, 1, 1),
+            item("shield", 1, 1, 1),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[
+            item("emerald_block", 5, 1, 1),
+            item("iron_block", 4, 1, 1),
+            enchanted("crossbow", 4, CROSSBOW_ENCHANTMENTS),
+            item("golden_apple", 3, 1, 1),
+            enchanted("diamond_axe", 3, TOOL_ENCHANTMENTS),
+            enchanted("diamond_chestplate", 3, ARMOR_ENCHANTMENTS),
+            item("enchanted_book", 2, 1, 1),
+            item("diamond", 1, 1, 2),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[
+            item("golden_apple", 4, 1, 1),
+            item("bolt_armor_trim_smithing_template", 3, 1, 1),
+            item("guster_banner_pattern", 1, 1, 1),
+            item("music_disc_precipice", 1, 1, 1),
+            item("trident", 1, 1, 1),
+            empty(30),
+        ],
+    },
+];
+
+/// 試練の間の不吉な宝物庫（開けたときに抽選される）
+const OMINOUS_VAULT_REWARD: &[LootPool] = &[
+    LootPool {
+        rolls: (1, 3),
+        entries: &[
+            item("emerald", 5, 4, 10),
+            item("wind_charge", 4, 4, 12),
+            item("tipped_arrow_strength", 3, 4, 12),
+            item("diamond", 2, 2, 3),
+            item("ominous_bottle", 1, 1, 1),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[
+            item("emerald_block", 5, 1, 1),
+            item("iron_block", 4, 1, 1),
+            enchanted_with_levels("crossbow", 4, CROSSBOW_ENCHANTMENTS),
+            item("golden_apple", 3, 1, 1),
+            enchanted_with_levels("diamond_axe", 3, TOOL_ENCHANTMENTS),
+            enchanted_with_levels("diamond_chestplate", 3, ARMOR_ENCHANTMENTS),
+            item("enchanted_book", 2, 1, 1),
+            item("diamond_block", 1, 1, 1),
+        ],
+    },
+    LootPool {
+        rolls: (1, 1),
+        entries: &[
+            item("enchanted_golden_apple", 3, 1, 1),
+            item("flow_armor_trim_smithing_template", 3, 1, 1),
+            item("flow_banner_pattern", 2, 1, 1),
+            item("music_disc_creator", 1, 1, 1),
+            item("heavy_core", 1, 1, 1),
+            empty(3),
+        ],
+    },
+];
+
+/// 廃坑のチェスト付きトロッコ
+const ABANDONED_MINESHAFT: &[LootPool] = &[
+    LootPool {
+        rolls: (1, 1),
+        entries: &[
+            item("golden_apple", 20, 1, 1),
+            item("enchanted_golden_apple", 1, 1, 1),
+            item("name_tag", 30, 1, 1),
+            enchanted("enchanted_book", 10, TOOL_ENCHANTMENTS),
+            item("iron_pickaxe", 5, 1, 1),
+            item("music_disc_13", 3, 1, 1),
+            item("music_disc_cat", 3, 1, 1),
+            empty(5),
+        ],
+    },
+    LootPool {
+        rolls: (2, 4),
+        entries: &[
+            item("iron_ingot", 10, 1, 5),
+            item("gold_ingot", 5, 1, 3),
+            item("redstone", 5, 4, 9),
+            item("lapis_lazuli", 5, 4, 9),
+            item("diamond", 3, 1, 2),
+            item("coal", 10, 3, 8),
+            item("bread", 15, 1, 3),
+            item("glow_berries", 15, 3, 6),
+            item("melon_seeds", 10, 2, 4),
+            item("pumpkin_seeds", 10, 2, 4),
+            item("beetroot_seeds", 10, 2, 4),
+        ],
+    },
+    LootPool {
+        rolls: (3, 3),
+        entries: &[
+            item("rail", 20, 4, 8),
+            item("powered_rail", 5, 1, 4),
+            item("detector_rail", 5, 1, 4),
+            item("activator_rail", 5, 1, 4),
+            item("torch", 15, 1, 16),
+        ],
+    },
+];
+
+/// 廃坑の通路の長さ（中心から各方向、ブロック）
+const MINESHAFT_CORRIDOR_LENGTH: u32 = 80;
+
+/// 試練の間の宝物庫が不吉な宝物庫になる確率（%）
+const OMINOUS_VAULT_PERCENT: u32 = 35;
+
+/// 難破船の船体の種類と出現率（%）
+const SHIPWRECK_HULLS: [(&str, u32); 3] = [("full", 40), ("bow", 30), ("stern", 30)];
+
+/// 難破船が逆さまに沈む確率（%）
+const SHIPWRECK_UPSIDE_DOWN_PERCENT: u32 = 25;
+
+/// エンドシティに船が付く確率（%）
+const END_SHIP_PERCENT: u32 = 35;
+
+/// ルート抽選用の乱数（構造物の配置と同じLCG）
+struct LootRandom(i64);
+
+impl LootRandom {
+    fn next_int(&mut self, bound: u32) -> u32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 17).unsigned_abs() % bound as u64) as u32
+    }
+
+    /// min 以上 max 以下
+    fn range(&mut self, min: u32, max: u32) -> u32 {
+        min + self.next_int(max - min + 1)
+    }
+
+    /// 重みに従って候補を1つ選ぶ
+    fn pick<'a, T>(&mut self, candidates: &'a [T], weight: impl Fn(&T) -> u32) -> &'a T {
+        let total: u32 = candidates.iter().map(&weight).sum();
+        let mut roll = self.next_int(total);
+        candidates
+            .iter()
+            .find(|c| {
+                if roll < weight(c) {
+                    return true;
+                }
+                roll -= weight(c);
+                false
+            })
+            .unwrap()
+    }
+}
+
+/// ワールドシードと座標を混ぜる
+fn mix_position(world_seed: i64, x: i32, y: i32, z: i32) -> i64 {
+    world_seed
+        .wrapping_add((x as i64).wrapping_mul(341873128712))
+        .wrapping_add((y as i64).wrapping_mul(42317861))
+        .wrapping_add((z as i64).wrapping_mul(132897987541))
+}
+
+/// ルートシードの導出に使う構造物の情報
+///
+/// 同じ座標のチェストでも構造物が違えば別のシードになるよう、構造物の配置のソルトを混ぜる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LootContext {
+    pub world_seed: i64,
+    /// 構造物の座標（検索結果の座標）
+    pub structure_x: i32,
+    pub structure_z: i32,
+    /// 構造物の配置のソルト
+    pub salt: i64,
+    /// 構造物のディメンションのワールドのずれ（シードは生成の座標から導出する）
+    pub offset: (i32, i32),
+}
+
+impl LootContext {
+    pub fn new(world_seed: i64, salt: i64, structure_x: i32, structure_z: i32) -> LootContext {
+        LootContext {
+            world_seed,
+            structure_x,
+            structure_z,
+            salt,
+            offset: (0, 0),
+        }
+    }
+
+    /// 構造物の種類からソルトを引いて作る（要塞と登録されていないカスタム構造物は None）
+    pub fn for_structure(world_seed: i64, kind: &StructureKind, structure_x: i32, structure_z: i32) -> Option<LootContext> {
+        let (salt, dimension) = match kind {
+            StructureKind::Builtin(st) => (st.salt(), st.dimension()),
+            StructureKind::Stronghold => return None,
+            StructureKind::Custom(id) => {
+                let custom = find_custom_structure(id)?;
+                (custom.salt, custom.dimension)
+            }
+        };
+        Some(LootContext {
+            offset: world_offset(dimension),
+            ..LootContext::new(world_seed, salt, structure_x, structure_z)
+        })
+    }
+
+    /// 座標 (x, y, z) のチェストのルートシード
+    pub fn chest_seed(&self, x: i32, y: i32, z: i32) -> i64 {
+        mix_position(self.world_seed, x - self.offset.0, y, z - self.offset.1) ^ self.salt
+    }
+
+    /// 構造物の形や配置の抽選に使うシード
+    ///
+    /// 独立した抽選ごとに `stream` を変える（同じ `stream` からは同じ乱数列になる）。
+    pub fn layout_seed(&self, stream: i32) -> i64 {
+        mix_position(
+            self.world_seed,
+            self.structure_x - self.offset.0,
+            stream,
+            self.structure_z - self.offset.1,
+        )
+        .wrapping_add(self.salt)
+    }
+
+    fn layout_random(&self, stream: i32) -> LootRandom {
+        LootRandom(self.layout_seed(stream))
+    }
+}
+
+/// ルートテーブルを抽選（同じアイテムは合計する）
+pub fn roll_loot_table(seed: i64, pools: &[LootPool]) -> Vec<ItemStack> {
+    let mut rng = LootRandom(seed);
+    let mut items: Vec<ItemStack> = Vec::new();
+
+    for pool in pools {
+        for _ in 0..rng.range(pool.rolls.0, pool.rolls.1) {
+            let entry = rng.pick(pool.entries, |e| e.weight);
+            let Some(name) = entry.item else {
+                continue;
+            };
+            let count = rng.range(entry.min, entry.max);
+            if !entry.enchantments.is_empty() {
+                // エンチャントされたアイテムはまとめない
+                items.push(ItemStack {
+                    item: name.to_string(),
+                    count,
+                    enchantments: roll_enchantments(&mut rng, entry),
+                });
+                continue;
+            }
+            match items.iter_mut().find(|stack| stack.item == name && stack.enchantments.is_empty()) {
+                Some(stack) => stack.count += count,
+                None => items.push(ItemStack {
+                    item: name.to_string(),
+                    count,
+                    enchantments: Vec::new(),
+                }),
+            }
+        }
+    }
+
+    items
+}
+
+/// エンチャントを抽選（同じエンチャントは重ならない）
+fn roll_enchantments(rng: &mut LootRandom, entry: &LootEntry) -> Vec<Enchantment> {
+    let mut enchantments: Vec<Enchantment> = Vec::new();
+    for _ in 0..rng.range(entry.enchantment_rolls.0, entry.enchantment_rolls.1) {
+        let (id, max_level) = entry.enchantments[rng.next_int(entry.enchantments.len() as u32) as usize];
+        let level = rng.range(1, max_level);
+        if !enchantments.iter().any(|e| e.id == id) {
+            enchantments.push(Enchantment {
+                id: id.to_string(),
+                level,
+            });
+        }
+    }
+    enchantments
+}
+
+/// 1つのチェストの中身を予測
+fn predict_chest(context: &LootContext, chest: &str, pools: &[LootPool], x: i32, y: i32, z: i32) -> ChestLoot {
+    let items = roll_loot_table(context.chest_seed(x, y, z), pools);
+    let notable = items
+        .iter()
+        .filter(|stack| NOTABLE_ITEMS.contains(&stack.item.as_str()))
+        .map(|stack| stack.item.clone())
+        .collect();
+    ChestLoot {
+        chest: chest.to_string(),
+        x,
+        y,
+        z,
+        items,
+        notable,
+        rolled_on_open: false,
+    }
+}
+
+/// 開けたときに中身が抽選される入れ物（中身は予測しない）
+fn unrolled_container(name: &str, x: i32, y: i32, z: i32) -> ChestLoot {
+    ChestLoot {
+        chest: name.to_string(),
+        x,
+        y,
+        z,
+        items: Vec::new(),
+        notable: Vec::new(),
+        rolled_on_open: true,
+    }
+}
+
+/// 難破船の形を予測（船体の種類と逆さまかどうか）
+fn shipwreck_variant(context: &LootContext) -> (&'static str, bool) {
+    let mut rng = context.layout_random(0);
+    let (hull, _) = rng.pick(&SHIPWRECK_HULLS, |(_, percent)| *percent);
+    (hull, rng.next_int(100) < SHIPWRECK_UPSIDE_DOWN_PERCENT)
+}
+
+/// 難破船のチェスト
+///
+/// 補給チェストは船首、地図チェストと宝箱は船尾にあるため、残っている船体のチェストだけを予測する。
+fn shipwreck_loot(context: &LootContext) -> StructureLoot {
+    let (x, z) = (context.structure_x, context.structure_z);
+    let (hull, upside_down) = shipwreck_variant(context);
+    let y = estimate_terrain_y(context.world_seed, x, z) + 2;
+    let mut chests = Vec::new();
+    if hull != "stern" {
+        chests.push(predict_chest(context, "shipwreck_supply", SHIPWRECK_SUPPLY, x, y, z - 8));
+    }
+    if hull != "bow" {
+        chests.push(predict_chest(context, "shipwreck_map", SHIPWRECK_MAP, x, y, z + 8));
+        chests.push(predict_chest(context, "shipwreck_treasure", SHIPWRECK_TREASURE, x, y, z + 4));
+    }
+    let variant = if upside_down { format!("{}_upside_down", hull) } else { hull.to_string() };
+    StructureLoot {
+        variant: Some(variant),
+        chests,
+        odds: Vec::new(),
+    }
+}
+
+/// エンドシティのチェストと船
+///
+/// 塔の部屋のチェストは2〜4個。船が付く場合は船のチェスト2個と、
+/// 額縁のエリトラ（固定）を加える。
+fn end_city_loot(context: &LootContext) -> StructureLoot {
+    let (x, z) = (context.structure_x, context.structure_z);
+    let mut rng = context.layout_random(0);
+    let rooms = rng.range(2, 4);
+    let has_ship = rng.next_int(100) < END_SHIP_PERCENT;
+
+    let mut chests: Vec<ChestLoot> = (0..rooms as i32)
+        .map(|i| predict_chest(context, "end_city_treasure", END_CITY_TREASURE, x + 4, 70 + i * 20, z - 4))
+        .collect();
+    if has_ship {
+        let (ship_x, ship_y, ship_z) = (x + 12, 70 + rooms as i32 * 20, z + 24);
+        for offset in [-2, 2] {
+            chests.push(predict_chest(context, "end_city_treasure", END_CITY_TREASURE, ship_x + offset, ship_y, ship_z));
+        }
+        chests.push(ChestLoot {
+            chest: "end_ship_item_frame".to_string(),
+            x: ship_x,
+            y: ship_y + 1,
+            z: ship_z + 6,
+            items: vec![ItemStack {
+                item: "elytra".to_string(),
+                count: 1,
+                enchantments: Vec::new(),
+            }],
+            notable: vec!["elytra".to_string()],
+            rolled_on_open: false,
+        });
+    }
+    StructureLoot {
+        variant: Some(if has_ship { "with_ship" } else { "no_ship" }.to_string()),
+        chests,
+        odds: Vec::new(),
+    }
+}
+
+/// ネザー要塞のチェスト
+///
+/// チェストは通路の脇の小部屋に1〜4個置かれる。
+fn fortress_loot(context: &LootContext) -> StructureLoot {
+    let (x, z) = (context.structure_x, context.structure_z);
+    let mut rng = context.layout_random(0);
+    let chests = (0..rng.range(1, 4))
+        .map(|_| {
+            let chest_x = x + rng.range(0, 96) as i32 - 48;
+            let chest_z = z + rng.range(0, 96) as i32 - 48;
+            let chest_y = 48 + rng.range(0, 22) as i32;
+            predict_chest(context, "nether_bridge", NETHER_BRIDGE, chest_x, chest_y, chest_z)
+        })
+        .collect();
+    StructureLoot {
+        variant: None,
+        chests,
+        odds: Vec::new(),
+    }
+}
+
+/// バスティオンの種類を予測
+fn bastion_type(context: &LootContext) -> &'static str {
+    let mut rng = context.layout_random(0);
+    BASTION_TYPES[rng.next_int(BASTION_TYPES.len() as u32) as usize].0
+}
+
+/// バスティオンのチェスト（種類ごとの数。宝物庫には宝物庫のチェストが加わる）
+fn bastion_loot(context: &LootContext) -> StructureLoot {
+    let (x, z) = (context.structure_x, context.structure_z);
+    let variant = bastion_type(context);
+    let (_, (min, max)) = BASTION_TYPES.iter().find(|(t, _)| *t == variant).unwrap();
+    let mut rng = context.layout_random(1);
+    let mut position = || {
+        (
+            x + rng.range(0, 80) as i32 - 40,
+            35 + rng.range(0, 60) as i32,
+            z + rng.range(0, 80) as i32 - 40,
+        )
+    };
+
+    let mut chests = Vec::new();
+    let mut tables: Vec<&[LootPool]> = Vec::new();
+    if variant == "treasure" {
+        let (cx, cy, cz) = position();
+        chests.push(predict_chest(context, "bastion_treasure", BASTION_TREASURE, cx, cy, cz));
+        tables.push(BASTION_TREASURE);
+    }
+    let count = context.layout_random(2).range(*min, *max);
+    for _ in 0..count {
+        let (cx, cy, cz) = position();
+        chests.push(predict_chest(context, "bastion_other", BASTION_OTHER, cx, cy, cz));
+        tables.push(BASTION_OTHER);
+    }
+
+    StructureLoot {
+        variant: Some(variant.to_string()),
+        chests,
+        odds: structure_odds(&tables, BASTION_ODDS_ITEMS),
+    }
+}
+
+/// 試練の間の補給チェストと宝物庫
+///
+/// 補給チェストの中身と宝物庫の数・種類（通常か不吉か）はワールド生成時に決まる。
+/// 宝物庫の中身は鍵で開けたときに抽選されるため、予測せず確率だけを示す。
+fn trial_chamber_loot(context: &LootContext) -> StructureLoot {
+    let (x, z) = (context.structure_x, context.structure_z);
+    let mut rng = context.layout_random(0);
+    let position = |rng: &mut LootRandom| {
+        (
+            x + rng.range(0, 64) as i32 - 32,
+            -40 + rng.range(0, 20) as i32,
+            z + rng.range(0, 64) as i32 - 32,
+        )
+    };
+
+    let mut chests = Vec::new();
+    for _ in 0..rng.range(2, 4) {
+        let (cx, cy, cz) = position(&mut rng);
+        chests.push(predict_chest(context, "trial_chambers_supply", TRIAL_CHAMBERS_SUPPLY, cx, cy, cz));
+    }
+
+    let mut tables: Vec<&[LootPool]> = Vec::new();
+    let mut ominous = 0;
+    let vaults = rng.range(2, 6);
+    for _ in 0..vaults {
+        let (vx, vy, vz) = position(&mut rng);
+        if rng.next_int(100) < OMINOUS_VAULT_PERCENT {
+            ominous += 1;
+            chests.push(unrolled_container("ominous_vault", vx, vy, vz));
+            tables.push(OMINOUS_VAULT_REWARD);
+        } else {
+            chests.push(unrolled_container("vault", vx, vy, vz));
+            tables.push(VAULT_REWARD);
+        }
+    }
+
+    StructureLoot {
+        variant: Some(format!("vaults_{}_ominous_{}", vaults - ominous, ominous)),
+        chests,
+        odds: structure_odds(&tables, TRIAL_CHAMBER_ODDS_ITEMS),
+    }
+}
+
+/// 廃坑のチェスト付きトロッコ
+///
+/// 中心の部屋から東西・南北に伸びる通路の上に3〜8台置く。
+/// 位置は通路の向きと中心からの距離による推定で、実際の通路の分岐は再現しない。
+fn mineshaft_loot(context: &LootContext) -> StructureLoot {
+    let (x, z) = (context.structure_x, context.structure_z);
+    let mut rng = context.layout_random(0);
+    let center_y = 10 + rng.range(0, 30) as i32;
+    let chests = (0..rng.range(3, 8))
+        .map(|_| {
+            let along = rng.range(0, MINESHAFT_CORRIDOR_LENGTH * 2) as i32 - MINESHAFT_CORRIDOR_LENGTH as i32;
+            let (cart_x, cart_z) = if rng.next_int(2) == 0 { (x + along, z) } else { (x, z + along) };
+            let cart_y = center_y + rng.range(0, 8) as i32 - 4;
+            predict_chest(context, "abandoned_mineshaft", ABANDONED_MINESHAFT, cart_x, cart_y, cart_z)
+        })
+        .collect();
+    StructureLoot {
+        variant: None,
+        chests,
+        odds: Vec::new(),
+    }
+}
+
+/// プールで1回以上アイテムが出る確率
+fn pool_probability(pool: &LootPool, item: &str) -> f64 {
+    let total: u32 = pool.entries.iter().map(|e| e.weight).sum();
+    let weight: u32 = pool.entries.iter().filter(|e| e.item == Some(item)).map(|e| e.weight).sum();
+    let miss = 1.0 - weight as f64 / total as f64;
+    // 抽選回数は範囲内で一様
+    let (min, max) = pool.rolls;
+    let none: f64 = (min..=max).map(|rolls| miss.powi(rolls as i32)).sum::<f64>() / (max - min + 1) as f64;
+    1.0 - none
+}
+
+/// ルートテーブルで1回以上アイテムが出る確率
+fn table_probability(pools: &[LootPool], item: &str) -> f64 {
+    1.0 - pools.iter().map(|pool| 1.0 - pool_probability(pool, item)).product::<f64>()
+}
+
+/// 構造物のチェストのいずれかに対象のアイテムが入る確率
+fn structure_odds(tables: &[&[LootPool]], items: &[&str]) -> Vec<ItemOdds> {
+    items
+        .iter()
+        .map(|item| ItemOdds {
+            item: item.to_string(),
+            probability: 1.0 - tables.iter().map(|t| 1.0 - table_probability(t, item)).product::<f64>(),
+        })
+        .filter(|odds| odds.probability > 0.0)
+        .collect()
+}
+
+/// 構造物の形とチェストの中身を予測（ルート予測に対応していない構造物は None）
+///
+/// `x`, `z` は検索結果の構造物の座標。
+pub fn predict_loot(world_seed: i64, kind: &StructureKind, x: i32, z: i32) -> Option<StructureLoot> {
+    let StructureKind::Builtin(st) = kind else {
+        return None;
+    };
+    let context = &LootContext::for_structure(world_seed, kind, x, z)?;
+    match st {
+        // 宝箱は構造物のチャンク中心の砂の中に埋まっている
+        StructureType::BuriedTreasure => {
+            let y = estimate_terrain_y(world_seed, x, z) - 3;
+            Some(StructureLoot {
+                variant: None,
+                chests: vec![predict_chest(context, "buried_treasure", BURIED_TREASURE, x, y, z)],
+                odds: Vec::new(),
+            })
+        }
+        StructureType::Shipwreck => Some(shipwreck_loot(context)),
+        StructureType::EndCity => Some(end_city_loot(context)),
+        StructureType::NetherFortress => Some(fortress_loot(context)),
+        StructureType::BastionRemnant => Some(bastion_loot(context)),
+        StructureType::TrialChamber => Some(trial_chamber_loot(context)),
+        StructureType::Mineshaft => Some(mineshaft_loot(context)),
+        // チェストはポータルの枠の脇の地表に置かれる
+        StructureType::RuinedPortal => {
+            let y = estimate_surface_y(world_seed, x + 3, z + 2);
+            Some(StructureLoot {
+                variant: None,
+                chests: vec![predict_chest(context, "ruined_portal", RUINED_PORTAL, x + 3, y, z + 2)],
+                odds: Vec::new(),
+            })
+        }
+        StructureType::Village
+        | StructureType::PillagerOutpost
+        | StructureType::OceanMonument
+        | StructureType::WoodlandMansion
+        | StructureType::Igloo
+        | StructureType::WitchHut
+        | StructureType::AncientCity => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buried_treasure_loot() {
+        let chests = predict_loot(12345, &StructureKind::Builtin(StructureType::BuriedTreasure), 136, -88).unwrap().chests;
+        assert_eq!(chests.len(), 1);
+        let chest = &chests[0];
+        assert!(chest.items.iter().any(|s| s.item == "heart_of_the_sea" && s.count == 1));
+        assert!(chest.notable.contains(&"heart_of_the_sea".to_string()));
+        // 鉄・金・TNTは5〜8回抽選される
+        let metals: u32 = chest
+            .items
+            .iter()
+            .filter(|s| ["iron_ingot", "gold_ingot", "tnt"].contains(&s.item.as_str()))
+            .map(|s| s.count)
+            .sum();
+        assert!((5..=32).contains(&metals), "metals = {}", metals);
+
+        let again = predict_loot(12345, &StructureKind::Builtin(StructureType::BuriedTreasure), 136, -88).unwrap();
+        assert_eq!(chest.items, again.chests[0].items);
+        assert!(predict_loot(12345, &StructureKind::Builtin(StructureType::Village), 0, 0).is_none());
+        assert!(predict_loot(12345, &StructureKind::Stronghold, 0, 0).is_none());
+    }
+
+    #[test]
+    fn test_loot_context_seeds() {
+        let context = LootContext::for_structure(12345, &StructureKind::Builtin(StructureType::Shipwreck), 136, -88).unwrap();
+        assert_eq!(context.salt, StructureType::Shipwreck.salt());
+        assert_eq!(context.chest_seed(136, 62, -80), context.chest_seed(136, 62, -80));
+        assert_ne!(context.chest_seed(136, 62, -80), context.chest_seed(136, 63, -80));
+        assert_ne!(context.layout_seed(0), context.layout_seed(1));
+
+        // 同じ座標でも構造物やワールドシードが違えば別のシード
+        let treasure = LootContext::for_structure(12345, &StructureKind::Builtin(StructureType::BuriedTreasure), 136, -88).unwrap();
+        assert_ne!(context.chest_seed(136, 62, -80), treasure.chest_seed(136, 62, -80));
+        let other_world = LootContext::new(54321, context.salt, 136, -88);
+        assert_ne!(context.chest_seed(136, 62, -80), other_world.chest_seed(136, 62, -80));
+        assert!(LootContext::for_structure(12345, &StructureKind::Custom("sky_castle".to_string()), 0, 0).is_none());
+
+        // 予測はすべて chest_seed で抽選している
+        let chest = &predict_loot(12345, &StructureKind::Builtin(StructureType::BuriedTreasure), 136, -88).unwrap().chests[0];
+        assert_eq!(chest.items, roll_loot_table(treasure.chest_seed(chest.x, chest.y, chest.z), BURIED_TREASURE));
+    }
+
+    #[test]
+    fn test_ruined_portal_enchantments() {
+        let mut enchanted = 0;
+        for x in (0..20000).step_by(640) {
+            let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::RuinedPortal), x, 0).unwrap();
+            for stack in &loot.chests[0].items {
+                if RUINED_PORTAL[0].entries.iter().any(|e| e.item == Some(stack.item.as_str()) && !e.enchantments.is_empty()) {
+                    assert_eq!(stack.enchantments.len(), 1, "{}", stack.item);
+                    assert!(stack.enchantments[0].level >= 1);
+                    enchanted += 1;
+                } else {
+                    assert!(stack.enchantments.is_empty(), "{}", stack.item);
+                }
+            }
+        }
+        assert!(enchanted > 0);
+    }
+
+    #[test]
+    fn test_end_city_ship_has_elytra() {
+        for x in (1000..8000).step_by(320) {
+            let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::EndCity), x, 0).unwrap();
+            let frame = loot.chests.iter().find(|c| c.chest == "end_ship_item_frame");
+            match loot.variant.as_deref() {
+                Some("with_ship") => assert_eq!(frame.unwrap().items[0].item, "elytra"),
+                Some("no_ship") => assert!(frame.is_none()),
+                other => panic!("{:?}", other),
+            }
+            let treasure = loot.chests.iter().filter(|c| c.chest == "end_city_treasure").count();
+            assert!((2..=6).contains(&treasure));
+        }
+    }
+
+    #[test]
+    fn test_fortress_loot() {
+        let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::NetherFortress), 250, -130).unwrap();
+        assert!((1..=4).contains(&loot.chests.len()));
+        for chest in &loot.chests {
+            assert_eq!(chest.chest, "nether_bridge");
+            assert!((48..=70).contains(&chest.y));
+            let rolls: u32 = chest.items.iter().map(|s| s.count).sum();
+            assert!(rolls >= 2, "{:?}", chest.items);
+        }
+    }
+
+    #[test]
+    fn test_bastion_odds() {
+        // 一般的なチェスト1個でピグステップが出る確率は 5/89
+        let pigstep = table_probability(BASTION_OTHER, "music_disc_pigstep");
+        assert!((pigstep - 5.0 / 89.0).abs() < 1e-9);
+        assert_eq!(table_probability(BASTION_TREASURE, "netherite_upgrade_smithing_template"), 1.0);
+
+        for x in (0..6000).step_by(480) {
+            let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::BastionRemnant), x, 200).unwrap();
+            let variant = loot.variant.as_deref().unwrap();
+            let has_treasure = loot.chests.iter().any(|c| c.chest == "bastion_treasure");
+            assert_eq!(variant == "treasure", has_treasure);
+            let template = loot.odds.iter().find(|o| o.item == "netherite_upgrade_smithing_template").unwrap();
+            assert!(template.probability > 0.0 && template.probability <= 1.0);
+            if has_treasure {
+                assert_eq!(template.probability, 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trial_chamber_vaults_are_not_rolled() {
+        let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::TrialChamber), 300, -500).unwrap();
+        let vaults: Vec<&ChestLoot> = loot.chests.iter().filter(|c| c.chest.ends_with("vault")).collect();
+        assert!((2..=6).contains(&vaults.len()));
+        assert!(vaults.iter().all(|v| v.rolled_on_open && v.items.is_empty()));
+        assert!(loot.chests.iter().filter(|c| c.chest == "trial_chambers_supply").all(|c| !c.rolled_on_open && !c.items.is_empty()));
+
+        let ominous = vaults.iter().filter(|v| v.chest == "ominous_vault").count();
+        let expected = format!("vaults_{}_ominous_{}", vaults.len() - ominous, ominous);
+        assert_eq!(loot.variant.as_deref(), Some(expected.as_str()));
+        // 重い核は不吉な宝物庫にしか入らない
+        let heavy_core = loot.odds.iter().find(|o| o.item == "heavy_core").map_or(0.0, |o| o.probability);
+        assert_eq!(heavy_core > 0.0, ominous > 0);
+    }
+
+    #[test]
+    fn test_mineshaft_minecarts_on_corridors() {
+        let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::Mineshaft), 136, 264).unwrap();
+        assert!((3..=8).contains(&loot.chests.len()));
+        for cart in &loot.chests {
+            assert_eq!(cart.chest, "abandoned_mineshaft");
+            // 東西か南北の通路上
+            assert!(cart.x == 136 || cart.z == 264, "({}, {})", cart.x, cart.z);
+            assert!((cart.x - 136).abs() <= 80 && (cart.z - 264).abs() <= 80);
+            assert!(!cart.items.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_shipwreck_chests_follow_variant() {
+        for x in (0..4000).step_by(384) {
+            let loot = predict_loot(12345, &StructureKind::Builtin(StructureType::Shipwreck), x, 200).unwrap();
+            let variant = loot.variant.unwrap();
+            let chests: Vec<&str> = loot.chests.iter().map(|c| c.chest.as_str()).collect();
+            if variant.starts_with("bow") {
+                assert_eq!(chests, ["shipwreck_supply"]);
+            } else if variant.starts_with("stern") {
+                assert_eq!(chests, ["shipwreck_map", "shipwreck_treasure"]);
+            } else {
+                assert_eq!(chests.len(), 3);
+            }
+            if let Some(map) = loot.chests.iter().find(|c| c.chest == "shipwreck_map") {
+                assert!(map.notable.contains(&"buried_treasure_map".to_string()));
+            }
+        }
+    }
+}
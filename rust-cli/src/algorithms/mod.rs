@@ -1,3 +1,14 @@
 //! アルゴリズムモジュール
 
 pub mod biome;
+pub mod column;
+pub mod deep_dark;
+pub mod dungeon;
+pub mod height;
+pub mod legacy_biome;
+pub mod loot;
+pub mod noise;
+pub mod ore;
+pub mod slime;
+pub mod spawn;
+pub mod underground;
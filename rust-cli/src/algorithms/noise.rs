@@ -0,0 +1,46 @@
+//! ノイズモジュール
+//!
+//! バイオームや地表の高さの近似に使う簡易ノイズ。整数の座標ごとに独立した値を返すハッシュ型のノイズで、
+//! 隣り合う座標の間の補間はしない。同じ引数に対して常に同じ値を返し、整数の演算はすべてラップアラウンドのため
+//! どの座標・シードでも panic しない。`internals` 機能を有効にするとライブラリとして公開される。
+
+/// 簡易パーリンノイズ（1D）
+///
+/// - 返り値は常に -1.0000001 〜 1.0 の範囲（`i32` の値を `i32::MAX` で割るため、下限はわずかに -1 を超える）
+/// - `seed` は下位32ビットだけを使う（上位32ビットだけが違うシードは同じ値になる）
+pub fn noise_1d(seed: i64, x: i32) -> f64 {
+    let n = x.wrapping_mul(374761393).wrapping_add((seed as i32).wrapping_mul(668265263));
+    let n = (n ^ (n >> 13)).wrapping_mul(1274126177);
+    (n as f64) / i32::MAX as f64
+}
+
+/// 簡易パーリンノイズ（2D）
+///
+/// - 返り値は `noise_1d` の3つの値の平均のため、同じく -1.0000001 〜 1.0 の範囲
+pub fn noise_2d(seed: i64, x: i32, z: i32) -> f64 {
+    let n1 = noise_1d(seed, x);
+    let n2 = noise_1d(seed.wrapping_add(12345), z);
+    let n3 = noise_1d(seed.wrapping_add(67890), x.wrapping_add(z));
+
+    (n1 + n2 + n3) / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_range() {
+        let range = -1.0000001..=1.0;
+        for seed in [0, 12345, -1, i64::MAX, i64::MIN] {
+            for x in [0, 1, -1, 1000, i32::MAX, i32::MIN] {
+                assert!(range.contains(&noise_1d(seed, x)));
+                for z in [0, -7, i32::MAX, i32::MIN] {
+                    assert!(range.contains(&noise_2d(seed, x, z)));
+                }
+            }
+        }
+        assert_eq!(noise_1d(12345, 5), noise_1d(12345 + (1 << 32), 5));
+        assert_eq!(noise_2d(12345, 100, -100), noise_2d(12345, 100, -100));
+    }
+}
@@ -0,0 +1,539 @@
+//! 鉱石の予測アルゴリズム
+//!
+//! チャンクごとの装飾の乱数（ワールドシードとチャンク座標、鉱石の配置ごとのソルトから作る）で
+//! 鉱脈を置こうとする位置（試行）を予測する。試行の位置に実際に鉱石が置かれるかは
+//! 周りのブロック（石か、空気に触れていないか）によるため、ここでは判定しない。
+//!
+//! 鉱石ごとに複数の配置（通常の鉱脈、大きな鉱脈、埋もれた鉱脈など）を [`OreFeature`] の表で持ち、
+//! 新しい鉱石は [`OreType`] と表を追加するだけで検索できる。高さの分布は 1.18 以降のもの。
+//! ネザーの鉱石（古代の残骸）の座標はネザーの座標。
+//!
+//! 1.18 で加わった大鉱脈（鉄・銅）はチャンクごとの試行ではなく3次元のノイズで決まるため、
+//! [`find_ore_veins`] でサブチャンク（16×16×16ブロック）ごとにノイズの条件を調べる。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::coords::{block_to_chunk, check_search_area, chunk_to_block, chunk_to_generation, to_generation, Dimension};
+use crate::error::SearchError;
+use crate::i18n::is_english;
+use crate::rng::{get_structure_seed, next_int};
+
+/// 鉱石予測の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 1;
+
+/// オーバーワールドの最も低い高さ（1.18 以降）
+const OVERWORLD_MIN_Y: i32 = -64;
+
+/// ネザーの最も低い高さ
+const NETHER_MIN_Y: i32 = 0;
+
+/// 試行の高さの分布
+#[derive(Debug, Clone, Copy)]
+enum HeightDistribution {
+    /// min 以上 max 以下で一様
+    Uniform(i32, i32),
+    /// min から max の三角分布（中央が最も多い）
+    Triangle(i32, i32),
+}
+
+impl HeightDistribution {
+    fn sample(&self, rng: &mut i64) -> i32 {
+        match *self {
+            HeightDistribution::Uniform(min, max) => min + next_int(rng, max - min + 1),
+            HeightDistribution::Triangle(min, max) => {
+                let half = (max - min) / 2;
+                min + next_int(rng, half + 1) + next_int(rng, max - min - half + 1)
+            }
+        }
+    }
+}
+
+/// 鉱石の配置（1チャンクあたりの試行）
+#[derive(Debug, Clone, Copy)]
+struct OreFeature {
+    /// 配置の識別子（結果に表示する）
+    name: &'static str,
+    /// 装飾の乱数のソルト
+    salt: i64,
+    /// 1チャンクあたりの試行回数
+    count: i32,
+    /// 1/rarity のチャンクでだけ試行する（1 は毎チャンク）
+    rarity: i32,
+    /// 鉱脈の大きさ（最大のブロック数）
+    size: u32,
+    height: HeightDistribution,
+    /// 空気に触れる鉱石ブロックが置かれない割合（%）
+    air_discard_percent: u32,
+}
+
+/// ダイヤモンド鉱石の配置
+///
+/// 三角分布の下半分は岩盤より下になるため、-64 付近ほど試行が多い。
+const DIAMOND_FEATURES: &[OreFeature] = &[
+    OreFeature {
+        name: "ore_diamond",
+        salt: 60011,
+        count: 7,
+        rarity: 1,
+        size: 4,
+        height: HeightDistribution::Triangle(-144, 16),
+        air_discard_percent: 50,
+    },
+    OreFeature {
+        name: "ore_diamond_large",
+        salt: 60012,
+        count: 1,
+        rarity: 9,
+        size: 12,
+        height: HeightDistribution::Triangle(-144, 16),
+        air_discard_percent: 70,
+    },
+    OreFeature {
+        name: "ore_diamond_buried",
+        salt: 60013,
+        count: 4,
+        rarity: 1,
+        size: 8,
+        height: HeightDistribution::Triangle(-144, 16),
+        air_discard_percent: 100,
+    },
+];
+
+/// 古代の残骸の配置
+///
+/// 大きい方は Y=8〜24 に集中し、小さい方はネザー全体の高さに散らばる。
+/// どちらも空気に触れる位置には置かれないため、溶岩の海の壁やトンネルの壁には出ない。
+const ANCIENT_DEBRIS_FEATURES: &[OreFeature] = &[
+    OreFeature {
+        name: "ore_ancient_debris_large",
+        salt: 60021,
+        count: 1,
+        rarity: 1,
+        size: 3,
+        height: HeightDistribution::Triangle(8, 24),
+        air_discard_percent: 100,
+    },
+    OreFeature {
+        name: "ore_debris_small",
+        salt: 60022,
+        count: 1,
+        rarity: 1,
+        size: 2,
+        height: HeightDistribution::Uniform(8, 119),
+        air_discard_percent: 100,
+    },
+];
+
+/// 予測できる鉱石
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OreType {
+    Diamond,
+    AncientDebris,
+}
+
+impl OreType {
+    pub const ALL: [OreType; 2] = [OreType::Diamond, OreType::AncientDebris];
+
+    /// 識別子（--ore オプションで使う名前）
+    pub fn id(&self) -> &'static str {
+        match self {
+            OreType::Diamond => "diamond",
+            OreType::AncientDebris => "ancient_debris",
+        }
+    }
+
+    /// 生成されるディメンション（オーバーワールドかネザー）
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            OreType::Diamond => Dimension::Overworld,
+            OreType::AncientDebris => Dimension::Nether,
+        }
+    }
+
+    /// ディメンションの最も低い高さ（これより下の試行は置かれない）
+    fn min_y(&self) -> i32 {
+        match self.dimension() {
+            Dimension::Nether => NETHER_MIN_Y,
+            _ => OVERWORLD_MIN_Y,
+        }
+    }
+
+    /// 識別子から鉱石を取得
+    pub fn from_id(id: &str) -> Option<OreType> {
+        OreType::ALL.into_iter().find(|ore| ore.id() == id)
+    }
+
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                OreType::Diamond => "💎 Diamond",
+                OreType::AncientDebris => "🟫 Ancient Debris",
+            };
+        }
+        match self {
+            OreType::Diamond => "💎 ダイヤモンド",
+            OreType::AncientDebris => "🟫 古代の残骸",
+        }
+    }
+
+    fn features(&self) -> &'static [OreFeature] {
+        match self {
+            OreType::Diamond => DIAMOND_FEATURES,
+            OreType::AncientDebris => ANCIENT_DEBRIS_FEATURES,
+        }
+    }
+}
+
+/// 鉱脈を置こうとする位置
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OreBlob {
+    /// 配置の識別子（ore_diamond_large 等）
+    pub feature: String,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    /// 鉱脈の中心のブロック座標
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    /// 鉱脈の大きさ（最大のブロック数）
+    pub size: u32,
+    /// 空気に触れる鉱石ブロックが置かれない割合（%。100 は洞窟の壁には出ない）
+    pub air_discard_percent: u32,
+    pub distance: f64,
+}
+
+/// 大鉱脈の種類（切り替えノイズが正なら銅、負なら鉄）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VeinType {
+    Copper,
+    Iron,
+}
+
+impl VeinType {
+    pub const ALL: [VeinType; 2] = [VeinType::Copper, VeinType::Iron];
+
+    /// 識別子（--vein オプションで使う名前）
+    pub fn id(&self) -> &'static str {
+        match self {
+            VeinType::Copper => "copper",
+            VeinType::Iron => "iron",
+        }
+    }
+
+    /// 識別子から大鉱脈の種類を取得
+    pub fn from_id(id: &str) -> Option<VeinType> {
+        VeinType::ALL.into_iter().find(|vein| vein.id() == id)
+    }
+
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                VeinType::Copper => "🟠 Copper vein",
+                VeinType::Iron => "⚪ Iron vein",
+            };
+        }
+        match self {
+            VeinType::Copper => "🟠 銅の大鉱脈",
+            VeinType::Iron => "⚪ 鉄の大鉱脈",
+        }
+    }
+
+    /// 生成される高さの範囲
+    fn height_range(&self) -> (i32, i32) {
+        match self {
+            VeinType::Copper => (0, 50),
+            VeinType::Iron => (-60, -8),
+        }
+    }
+}
+
+/// 大鉱脈を含むサブチャンク
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OreVein {
+    pub vein: VeinType,
+    /// サブチャンクの座標（ブロック座標を16で割った値）
+    pub section_x: i32,
+    pub section_y: i32,
+    pub section_z: i32,
+    /// 条件を満たした地点の中心のブロック座標
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    /// サブチャンク内で条件を満たした地点の割合（0.0〜1.0。大きいほど鉱脈が太い）
+    pub density: f64,
+    pub distance: f64,
+}
+
+/// 大鉱脈のノイズのシードに加える値
+const VEIN_TOGGLE_SALT: i64 = 70001;
+const VEIN_RIDGE_A_SALT: i64 = 70002;
+const VEIN_RIDGE_B_SALT: i64 = 70003;
+
+/// 切り替えノイズの波長（ブロック）
+const VEIN_TOGGLE_SCALE: f64 = 96.0;
+
+/// 尾根ノイズの波長（ブロック）
+const VEIN_RIDGE_SCALE: f64 = 24.0;
+
+/// 切り替えノイズの絶対値がこれ未満の場所には大鉱脈がない
+const VEIN_TOGGLE_THRESHOLD: f64 = 0.4;
+
+/// 尾根ノイズの絶対値がこれ未満の場所（2つの尾根の交わる筒）が鉱脈になる
+const VEIN_RIDGE_THRESHOLD: f64 = 0.08;
+
+/// 高さの範囲の端からこの距離までは鉱脈が細くなる
+const VEIN_EDGE_FALLOFF: i32 = 20;
+
+/// サブチャンク内を調べる間隔（ブロック）
+const VEIN_SAMPLE_STEP: i32 = 4;
+
+/// 格子点の値（-1.0〜1.0）
+fn lattice_value(seed: i64, x: i32, y: i32, z: i32) -> f64 {
+    let mut n = (seed as i32)
+        .wrapping_mul(668265263)
+        .wrapping_add(x.wrapping_mul(374761393))
+        .wrapping_add(y.wrapping_mul(1442695041))
+        .wrapping_add(z.wrapping_mul(-1640531535));
+    // 符号ビットを残すため論理シフトで混ぜる
+    n = (n ^ ((n as u32) >> 13) as i32).wrapping_mul(1274126177);
+    n ^= ((n as u32) >> 16) as i32;
+    n as f64 / i32::MAX as f64
+}
+
+/// 3次元のバリューノイズ（格子点の値を滑らかに補間する）
+fn value_noise_3d(seed: i64, x: f64, y: f64, z: f64) -> f64 {
+    let (x0, y0, z0) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+    let fade = |t: f64| t * t * (3.0 - 2.0 * t);
+    let (tx, ty, tz) = (fade(x - x0 as f64), fade(y - y0 as f64), fade(z - z0 as f64));
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+    let corner = |dx: i32, dy: i32, dz: i32| lattice_value(seed, x0 + dx, y0 + dy, z0 + dz);
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), tx);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), tx);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), tx);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), tx);
+    lerp(lerp(x00, x10, ty), lerp(x01, x11, ty), tz)
+}
+
+/// ブロック座標が大鉱脈の一部になるか（なる場合は種類）
+fn vein_at(seed: i64, x: i32, y: i32, z: i32) -> Option<VeinType> {
+    let sample = |salt: i64, scale: f64| {
+        value_noise_3d(seed.wrapping_add(salt), x as f64 / scale, y as f64 / scale, z as f64 / scale)
+    };
+    let toggle = sample(VEIN_TOGGLE_SALT, VEIN_TOGGLE_SCALE);
+    let vein = if toggle > 0.0 { VeinType::Copper } else { VeinType::Iron };
+
+    let (min_y, max_y) = vein.height_range();
+    if y < min_y || y > max_y {
+        return None;
+    }
+    // 高さの範囲の端に近いほど条件を厳しくする
+    let edge = (y - min_y).min(max_y - y).min(VEIN_EDGE_FALLOFF);
+    let falloff = -0.2 * (1.0 - edge as f64 / VEIN_EDGE_FALLOFF as f64);
+    if toggle.abs() + falloff < VEIN_TOGGLE_THRESHOLD {
+        return None;
+    }
+
+    let ridge_a = sample(VEIN_RIDGE_A_SALT, VEIN_RIDGE_SCALE).abs();
+    let ridge_b = sample(VEIN_RIDGE_B_SALT, VEIN_RIDGE_SCALE).abs();
+    if ridge_a.max(ridge_b) >= VEIN_RIDGE_THRESHOLD {
+        return None;
+    }
+    Some(vein)
+}
+
+/// サブチャンクの大鉱脈を調べる（含まない場合は None）
+fn section_vein(seed: i64, section_x: i32, section_y: i32, section_z: i32) -> Option<(VeinType, i32, i32, i32, f64)> {
+    let offsets: Vec<i32> = (0..16).step_by(VEIN_SAMPLE_STEP as usize).map(|o| o + VEIN_SAMPLE_STEP / 2).collect();
+    let samples = offsets.len().pow(3);
+    let mut hits: Vec<(VeinType, i32, i32, i32)> = Vec::new();
+    for &dx in &offsets {
+        for &dy in &offsets {
+            for &dz in &offsets {
+                let (x, y, z) = (section_x * 16 + dx, section_y * 16 + dy, section_z * 16 + dz);
+                let (gen_x, gen_z) = to_generation(Dimension::Overworld, x, z);
+                if let Some(vein) = vein_at(seed, gen_x, y, gen_z) {
+                    hits.push((vein, x, y, z));
+                }
+            }
+        }
+    }
+
+    // 1つのサブチャンクに両方がかかる場合は多い方
+    let copper = hits.iter().filter(|h| h.0 == VeinType::Copper).count();
+    let vein = if copper * 2 >= hits.len() { VeinType::Copper } else { VeinType::Iron };
+    hits.retain(|h| h.0 == vein);
+    if hits.is_empty() {
+        return None;
+    }
+    let n = hits.len() as i64;
+    let (x, y, z) = hits
+        .iter()
+        .fold((0i64, 0i64, 0i64), |(ax, ay, az), h| (ax + h.1 as i64, ay + h.2 as i64, az + h.3 as i64));
+    Some((vein, (x / n) as i32, (y / n) as i32, (z / n) as i32, hits.len() as f64 / samples as f64))
+}
+
+/// 範囲内の大鉱脈を含むサブチャンクを検索（距離順）
+pub fn find_ore_veins(seed: i64, center_x: i32, center_z: i32, radius: i32) -> Result<Vec<OreVein>, SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let min_sx = block_to_chunk(center_x - radius);
+    let max_sx = block_to_chunk(center_x + radius);
+    let min_sz = block_to_chunk(center_z - radius);
+    let max_sz = block_to_chunk(center_z + radius);
+    let min_sy = VeinType::ALL.iter().map(|v| v.height_range().0).min().unwrap().div_euclid(16);
+    let max_sy = VeinType::ALL.iter().map(|v| v.height_range().1).max().unwrap().div_euclid(16);
+
+    let mut results = Vec::new();
+    for section_x in min_sx..=max_sx {
+        for section_z in min_sz..=max_sz {
+            for section_y in min_sy..=max_sy {
+                let Some((vein, x, y, z, density)) = section_vein(seed, section_x, section_y, section_z) else {
+                    continue;
+                };
+                let dist_sq = (x as i64 - center_x as i64).pow(2) + (z as i64 - center_z as i64).pow(2);
+                if dist_sq > (radius as i64).pow(2) {
+                    continue;
+                }
+                results.push(OreVein {
+                    vein,
+                    section_x,
+                    section_y,
+                    section_z,
+                    x,
+                    y,
+                    z,
+                    density,
+                    distance: (dist_sq as f64).sqrt(),
+                });
+            }
+        }
+    }
+
+    tracing::info!(columns = (max_sx - min_sx + 1) as u64 * (max_sz - min_sz + 1) as u64, hits = results.len(), "サブチャンクを走査");
+    results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+    Ok(results)
+}
+
+/// チャンクの1つの配置の試行を予測（ワールドの高さの外になる試行は除く）
+fn feature_attempts(seed: i64, ore: OreType, feature: &OreFeature, chunk_x: i32, chunk_z: i32) -> Vec<(i32, i32, i32)> {
+    let (gen_x, gen_z) = chunk_to_generation(ore.dimension(), chunk_x, chunk_z);
+    let mut rng = get_structure_seed(seed, gen_x, gen_z, feature.salt);
+    if feature.rarity > 1 && next_int(&mut rng, feature.rarity) != 0 {
+        return Vec::new();
+    }
+    (0..feature.count)
+        .map(|_| {
+            let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+            let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+            (x, feature.height.sample(&mut rng), z)
+        })
+        .filter(|(_, y, _)| *y >= ore.min_y())
+        .collect()
+}
+
+/// 範囲内のチャンクの鉱脈の試行を検索（距離順）
+///
+/// 検索中心のブロックを含むチャンクは、半径が 0 でも必ず含める。
+pub fn find_ore_blobs(
+    seed: i64,
+    ore: OreType,
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+) -> Result<Vec<OreBlob>, SearchError> {
+    // 半径 0 は検索中心のチャンクだけなので、範囲は半径 1 として確かめる
+    check_search_area(center_x, center_z, if radius == 0 { 1 } else { radius })?;
+    let min_cx = block_to_chunk(center_x - radius);
+    let max_cx = block_to_chunk(center_x + radius);
+    let min_cz = block_to_chunk(center_z - radius);
+    let max_cz = block_to_chunk(center_z + radius);
+
+    let mut results = Vec::new();
+    for chunk_x in min_cx..=max_cx {
+        for chunk_z in min_cz..=max_cz {
+            for feature in ore.features() {
+                for (x, y, z) in feature_attempts(seed, ore, feature, chunk_x, chunk_z) {
+                    let dist_sq = (x as i64 - center_x as i64).pow(2) + (z as i64 - center_z as i64).pow(2);
+                    if radius > 0 && dist_sq > (radius as i64).pow(2) {
+                        continue;
+                    }
+                    results.push(OreBlob {
+                        feature: feature.name.to_string(),
+                        chunk_x,
+                        chunk_z,
+                        x,
+                        y,
+                        z,
+                        size: feature.size,
+                        air_discard_percent: feature.air_discard_percent,
+                        distance: (dist_sq as f64).sqrt(),
+                    });
+                }
+            }
+        }
+    }
+
+    tracing::info!(chunks = (max_cx - min_cx + 1) as u64 * (max_cz - min_cz + 1) as u64, hits = results.len(), "チャンクを走査");
+    results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_diamond_blobs() {
+        // 半径 0 は検索中心のチャンクだけ
+        let chunk = find_ore_blobs(12345, OreType::Diamond, 40, -24, 0).unwrap();
+        assert!(!chunk.is_empty());
+        assert!(chunk.iter().all(|b| b.chunk_x == 2 && b.chunk_z == -2));
+        assert!(chunk.iter().all(|b| (32..48).contains(&b.x) && (-32..-16).contains(&b.z)));
+        assert!(chunk.iter().all(|b| (OVERWORLD_MIN_Y..=16).contains(&b.y)));
+        assert!(chunk.iter().filter(|b| b.feature == "ore_diamond").count() <= 7);
+
+        let blobs = find_ore_blobs(12345, OreType::Diamond, 0, 0, 200).unwrap();
+        assert!(blobs.windows(2).all(|w| w[0].distance <= w[1].distance));
+        assert!(blobs.iter().any(|b| b.feature == "ore_diamond_large"));
+        // 三角分布の下半分が岩盤より下になるため、低いほど多い
+        let deep = blobs.iter().filter(|b| b.y < -32).count();
+        let shallow = blobs.iter().filter(|b| b.y >= -16).count();
+        assert!(deep > shallow, "deep = {}, shallow = {}", deep, shallow);
+        assert_eq!(OreType::from_id("diamond"), Some(OreType::Diamond));
+    }
+
+    #[test]
+    fn test_find_ancient_debris() {
+        let debris = find_ore_blobs(12345, OreType::AncientDebris, 0, 0, 100).unwrap();
+        // 大きい方と小さい方が1チャンクに1回ずつ
+        let large: Vec<&OreBlob> = debris.iter().filter(|b| b.feature == "ore_ancient_debris_large").collect();
+        let small = debris.iter().filter(|b| b.feature == "ore_debris_small").count();
+        assert!(!large.is_empty() && small > 0);
+        assert!(large.iter().all(|b| (8..=24).contains(&b.y)));
+        assert!(debris.iter().all(|b| (8..=119).contains(&b.y) && b.air_discard_percent == 100));
+        assert_eq!(OreType::from_id("ancient_debris").map(|o| o.dimension()), Some(Dimension::Nether));
+    }
+
+    #[test]
+    fn test_find_ore_veins() {
+        let veins = find_ore_veins(12345, 0, 0, 400).unwrap();
+        assert!(find_ore_veins(12345, 0, 0, i32::MAX).is_err());
+        assert!(veins.iter().any(|v| v.vein == VeinType::Copper));
+        assert!(veins.iter().any(|v| v.vein == VeinType::Iron));
+        assert!(veins.windows(2).all(|w| w[0].distance <= w[1].distance));
+        for vein in &veins {
+            let (min_y, max_y) = vein.vein.height_range();
+            assert!((min_y..=max_y).contains(&vein.y), "{:?}", vein);
+            assert_eq!(vein.y.div_euclid(16), vein.section_y);
+            assert!(vein.density > 0.0 && vein.density <= 1.0);
+        }
+        // 大鉱脈は珍しい
+        let sections = (2 * 400 / 16 + 1) * (2 * 400 / 16 + 1) * 8;
+        assert!(veins.len() < sections / 10, "{} / {}", veins.len(), sections);
+    }
+}
@@ -0,0 +1,222 @@
+//! スライムチャンク判定アルゴリズム
+//!
+//! Bedrock Editionのスライムチャンクはシード値に依存せず、
+//! チャンク座標から作ったシードでMT19937の最初の出力を使って判定する
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::coords::{block_to_chunk, check_search_area, chunk_to_block, chunk_to_generation, Dimension};
+use crate::error::SearchError;
+
+/// スライムチャンク判定の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 1;
+
+/// MT19937の最初の出力を計算（必要な状態 mt[0], mt[1], mt[397] のみ生成）
+fn mt19937_first(seed: u32) -> u32 {
+    const M: u32 = 397;
+
+    let mut state = seed;
+    let mut mt1 = 0;
+    for i in 1..=M {
+        state = 1812433253u32
+            .wrapping_mul(state ^ (state >> 30))
+            .wrapping_add(i);
+        if i == 1 {
+            mt1 = state;
+        }
+    }
+    let mt_m = state;
+
+    let y = (seed & 0x8000_0000) | (mt1 & 0x7fff_ffff);
+    let mut y = mt_m ^ (y >> 1) ^ if y & 1 != 0 { 0x9908_b0df } else { 0 };
+
+    // テンパリング
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c_5680;
+    y ^= (y << 15) & 0xefc6_0000;
+    y ^= y >> 18;
+    y
+}
+
+/// スライムチャンクかどうか判定
+pub fn is_slime_chunk(chunk_x: i32, chunk_z: i32) -> bool {
+    let (chunk_x, chunk_z) = chunk_to_generation(Dimension::Overworld, chunk_x, chunk_z);
+    let seed = (chunk_x as u32).wrapping_mul(0x1f1f_1f1f) ^ (chunk_z as u32);
+    mt19937_first(seed).is_multiple_of(10)
+}
+
+/// スライムチャンク
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SlimeChunk {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    /// チャンク中心のブロック座標
+    pub block_x: i32,
+    pub block_z: i32,
+    pub distance: f64,
+}
+
+/// 隣接するスライムチャンクのまとまり
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SlimeCluster {
+    pub size: usize,
+    /// まとまりの中心ブロック座標
+    pub center_x: i32,
+    pub center_z: i32,
+    pub chunks: Vec<(i32, i32)>,
+}
+
+/// 範囲内のスライムチャンクを検索（距離順）
+pub fn find_slime_chunks(center_x: i32, center_z: i32, radius: i32) -> Result<Vec<SlimeChunk>, SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let min_cx = block_to_chunk(center_x - radius);
+    let max_cx = block_to_chunk(center_x + radius);
+    let min_cz = block_to_chunk(center_z - radius);
+    let max_cz = block_to_chunk(center_z + radius);
+
+    let mut results = Vec::new();
+    for chunk_x in min_cx..=max_cx {
+        for chunk_z in min_cz..=max_cz {
+            let block_x = chunk_to_block(chunk_x) + 8;
+            let block_z = chunk_to_block(chunk_z) + 8;
+            let dist_sq = (block_x as i64 - center_x as i64).pow(2) + (block_z as i64 - center_z as i64).pow(2);
+            if dist_sq > (radius as i64).pow(2) || !is_slime_chunk(chunk_x, chunk_z) {
+                continue;
+            }
+            results.push(SlimeChunk {
+                chunk_x,
+                chunk_z,
+                block_x,
+                block_z,
+                distance: (dist_sq as f64).sqrt(),
+            });
+        }
+    }
+
+    results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+    Ok(results)
+}
+
+/// 上下左右で隣接する2つ以上のスライムチャンクのまとまりを抽出（大きい順）
+pub fn find_slime_clusters(chunks: &[SlimeChunk]) -> Vec<SlimeCluster> {
+    let index: HashMap<(i32, i32), usize> =
+        chunks.iter().enumerate().map(|(i, c)| ((c.chunk_x, c.chunk_z), i)).collect();
+    let mut visited = vec![false; chunks.len()];
+    let mut clusters = Vec::new();
+
+    for start in 0..chunks.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+
+        let mut members = vec![start];
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            let (cx, cz) = (chunks[i].chunk_x, chunks[i].chunk_z);
+            let neighbors = [
+                cx.checked_sub(1).map(|x| (x, cz)),
+                cx.checked_add(1).map(|x| (x, cz)),
+                cz.checked_sub(1).map(|z| (cx, z)),
+                cz.checked_add(1).map(|z| (cx, z)),
+            ];
+            for pos in neighbors.into_iter().flatten() {
+                let Some(&j) = index.get(&pos) else {
+                    continue;
+                };
+                if !visited[j] {
+                    visited[j] = true;
+                    members.push(j);
+                    stack.push(j);
+                }
+            }
+        }
+
+        if members.len() >= 2 {
+            let size = members.len();
+            let center = |coord: fn(&SlimeChunk) -> i32| {
+                (members.iter().map(|&i| coord(&chunks[i]) as i64).sum::<i64>() / size as i64) as i32
+            };
+            clusters.push(SlimeCluster {
+                size,
+                center_x: center(|c| c.block_x),
+                center_z: center(|c| c.block_z),
+                chunks: members.iter().map(|&i| (chunks[i].chunk_x, chunks[i].chunk_z)).collect(),
+            });
+        }
+    }
+
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.size));
+    clusters
+}
+
+/// スライムチャンクのグリッドマップを文字列で生成
+///
+/// 🟩 = スライムチャンク、⬛ = 通常チャンク、⭐ = 中心チャンク
+pub fn render_slime_map(center_x: i32, center_z: i32, radius: i32) -> String {
+    let center_cx = block_to_chunk(center_x);
+    let center_cz = block_to_chunk(center_z);
+    let chunk_radius = radius / 16;
+
+    let mut lines = Vec::new();
+    for dz in -chunk_radius..=chunk_radius {
+        let mut line = String::new();
+        for dx in -chunk_radius..=chunk_radius {
+            let cell = if dx == 0 && dz == 0 {
+                "⭐"
+            } else if is_slime_chunk(center_cx + dx, center_cz + dz) {
+                "🟩"
+            } else {
+                "⬛"
+            };
+            line.push_str(cell);
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slime_chunk_ratio() {
+        let mut count = 0;
+        for x in -50..50 {
+            for z in -50..50 {
+                if is_slime_chunk(x, z) {
+                    count += 1;
+                }
+            }
+        }
+        // 約10%
+        assert!((800..1200).contains(&count), "count = {}", count);
+    }
+
+    #[test]
+    fn test_clusters_are_adjacent() {
+        let chunks = find_slime_chunks(0, 0, 1000).unwrap();
+        for cluster in find_slime_clusters(&chunks) {
+            assert!(cluster.size >= 2);
+            assert_eq!(cluster.size, cluster.chunks.len());
+        }
+
+        // 縦に2つ並んだチャンクと、離れた1つ
+        let chunk = |chunk_x: i32, chunk_z: i32| SlimeChunk {
+            chunk_x,
+            chunk_z,
+            block_x: chunk_to_block(chunk_x) + 8,
+            block_z: chunk_to_block(chunk_z) + 8,
+            distance: 0.0,
+        };
+        let clusters = find_slime_clusters(&[chunk(3, 4), chunk(5, 5), chunk(3, 5)]);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].chunks, vec![(3, 4), (3, 5)]);
+        assert_eq!((clusters[0].center_x, clusters[0].center_z), (56, 80));
+        assert!(find_slime_chunks(0, 0, i32::MAX).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! ワールドスポーン推定アルゴリズム
+//!
+//! Bedrock Editionは原点付近からスポーン可能なバイオームを探して
+//! 初期スポーン地点を決める。ここではその探索をバイオーム近似上で再現する
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::biome::{get_biome_at, BiomeType};
+use super::height::estimate_surface_y;
+use crate::coords::{to_world, BlockPos, Dimension};
+
+/// スポーン推定の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 1;
+
+/// スポーン探索の最大半径（ブロック）
+const SPAWN_SEARCH_RADIUS: i32 = 1024;
+
+/// スポーン探索のサンプリング間隔（ブロック）
+const SPAWN_SEARCH_STEP: i32 = 32;
+
+/// スポーン推定結果
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SpawnEstimate {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub biome: String,
+    /// 有効なバイオームが見つからず原点にフォールバックしたか
+    pub fallback: bool,
+}
+
+/// スポーン可能なバイオームか判定
+pub fn is_spawn_biome(biome: BiomeType) -> bool {
+    matches!(
+        biome,
+        BiomeType::Plains
+            | BiomeType::Forest
+            | BiomeType::Jungle
+            | BiomeType::Taiga
+            | BiomeType::SnowyTaiga
+            | BiomeType::Savanna
+    )
+}
+
+/// ワールドスポーン地点を推定
+///
+/// 原点（生成の座標の原点）から正方形のリング状に外側へ探索し、最初に見つかった
+/// スポーン可能なバイオームの地点を返す。
+pub fn estimate_spawn(seed: i64) -> SpawnEstimate {
+    let (origin_x, origin_z) = to_world(Dimension::Overworld, 0, 0);
+    for ring in 0..=(SPAWN_SEARCH_RADIUS / SPAWN_SEARCH_STEP) {
+        for i in -ring..=ring {
+            for j in -ring..=ring {
+                if i.abs() != ring && j.abs() != ring {
+                    continue;
+                }
+                let x = origin_x + i * SPAWN_SEARCH_STEP;
+                let z = origin_z + j * SPAWN_SEARCH_STEP;
+                let biome = get_biome_at(seed, BlockPos::new(x, z));
+                if is_spawn_biome(biome) {
+                    return SpawnEstimate {
+                        x,
+                        y: estimate_surface_y(seed, x, z),
+                        z,
+                        biome: format!("{:?}", biome),
+                        fallback: false,
+                    };
+                }
+            }
+        }
+    }
+
+    SpawnEstimate {
+        x: origin_x,
+        y: estimate_surface_y(seed, origin_x, origin_z),
+        z: origin_z,
+        biome: format!("{:?}", get_biome_at(seed, BlockPos::new(origin_x, origin_z))),
+        fallback: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_spawn() {
+        let spawn = estimate_spawn(12345);
+        assert!(spawn.x.abs() <= SPAWN_SEARCH_RADIUS);
+        assert!(spawn.z.abs() <= SPAWN_SEARCH_RADIUS);
+        if !spawn.fallback {
+            assert!(is_spawn_biome(get_biome_at(12345, BlockPos::new(spawn.x, spawn.z))));
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! 地下の地形（洞窟の入口・渓谷・帯水層・鍾乳石の群生など）と地表の目印の予測アルゴリズム
+//!
+//! チャンクごとの装飾の乱数と洞窟を掘る乱数（ワールドシードとチャンク座標、地形ごとのソルトから作る）で
+//! 地形の有無と位置を決め、地表の高さの推定と組み合わせて地表に出るものを選ぶ。
+//! 1.18 以降のノイズで作られる洞窟（スパゲッティ洞窟など）の入口は予測しない。
+//!
+//! 帯水層は64ブロック四方の区画ごとに、海面とは別の水位（局所的な水位）を持つかと、
+//! その水位を予測する。水位が低い帯水層は水ではなく溶岩で満たされる。
+//!
+//! ツツジの木は下に繁茂した洞窟がある地表にだけ生えるため、洞窟の深さで洞窟のバイオームを調べ、
+//! 地表が陸の場所を選んで繁茂した洞窟の目印として予測する。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::biome::{get_cave_biome_at, get_continentalness, CaveBiome};
+use super::height::{estimate_terrain_y, SEA_LEVEL};
+use crate::coords::{block_to_chunk, check_search_area, chunk_to_block, chunk_to_generation, BlockPos, Dimension};
+use crate::error::SearchError;
+use crate::i18n::is_english;
+use crate::rng::{get_structure_seed, next_int};
+
+/// 地下の地形の予測の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 1;
+
+/// 地表の溶岩湖の装飾の乱数のソルト
+const LAVA_LAKE_SALT: i64 = 10000;
+
+/// 地表の溶岩湖が試行されるチャンクの割合（1/n）
+const LAVA_LAKE_RARITY: i32 = 200;
+
+/// 洞窟を掘る乱数のソルト
+const CAVE_SALT: i64 = 20010;
+
+/// 洞窟が掘り始められるチャンクの割合（%）
+const CAVE_CHUNK_PERCENT: i32 = 15;
+
+/// 洞窟の掘り始めの高さの範囲
+const CAVE_MIN_Y: i32 = 8;
+const CAVE_MAX_Y: i32 = 180;
+
+/// 掘り始めが地表からこの深さまでなら地表に口を開ける
+const CAVE_ENTRANCE_DEPTH: i32 = 8;
+
+/// 渓谷を掘る乱数のソルト
+const RAVINE_SALT: i64 = 20020;
+
+/// 渓谷が掘られるチャンクの割合（1/n）
+const RAVINE_RARITY: i32 = 100;
+
+/// 渓谷の掘り始めの高さの範囲
+const RAVINE_MIN_Y: i32 = 10;
+const RAVINE_MAX_Y: i32 = 67;
+
+/// 渓谷の長さの範囲（ブロック）
+const RAVINE_MIN_LENGTH: i32 = 84;
+const RAVINE_MAX_LENGTH: i32 = 112;
+
+/// 掘り始めがこの高さより低い渓谷は底が深層岩に届く
+pub const RAVINE_DEEPSLATE_Y: i32 = 24;
+
+/// 帯水層の乱数のソルト
+const AQUIFER_SALT: i64 = 20030;
+
+/// 帯水層の区画の一辺（チャンク）
+const AQUIFER_CELL_CHUNKS: i32 = 4;
+
+/// 区画が局所的な水位を持つ確率（%）
+const AQUIFER_LOCAL_PERCENT: i32 = 30;
+
+/// 局所的な水位の範囲
+const AQUIFER_MIN_LEVEL: i32 = -60;
+const AQUIFER_MAX_LEVEL: i32 = 40;
+
+/// 水位がこれより低い帯水層は溶岩になる
+const AQUIFER_LAVA_LEVEL: i32 = -54;
+
+/// 鍾乳石の群生の乱数のソルト
+const DRIPSTONE_SALT: i64 = 20040;
+
+/// 鍾乳石の群生が試行されるチャンクの割合（1/n）
+const DRIPSTONE_RARITY: i32 = 32;
+
+/// 鍾乳石の群生の高さの範囲
+const DRIPSTONE_MIN_Y: i32 = -48;
+const DRIPSTONE_MAX_Y: i32 = 40;
+
+/// 鍾乳洞は内陸（大陸性ノイズがこれ以上）の地下にできる
+const DRIPSTONE_MIN_CONTINENTALNESS: f64 = 0.0;
+
+/// ツツジの木の装飾の乱数のソルト
+const AZALEA_SALT: i64 = 20050;
+
+/// 下に繁茂した洞窟があるチャンクでツツジの木が生える確率（%）
+const AZALEA_PERCENT: i32 = 40;
+
+/// 洞窟のバイオームを調べる間隔（ブロック）と、地表からの最小の深さ
+const CAVE_SAMPLE_STEP: i32 = 16;
+const CAVE_SAMPLE_MIN_DEPTH: i32 = 16;
+
+/// 洞窟のバイオームを調べる最も低い高さ
+const CAVE_SAMPLE_MIN_Y: i32 = -48;
+
+/// 帯水層を満たす液体
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Fluid {
+    Water,
+    Lava,
+}
+
+impl Fluid {
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                Fluid::Water => "water",
+                Fluid::Lava => "lava",
+            };
+        }
+        match self {
+            Fluid::Water => "水",
+            Fluid::Lava => "溶岩",
+        }
+    }
+}
+
+/// 予測できる地形
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UndergroundFeature {
+    LavaLake,
+    CaveEntrance,
+    Ravine,
+    Aquifer,
+    DripstoneCluster,
+    AzaleaTree,
+}
+
+impl UndergroundFeature {
+    pub const ALL: [UndergroundFeature; 6] = [
+        UndergroundFeature::LavaLake,
+        UndergroundFeature::CaveEntrance,
+        UndergroundFeature::Ravine,
+        UndergroundFeature::Aquifer,
+        UndergroundFeature::DripstoneCluster,
+        UndergroundFeature::AzaleaTree,
+    ];
+
+    /// 識別子（--feature オプションで使う名前）
+    pub fn id(&self) -> &'static str {
+        match self {
+            UndergroundFeature::LavaLake => "lava_lake",
+            UndergroundFeature::CaveEntrance => "cave_entrance",
+            UndergroundFeature::Ravine => "ravine",
+            UndergroundFeature::Aquifer => "aquifer",
+            UndergroundFeature::DripstoneCluster => "dripstone_cluster",
+            UndergroundFeature::AzaleaTree => "azalea_tree",
+        }
+    }
+
+    /// 識別子から地形を取得
+    pub fn from_id(id: &str) -> Option<UndergroundFeature> {
+        UndergroundFeature::ALL.into_iter().find(|feature| feature.id() == id)
+    }
+
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                UndergroundFeature::LavaLake => "🌋 Surface lava lake",
+                UndergroundFeature::CaveEntrance => "🕳️ Cave entrance",
+                UndergroundFeature::Ravine => "🏞️ Ravine",
+                UndergroundFeature::Aquifer => "💧 Aquifer",
+                UndergroundFeature::DripstoneCluster => "🦴 Large dripstone cluster",
+                UndergroundFeature::AzaleaTree => "🌸 Azalea tree (lush cave below)",
+            };
+        }
+        match self {
+            UndergroundFeature::LavaLake => "🌋 地表の溶岩湖",
+            UndergroundFeature::CaveEntrance => "🕳️ 洞窟の入口",
+            UndergroundFeature::Ravine => "🏞️ 渓谷",
+            UndergroundFeature::Aquifer => "💧 帯水層",
+            UndergroundFeature::DripstoneCluster => "🦴 大きな鍾乳石の群生",
+            UndergroundFeature::AzaleaTree => "🌸 ツツジの木（下に繁茂した洞窟）",
+        }
+    }
+}
+
+/// 予測した地形
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct UndergroundSite {
+    pub feature: UndergroundFeature,
+    pub x: i32,
+    /// 推定の高さ（地表の高さの推定を使うため誤差が大きい。渓谷は掘り始めの高さ、帯水層は水位）
+    pub y: i32,
+    pub z: i32,
+    /// 伸びる向き（度。渓谷のみ）。Minecraftの表記どおり 0 = 南(+Z)、90 = 西(-X)、180 = 北、-90 = 東(+X)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yaw: Option<f64>,
+    /// 長さ（ブロック。渓谷のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<i32>,
+    /// 満たす液体（帯水層のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fluid: Option<Fluid>,
+    /// 下にある繁茂した洞窟の高さ（ツツジの木のみ。見つかった最も高い位置）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cave_y: Option<i32>,
+    pub distance: f64,
+}
+
+impl UndergroundSite {
+    fn at(feature: UndergroundFeature, x: i32, y: i32, z: i32) -> UndergroundSite {
+        UndergroundSite {
+            feature,
+            x,
+            y,
+            z,
+            yaw: None,
+            length: None,
+            fluid: None,
+            cave_y: None,
+            distance: 0.0,
+        }
+    }
+}
+
+/// 向き（度）の8方位
+pub fn compass_direction(yaw: f64) -> &'static str {
+    const DIRECTIONS: [&str; 8] = ["南", "南西", "西", "北西", "北", "北東", "東", "南東"];
+    DIRECTIONS[((yaw.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+/// チャンク（ワールドのチャンク座標）の乱数の初期値
+fn chunk_seed(seed: i64, chunk_x: i32, chunk_z: i32, salt: i64) -> i64 {
+    let (chunk_x, chunk_z) = chunk_to_generation(Dimension::Overworld, chunk_x, chunk_z);
+    get_structure_seed(seed, chunk_x, chunk_z, salt)
+}
+
+/// チャンクの地表の溶岩湖を予測
+///
+/// 水面より下の地表（海や川の底）には置かれない。
+fn chunk_lava_lake(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    let mut rng = chunk_seed(seed, chunk_x, chunk_z, LAVA_LAKE_SALT);
+    if next_int(&mut rng, LAVA_LAKE_RARITY) != 0 {
+        return None;
+    }
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+    let y = estimate_terrain_y(seed, x, z);
+    (y >= SEA_LEVEL).then(|| UndergroundSite::at(UndergroundFeature::LavaLake, x, y, z))
+}
+
+/// チャンクの洞窟の入口を予測
+///
+/// 洞窟の掘り始めが地表のすぐ下にある場合に、地表に口を開けるとみなす。
+fn chunk_cave_entrance(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    let mut rng = chunk_seed(seed, chunk_x, chunk_z, CAVE_SALT);
+    if next_int(&mut rng, 100) >= CAVE_CHUNK_PERCENT {
+        return None;
+    }
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+    let start_y = CAVE_MIN_Y + next_int(&mut rng, CAVE_MAX_Y - CAVE_MIN_Y + 1);
+    let surface = estimate_terrain_y(seed, x, z);
+    (surface >= SEA_LEVEL && (surface - CAVE_ENTRANCE_DEPTH..=surface).contains(&start_y))
+        .then(|| UndergroundSite::at(UndergroundFeature::CaveEntrance, x, surface, z))
+}
+
+/// チャンクの渓谷を予測（掘り始めの位置、向き、長さ）
+///
+/// 渓谷は掘り始めから両方向に伸びるため、向きは片側の向き。
+fn chunk_ravine(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    let mut rng = chunk_seed(seed, chunk_x, chunk_z, RAVINE_SALT);
+    if next_int(&mut rng, RAVINE_RARITY) != 0 {
+        return None;
+    }
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+    let y = RAVINE_MIN_Y + next_int(&mut rng, RAVINE_MAX_Y - RAVINE_MIN_Y + 1);
+    let yaw = (next_int(&mut rng, 360) - 180) as f64;
+    let length = RAVINE_MIN_LENGTH + next_int(&mut rng, RAVINE_MAX_LENGTH - RAVINE_MIN_LENGTH + 1);
+    Some(UndergroundSite {
+        yaw: Some(yaw),
+        length: Some(length),
+        ..UndergroundSite::at(UndergroundFeature::Ravine, x, y, z)
+    })
+}
+
+/// 帯水層の区画の局所的な水位を予測（区画の北西端のチャンクでだけ判定する）
+fn chunk_aquifer(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    let (gen_x, gen_z) = chunk_to_generation(Dimension::Overworld, chunk_x, chunk_z);
+    if gen_x.rem_euclid(AQUIFER_CELL_CHUNKS) != 0 || gen_z.rem_euclid(AQUIFER_CELL_CHUNKS) != 0 {
+        return None;
+    }
+    let cell_x = gen_x.div_euclid(AQUIFER_CELL_CHUNKS);
+    let cell_z = gen_z.div_euclid(AQUIFER_CELL_CHUNKS);
+    let mut rng = get_structure_seed(seed, cell_x, cell_z, AQUIFER_SALT);
+    if next_int(&mut rng, 100) >= AQUIFER_LOCAL_PERCENT {
+        return None;
+    }
+    let cell_size = chunk_to_block(AQUIFER_CELL_CHUNKS);
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, cell_size);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, cell_size);
+    let level = AQUIFER_MIN_LEVEL + next_int(&mut rng, AQUIFER_MAX_LEVEL - AQUIFER_MIN_LEVEL + 1);
+    let fluid = if level < AQUIFER_LAVA_LEVEL { Fluid::Lava } else { Fluid::Water };
+    Some(UndergroundSite {
+        fluid: Some(fluid),
+        ..UndergroundSite::at(UndergroundFeature::Aquifer, x, level, z)
+    })
+}
+
+/// チャンクの大きな鍾乳石の群生を予測
+///
+/// 鍾乳洞のバイオームは内陸の地下にできるため、大陸性ノイズが低い（海に近い）場所は除く。
+fn chunk_dripstone_cluster(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    let mut rng = chunk_seed(seed, chunk_x, chunk_z, DRIPSTONE_SALT);
+    if next_int(&mut rng, DRIPSTONE_RARITY) != 0 {
+        return None;
+    }
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+    let y = DRIPSTONE_MIN_Y + next_int(&mut rng, DRIPSTONE_MAX_Y - DRIPSTONE_MIN_Y + 1);
+    (get_continentalness(seed, BlockPos::new(x, z)) >= DRIPSTONE_MIN_CONTINENTALNESS)
+        .then(|| UndergroundSite::at(UndergroundFeature::DripstoneCluster, x, y, z))
+}
+
+/// 地表の下の繁茂した洞窟の最も高い位置（見つからない場合は None）
+fn lush_cave_below(seed: i64, x: i32, surface: i32, z: i32) -> Option<i32> {
+    let top = surface - CAVE_SAMPLE_MIN_DEPTH;
+    (CAVE_SAMPLE_MIN_Y..=top)
+        .rev()
+        .step_by(CAVE_SAMPLE_STEP as usize)
+        .find(|y| get_cave_biome_at(seed, BlockPos::new(x, z), *y) == Some(CaveBiome::LushCaves))
+}
+
+/// チャンクのツツジの木を予測
+///
+/// 地表の乱数で選んだ位置の下を洞窟の深さまで調べ、繁茂した洞窟がある陸地だけを選ぶ。
+fn chunk_azalea_tree(seed: i64, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    let mut rng = chunk_seed(seed, chunk_x, chunk_z, AZALEA_SALT);
+    if next_int(&mut rng, 100) >= AZALEA_PERCENT {
+        return None;
+    }
+    let x = chunk_to_block(chunk_x) + next_int(&mut rng, 16);
+    let z = chunk_to_block(chunk_z) + next_int(&mut rng, 16);
+    let surface = estimate_terrain_y(seed, x, z);
+    if surface < SEA_LEVEL {
+        return None;
+    }
+    let cave_y = lush_cave_below(seed, x, surface, z)?;
+    Some(UndergroundSite {
+        cave_y: Some(cave_y),
+        ..UndergroundSite::at(UndergroundFeature::AzaleaTree, x, surface + 1, z)
+    })
+}
+
+/// チャンクの地形を予測
+fn chunk_feature(seed: i64, feature: UndergroundFeature, chunk_x: i32, chunk_z: i32) -> Option<UndergroundSite> {
+    match feature {
+        UndergroundFeature::LavaLake => chunk_lava_lake(seed, chunk_x, chunk_z),
+        UndergroundFeature::CaveEntrance => chunk_cave_entrance(seed, chunk_x, chunk_z),
+        UndergroundFeature::Ravine => chunk_ravine(seed, chunk_x, chunk_z),
+        UndergroundFeature::Aquifer => chunk_aquifer(seed, chunk_x, chunk_z),
+        UndergroundFeature::DripstoneCluster => chunk_dripstone_cluster(seed, chunk_x, chunk_z),
+        UndergroundFeature::AzaleaTree => chunk_azalea_tree(seed, chunk_x, chunk_z),
+    }
+}
+
+/// 範囲内の地形を検索（距離順）
+pub fn find_underground_features(
+    seed: i64,
+    features: &[UndergroundFeature],
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+) -> Result<Vec<UndergroundSite>, SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let min_cx = block_to_chunk(center_x - radius);
+    let max_cx = block_to_chunk(center_x + radius);
+    let min_cz = block_to_chunk(center_z - radius);
+    let max_cz = block_to_chunk(center_z + radius);
+
+    let mut results = Vec::new();
+    for chunk_x in min_cx..=max_cx {
+        for chunk_z in min_cz..=max_cz {
+            for &feature in features {
+                let Some(mut site) = chunk_feature(seed, feature, chunk_x, chunk_z) else {
+                    continue;
+                };
+                let dist_sq = (site.x as i64 - center_x as i64).pow(2) + (site.z as i64 - center_z as i64).pow(2);
+                if dist_sq > (radius as i64).pow(2) {
+                    continue;
+                }
+                site.distance = (dist_sq as f64).sqrt();
+                results.push(site);
+            }
+        }
+    }
+
+    tracing::info!(chunks = (max_cx - min_cx + 1) as u64 * (max_cz - min_cz + 1) as u64, hits = results.len(), "チャンクを走査");
+    results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_underground_features() {
+        let sites = find_underground_features(12345, &UndergroundFeature::ALL, 0, 0, 2000).unwrap();
+        assert!(find_underground_features(12345, &UndergroundFeature::ALL, 0, 0, -1).is_err());
+        assert!(sites.windows(2).all(|w| w[0].distance <= w[1].distance));
+        let lakes = sites.iter().filter(|s| s.feature == UndergroundFeature::LavaLake).count();
+        let entrances = sites.iter().filter(|s| s.feature == UndergroundFeature::CaveEntrance).count();
+        assert!(lakes > 0 && entrances > 0);
+        // 地表の溶岩湖は 1/200 チャンク
+        assert!(lakes < (2 * 2000 / 16 + 1) * (2 * 2000 / 16 + 1) / 100, "lakes = {}", lakes);
+        let surface = [UndergroundFeature::LavaLake, UndergroundFeature::CaveEntrance, UndergroundFeature::AzaleaTree];
+        assert!(sites.iter().filter(|s| surface.contains(&s.feature)).all(|s| s.y >= SEA_LEVEL));
+
+        let lakes_only = find_underground_features(12345, &[UndergroundFeature::LavaLake], 0, 0, 2000).unwrap();
+        assert_eq!(lakes_only.len(), lakes);
+    }
+
+    #[test]
+    fn test_find_ravines() {
+        let ravines = find_underground_features(12345, &[UndergroundFeature::Ravine], 0, 0, 1000).unwrap();
+        assert!(!ravines.is_empty());
+        for ravine in &ravines {
+            assert!((RAVINE_MIN_Y..=RAVINE_MAX_Y).contains(&ravine.y));
+            assert!((-180.0..180.0).contains(&ravine.yaw.unwrap()));
+            assert!((RAVINE_MIN_LENGTH..=RAVINE_MAX_LENGTH).contains(&ravine.length.unwrap()));
+        }
+        assert!(find_underground_features(12345, &[UndergroundFeature::LavaLake], 0, 0, 2000).unwrap().iter().all(|s| s.yaw.is_none()));
+
+        assert_eq!(compass_direction(0.0), "南");
+        assert_eq!(compass_direction(-90.0), "東");
+        assert_eq!(compass_direction(180.0), "北");
+        assert_eq!(compass_direction(135.0), "北西");
+    }
+
+    #[test]
+    fn test_find_aquifers_and_dripstone() {
+        let sites = find_underground_features(
+            12345,
+            &[UndergroundFeature::Aquifer, UndergroundFeature::DripstoneCluster],
+            0,
+            0,
+            1500,
+        ).unwrap();
+        let aquifers: Vec<&UndergroundSite> = sites.iter().filter(|s| s.feature == UndergroundFeature::Aquifer).collect();
+        assert!(!aquifers.is_empty());
+        for aquifer in &aquifers {
+            assert!((AQUIFER_MIN_LEVEL..=AQUIFER_MAX_LEVEL).contains(&aquifer.y));
+            let expected = if aquifer.y < AQUIFER_LAVA_LEVEL { Fluid::Lava } else { Fluid::Water };
+            assert_eq!(aquifer.fluid, Some(expected));
+        }
+        // 区画ごとに1つまで
+        let cell = |s: &&UndergroundSite| (s.x.div_euclid(64), s.z.div_euclid(64));
+        let mut cells: Vec<(i32, i32)> = aquifers.iter().map(cell).collect();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), aquifers.len());
+
+        let dripstone: Vec<&UndergroundSite> =
+            sites.iter().filter(|s| s.feature == UndergroundFeature::DripstoneCluster).collect();
+        assert!(!dripstone.is_empty());
+        assert!(dripstone.iter().all(|s| (DRIPSTONE_MIN_Y..=DRIPSTONE_MAX_Y).contains(&s.y) && s.fluid.is_none()));
+    }
+
+    #[test]
+    fn test_azalea_trees_mark_lush_caves() {
+        let trees = find_underground_features(12345, &[UndergroundFeature::AzaleaTree], 0, 0, 1500).unwrap();
+        assert!(!trees.is_empty());
+        for tree in &trees {
+            let cave_y = tree.cave_y.unwrap();
+            assert!(cave_y < tree.y - CAVE_SAMPLE_MIN_DEPTH);
+            assert_eq!(get_cave_biome_at(12345, BlockPos::new(tree.x, tree.z), cave_y), Some(CaveBiome::LushCaves));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! 検索範囲の絞り込みモジュール
+//!
+//! 検索結果を、中心から見た方角の範囲（`--direction ne`、`--quadrant +x+z`）に入り、
+//! `--exclude x,z,r` で指定した円（探索済みで候補から外した場所等）のどれにも入らないものに絞り込む。
+
+use crate::structures::StructureHit;
+
+/// 結果から除く円の範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExcludeArea {
+    pub x: i32,
+    pub z: i32,
+    pub radius: i32,
+}
+
+impl ExcludeArea {
+    /// "x,z,r" の形式を解釈
+    pub fn parse(spec: &str) -> Result<ExcludeArea, String> {
+        let parts: Vec<Option<i32>> = spec.split(',').map(|p| p.trim().parse().ok()).collect();
+        match parts[..] {
+            [Some(x), Some(z), Some(radius)] if radius >= 0 => Ok(ExcludeArea { x, z, radius }),
+            _ => Err(format!("除外する範囲の形式が不正です: {}（例: 100,-200,500）", spec)),
+        }
+    }
+
+    pub fn contains(&self, x: i32, z: i32) -> bool {
+        ((x - self.x) as i64).pow(2) + ((z - self.z) as i64).pow(2) <= (self.radius as i64).pow(2)
+    }
+}
+
+/// 方角の名前と向き（Minecraftの yaw。南が0°、西が90°、北が180°、東が270°）
+pub const DIRECTIONS: [(&str, f64); 8] = [
+    ("s", 0.0),
+    ("sw", 45.0),
+    ("w", 90.0),
+    ("nw", 135.0),
+    ("n", 180.0),
+    ("ne", 225.0),
+    ("e", 270.0),
+    ("se", 315.0),
+];
+
+/// 中心から見た方角の範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sector {
+    /// 方角（yaw）を中心とした90°の扇形
+    Direction(f64),
+    /// X・Z軸で区切った象限（各軸の向き。1 は中心より大きい側、-1 は小さい側）
+    Quadrant(i32, i32),
+}
+
+impl Sector {
+    /// 8方位の名前（n, ne, e, se, s, sw, w, nw）から扇形を得る
+    pub fn direction(name: &str) -> Result<Sector, String> {
+        let name = name.to_ascii_lowercase();
+        DIRECTIONS.iter().find(|(n, _)| *n == name).map(|(_, yaw)| Sector::Direction(*yaw)).ok_or_else(|| {
+            let names: Vec<&str> = DIRECTIONS.iter().map(|(n, _)| *n).collect();
+            format!("不明な方角: {}（{}）", name, names.join(", "))
+        })
+    }
+
+    /// "+x+z" の形式（軸の順は問わない）から象限を得る
+    pub fn quadrant(spec: &str) -> Result<Sector, String> {
+        let invalid = || format!("象限の形式が不正です: {}（例: +x+z, -x+z）", spec);
+        let spec = spec.to_ascii_lowercase();
+        let (mut sign_x, mut sign_z) = (None, None);
+        let mut chars = spec.chars();
+        while let Some(sign) = chars.next() {
+            let sign = match sign {
+                '+' => 1,
+                '-' => -1,
+                _ => return Err(invalid()),
+            };
+            match chars.next() {
+                Some('x') if sign_x.is_none() => sign_x = Some(sign),
+                Some('z') if sign_z.is_none() => sign_z = Some(sign),
+                _ => return Err(invalid()),
+            }
+        }
+        match (sign_x, sign_z) {
+            (Some(x), Some(z)) => Ok(Sector::Quadrant(x, z)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// 座標が中心から見て範囲に入るか（中心と境界の上は含む）
+    pub fn contains(&self, center_x: i32, center_z: i32, x: i32, z: i32) -> bool {
+        let (dx, dz) = ((x - center_x) as f64, (z - center_z) as f64);
+        match *self {
+            Sector::Direction(_) if dx == 0.0 && dz == 0.0 => true,
+            Sector::Direction(yaw) => {
+                let angle = (-dx).atan2(dz).to_degrees();
+                let diff = (angle - yaw).rem_euclid(360.0);
+                diff.min(360.0 - diff) <= 45.0
+            }
+            Sector::Quadrant(sign_x, sign_z) => dx * sign_x as f64 >= 0.0 && dz * sign_z as f64 >= 0.0,
+        }
+    }
+}
+
+/// 検索結果を残す範囲
+#[derive(Debug, Clone, Default)]
+pub struct SearchArea {
+    pub sector: Option<Sector>,
+    pub exclude: Vec<ExcludeArea>,
+}
+
+impl SearchArea {
+    /// 絞り込みの指定があるか
+    pub fn is_restricted(&self) -> bool {
+        self.sector.is_some() || !self.exclude.is_empty()
+    }
+
+    /// 座標の結果を残すか
+    pub fn accepts(&self, center_x: i32, center_z: i32, x: i32, z: i32) -> bool {
+        self.sector.is_none_or(|s| s.contains(center_x, center_z, x, z)) && !self.exclude.iter().any(|area| area.contains(x, z))
+    }
+
+    /// 範囲に入る結果だけを残す
+    pub fn apply(&self, center_x: i32, center_z: i32, structures: Vec<StructureHit>) -> Vec<StructureHit> {
+        structures.into_iter().filter(|hit| self.accepts(center_x, center_z, hit.pos.x, hit.pos.z)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::structures::{StructureKind, StructureType};
+
+    #[test]
+    fn test_exclude_areas() {
+        let area = ExcludeArea::parse("100, -200, 50").unwrap();
+        assert_eq!(area, ExcludeArea { x: 100, z: -200, radius: 50 });
+        assert!(ExcludeArea::parse("100,-200").is_err());
+        assert!(ExcludeArea::parse("100,-200,-5").is_err());
+
+        let village = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::Village), BlockPos::new(x, z));
+        let structures = vec![village(130, -160), village(140, -160), village(-900, 0)];
+        let area = SearchArea {
+            sector: None,
+            exclude: vec![area, ExcludeArea { x: -1000, z: 0, radius: 100 }],
+        };
+        assert_eq!(area.apply(0, 0, structures), vec![village(140, -160)]);
+    }
+
+    #[test]
+    fn test_sector() {
+        // 北は -Z、東は +X
+        let ne = Sector::direction("NE").unwrap();
+        assert!(ne.contains(0, 0, 100, -100));
+        assert!(ne.contains(0, 0, 0, -100));
+        assert!(!ne.contains(0, 0, -10, -100));
+        assert!(!ne.contains(0, 0, 100, 100));
+        assert!(Sector::direction("s").unwrap().contains(50, 50, 50, 500));
+        assert!(Sector::direction("up").is_err());
+
+        assert_eq!(Sector::quadrant("+x+z").unwrap(), Sector::Quadrant(1, 1));
+        assert_eq!(Sector::quadrant("+z-x").unwrap(), Sector::Quadrant(-1, 1));
+        assert!(Sector::quadrant("+x").is_err());
+        assert!(Sector::quadrant("+x+x").is_err());
+        let quadrant = Sector::quadrant("-x+z").unwrap();
+        assert!(quadrant.contains(100, 100, 0, 200));
+        assert!(!quadrant.contains(100, 100, 200, 200));
+    }
+}
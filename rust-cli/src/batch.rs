@@ -0,0 +1,137 @@
+//! バッチ実行モジュール
+//!
+//! 1行に1件の検索条件を書いたJSONL（JSON Lines）を読み込み、結果を1行ずつJSONLで出力する。
+//!
+//! ```text
+//! {"id": 1, "command": "structures", "seed": 12345, "structure_type": "village", "radius": 3000}
+//! {"id": 2, "command": "biome", "seed": 12345, "target": "jungle"}
+//! {"command": "info", "args": ["stronghold"]}
+//! ```
+//!
+//! `command` 以外のキーは各サブコマンドの長いオプション名（`_` は `-` と同じ）、
+//! `args` は位置引数。`id` は結果にそのまま付けて返す。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+/// 1件分の実行結果
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BatchResult {
+    /// 入力に付けられていた識別子
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    /// 入力の行番号（1始まり）
+    pub line: usize,
+    /// 単独で実行した場合の終了コード
+    pub exit_code: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 実行できないコマンド（全画面・対話・入れ子のバッチ）
+const UNSUPPORTED_COMMANDS: [&str; 3] = ["batch", "repl", "explore"];
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// JSONの検索条件をコマンドライン引数（プログラム名を除く）に変換
+///
+/// 出力形式はJSONに固定するため `output` は無視し、テキストを出力する `export` は受け付けない。
+pub fn record_to_args(record: &Value) -> Result<Vec<String>, String> {
+    let object = record.as_object().ok_or("検索条件はJSONオブジェクトで指定してください")?;
+    let command = object
+        .get("command")
+        .and_then(Value::as_str)
+        .ok_or("command（サブコマンド名）を指定してください")?;
+
+    let mut args: Vec<String> = command.split_whitespace().map(|c| c.to_string()).collect();
+    match args.first() {
+        None => return Err("command が空です".to_string()),
+        Some(c) if UNSUPPORTED_COMMANDS.contains(&c.as_str()) => {
+            return Err(format!("{} はバッチでは実行できません", c));
+        }
+        _ => {}
+    }
+
+    for (key, value) in object {
+        match key.as_str() {
+            "command" | "id" | "args" | "output" => continue,
+            "export" => return Err("バッチでは export を指定できません（結果はJSONで出力されます）".to_string()),
+            _ => {}
+        }
+        let flag = format!("--{}", key.replace('_', "-"));
+        let values = match value {
+            Value::Array(items) => items.iter().collect(),
+            _ => vec![value],
+        };
+        for v in values {
+            match v {
+                Value::Bool(true) => args.push(flag.clone()),
+                Value::Bool(false) | Value::Null => {}
+                _ => {
+                    let v = scalar_to_string(v).ok_or_else(|| format!("{} の値が不正です", key))?;
+                    // 負の数も値として渡せるように "=" で連結する
+                    args.push(format!("{}={}", flag, v));
+                }
+            }
+        }
+    }
+
+    if let Some(positional) = object.get("args") {
+        let positional = positional.as_array().ok_or("args は配列で指定してください")?;
+        for v in positional {
+            args.push(scalar_to_string(v).ok_or("args の値が不正です")?);
+        }
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_to_args() {
+        let record = json!({
+            "id": "a",
+            "command": "structures",
+            "seed": 12345,
+            "structure_type": "village",
+            "center_x": -500,
+            "near_spawn": true,
+            "output": "text"
+        });
+        let args = record_to_args(&record).unwrap();
+        assert_eq!(args[0], "structures");
+        assert!(args.contains(&"--seed=12345".to_string()));
+        assert!(args.contains(&"--structure-type=village".to_string()));
+        assert!(args.contains(&"--center-x=-500".to_string()));
+        assert!(args.contains(&"--near-spawn".to_string()));
+        assert!(!args.iter().any(|a| a.starts_with("--output")));
+
+        let args = record_to_args(&json!({"command": "route", "point": ["1,2", "-3,4,A"]})).unwrap();
+        assert_eq!(args, ["route", "--point=1,2", "--point=-3,4,A"]);
+
+        let args = record_to_args(&json!({"command": "info", "args": ["stronghold"]})).unwrap();
+        assert_eq!(args, ["info", "stronghold"]);
+    }
+
+    #[test]
+    fn test_invalid_records() {
+        assert!(record_to_args(&json!([1, 2])).is_err());
+        assert!(record_to_args(&json!({"seed": 1})).is_err());
+        assert!(record_to_args(&json!({"command": "repl"})).is_err());
+        assert!(record_to_args(&json!({"command": "nether", "export": "commands"})).is_err());
+        assert!(record_to_args(&json!({"command": "nether", "seed": {"a": 1}})).is_err());
+    }
+}
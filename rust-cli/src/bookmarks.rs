@@ -0,0 +1,224 @@
+//! ブックマークモジュール
+//!
+//! 気になった検索結果をメモ付きでSQLiteに保存し、経由地点やコマンドとして書き出す。
+//! 保存先は `~/.config/bedrockmate/bookmarks.db`（または `--db` で指定したファイル）。
+//! テーブルの列はサーバー側（server/database.py）のブックマークに合わせている。
+
+#[cfg(feature = "db")]
+use rusqlite::{params, Connection, Row};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[cfg(feature = "db")]
+use crate::coords::Dimension;
+#[cfg(feature = "db")]
+use crate::db::open_db;
+#[cfg(feature = "db")]
+use crate::export::teleport_lines;
+#[cfg(feature = "db")]
+use crate::route::Waypoint;
+
+#[cfg(feature = "db")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS bookmarks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    seed INTEGER,
+    name TEXT NOT NULL,
+    x INTEGER NOT NULL,
+    y INTEGER DEFAULT 64,
+    z INTEGER NOT NULL,
+    dimension TEXT DEFAULT 'overworld',
+    category TEXT,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+)";
+
+/// 保存したブックマーク
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Bookmark {
+    pub id: i64,
+    /// 保存時のワールドシード値（シード不要な地点は None）
+    pub seed: Option<i64>,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    /// overworld または nether
+    pub dimension: String,
+    pub category: Option<String>,
+    /// メモ（「拠点予定地」「襲撃トラップ」等）
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+/// 追加するブックマークの内容
+#[cfg(feature = "db")]
+#[derive(Debug)]
+pub struct NewBookmark {
+    pub seed: Option<i64>,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub dimension: String,
+    pub category: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// SQLiteのブックマーク保存先
+#[cfg(feature = "db")]
+pub struct BookmarkStore {
+    conn: Connection,
+}
+
+#[cfg(feature = "db")]
+impl BookmarkStore {
+    /// ブックマークファイルを開く（省略時は ~/.config/bedrockmate/bookmarks.db。存在しない場合は作成）
+    pub fn open(path: Option<&str>) -> Result<BookmarkStore, String> {
+        BookmarkStore::init(open_db(path, "bookmarks.db")?)
+    }
+
+    fn init(conn: Connection) -> Result<BookmarkStore, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("ブックマークファイルを初期化できません: {}", e))?;
+        Ok(BookmarkStore { conn })
+    }
+
+    /// ブックマークを追加して採番したIDを返す
+    pub fn add(&self, bookmark: &NewBookmark) -> Result<i64, String> {
+        self.conn
+            .execute(
+                "INSERT INTO bookmarks (seed, name, x, y, z, dimension, category, notes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    bookmark.seed,
+                    bookmark.name,
+                    bookmark.x,
+                    bookmark.y,
+                    bookmark.z,
+                    bookmark.dimension,
+                    bookmark.category,
+                    bookmark.notes
+                ],
+            )
+            .map_err(|e| format!("ブックマークを保存できません: {}", e))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// ブックマークを追加順に取得（シード指定時はそのシードのものだけ）
+    pub fn list(&self, seed: Option<i64>) -> Result<Vec<Bookmark>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, seed, name, x, y, z, dimension, category, notes, created_at
+                 FROM bookmarks WHERE ?1 IS NULL OR seed = ?1 ORDER BY id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![seed], bookmark_from_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("ブックマークを読み込めません: {}", e))
+    }
+
+    /// ブックマークを削除（該当するIDがない場合は偽）
+    pub fn remove(&self, id: i64) -> Result<bool, String> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM bookmarks WHERE id = ?1", params![id])
+            .map_err(|e| format!("ブックマークを削除できません: {}", e))?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(feature = "db")]
+fn bookmark_from_row(row: &Row) -> rusqlite::Result<Bookmark> {
+    Ok(Bookmark {
+        id: row.get(0)?,
+        seed: row.get(1)?,
+        name: row.get(2)?,
+        x: row.get(3)?,
+        y: row.get(4)?,
+        z: row.get(5)?,
+        dimension: row.get(6)?,
+        category: row.get(7)?,
+        notes: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// ブックマークを経由地点のJSONに変換（route --input でそのまま読み込める）
+#[cfg(feature = "db")]
+pub fn export_waypoints(bookmarks: &[Bookmark]) -> String {
+    let waypoints: Vec<Waypoint> = bookmarks
+        .iter()
+        .map(|b| Waypoint {
+            name: b.name.clone(),
+            x: b.x,
+            z: b.z,
+        })
+        .collect();
+    serde_json::to_string_pretty(&waypoints).unwrap()
+}
+
+/// ブックマークを `/tp` と `/execute` コマンドに変換（メモはコメント行として出力）
+#[cfg(feature = "db")]
+pub fn export_bookmark_commands(bookmarks: &[Bookmark]) -> String {
+    let mut lines = Vec::new();
+    for b in bookmarks {
+        // 保存できるのはオーバーワールドとネザーのみ
+        let dimension = Dimension::from_id(&b.dimension).unwrap_or(Dimension::Overworld);
+        lines.extend(teleport_lines(&b.name, dimension, b.x, b.y, b.z));
+        if let Some(notes) = &b.notes {
+            lines.insert(lines.len() - 2, format!("# {}", notes));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use super::*;
+
+    fn new_bookmark(seed: Option<i64>, name: &str, notes: Option<&str>) -> NewBookmark {
+        NewBookmark {
+            seed,
+            name: name.to_string(),
+            x: 100,
+            y: 70,
+            z: -200,
+            dimension: "overworld".to_string(),
+            category: None,
+            notes: notes.map(|n| n.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_add_list_remove() {
+        let store = BookmarkStore::init(Connection::open_in_memory().unwrap()).unwrap();
+        let id = store.add(&new_bookmark(Some(12345), "🏘️ 村", Some("拠点予定地"))).unwrap();
+        store.add(&new_bookmark(None, "スライムチャンク", None)).unwrap();
+
+        assert_eq!(store.list(None).unwrap().len(), 2);
+        let filtered = store.list(Some(12345)).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].notes.as_deref(), Some("拠点予定地"));
+
+        assert!(store.remove(id).unwrap());
+        assert!(!store.remove(id).unwrap());
+        assert_eq!(store.list(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_export_bookmarks() {
+        let store = BookmarkStore::init(Connection::open_in_memory().unwrap()).unwrap();
+        store.add(&new_bookmark(Some(1), "襲撃トラップ", Some("前哨基地の横"))).unwrap();
+        let bookmarks = store.list(None).unwrap();
+
+        let commands = export_bookmark_commands(&bookmarks);
+        assert!(commands.contains("# 前哨基地の横\n/tp @s 100 70 -200"));
+
+        let waypoints = crate::route::waypoints_from_json(&export_waypoints(&bookmarks)).unwrap();
+        assert_eq!((waypoints[0].x, waypoints[0].z), (100, -200));
+    }
+}
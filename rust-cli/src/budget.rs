@@ -0,0 +1,90 @@
+//! 検索の制限時間モジュール
+//!
+//! `--timeout` で指定した時間を過ぎた検索は、それまでに見つかった結果を返して打ち切り、
+//! JSON出力に `truncated: true` を付ける。打ち切っても中心に近い結果が残るよう、
+//! 各検索は中心に近い列から順に走査する。バックグラウンドの検索（`task`）を取り消した場合も同じく打ち切る。
+
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static TRUNCATED: Cell<bool> = const { Cell::new(false) };
+    static CANCEL: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// 実行するコマンドの制限時間を設定（None は無制限）
+pub fn set_timeout(timeout: Option<Duration>) {
+    DEADLINE.with(|d| d.set(timeout.map(|t| Instant::now() + t)));
+    TRUNCATED.with(|t| t.set(false));
+}
+
+/// 制限時間の期限（別のスレッドで実行する検索に引き継ぐ）
+#[cfg_attr(not(feature = "tui"), allow(dead_code))] // バックグラウンド検索（task）で使う
+pub fn deadline() -> Option<Instant> {
+    DEADLINE.with(Cell::get)
+}
+
+/// 期限を設定（別のスレッドから引き継いだ期限。打ち切りの記録は消す）
+#[cfg_attr(not(feature = "tui"), allow(dead_code))] // バックグラウンド検索（task）で使う
+pub fn set_deadline(deadline: Option<Instant>) {
+    DEADLINE.with(|d| d.set(deadline));
+    TRUNCATED.with(|t| t.set(false));
+}
+
+/// 取り消しの合図を設定（合図が真になると制限時間を過ぎたときと同じく打ち切る）
+#[cfg_attr(not(feature = "tui"), allow(dead_code))] // バックグラウンド検索（task）で使う
+pub fn set_cancel(flag: Option<Arc<AtomicBool>>) {
+    CANCEL.with(|c| *c.borrow_mut() = flag);
+}
+
+/// 制限時間を過ぎたか、取り消されたか（どちらの場合も検索を打ち切ったことを記録する）
+pub fn expired() -> bool {
+    let expired = DEADLINE.with(Cell::get).is_some_and(|deadline| Instant::now() >= deadline)
+        || CANCEL.with(|c| c.borrow().as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)));
+    if expired {
+        TRUNCATED.with(|t| t.set(true));
+    }
+    expired
+}
+
+/// 制限時間を過ぎて検索を打ち切ったか
+pub fn truncated() -> bool {
+    TRUNCATED.with(Cell::get)
+}
+
+/// min..=max を center に近い順に並べる（同じ近さは小さい順）
+pub fn outward(min: i32, max: i32, center: i32) -> Vec<i32> {
+    let mut order: Vec<i32> = (min..=max).collect();
+    order.sort_by_key(|i| ((*i as i64 - center as i64).abs(), *i));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout() {
+        assert_eq!(outward(-2, 3, 1), vec![1, 0, 2, -1, 3, -2]);
+
+        set_timeout(None);
+        assert!(!expired());
+        set_timeout(Some(Duration::ZERO));
+        assert!(expired());
+        assert!(truncated());
+        set_timeout(None);
+        assert!(!truncated());
+
+        let flag = Arc::new(AtomicBool::new(false));
+        set_cancel(Some(flag.clone()));
+        assert!(!expired());
+        flag.store(true, Ordering::Relaxed);
+        assert!(expired());
+        set_cancel(None);
+        set_deadline(None);
+        assert!(!expired() && !truncated());
+    }
+}
@@ -0,0 +1,276 @@
+//! 構造物のない範囲の検索モジュール
+//!
+//! - ネザー: `nether` コマンドの逆で、バスティオン（指定すればネザー要塞も）が入らない円の範囲を探す。
+//!   ネザーの構造物は480ブロックの区画ごとに必ず1つあるため、区画単位ではなく、
+//!   構造物の広がりを見込んで構造物から離れた地点を探す。金トラップ等の建設場所を選ぶためのもの。
+//! - オーバーワールド: 地上の構造物が入らず起伏の小さい正方形の範囲を探す。
+//!   巨大建築や配布ワールドの土台になる、手の入っていない平らな土地を選ぶためのもの。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::height::{sample_heights, SEA_LEVEL};
+use crate::coords::{check_nether_search_area, check_search_area, nether_to_overworld, BlockPos, Dimension};
+use crate::error::SearchError;
+use crate::structures::{find_in_dimension, find_structures, StructureKind, StructureType, FORTRESS_REACH};
+use crate::travel::distance_2d;
+
+/// 候補の地点を調べる間隔（ネザーのブロック数）
+const SAMPLE_STEP: i32 = 16;
+
+/// 構造物の位置からの広がりの目安（ネザーのブロック数）
+fn structure_extent(st: StructureType) -> i32 {
+    match st {
+        StructureType::NetherFortress => FORTRESS_REACH,
+        _ => 64,
+    }
+}
+
+/// 構造物のない範囲
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ClearArea {
+    /// 範囲の中心（ネザー座標）
+    pub x: i32,
+    pub z: i32,
+    /// 対応するオーバーワールドの座標
+    pub overworld_x: i32,
+    pub overworld_z: i32,
+    /// 検索中心からの距離
+    pub distance: f64,
+    /// 最も近い避ける構造物の種類の識別子（範囲内に見つからなければ null）
+    pub nearest_type: Option<String>,
+    /// 最も近い避ける構造物の位置までの距離
+    pub nearest_distance: Option<f64>,
+}
+
+/// 中心から radius ブロック以内で、半径 clearance ブロックの円に avoid の構造物が入らない地点を探す
+///
+/// 構造物は位置から `structure_extent` ブロックまで広がるものとして判定する。
+/// 結果は中心に近い順で、互いの円が重ならないよう近い地点は1つにまとめる。
+pub fn find_clear_areas(
+    seed: i64,
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+    clearance: i32,
+    avoid: &[StructureType],
+) -> Result<Vec<ClearArea>, SearchError> {
+    check_nether_search_area(center_x, center_z, radius)?;
+    let margin = clearance.max(0).saturating_add(avoid.iter().map(|&st| structure_extent(st)).max().unwrap_or(0));
+    let structures: Vec<(StructureType, i32, i32)> =
+        find_in_dimension(seed, Dimension::Nether, BlockPos::new(center_x, center_z), radius.saturating_add(margin), avoid)?
+            .into_iter()
+            .filter_map(|hit| match hit.kind {
+                StructureKind::Builtin(st) => Some((st, hit.pos.x, hit.pos.z)),
+                _ => None,
+            })
+            .collect();
+
+    let mut candidates = Vec::new();
+    let steps = radius / SAMPLE_STEP;
+    for i in -steps..=steps {
+        for j in -steps..=steps {
+            let (x, z) = (center_x + i * SAMPLE_STEP, center_z + j * SAMPLE_STEP);
+            let distance = distance_2d(center_x, center_z, x, z);
+            if distance > radius as f64 {
+                continue;
+            }
+            let nearest = structures
+                .iter()
+                .map(|&(st, sx, sz)| (st, distance_2d(x, z, sx, sz)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            let clear = structures
+                .iter()
+                .all(|&(st, sx, sz)| distance_2d(x, z, sx, sz) >= clearance as f64 + structure_extent(st) as f64);
+            if clear {
+                candidates.push(ClearArea {
+                    x,
+                    z,
+                    overworld_x: nether_to_overworld(x),
+                    overworld_z: nether_to_overworld(z),
+                    distance,
+                    nearest_type: nearest.map(|(st, _)| st.id().to_string()),
+                    nearest_distance: nearest.map(|(_, d)| d),
+                });
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    let mut areas: Vec<ClearArea> = Vec::new();
+    for candidate in candidates {
+        let overlaps =
+            areas.iter().any(|a| distance_2d(a.x, a.z, candidate.x, candidate.z) < clearance as f64 * 2.0);
+        if !overlaps {
+            areas.push(candidate);
+        }
+    }
+    Ok(areas)
+}
+
+/// 建築の邪魔になる地上の構造物（地下の構造物は除く）
+const SURFACE_STRUCTURES: [StructureType; 8] = [
+    StructureType::Village,
+    StructureType::PillagerOutpost,
+    StructureType::OceanMonument,
+    StructureType::WoodlandMansion,
+    StructureType::Igloo,
+    StructureType::WitchHut,
+    StructureType::Shipwreck,
+    StructureType::RuinedPortal,
+];
+
+/// 建築の範囲の高さを調べる間隔（ブロック）
+const BUILD_HEIGHT_STEP: i32 = 8;
+
+/// 建築の範囲の一辺の上限（ブロック）
+pub const MAX_BUILD_SIZE: i32 = 1024;
+
+/// 構造物がなく平らな建築の範囲
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BuildArea {
+    /// 範囲の中心
+    pub x: i32,
+    pub z: i32,
+    /// 範囲（両端を含む）
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_z: i32,
+    pub max_z: i32,
+    /// 検索中心からの距離
+    pub distance: f64,
+    /// 推定した地表の最低と最高の高さ
+    pub min_y: i32,
+    pub max_y: i32,
+    /// 地表の高さの標準偏差
+    pub stddev: f64,
+}
+
+/// 中心から radius ブロック以内で、一辺 size ブロックの正方形に地上の構造物が入らず、
+/// 地表の高さの標準偏差が max_stddev 以下の陸の範囲を中心に近い順に最大 limit 件探す
+///
+/// 構造物は `StructureType::footprint` の広がりで判定する。結果の範囲は互いに重ならない。
+pub fn find_build_areas(
+    seed: i64,
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+    size: i32,
+    max_stddev: f64,
+    limit: usize,
+) -> Result<Vec<BuildArea>, SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let half = size.max(1) / 2;
+    let margin = half + SURFACE_STRUCTURES.iter().map(|st| st.footprint().0).max().unwrap_or(0);
+    let mut structures = Vec::new();
+    for &st in &SURFACE_STRUCTURES {
+        for hit in find_structures(seed, BlockPos::new(center_x, center_z), radius.saturating_add(margin), st)? {
+            structures.push((st.footprint().0, hit.pos.x, hit.pos.z));
+        }
+    }
+
+    // 範囲の半分の間隔で候補の中心を並べ、中心に近い順に調べる
+    let step = half.max(SAMPLE_STEP);
+    let steps = radius / step;
+    let mut candidates = Vec::new();
+    for i in -steps..=steps {
+        for j in -steps..=steps {
+            let (x, z) = (center_x + i * step, center_z + j * step);
+            let distance = distance_2d(center_x, center_z, x, z);
+            if distance <= radius as f64 {
+                candidates.push((distance, x, z));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut areas: Vec<BuildArea> = Vec::new();
+    for (distance, x, z) in candidates {
+        if areas.len() >= limit {
+            break;
+        }
+        let overlaps = areas.iter().any(|a| (a.x - x).abs() < size && (a.z - z).abs() < size);
+        let blocked = structures
+            .iter()
+            .any(|&(extent, sx, sz)| (sx - x).abs() <= half + extent && (sz - z).abs() <= half + extent);
+        if overlaps || blocked {
+            continue;
+        }
+        let grid = sample_heights(seed, x, z, half, BUILD_HEIGHT_STEP);
+        let (min_y, max_y) = grid.range();
+        let stddev = grid.stddev();
+        if min_y < SEA_LEVEL || stddev > max_stddev {
+            continue;
+        }
+        areas.push(BuildArea {
+            x,
+            z,
+            min_x: x - half,
+            max_x: x + half - 1,
+            min_z: z - half,
+            max_z: z + half - 1,
+            distance,
+            min_y,
+            max_y,
+            stddev,
+        });
+    }
+    Ok(areas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_clear_areas() {
+        let avoid = [StructureType::BastionRemnant];
+        let areas = find_clear_areas(12345, 0, 0, 1000, 64, &avoid).unwrap();
+        assert!(!areas.is_empty());
+        let bastions = find_in_dimension(12345, Dimension::Nether, BlockPos::new(0, 0), 1000 + 64, &avoid).unwrap();
+        for area in &areas {
+            assert!(area.distance <= 1000.0);
+            for hit in &bastions {
+                assert!(distance_2d(area.x, area.z, hit.pos.x, hit.pos.z) >= 128.0);
+            }
+        }
+        for pair in areas.windows(2) {
+            assert!(pair[0].distance <= pair[1].distance);
+        }
+
+        // 要塞も避けると要塞の広がりの分だけ離れる
+        let both = [StructureType::BastionRemnant, StructureType::NetherFortress];
+        let fortresses =
+            find_in_dimension(12345, Dimension::Nether, BlockPos::new(0, 0), 1000 + 176, &[StructureType::NetherFortress]).unwrap();
+        for area in find_clear_areas(12345, 0, 0, 1000, 64, &both).unwrap() {
+            for hit in &fortresses {
+                assert!(distance_2d(area.x, area.z, hit.pos.x, hit.pos.z) >= (64 + FORTRESS_REACH) as f64);
+            }
+        }
+
+        assert!(find_clear_areas(12345, 0, 0, -1, 64, &avoid).is_err());
+        assert!(find_clear_areas(12345, 200_000_000, 0, 1000, 64, &avoid).is_err());
+    }
+
+    #[test]
+    fn test_find_build_areas() {
+        let areas = find_build_areas(12345, 0, 0, 2000, 128, 3.0, 3).unwrap();
+        assert!(!areas.is_empty() && areas.len() <= 3);
+        for area in &areas {
+            assert_eq!((area.max_x - area.min_x + 1, area.max_z - area.min_z + 1), (128, 128));
+            assert!(area.stddev <= 3.0);
+            assert!(area.min_y >= SEA_LEVEL);
+            for st in SURFACE_STRUCTURES {
+                for hit in find_structures(12345, BlockPos::new(area.x, area.z), 64, st).unwrap() {
+                    let inside = (area.min_x..=area.max_x).contains(&hit.pos.x)
+                        && (area.min_z..=area.max_z).contains(&hit.pos.z);
+                    assert!(!inside, "{:?}", hit);
+                }
+            }
+        }
+        for pair in areas.windows(2) {
+            assert!(pair[0].distance <= pair[1].distance);
+        }
+        assert!(find_build_areas(12345, 0, 0, -1, 128, 3.0, 3).is_err());
+    }
+}
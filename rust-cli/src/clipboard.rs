@@ -0,0 +1,19 @@
+//! クリップボードモジュール
+//!
+//! `--copy` で最寄りの結果をシステムのクリップボードに置き、
+//! 検索してからゲームに切り替えて座標を打ち込む手間を省く。
+//! Linux では終了後もクリップボードマネージャーが内容を保持している必要がある。
+
+/// テキストをシステムのクリップボードにコピー
+#[cfg(feature = "clipboard")]
+pub fn set_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("クリップボードにコピーできません: {}", e))
+}
+
+/// clipboard 機能なしでビルドした場合はコピーできない
+#[cfg(not(feature = "clipboard"))]
+pub fn set_clipboard(_text: &str) -> Result<(), String> {
+    Err("--copy には clipboard 機能を有効にしたビルドが必要です（cargo build --features clipboard）".to_string())
+}
@@ -0,0 +1,134 @@
+//! 設定ファイルモジュール
+//!
+//! `~/.config/bedrockmate/config.toml`（または `--config` で指定したファイル）から
+//! 各コマンドの既定値を読み込む。コマンドラインの指定が常に優先される。
+//!
+//! ```toml
+//! seed = 12345
+//! version = "1.21.0"
+//! channel = "retail"
+//! world_type = "infinite"
+//! world_offset = "0,0"
+//! experimental = false
+//! output = "json"
+//! lang = "ja"
+//! radius = 3000
+//! history = true
+//! structures_file = "/path/to/structures.toml"
+//! data_dir = "/path/to/data"
+//! ```
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+/// 設定ファイルの内容
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// 既定のワールドシード値
+    pub seed: Option<i64>,
+    /// 既定のゲームバージョン（--mc-version）
+    pub version: Option<String>,
+    /// 既定の配信チャンネル（--channel）
+    pub channel: Option<String>,
+    /// 既定のワールドの種類（--world-type）
+    pub world_type: Option<String>,
+    /// 既定のワールドのずれ（--world-offset）
+    pub world_offset: Option<String>,
+    /// 実験的機能の構造物・バイオームも対象にするか（--experimental）
+    pub experimental: Option<bool>,
+    /// 既定の出力形式（--output）
+    pub output: Option<String>,
+    /// 既定の表示言語（--lang）
+    pub lang: Option<String>,
+    /// 既定の検索半径（--radius）
+    pub radius: Option<i32>,
+    /// 実行したコマンドを履歴に記録するか（history コマンドで再実行できる）
+    pub history: Option<bool>,
+    /// カスタム構造物の定義ファイル（省略時は ~/.config/bedrockmate/structures.toml）
+    pub structures_file: Option<String>,
+    /// 構造物の配置・バイオームのパラメータの表を差し替えるディレクトリ（--data-dir）
+    pub data_dir: Option<String>,
+    /// コマンドラインの指定より優先する出力形式（--format-template 指定時）
+    #[serde(skip)]
+    forced_output: Option<String>,
+}
+
+/// 設定ファイルやブックマーク等を置くディレクトリ（~/.config/bedrockmate）
+pub fn config_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    Some(config_dir.join("bedrockmate"))
+}
+
+/// 既定の設定ファイルのパス
+pub fn default_config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+impl Config {
+    /// 設定ファイルを読み込む
+    ///
+    /// `path` 未指定で既定のファイルも存在しない場合は空の設定を返す。
+    /// 明示的に指定したファイルが読めない場合や、内容が不正な場合はエラー。
+    pub fn load(path: Option<&str>) -> Result<Config, String> {
+        let path = match path {
+            Some(p) => PathBuf::from(p),
+            None => match default_config_path() {
+                Some(p) if p.exists() => p,
+                _ => return Ok(Config::default()),
+            },
+        };
+
+        tracing::info!("設定ファイル: {}", path.display());
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("設定ファイルを読み込めません: {} ({})", path.display(), e))?;
+        toml::from_str(&text).map_err(|e| format!("設定ファイルの形式が不正です: {} ({})", path.display(), e))
+    }
+
+    /// シード値を決定（コマンドライン > 設定ファイル）
+    pub fn seed(&self, cli: Option<i64>) -> Option<i64> {
+        cli.or(self.seed)
+    }
+
+    /// 検索半径を決定（コマンドライン > 設定ファイル > コマンドごとの既定値）
+    pub fn radius(&self, cli: Option<i32>, default: i32) -> i32 {
+        cli.or(self.radius).unwrap_or(default)
+    }
+
+    /// 出力形式を決定（コマンドライン > 設定ファイル > text）
+    pub fn output(&self, cli: Option<String>) -> String {
+        if let Some(forced) = &self.forced_output {
+            return forced.clone();
+        }
+        cli.or_else(|| self.output.clone()).unwrap_or_else(|| "text".to_string())
+    }
+
+    /// コマンドラインや設定ファイルの指定によらず出力形式を固定する
+    pub fn force_output(&mut self, format: &str) {
+        self.forced_output = Some(format.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str("seed = 42\nradius = 3000\noutput = \"json\"").unwrap();
+        assert_eq!(config.seed(None), Some(42));
+        assert_eq!(config.seed(Some(7)), Some(7));
+        assert_eq!(config.radius(None, 5000), 3000);
+        assert_eq!(config.output(None), "json");
+        assert_eq!(config.output(Some("text".to_string())), "text");
+
+        assert!(toml::from_str::<Config>("unknown = 1").is_err());
+    }
+}
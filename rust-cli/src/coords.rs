@@ -0,0 +1,403 @@
+//! 座標変換モジュール
+//!
+//! オーバーワールド⇔ネザー、ブロック⇔チャンク⇔リージョンの変換
+//!
+//! 移行や中心の付け替えで原点がずれたワールド（`--world-offset`）では、入力と出力はすべてワールドの座標のまま扱い、
+//! 生成の計算をする箇所だけで生成の座標（ずれを引いた座標）に直す。
+
+use std::cell::Cell;
+
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SearchError;
+
+/// チャンクの一辺（ブロック）
+pub const CHUNK_SIZE: i32 = 16;
+
+/// リージョンの一辺（チャンク）
+pub const REGION_CHUNKS: i32 = 32;
+
+/// ネザーの座標倍率
+pub const NETHER_SCALE: i32 = 8;
+
+/// ワールドのずれ（--world-offset）に使える単位（ネザーの1/8でもチャンクの境界に揃う）
+pub const WORLD_OFFSET_UNIT: i32 = CHUNK_SIZE * NETHER_SCALE;
+
+/// ディメンション
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Overworld,
+    Nether,
+    #[serde(rename = "the_end", alias = "end")]
+    End,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 3] = [Dimension::Overworld, Dimension::Nether, Dimension::End];
+
+    /// 識別子（overworld, nether, the_end）
+    pub fn id(&self) -> &'static str {
+        match self {
+            Dimension::Overworld => "overworld",
+            Dimension::Nether => "nether",
+            Dimension::End => "the_end",
+        }
+    }
+
+    /// 識別子からディメンションを取得
+    #[cfg_attr(not(feature = "db"), allow(dead_code))] // 保存したブックマークのディメンションの読み込みに使う
+    pub fn from_id(id: &str) -> Option<Dimension> {
+        Dimension::ALL.into_iter().find(|d| d.id() == id)
+    }
+}
+
+impl ValueEnum for Dimension {
+    fn value_variants<'a>() -> &'a [Dimension] {
+        &Dimension::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let value = PossibleValue::new(self.id());
+        Some(if *self == Dimension::End { value.alias("end") } else { value })
+    }
+}
+
+/// 検索できる座標の上限（中心から半径の範囲がこれを超えると走査の計算があふれる）
+pub const SEARCH_LIMIT: i64 = 1 << 30;
+
+/// 検索の中心と半径を確かめる（半径が0以下か、範囲が座標の上限を超える場合はエラー）
+pub fn check_search_area(center_x: i32, center_z: i32, radius: i32) -> Result<(), SearchError> {
+    if radius <= 0 {
+        return Err(SearchError::InvalidRadius(radius));
+    }
+    let within = |c: i32| (c as i64 - radius as i64).abs() <= SEARCH_LIMIT && (c as i64 + radius as i64).abs() <= SEARCH_LIMIT;
+    if within(center_x) && within(center_z) {
+        Ok(())
+    } else {
+        Err(SearchError::OutOfRange { x: center_x, z: center_z, radius, limit: SEARCH_LIMIT })
+    }
+}
+
+/// ネザーの検索範囲を確かめる（オーバーワールドの座標に8倍しても上限を超えないこと）
+pub fn check_nether_search_area(center_x: i32, center_z: i32, radius: i32) -> Result<(), SearchError> {
+    check_search_area(center_x, center_z, radius)?;
+    let limit = SEARCH_LIMIT / NETHER_SCALE as i64;
+    let within = |c: i32| (c as i64).abs() + radius as i64 <= limit;
+    if within(center_x) && within(center_z) {
+        Ok(())
+    } else {
+        Err(SearchError::OutOfRange { x: center_x, z: center_z, radius, limit })
+    }
+}
+
+thread_local! {
+    static WORLD_OFFSET: Cell<(i32, i32)> = const { Cell::new((0, 0)) };
+}
+
+/// 実行するコマンドのワールドのずれ（オーバーワールドのブロック単位。生成の座標にこの値を足すとワールドの座標）を設定
+pub fn set_world_offset(dx: i32, dz: i32) {
+    WORLD_OFFSET.with(|o| o.set((dx, dz)));
+}
+
+/// ディメンションのワールドのずれ（ネザーはオーバーワールドの1/8）
+pub fn world_offset(dimension: Dimension) -> (i32, i32) {
+    let (dx, dz) = WORLD_OFFSET.with(Cell::get);
+    if dimension == Dimension::Nether {
+        (overworld_to_nether(dx), overworld_to_nether(dz))
+    } else {
+        (dx, dz)
+    }
+}
+
+/// ワールドの座標から生成の座標に変換
+pub fn to_generation(dimension: Dimension, x: i32, z: i32) -> (i32, i32) {
+    let (dx, dz) = world_offset(dimension);
+    (x - dx, z - dz)
+}
+
+/// 生成の座標からワールドの座標に変換
+pub fn to_world(dimension: Dimension, x: i32, z: i32) -> (i32, i32) {
+    let (dx, dz) = world_offset(dimension);
+    (x + dx, z + dz)
+}
+
+/// ワールドのチャンク座標から生成のチャンク座標に変換（ずれはチャンクの境界に揃っている）
+pub fn chunk_to_generation(dimension: Dimension, chunk_x: i32, chunk_z: i32) -> (i32, i32) {
+    let (dx, dz) = world_offset(dimension);
+    (chunk_x - block_to_chunk(dx), chunk_z - block_to_chunk(dz))
+}
+
+/// ブロック座標からチャンク座標に変換
+pub fn block_to_chunk(block: i32) -> i32 {
+    block.div_euclid(CHUNK_SIZE)
+}
+
+/// チャンク内の相対座標を計算（0-15）
+pub fn chunk_local(block: i32) -> i32 {
+    block.rem_euclid(CHUNK_SIZE)
+}
+
+/// チャンク座標からリージョン座標に変換
+pub fn chunk_to_region(chunk: i32) -> i32 {
+    chunk.div_euclid(REGION_CHUNKS)
+}
+
+/// チャンク座標から最小ブロック座標に変換
+pub fn chunk_to_block(chunk: i32) -> i32 {
+    chunk * CHUNK_SIZE
+}
+
+/// オーバーワールド座標からネザー座標に変換
+pub fn overworld_to_nether(block: i32) -> i32 {
+    block.div_euclid(NETHER_SCALE)
+}
+
+/// ネザー座標からオーバーワールド座標に変換
+pub fn nether_to_overworld(block: i32) -> i32 {
+    block * NETHER_SCALE
+}
+
+/// "x,z" 形式の文字列を座標の組に変換
+pub fn parse_xz(s: &str) -> Option<(i32, i32)> {
+    let (x, z) = s.split_once(',')?;
+    Some((x.trim().parse().ok()?, z.trim().parse().ok()?))
+}
+
+/// X/Z座標の組
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+pub struct XZ {
+    pub x: i32,
+    pub z: i32,
+}
+
+/// ブロック座標（X, Z）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockPos {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl BlockPos {
+    pub const fn new(x: i32, z: i32) -> BlockPos {
+        BlockPos { x, z }
+    }
+
+    /// ブロックを含むチャンク
+    pub fn chunk(self) -> ChunkPos {
+        ChunkPos::new(block_to_chunk(self.x), block_to_chunk(self.z))
+    }
+
+    /// ブロックを含むリージョン（一辺 `spacing` チャンク）
+    pub fn region(self, spacing: i32) -> RegionPos {
+        self.chunk().region(spacing)
+    }
+
+    /// 別のブロックまでの距離の2乗
+    pub fn distance_sq(self, other: BlockPos) -> i64 {
+        (self.x as i64 - other.x as i64).pow(2) + (self.z as i64 - other.z as i64).pow(2)
+    }
+
+    /// ワールドの座標から生成の座標に変換
+    pub fn to_generation(self, dimension: Dimension) -> BlockPos {
+        let (x, z) = to_generation(dimension, self.x, self.z);
+        BlockPos::new(x, z)
+    }
+
+    /// 生成の座標からワールドの座標に変換
+    pub fn to_world(self, dimension: Dimension) -> BlockPos {
+        let (x, z) = to_world(dimension, self.x, self.z);
+        BlockPos::new(x, z)
+    }
+}
+
+/// チャンク座標（X, Z）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl ChunkPos {
+    pub const fn new(x: i32, z: i32) -> ChunkPos {
+        ChunkPos { x, z }
+    }
+
+    /// チャンクの最小のブロック
+    pub fn min_block(self) -> BlockPos {
+        BlockPos::new(chunk_to_block(self.x), chunk_to_block(self.z))
+    }
+
+    /// チャンクの中心のブロック
+    pub fn center(self) -> BlockPos {
+        BlockPos::new(chunk_to_block(self.x) + CHUNK_SIZE / 2, chunk_to_block(self.z) + CHUNK_SIZE / 2)
+    }
+
+    /// チャンクを含むリージョン（一辺 `spacing` チャンク）
+    pub fn region(self, spacing: i32) -> RegionPos {
+        RegionPos::new(self.x.div_euclid(spacing), self.z.div_euclid(spacing))
+    }
+}
+
+/// リージョン座標（X, Z）
+///
+/// 一辺のチャンク数は使う場面で決まる（構造物の配置では構造物の間隔、ファイルでは `REGION_CHUNKS`）ため、
+/// チャンクとの変換には一辺を渡す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionPos {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl RegionPos {
+    pub const fn new(x: i32, z: i32) -> RegionPos {
+        RegionPos { x, z }
+    }
+
+    /// リージョンの最小のチャンク（一辺 `spacing` チャンク）
+    pub fn min_chunk(self, spacing: i32) -> ChunkPos {
+        ChunkPos::new(self.x * spacing, self.z * spacing)
+    }
+}
+
+/// オーバーワールドのブロック範囲（両端を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+pub struct BlockRange {
+    pub min_x: i32,
+    pub min_z: i32,
+    pub max_x: i32,
+    pub max_z: i32,
+}
+
+/// 座標変換の結果一式
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Conversion {
+    /// 入力の種類（block, nether, chunk, region）
+    pub from: String,
+    /// 入力が表すオーバーワールドのブロック範囲
+    pub block_range: BlockRange,
+    /// 範囲の最小角のオーバーワールド座標
+    pub overworld: XZ,
+    pub nether: XZ,
+    pub chunk: XZ,
+    pub chunk_local: XZ,
+    pub region: XZ,
+    pub region_file: String,
+}
+
+/// 座標を変換
+///
+/// `from` は入力座標の種類。不明な種類の場合や、表すブロック範囲が i32 に収まらない場合はエラーを返す。
+pub fn convert(x: i32, z: i32, from: &str) -> Result<Conversion, SearchError> {
+    // 入力1単位あたりのオーバーワールドのブロック数
+    let scale = match from {
+        "block" | "overworld" => 1,
+        "nether" => NETHER_SCALE,
+        "chunk" => CHUNK_SIZE,
+        "region" => REGION_CHUNKS * CHUNK_SIZE,
+        _ => return Err(SearchError::UnknownCoordinateKind(from.to_string())),
+    };
+    let span = |c: i32| -> Option<(i32, i32)> {
+        let min = c as i64 * scale as i64;
+        Some((i32::try_from(min).ok()?, i32::try_from(min + scale as i64 - 1).ok()?))
+    };
+    let out_of_range = || SearchError::CoordinateOutOfRange { kind: from.to_string(), x, z };
+    let (min_x, max_x) = span(x).ok_or_else(out_of_range)?;
+    let (min_z, max_z) = span(z).ok_or_else(out_of_range)?;
+    let block_range = BlockRange { min_x, min_z, max_x, max_z };
+
+    let bx = block_range.min_x;
+    let bz = block_range.min_z;
+    let chunk = XZ {
+        x: block_to_chunk(bx),
+        z: block_to_chunk(bz),
+    };
+    let region = XZ {
+        x: chunk_to_region(chunk.x),
+        z: chunk_to_region(chunk.z),
+    };
+
+    Ok(Conversion {
+        from: from.to_string(),
+        block_range,
+        overworld: XZ { x: bx, z: bz },
+        nether: XZ {
+            x: overworld_to_nether(bx),
+            z: overworld_to_nether(bz),
+        },
+        chunk,
+        chunk_local: XZ {
+            x: chunk_local(bx),
+            z: chunk_local(bz),
+        },
+        region,
+        region_file: format!("r.{}.{}.mca", region.x, region.z),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negative_coordinates() {
+        assert_eq!(block_to_chunk(-1), -1);
+        assert_eq!(chunk_local(-1), 15);
+        assert_eq!(overworld_to_nether(-1), -1);
+        assert_eq!(chunk_to_region(-33), -2);
+        assert_eq!(parse_xz("-12, 40"), Some((-12, 40)));
+        assert_eq!(parse_xz("12"), None);
+    }
+
+    #[test]
+    fn test_typed_positions() {
+        let block = BlockPos::new(-1, 500);
+        assert_eq!(block.chunk(), ChunkPos::new(-1, 31));
+        assert_eq!(block.region(32), RegionPos::new(-1, 0));
+        assert_eq!(block.chunk().center(), BlockPos::new(-8, 504));
+        assert_eq!(RegionPos::new(-1, 2).min_chunk(30).min_block(), BlockPos::new(-480, 960));
+        assert_eq!(BlockPos::new(3, 4).distance_sq(BlockPos::new(0, 0)), 25);
+    }
+
+    #[test]
+    fn test_dimension() {
+        for dimension in Dimension::ALL {
+            assert_eq!(Dimension::from_id(dimension.id()), Some(dimension));
+        }
+        assert_eq!(Dimension::from_str("end", false), Ok(Dimension::End));
+        assert!(Dimension::from_id("end").is_none());
+    }
+
+    #[test]
+    fn test_check_search_area() {
+        assert_eq!(check_search_area(-1000, 1000, 5000), Ok(()));
+        assert_eq!(check_search_area(0, 0, 0), Err(SearchError::InvalidRadius(0)));
+        assert_eq!(check_search_area(0, 0, -100), Err(SearchError::InvalidRadius(-100)));
+        assert!(matches!(check_search_area(2_000_000_000, 0, 500), Err(SearchError::OutOfRange { .. })));
+        assert!(matches!(check_search_area(0, -1000, i32::MAX), Err(SearchError::OutOfRange { .. })));
+        assert_eq!(check_nether_search_area(1000, 0, 1000), Ok(()));
+        assert!(matches!(check_nether_search_area(200_000_000, 0, 500), Err(SearchError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_convert_round_trip() {
+        let from_block = convert(1000, -250, "block").unwrap();
+        let from_chunk = convert(from_block.chunk.x, from_block.chunk.z, "chunk").unwrap();
+        assert!(from_chunk.block_range.min_x <= 1000 && 1000 <= from_chunk.block_range.max_x);
+        assert!(from_chunk.block_range.min_z <= -250 && -250 <= from_chunk.block_range.max_z);
+
+        let from_nether = convert(from_block.nether.x, from_block.nether.z, "nether").unwrap();
+        assert_eq!(from_nether.block_range.min_x, 1000);
+        assert_eq!(from_nether.block_range.min_z, -256);
+
+        assert!(convert(0, 0, "end").is_err());
+
+        // ブロック範囲が i32 に収まらない入力はパニックせずエラーになる
+        assert!(convert(2_000_000_000, 0, "nether").is_err());
+        assert!(convert(0, -5_000_000, "region").is_err());
+        assert_eq!(convert(i32::MAX, i32::MIN, "block").unwrap().block_range.max_x, i32::MAX);
+    }
+}
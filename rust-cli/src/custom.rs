@@ -0,0 +1,185 @@
+//! カスタム構造物モジュール
+//!
+//! 新しいバージョンや実験的な構造物を、リリースを待たずに検索できるよう
+//! TOMLファイルからグリッド配置の構造物を追加する。
+//! 既定では `~/.config/bedrockmate/structures.toml` を読み込み、設定ファイルの
+//! `structures_file` で別のファイルを指定できる。
+//!
+//! ```toml
+//! [[structure]]
+//! id = "trail_ruins"
+//! name = "🏺 旅路の遺跡"
+//! spacing = 34
+//! separation = 8
+//! salt = 83469867
+//! biomes = ["taiga", "jungle"]
+//! dimension = "overworld"
+//! ```
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::algorithms::biome::BiomeType;
+use crate::config::config_dir;
+use crate::coords::Dimension;
+use crate::structures::{GridPlacement, StructureType, STRONGHOLD_ID};
+
+static CUSTOM_STRUCTURES: OnceLock<Vec<CustomStructure>> = OnceLock::new();
+
+/// カスタム構造物の定義
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomStructure {
+    /// -t オプションで使う識別子
+    pub id: String,
+    /// 表示名（省略時は識別子）
+    pub name: Option<String>,
+    /// リージョンの一辺（チャンク）
+    pub spacing: i32,
+    /// 分離距離（チャンク）
+    pub separation: i32,
+    pub salt: i64,
+    /// 生成条件となるバイオーム（説明用。座標計算では判定しない）
+    #[serde(default)]
+    pub biomes: Vec<String>,
+    /// overworld または nether
+    #[serde(default = "default_dimension")]
+    pub dimension: Dimension,
+}
+
+fn default_dimension() -> Dimension {
+    Dimension::Overworld
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomStructureFile {
+    #[serde(default, rename = "structure")]
+    structures: Vec<CustomStructure>,
+}
+
+impl CustomStructure {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn placement(&self) -> GridPlacement {
+        GridPlacement {
+            spacing: self.spacing,
+            separation: self.separation,
+            salt: self.salt,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id == "all" || self.id == STRONGHOLD_ID || self.id.parse::<StructureType>().is_ok() {
+            return Err(format!("{} は組み込みの構造物と同じ識別子（または別名）です", self.id));
+        }
+        if self.separation < 0 || self.spacing <= self.separation {
+            return Err(format!("{}: spacing は separation より大きくしてください", self.id));
+        }
+        match self.dimension {
+            Dimension::Overworld => {}
+            Dimension::Nether if self.biomes.is_empty() => {}
+            Dimension::Nether => return Err(format!("{}: ネザーの構造物にはバイオームを指定できません", self.id)),
+            Dimension::End => return Err(format!("{}: エンドの構造物には対応していません（overworld, nether）", self.id)),
+        }
+        if let Some(biome) = self.biomes.iter().find(|b| BiomeType::from_str(b).is_none()) {
+            return Err(format!("{}: 不明なバイオーム: {}", self.id, biome));
+        }
+        Ok(())
+    }
+}
+
+/// カスタム構造物のTOMLを解釈
+pub fn parse_custom_structures(text: &str) -> Result<Vec<CustomStructure>, String> {
+    let file: CustomStructureFile = toml::from_str(text).map_err(|e| e.to_string())?;
+    for (i, structure) in file.structures.iter().enumerate() {
+        structure.validate()?;
+        if file.structures[..i].iter().any(|other| other.id == structure.id) {
+            return Err(format!("{} が重複しています", structure.id));
+        }
+    }
+    Ok(file.structures)
+}
+
+/// カスタム構造物のファイルを読み込む
+///
+/// `path` 未指定で既定のファイルも存在しない場合は空。
+pub fn load_custom_structures(path: Option<&str>) -> Result<Vec<CustomStructure>, String> {
+    let path = match path {
+        Some(p) => PathBuf::from(p),
+        None => match config_dir().map(|dir| dir.join("structures.toml")) {
+            Some(p) if p.exists() => p,
+            _ => return Ok(Vec::new()),
+        },
+    };
+
+    tracing::info!("カスタム構造物: {}", path.display());
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("カスタム構造物のファイルを読み込めません: {} ({})", path.display(), e))?;
+    parse_custom_structures(&text)
+        .map_err(|e| format!("カスタム構造物のファイルの形式が不正です: {} ({})", path.display(), e))
+}
+
+/// カスタム構造物を登録（起動時に一度だけ）
+pub fn register_custom_structures(structures: Vec<CustomStructure>) {
+    let _ = CUSTOM_STRUCTURES.set(structures);
+}
+
+/// 登録済みのカスタム構造物
+pub fn custom_structures() -> &'static [CustomStructure] {
+    CUSTOM_STRUCTURES.get().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// 識別子からカスタム構造物を取得
+pub fn find_custom_structure(id: &str) -> Option<&'static CustomStructure> {
+    custom_structures().iter().find(|c| c.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::locator::{locate, GridLocator};
+    use crate::structures::StructureKind;
+
+    const TRAIL_RUINS: &str = r#"
+[[structure]]
+id = "trail_ruins"
+spacing = 34
+separation = 8
+salt = 83469867
+biomes = ["taiga"]
+"#;
+
+    #[test]
+    fn test_parse_custom_structures() {
+        let structures = parse_custom_structures(TRAIL_RUINS).unwrap();
+        assert_eq!(structures.len(), 1);
+        let ruins = &structures[0];
+        assert_eq!(ruins.display_name(), "trail_ruins");
+        assert_eq!(ruins.dimension, Dimension::Overworld);
+
+        let locator = GridLocator { kind: StructureKind::Custom(ruins.id.clone()), placement: ruins.placement() };
+        let results = locate(&locator, 12345, BlockPos::new(0, 0), 2000).unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|hit| hit.kind.id() == "trail_ruins"));
+    }
+
+    #[test]
+    fn test_invalid_custom_structures() {
+        let invalid = [
+            "[[structure]]\nid = \"village\"\nspacing = 34\nseparation = 8\nsalt = 1",
+            "[[structure]]\nid = \"a\"\nspacing = 8\nseparation = 8\nsalt = 1",
+            "[[structure]]\nid = \"a\"\nspacing = 34\nseparation = 8\nsalt = 1\ndimension = \"end\"",
+            "[[structure]]\nid = \"a\"\nspacing = 34\nseparation = 8\nsalt = 1\nbiomes = [\"moon\"]",
+            "[[structure]]\nid = \"a\"\nspacing = 34\nseparation = 8\nsalt = 1\n[[structure]]\nid = \"a\"\nspacing = 34\nseparation = 8\nsalt = 2",
+        ];
+        for text in invalid {
+            assert!(parse_custom_structures(text).is_err(), "{}", text);
+        }
+    }
+}
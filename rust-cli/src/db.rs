@@ -0,0 +1,26 @@
+//! SQLiteファイルモジュール
+//!
+//! ブックマークや検索履歴の保存に使うSQLiteファイルを開く
+
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+
+use crate::config::config_dir;
+
+/// SQLiteファイルを開く（存在しない場合は親ディレクトリごと作成）
+///
+/// `path` 未指定の場合は設定ディレクトリ内の `file_name` を使う。
+pub fn open_db(path: Option<&str>, file_name: &str) -> Result<Connection, String> {
+    let path = match path {
+        Some(p) => PathBuf::from(p),
+        None => config_dir()
+            .map(|dir| dir.join(file_name))
+            .ok_or_else(|| format!("{} の保存先を決められません（--db で指定してください）", file_name))?,
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("ディレクトリを作成できません: {} ({})", parent.display(), e))?;
+    }
+    Connection::open(&path).map_err(|e| format!("ファイルを開けません: {} ({})", path.display(), e))
+}
@@ -0,0 +1,228 @@
+//! 比較地図モジュール
+//!
+//! 同じ範囲を2つのシード（またはバージョン）で描いた地図を、左右に並べるか違いを重ねて1枚の画像にする。
+//! ワールドを移行するときや生成の処理を変えたときに、バイオームと構造物の違いを目で確かめるのに使う。
+
+use crate::icons::structure_icon;
+use crate::palette::Palette;
+use crate::render::{draw_decorations, render_biome_map, Image, MapView, BLACK, GLYPH_ADVANCE, GLYPH_HEIGHT, WHITE};
+use crate::structures::StructureHit;
+use crate::version::McVersion;
+
+/// Aにだけある構造物の枠の色
+pub const REMOVED_COLOR: [u8; 3] = [230, 40, 40];
+
+/// Bにだけある構造物の枠の色
+pub const ADDED_COLOR: [u8; 3] = [40, 220, 80];
+
+/// 左右に並べる場合の間隔（ピクセル数）
+const GAP: usize = 4;
+
+/// 見出しの余白（ピクセル数）
+const PADDING: i64 = 8;
+
+/// 見出しの行の高さ（ピクセル数）
+const ROW_HEIGHT: i64 = GLYPH_HEIGHT + 5;
+
+/// 比較地図の描き方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMode {
+    /// Aの地図を灰色にし、バイオームが違う所をBの色で塗る
+    Overlay,
+    /// AとBの地図を左右に並べる
+    SideBySide,
+}
+
+impl DiffMode {
+    pub const ALL: [DiffMode; 2] = [DiffMode::Overlay, DiffMode::SideBySide];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            DiffMode::Overlay => "overlay",
+            DiffMode::SideBySide => "side",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<DiffMode> {
+        DiffMode::ALL.into_iter().find(|m| m.id() == id)
+    }
+}
+
+/// 比較する地図の片方
+pub struct DiffSide<'a> {
+    pub seed: i64,
+    pub version: McVersion,
+    /// 地図の範囲の構造物
+    pub structures: &'a [StructureHit],
+}
+
+impl DiffSide<'_> {
+    fn label(&self, name: &str) -> String {
+        format!("{} SEED {} {}", name, self.seed, self.version)
+    }
+}
+
+/// 比較地図を描く（戻り値の2つ目はバイオームが違うピクセルの割合）
+pub fn render_diff_map(view: &MapView, mode: DiffMode, a: &DiffSide, b: &DiffSide, marker_size: u32, center_x: i32, center_z: i32) -> (Image, f64) {
+    let palette = Palette::default();
+    let base = render_biome_map(a.seed, view, &palette);
+    let other = if b.seed == a.seed { base.clone() } else { render_biome_map(b.seed, view, &palette) };
+    let changed = (0..view.height)
+        .flat_map(|py| (0..view.width).map(move |px| (px, py)))
+        .filter(|&(px, py)| base.get(px, py) != other.get(px, py))
+        .count();
+    let ratio = changed as f64 / (view.width * view.height).max(1) as f64;
+
+    let (removed, added) = structure_diff(a.structures, b.structures);
+    let image = match mode {
+        DiffMode::Overlay => {
+            let mut image = diff_overlay(&base, &other);
+            draw_decorations(&mut image, view, center_x, center_z);
+            let common: Vec<_> = a.structures.iter().filter(|s| !removed.contains(s)).collect();
+            draw_structures(&mut image, view, &common, marker_size, None);
+            draw_structures(&mut image, view, &removed, marker_size, Some(REMOVED_COLOR));
+            draw_structures(&mut image, view, &added, marker_size, Some(ADDED_COLOR));
+            draw_header(
+                &mut image,
+                &[
+                    (a.label("A"), WHITE),
+                    (b.label("B"), WHITE),
+                    ("CHANGED = B COLORS".to_string(), WHITE),
+                    ("- ONLY A".to_string(), REMOVED_COLOR),
+                    ("+ ONLY B".to_string(), ADDED_COLOR),
+                ],
+            );
+            image
+        }
+        DiffMode::SideBySide => {
+            let mut left = base;
+            draw_decorations(&mut left, view, center_x, center_z);
+            let common_a: Vec<_> = a.structures.iter().filter(|s| !removed.contains(s)).collect();
+            draw_structures(&mut left, view, &common_a, marker_size, None);
+            draw_structures(&mut left, view, &removed, marker_size, Some(REMOVED_COLOR));
+            draw_header(&mut left, &[(a.label("A"), WHITE), ("- ONLY A".to_string(), REMOVED_COLOR)]);
+
+            let mut right = other;
+            draw_decorations(&mut right, view, center_x, center_z);
+            let common_b: Vec<_> = b.structures.iter().filter(|s| !added.contains(s)).collect();
+            draw_structures(&mut right, view, &common_b, marker_size, None);
+            draw_structures(&mut right, view, &added, marker_size, Some(ADDED_COLOR));
+            draw_header(&mut right, &[(b.label("B"), WHITE), ("+ ONLY B".to_string(), ADDED_COLOR)]);
+            side_by_side(&left, &right)
+        }
+    };
+    (image, ratio)
+}
+
+/// Aにだけある構造物とBにだけある構造物
+pub fn structure_diff<'a>(a: &'a [StructureHit], b: &'a [StructureHit]) -> (Vec<&'a StructureHit>, Vec<&'a StructureHit>) {
+    let removed = a.iter().filter(|s| !b.contains(s)).collect();
+    let added = b.iter().filter(|s| !a.contains(s)).collect();
+    (removed, added)
+}
+
+/// 同じ色のピクセルを暗い灰色にし、違うピクセルを other の色にする
+fn diff_overlay(base: &Image, other: &Image) -> Image {
+    let mut image = Image::new(base.width, base.height);
+    for py in 0..base.height {
+        for px in 0..base.width {
+            let [r, g, b] = base.get(px, py);
+            let color = if other.get(px, py) == [r, g, b] {
+                let gray = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64) * 0.4 + 40.0;
+                [gray.round() as u8; 3]
+            } else {
+                other.get(px, py)
+            };
+            image.set(px, py, color);
+        }
+    }
+    image
+}
+
+/// 2枚の画像を間を空けて左右に並べる
+fn side_by_side(left: &Image, right: &Image) -> Image {
+    let mut image = Image::new(left.width + GAP + right.width, left.height.max(right.height));
+    for (offset, part) in [(0, left), (left.width + GAP, right)] {
+        for py in 0..part.height {
+            for px in 0..part.width {
+                image.set(offset + px, py, part.get(px, py));
+            }
+        }
+    }
+    image
+}
+
+/// 構造物のアイコンを描く（ring を指定した場合は枠で囲む）
+fn draw_structures(image: &mut Image, view: &MapView, structures: &[&StructureHit], size: u32, ring: Option<[u8; 3]>) {
+    for hit in structures {
+        let (px, py) = view.pixel_of(hit.pos.x, hit.pos.z);
+        let (px, py) = (px as i64, py as i64);
+        if let Some(color) = ring {
+            let half = size as i64 / 2 + 4;
+            // 黒で縁取った太さ2ピクセルの枠
+            for (c, inset, thickness) in [(BLACK, -1, 4), (color, 0, 2)] {
+                let (left, top, side) = (px - half + inset, py - half + inset, half * 2 - inset * 2);
+                image.fill_rect(left, top, side, thickness, c);
+                image.fill_rect(left, top + side - thickness, side, thickness, c);
+                image.fill_rect(left, top, thickness, side, c);
+                image.fill_rect(left + side - thickness, top, thickness, side, c);
+            }
+        }
+        structure_icon(hit.kind.id()).draw(image, px, py, size);
+    }
+}
+
+/// 左上に見出しを描く
+fn draw_header(image: &mut Image, lines: &[(String, [u8; 3])]) {
+    let width = PADDING * 2 + lines.iter().map(|(text, _)| text.len() as i64).max().unwrap_or(0) * GLYPH_ADVANCE;
+    let height = PADDING * 2 + lines.len() as i64 * ROW_HEIGHT;
+    image.blend_rect(0, 0, width, height, BLACK, 0.7);
+    for (i, (text, color)) in lines.iter().enumerate() {
+        image.draw_text(PADDING, PADDING + i as i64 * ROW_HEIGHT, text, *color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::structures::{StructureKind, StructureType};
+
+    #[test]
+    fn test_structure_diff() {
+        let village = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::Village), BlockPos::new(x, z));
+        let outpost = StructureHit::new(StructureKind::Builtin(StructureType::PillagerOutpost), BlockPos::new(100, 100));
+        let a = vec![village(0, 0), outpost];
+        let b = vec![village(0, 0), village(500, 0)];
+        let (removed, added) = structure_diff(&a, &b);
+        assert_eq!(removed, vec![&a[1]]);
+        assert_eq!(added, vec![&b[1]]);
+        assert_eq!(DiffMode::from_id("side"), Some(DiffMode::SideBySide));
+    }
+
+    #[test]
+    fn test_render_diff_map() {
+        let village = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::Village), BlockPos::new(x, z));
+        let view = MapView::around(0, 0, 200, 2);
+        let structures = vec![village(50, 50)];
+        let a = DiffSide {
+            seed: 12345,
+            version: McVersion(1, 21, 0),
+            structures: &structures,
+        };
+        let b = DiffSide {
+            seed: 12345,
+            version: McVersion(1, 20, 0),
+            structures: &[],
+        };
+        let (image, ratio) = render_diff_map(&view, DiffMode::Overlay, &a, &b, 12, 0, 0);
+        assert_eq!(ratio, 0.0);
+        // 同じバイオームは灰色になり、Aにだけある構造物は赤い枠で囲む
+        let [r, g, b_] = image.get(190, 150);
+        assert!(r == g && g == b_);
+        assert_eq!(image.get(125 - 10, 125), REMOVED_COLOR);
+
+        let (image, _) = render_diff_map(&view, DiffMode::SideBySide, &a, &b, 12, 0, 0);
+        assert_eq!((image.width, image.height), (200 * 2 + GAP, 200));
+    }
+}
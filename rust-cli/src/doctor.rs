@@ -0,0 +1,221 @@
+//! 自己診断モジュール
+//!
+//! 組み込みのデータテーブルの整合性チェックと、各アルゴリズムの簡易動作確認を行う
+
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::biome::{find_nearest_biome, get_biome_at, BiomeType};
+use crate::algorithms::height::{estimate_surface_y, SEA_LEVEL};
+use crate::algorithms::slime::is_slime_chunk;
+use crate::algorithms::spawn::estimate_spawn;
+use crate::coords::{BlockPos, Dimension};
+use crate::structures::{default_types, find_in_dimension, find_strongholds, find_structures, PlacementModel, StructureType};
+use crate::triangulate::{triangulate, Throw};
+
+/// 動作確認に使うシード
+const SMOKE_TEST_SEED: i64 = 12345;
+
+/// 診断項目の結果
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    /// 失敗時の詳細
+    pub detail: String,
+}
+
+fn check(name: &str, failures: Vec<String>) -> Check {
+    Check {
+        name: name.to_string(),
+        passed: failures.is_empty(),
+        detail: failures.join("; "),
+    }
+}
+
+/// バージョン文字列が "数字.数字.数字" 形式か判定
+fn is_valid_version(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|p| !p.is_empty() && p.parse::<u32>().is_ok())
+}
+
+/// 構造物テーブルの整合性
+fn check_structure_table() -> Vec<Check> {
+    let mut id_failures = Vec::new();
+    let mut ids = HashSet::new();
+    for st in StructureType::ALL {
+        if !ids.insert(st.id()) {
+            id_failures.push(format!("識別子 {} が重複", st.id()));
+        }
+    }
+
+    let mut spacing_failures = Vec::new();
+    for st in StructureType::ALL {
+        if st.separation() <= 0 || st.spacing() <= st.separation() {
+            spacing_failures.push(format!(
+                "{}: spacing={} separation={}",
+                st.id(),
+                st.spacing(),
+                st.separation()
+            ));
+        }
+    }
+
+    // 同じディメンションのグリッド配置の構造物同士でソルトが重複すると同じ位置に生成されてしまう
+    let mut salt_failures = Vec::new();
+    let mut salts = HashSet::new();
+    for st in StructureType::ALL.iter().filter(|st| st.placement() == PlacementModel::Grid) {
+        if !salts.insert((st.dimension(), st.salt())) {
+            salt_failures.push(format!("{}: ソルト {} が重複", st.id(), st.salt()));
+        }
+    }
+
+    let version_failures = StructureType::ALL
+        .iter()
+        .filter(|st| !is_valid_version(st.since_version()))
+        .map(|st| format!("{}: {}", st.id(), st.since_version()))
+        .collect();
+
+    vec![
+        check("構造物: 識別子の重複なし", id_failures),
+        check("構造物: spacing > separation > 0", spacing_failures),
+        check("構造物: グリッド配置のソルトの重複なし", salt_failures),
+        check("構造物: バージョン表記", version_failures),
+    ]
+}
+
+/// バイオームテーブルの整合性
+fn check_biome_table() -> Vec<Check> {
+    let mut id_failures = Vec::new();
+    let mut ids = HashSet::new();
+    for biome in BiomeType::ALL {
+        if !ids.insert(biome.id()) {
+            id_failures.push(format!("識別子 {} が重複", biome.id()));
+        }
+        if BiomeType::from_str(biome.id()) != Some(biome) {
+            id_failures.push(format!("識別子 {} から {:?} に戻せない", biome.id(), biome));
+        }
+    }
+
+    let param_failures = BiomeType::ALL
+        .iter()
+        .filter(|b| !(0.0..=1.0).contains(&b.rarity()) || b.sampling_step() <= 0)
+        .map(|b| format!("{}: rarity={} step={}", b.id(), b.rarity(), b.sampling_step()))
+        .collect();
+
+    vec![
+        check("バイオーム: 識別子の重複なし・往復変換", id_failures),
+        check("バイオーム: 希少度とサンプリング間隔", param_failures),
+    ]
+}
+
+/// 各アルゴリズムの決定性と簡易動作確認
+fn smoke_tests() -> Vec<Check> {
+    let seed = SMOKE_TEST_SEED;
+    let mut checks = Vec::new();
+
+    let mut failures = Vec::new();
+    for st in StructureType::ALL.iter().filter(|st| st.placement() != PlacementModel::NetherQuadrant) {
+        let first = find_structures(seed, BlockPos::new(0, 0), 3000, *st).unwrap_or_default();
+        if first.is_empty() {
+            failures.push(format!("{}: 結果が空", st.id()));
+        }
+        if first != find_structures(seed, BlockPos::new(0, 0), 3000, *st).unwrap_or_default() {
+            failures.push(format!("{}: 結果が一致しない", st.id()));
+        }
+    }
+    checks.push(check("動作確認: グリッド構造物", failures));
+
+    let mut failures = Vec::new();
+    let find_nether = || find_in_dimension(seed, Dimension::Nether, BlockPos::new(0, 0), 1000, default_types(Dimension::Nether)).unwrap_or_default();
+    let nether = find_nether();
+    if nether.is_empty() {
+        failures.push("結果が空".to_string());
+    }
+    if nether != find_nether() {
+        failures.push("結果が一致しない".to_string());
+    }
+    checks.push(check("動作確認: ネザー構造物", failures));
+
+    let strongholds = find_strongholds(seed);
+    let failures = if strongholds.len() == 128 {
+        Vec::new()
+    } else {
+        vec![format!("要塞の数が {}（128個のはず）", strongholds.len())]
+    };
+    checks.push(check("動作確認: 要塞", failures));
+
+    let mut failures = Vec::new();
+    if get_biome_at(seed, BlockPos::new(1000, -1000)) != get_biome_at(seed, BlockPos::new(1000, -1000)) {
+        failures.push("get_biome_at が一致しない".to_string());
+    }
+    if find_nearest_biome(seed, BlockPos::new(0, 0), 2000, "plains", |_| true) != find_nearest_biome(seed, BlockPos::new(0, 0), 2000, "plains", |_| true) {
+        failures.push("find_nearest_biome が一致しない".to_string());
+    }
+    checks.push(check("動作確認: バイオーム", failures));
+
+    let failures = (-1000..1000)
+        .step_by(100)
+        .map(|x| (x, estimate_surface_y(seed, x, -x)))
+        .filter(|(_, y)| !(SEA_LEVEL..=320).contains(y))
+        .map(|(x, y)| format!("X={}: Y={}", x, y))
+        .collect();
+    checks.push(check("動作確認: 地表高度", failures));
+
+    let slime_count = (0..100)
+        .flat_map(|x| (0..100).map(move |z| (x, z)))
+        .filter(|&(x, z)| is_slime_chunk(x, z))
+        .count();
+    let failures = if (800..1200).contains(&slime_count) {
+        Vec::new()
+    } else {
+        vec![format!("スライムチャンクの割合が {}/10000", slime_count)]
+    };
+    checks.push(check("動作確認: スライムチャンク", failures));
+
+    let (first, second) = (estimate_spawn(seed), estimate_spawn(seed));
+    let failures = if (first.x, first.z) == (second.x, second.z) {
+        Vec::new()
+    } else {
+        vec!["結果が一致しない".to_string()]
+    };
+    checks.push(check("動作確認: スポーン推定", failures));
+
+    let throws = [Throw::parse("0,0,-90").unwrap(), Throw::parse("1000,-1000,0").unwrap()];
+    let failures = match triangulate(&throws) {
+        Some(t) if (t.x, t.z) == (1000, 0) => Vec::new(),
+        other => vec![format!("期待値 (1000, 0) に対して {:?}", other)],
+    };
+    checks.push(check("動作確認: 三角測量", failures));
+
+    checks
+}
+
+/// すべての診断を実行
+pub fn run_checks() -> Vec<Check> {
+    let mut checks = check_structure_table();
+    checks.extend(check_biome_table());
+    checks.extend(smoke_tests());
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_checks_pass() {
+        for c in run_checks() {
+            assert!(c.passed, "{}: {}", c.name, c.detail);
+        }
+    }
+
+    #[test]
+    fn test_is_valid_version() {
+        assert!(is_valid_version("1.16.0"));
+        assert!(!is_valid_version("1.16"));
+        assert!(!is_valid_version("1.x.0"));
+    }
+}
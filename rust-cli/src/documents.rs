@@ -0,0 +1,460 @@
+//! 出力文書モジュール
+//!
+//! 各コマンドがJSONで出力する文書のうち、コマンド側で組み立てるもの。
+//! `schema` コマンドはこれらの型と各モジュールの結果型からJSON Schemaを生成する。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::column::ColumnBand;
+use crate::algorithms::deep_dark::{AncientCityCandidate, DeepDarkRegion};
+use crate::algorithms::dungeon::Dungeon;
+use crate::algorithms::loot::StructureLoot;
+use crate::algorithms::ore::{OreBlob, OreType, OreVein};
+use crate::algorithms::slime::{SlimeChunk, SlimeCluster};
+use crate::algorithms::spawn::SpawnEstimate;
+use crate::algorithms::underground::UndergroundSite;
+use crate::coords::XZ;
+use crate::clearance::{BuildArea, ClearArea};
+use crate::doctor::Check;
+use crate::farm::FarmSite;
+use crate::portal::{PlacementSuggestion, PortalLink};
+use crate::render::{DensityCell, Georeference};
+use crate::site::{LandCorridor, ScoreComponent, WoodAvailability};
+use crate::structures::{BoundingBox, Confidence, FortressArea};
+use crate::triangulate::{Throw, Triangulation};
+
+/// 見つかった構造物
+#[derive(Serialize, JsonSchema)]
+pub struct StructureResult {
+    pub structure_type: String,
+    pub x: i32,
+    pub z: i32,
+    pub distance: f64,
+    /// 座標の信頼度（exact: ブロック単位で一致、likely: 位置は一致するが生成条件は未確認、approximate: 近似）
+    pub confidence: Confidence,
+    /// 構造物のおおよその範囲（地図への描画やテレポート先の指定用）
+    pub bbox: BoundingBox,
+    /// 位置のバイオームの識別子（オーバーワールドの構造物のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biome: Option<String>,
+    /// ネザー要塞の推定範囲（ネザー要塞のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fortress_area: Option<FortressArea>,
+    /// --loot 指定時の予測（ルート予測に対応した構造物のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loot: Option<StructureLoot>,
+}
+
+/// structures / nether コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct SearchResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 検索中心からの距離順（同じ距離は構造物の識別子、X、Zの順）
+    pub structures: Vec<StructureResult>,
+    /// 制限時間（--timeout）を過ぎて途中までの結果を返した場合に true
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// biome コマンドの結果（見つからなかった場合は座標と距離を含まない）
+#[derive(Serialize, JsonSchema)]
+pub struct BiomeResult {
+    pub seed: i64,
+    pub target_biome: String,
+    pub found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+    /// 座標の信頼度（バイオームの生成は近似のため常に approximate）
+    pub confidence: Confidence,
+    /// 制限時間（--timeout）を過ぎて途中までの結果を返した場合に true
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// slime コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct SlimeResult {
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    pub slime_chunks: Vec<SlimeChunk>,
+    pub clusters: Vec<SlimeCluster>,
+}
+
+/// column コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct ColumnResult {
+    pub seed: i64,
+    pub x: i32,
+    pub z: i32,
+    /// 地表の推定の高さ
+    pub surface_y: i32,
+    /// 下から順
+    pub bands: Vec<ColumnBand>,
+}
+
+/// 拠点の候補の地点
+#[derive(Serialize, JsonSchema)]
+pub struct HubEntry {
+    pub x: i32,
+    pub z: i32,
+    /// 検索中心からの距離
+    pub distance: f64,
+    /// 地点から距離以内にある構造物（種類ごとに最も近いもの。distance は地点からの距離）
+    pub structures: Vec<StructureResult>,
+}
+
+/// hub コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct HubResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 地点から構造物までの最大の距離
+    pub distance: i32,
+    /// 距離以内にある必要がある構造物の種類の数
+    pub min_types: usize,
+    /// 行ける種類が多く、構造物までの距離の合計が短い順
+    pub hubs: Vec<HubEntry>,
+}
+
+/// score-location コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct LocationScoreResult {
+    pub seed: i64,
+    pub x: i32,
+    pub z: i32,
+    /// 重み付きの平均（0〜100点）
+    pub total: f64,
+    pub components: Vec<ScoreComponent>,
+}
+
+/// corridor コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct CorridorResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 海に出るまでの距離が長い順
+    pub corridors: Vec<LandCorridor>,
+}
+
+/// wood コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct WoodResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    pub search_radius: i32,
+    pub woods: Vec<WoodAvailability>,
+}
+
+/// build-site コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct BuildSiteResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    pub size: i32,
+    pub max_stddev: f64,
+    /// 検索中心に近い順
+    pub areas: Vec<BuildArea>,
+}
+
+/// farm コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct FarmResult {
+    pub seed: i64,
+    /// ファームの種類（guardian）
+    pub kind: String,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 点数の高い順（同じ点数は中心に近い順）
+    pub sites: Vec<FarmSite>,
+}
+
+/// nether-clear コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct NetherClearResult {
+    pub seed: i64,
+    /// 検索中心（ネザー座標）
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    pub clearance: i32,
+    /// 避けた構造物の識別子
+    pub avoid: Vec<String>,
+    pub areas: Vec<ClearArea>,
+}
+
+/// map コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct MapResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    pub blocks_per_pixel: i32,
+    pub width: usize,
+    pub height: usize,
+    /// 書き出したPNG画像
+    pub file: String,
+    /// 画像のピクセルとブロック座標の対応（画像の隣に .pgw と .json でも書き出す）
+    pub georeference: Georeference,
+    /// 地図に重ねた構造物
+    pub structures: Vec<StructureResult>,
+    /// --heatmap 指定時の区画ごとの構造物の数（北の行から順）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub density: Vec<DensityCell>,
+}
+
+/// diffmap コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct DiffMapResult {
+    /// 比較元（A）のシード
+    pub seed: i64,
+    /// 比較先（B）のシード
+    pub other_seed: i64,
+    pub version: String,
+    pub other_version: String,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    pub blocks_per_pixel: i32,
+    /// 描き方（overlay, side）
+    pub mode: String,
+    pub width: usize,
+    pub height: usize,
+    /// 書き出したPNG画像
+    pub file: String,
+    /// バイオームが違うピクセルの割合（0〜1）
+    pub changed_biome_ratio: f64,
+    /// Aにだけある構造物（近い順）
+    pub only_a: Vec<StructureResult>,
+    /// Bにだけある構造物（近い順）
+    pub only_b: Vec<StructureResult>,
+}
+
+/// version-diff コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct VersionDiffResult {
+    pub seed: i64,
+    /// 比較元のバージョン
+    pub from_version: String,
+    /// 比較先のバージョン
+    pub to_version: String,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 移動・出現・消滅した構造物
+    pub structures: Vec<StructureChangeEntry>,
+    /// 位置の変わらない構造物の数
+    pub unchanged_structures: usize,
+    /// 最寄りの位置が移動・出現・消滅したバイオーム
+    pub biomes: Vec<BiomeChangeEntry>,
+    /// 最寄りの位置の変わらないバイオームの数
+    pub unchanged_biomes: usize,
+}
+
+/// バージョン間の構造物の変化
+#[derive(Serialize, JsonSchema)]
+pub struct StructureChangeEntry {
+    pub structure_type: String,
+    /// 変化（moved, appeared, disappeared）
+    pub change: String,
+    /// 比較元のバージョンの位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<XZ>,
+    /// 比較先のバージョンの位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<XZ>,
+}
+
+/// バージョン間の最寄りのバイオームの変化
+#[derive(Serialize, JsonSchema)]
+pub struct BiomeChangeEntry {
+    pub biome: String,
+    /// 変化（moved, appeared, disappeared）
+    pub change: String,
+    /// 比較元のバージョンの最寄りの位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<XZ>,
+    /// 比較先のバージョンの最寄りの位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<XZ>,
+}
+
+/// heightmap コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct HeightmapResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 1ピクセルあたりのブロック数
+    pub step: i32,
+    pub width: usize,
+    pub height: usize,
+    pub min_y: i32,
+    pub max_y: i32,
+    /// 書き出したファイル
+    pub files: Vec<String>,
+}
+
+/// deepdark コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct DeepDarkResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 面積の大きい順
+    pub regions: Vec<DeepDarkRegion>,
+    /// 検索中心からの距離順
+    pub cities: Vec<AncientCityCandidate>,
+}
+
+/// dungeon コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct DungeonResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 検索中心からの距離順
+    pub dungeons: Vec<Dungeon>,
+}
+
+/// ore コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct OreResult {
+    pub seed: i64,
+    pub ore: OreType,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 検索中心からの距離順
+    pub blobs: Vec<OreBlob>,
+}
+
+/// veins コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct VeinResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 検索中心からの距離順
+    pub veins: Vec<OreVein>,
+}
+
+/// underground コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct UndergroundResult {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 検索中心からの距離順
+    pub sites: Vec<UndergroundSite>,
+}
+
+/// spawn コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct SpawnResult {
+    pub seed: i64,
+    pub spawn: SpawnEstimate,
+}
+
+/// 推定地点に最も近い予測要塞
+#[derive(Serialize, JsonSchema)]
+pub struct StrongholdMatch {
+    pub x: i32,
+    pub z: i32,
+    pub distance_from_estimate: f64,
+}
+
+/// triangulate コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct TriangulateResult {
+    pub throws: Vec<Throw>,
+    pub estimate: Triangulation,
+    /// シード指定時のみ
+    pub stronghold: Option<StrongholdMatch>,
+}
+
+/// list コマンドの構造物
+#[derive(Serialize, JsonSchema)]
+pub struct StructureEntry {
+    pub id: String,
+    pub name: String,
+    /// -t オプションで識別子の代わりに使える別名
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// grid, nether_quadrant, chunk, ring
+    pub placement: String,
+    pub spacing: Option<i32>,
+    pub separation: Option<i32>,
+    pub salt: Option<i64>,
+    /// 対応バージョン（"1.10.0+" 等。カスタム構造物は "custom"）
+    pub versions: String,
+    /// 実験的機能（--experimental）として生成されるバージョン（"1.20.50+" 等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental_versions: Option<String>,
+    /// 対象バージョンで生成されるか
+    pub available: bool,
+}
+
+/// list コマンドのバイオーム
+#[derive(Serialize, JsonSchema)]
+pub struct BiomeEntry {
+    pub id: String,
+    pub name: String,
+    pub rarity: f64,
+    pub versions: String,
+    /// 実験的機能（--experimental）として生成されるバージョン
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental_versions: Option<String>,
+    /// 対象バージョンで生成されるか
+    pub available: bool,
+}
+
+/// list コマンドの結果（--kind で選んだ種類だけを含む）
+#[derive(Serialize, JsonSchema)]
+pub struct ListResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structures: Option<Vec<StructureEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biomes: Option<Vec<BiomeEntry>>,
+}
+
+/// portal コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct PortalResult {
+    pub link: PortalLink,
+    /// 接続先から戻った場合の接続
+    pub return_link: PortalLink,
+    pub returns_to_new_portal: bool,
+    /// --target 指定時のみ
+    pub suggestion: Option<PlacementSuggestion>,
+}
+
+/// doctor コマンドの結果
+#[derive(Serialize, JsonSchema)]
+pub struct DoctorResult {
+    pub passed: bool,
+    pub checks: Vec<Check>,
+}
@@ -0,0 +1,123 @@
+//! エラーと終了コードモジュール
+//!
+//! 終了コード:
+//! - 0: 結果が見つかった（または正常に完了した）
+//! - 1: 検索結果が見つからなかった
+//! - 2: 引数・入力・設定の誤り
+//! - 3: 内部エラー（ファイルの読み書き、自己診断の失敗等）
+
+use thiserror::Error;
+
+use crate::coords::Dimension;
+
+pub const EXIT_FOUND: i32 = 0;
+pub const EXIT_NOT_FOUND: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_INTERNAL: i32 = 3;
+
+/// コマンドの実行結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Found,
+    NotFound,
+}
+
+impl Outcome {
+    /// 結果の有無から実行結果を作る
+    pub fn from_found(found: bool) -> Outcome {
+        if found {
+            Outcome::Found
+        } else {
+            Outcome::NotFound
+        }
+    }
+
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Found => EXIT_FOUND,
+            Outcome::NotFound => EXIT_NOT_FOUND,
+        }
+    }
+}
+
+/// コマンドのエラー
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("シード値を --seed または設定ファイルで指定してください")]
+    MissingSeed,
+
+    /// 引数や入力ファイルの内容が不正
+    #[error("{0}")]
+    Usage(String),
+
+    /// 設定ファイルが読めない・不正
+    #[error("{0}")]
+    Config(String),
+
+    /// ブックマーク・履歴ファイルの読み書きに失敗
+    #[cfg_attr(not(feature = "db"), allow(dead_code))] // db 機能なしのビルドでは作らない
+    #[error("{0}")]
+    Storage(String),
+
+    /// 端末・標準入出力の操作に失敗
+    #[error("入出力エラー: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 自己診断の失敗等、ツール自体の不具合
+    #[error("{0}")]
+    Internal(String),
+}
+
+/// 検索の引数の誤り（検索して見つからなかったこととは区別する）
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SearchError {
+    #[error("不明なバイオーム: {0}（一覧は list コマンド）")]
+    UnknownBiome(String),
+
+    #[error("検索半径は1以上を指定してください（指定: {0}）")]
+    InvalidRadius(i32),
+
+    /// 中心から半径の範囲が計算できる座標の範囲を超える
+    #[error("検索範囲（中心 X={x}, Z={z}、半径 {radius}）が座標の上限 ±{limit} を超えています")]
+    OutOfRange { x: i32, z: i32, radius: i32, limit: i64 },
+
+    #[error("不明な座標の種類: {0}")]
+    UnknownCoordinateKind(String),
+
+    /// 入力の座標が表すブロック範囲が座標の型に収まらない
+    #[error("{kind} 座標 X={x}, Z={z} はブロック座標の範囲を超えています")]
+    CoordinateOutOfRange { kind: String, x: i32, z: i32 },
+
+    #[error("{structure} は {} の構造物ではありません", dimension.id())]
+    WrongDimension { structure: String, dimension: Dimension },
+}
+
+impl From<SearchError> for CliError {
+    fn from(e: SearchError) -> CliError {
+        CliError::Usage(e.to_string())
+    }
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::MissingSeed | CliError::Usage(_) | CliError::Config(_) => EXIT_USAGE,
+            CliError::Storage(_) | CliError::Io(_) | CliError::Internal(_) => EXIT_INTERNAL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(Outcome::from_found(true).exit_code(), 0);
+        assert_eq!(Outcome::from_found(false).exit_code(), 1);
+        assert_eq!(CliError::MissingSeed.exit_code(), 2);
+        assert_eq!(CliError::Usage("x".to_string()).exit_code(), 2);
+        assert_eq!(CliError::Storage("x".to_string()).exit_code(), 3);
+        assert_eq!(CliError::from(SearchError::InvalidRadius(0)).exit_code(), 2);
+    }
+}
@@ -0,0 +1,470 @@
+//! 地図エクスプローラーモジュール
+//!
+//! バイオームの色分け地図に構造物の位置を重ねて表示する全画面モード。
+//! 矢印キー（hjkl）で移動、+/- で拡大縮小、Tab で構造物を選んで詳細を確認できる。
+//! 構造物はバックグラウンドで検索するため、広い範囲を表示しても検索中に操作できる。
+
+use std::io;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::algorithms::biome::{get_biome_at, BiomeType};
+use crate::coords::{overworld_to_nether, BlockPos, Dimension};
+use crate::custom::custom_structures;
+use crate::palette::PalettePreset;
+use crate::structures::{
+    find_kind, find_strongholds, find_structures, result_order, stronghold_name, StructureHit, StructureKind, StructureType, STRONGHOLD_ID,
+    STRONGHOLD_SINCE_VERSION,
+};
+use crate::task::SearchHandle;
+use crate::travel::distance_2d;
+use crate::version::McVersion;
+
+/// 拡大率（1セルあたりのブロック数）
+const ZOOM_LEVELS: [i32; 8] = [4, 8, 16, 32, 64, 128, 256, 512];
+
+/// 1回の移動量（セル数）
+const PAN_CELLS: i32 = 8;
+
+/// 詳細欄の幅
+const DETAILS_WIDTH: u16 = 36;
+
+/// 構造物の検索中にキー入力を待つ間隔（この間隔で検索の完了を確認する）
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 地図上の構造物
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub id: &'static str,
+    pub name: String,
+    pub x: i32,
+    pub z: i32,
+}
+
+impl Marker {
+    fn from_hit(id: &'static str, hit: StructureHit) -> Marker {
+        Marker {
+            id,
+            name: hit.kind.display_name().to_string(),
+            x: hit.pos.x,
+            z: hit.pos.z,
+        }
+    }
+}
+
+/// 地図の表示範囲（変わったときだけ再計算する）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct View {
+    center_x: i32,
+    center_z: i32,
+    zoom: i32,
+    cols: i32,
+    rows: i32,
+}
+
+/// エクスプローラーの状態
+pub struct Explorer {
+    seed: i64,
+    mc_version: McVersion,
+    center_x: i32,
+    center_z: i32,
+    zoom_index: usize,
+    markers: Vec<Marker>,
+    biomes: Vec<Vec<BiomeType>>,
+    selected: Option<usize>,
+    view: Option<View>,
+    /// 表示範囲の構造物の検索（完了するまで前の表示範囲の構造物を表示する）
+    search: Option<SearchHandle<Vec<Marker>>>,
+}
+
+/// 構造物の地図記号
+fn marker_symbol(id: &str) -> char {
+    match id {
+        "village" => 'V',
+        "outpost" => 'O',
+        "monument" => 'M',
+        "mansion" => 'W',
+        "igloo" => 'I',
+        "witch_hut" => 'H',
+        "shipwreck" => 'S',
+        "buried_treasure" => 'T',
+        "ruined_portal" => 'R',
+        "trial_chamber" => 'C',
+        "mineshaft" => 'X',
+        "ancient_city" => 'A',
+        STRONGHOLD_ID => 'E',
+        _ => '?',
+    }
+}
+
+/// バイオームの表示色
+fn biome_color(biome: BiomeType) -> Color {
+    match biome {
+        BiomeType::Unknown => Color::Black,
+        _ => {
+            let [r, g, b] = PalettePreset::Default.color(biome);
+            Color::Rgb(r, g, b)
+        }
+    }
+}
+
+/// 中心から radius ブロック以内の構造物を検索（座標の上限の外まで移動した場合は空）
+fn find_markers(seed: i64, mc_version: McVersion, center_x: i32, center_z: i32, radius: i32) -> Vec<Marker> {
+    let mut markers = Vec::new();
+    for st in StructureType::ALL.iter().filter(|st| st.dimension() == Dimension::Overworld && st.is_available(mc_version)) {
+        markers.extend(
+            find_structures(seed, BlockPos::new(center_x, center_z), radius, *st)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|hit| Marker::from_hit(st.id(), hit)),
+        );
+    }
+    for custom in custom_structures().iter().filter(|c| c.dimension == Dimension::Overworld) {
+        markers.extend(
+            find_kind(seed, &StructureKind::Custom(custom.id.clone()), BlockPos::new(center_x, center_z), radius)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|hit| Marker::from_hit(&custom.id, hit)),
+        );
+    }
+    if mc_version.is_at_least(STRONGHOLD_SINCE_VERSION) {
+        markers.extend(find_strongholds(seed).into_iter().map(|hit| Marker::from_hit(STRONGHOLD_ID, hit)));
+    }
+    markers
+}
+
+impl Explorer {
+    pub fn new(seed: i64, mc_version: McVersion, center_x: i32, center_z: i32) -> Explorer {
+        Explorer {
+            seed,
+            mc_version,
+            center_x,
+            center_z,
+            zoom_index: 3,
+            markers: Vec::new(),
+            biomes: Vec::new(),
+            selected: None,
+            view: None,
+            search: None,
+        }
+    }
+
+    /// 1セルあたりのブロック数
+    pub fn zoom(&self) -> i32 {
+        ZOOM_LEVELS[self.zoom_index]
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom_index = self.zoom_index.saturating_sub(1);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom_index = (self.zoom_index + 1).min(ZOOM_LEVELS.len() - 1);
+    }
+
+    /// セル単位で表示中心を移動
+    pub fn pan(&mut self, dx: i32, dz: i32) {
+        self.center_x += dx * self.zoom();
+        self.center_z += dz * self.zoom();
+    }
+
+    /// セルの左上のブロック座標
+    fn cell_origin(&self, col: i32, row: i32, cols: i32, rows: i32) -> (i32, i32) {
+        (
+            self.center_x + (col - cols / 2) * self.zoom(),
+            self.center_z + (row - rows / 2) * self.zoom(),
+        )
+    }
+
+    /// ブロック座標を含むセル
+    fn cell_of(&self, x: i32, z: i32, cols: i32, rows: i32) -> (i32, i32) {
+        (
+            (x - self.center_x).div_euclid(self.zoom()) + cols / 2,
+            (z - self.center_z).div_euclid(self.zoom()) + rows / 2,
+        )
+    }
+
+    /// 表示範囲が変わっていればバイオームを計算し直し、構造物の検索を始める
+    pub fn refresh(&mut self, cols: i32, rows: i32) {
+        let view = View {
+            center_x: self.center_x,
+            center_z: self.center_z,
+            zoom: self.zoom(),
+            cols,
+            rows,
+        };
+        if self.view == Some(view) {
+            tracing::debug!("表示範囲が同じためキャッシュを使用");
+            return;
+        }
+        self.view = Some(view);
+
+        // バイオームはセルの中央でサンプリング
+        let half = self.zoom() / 2;
+        self.biomes = (0..rows)
+            .map(|row| {
+                (0..cols)
+                    .map(|col| {
+                        let (x, z) = self.cell_origin(col, row, cols, rows);
+                        get_biome_at(self.seed, BlockPos::new(x + half, z + half))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let half_width = (cols / 2 + 1) * self.zoom();
+        let half_height = (rows / 2 + 1) * self.zoom();
+        let radius = ((half_width as f64).hypot(half_height as f64)) as i32;
+        let (seed, mc_version, center_x, center_z) = (self.seed, self.mc_version, self.center_x, self.center_z);
+        // 前の表示範囲の検索は破棄すると取り消される
+        self.search = Some(SearchHandle::spawn(move || find_markers(seed, mc_version, center_x, center_z, radius)));
+    }
+
+    /// 構造物の検索が終わっていれば結果を表示範囲に反映する（反映した場合は真）
+    fn receive_markers(&mut self) -> bool {
+        let Some(markers) = self.search.as_mut().and_then(SearchHandle::poll) else {
+            return false;
+        };
+        self.search = None;
+        self.apply_markers(markers);
+        true
+    }
+
+    /// 表示範囲の構造物を中心に近い順に並べて表示する（選択中の構造物は選択を保つ）
+    fn apply_markers(&mut self, mut markers: Vec<Marker>) {
+        let Some(View { cols, rows, .. }) = self.view else {
+            return;
+        };
+        let previous = self.selected.map(|i| self.markers[i].clone());
+        markers.retain(|m| {
+            let (col, row) = self.cell_of(m.x, m.z, cols, rows);
+            (0..cols).contains(&col) && (0..rows).contains(&row)
+        });
+        markers.sort_by_cached_key(|m| result_order(m.id, BlockPos::new(m.x, m.z), BlockPos::new(self.center_x, self.center_z)));
+
+        self.selected = previous.and_then(|p| markers.iter().position(|m| *m == p));
+        self.markers = markers;
+    }
+
+    /// 次の構造物を選択（中心に近い順）
+    pub fn select_next(&mut self) {
+        if !self.markers.is_empty() {
+            self.selected = Some(self.selected.map_or(0, |i| (i + 1) % self.markers.len()));
+        }
+    }
+
+    /// 前の構造物を選択
+    pub fn select_prev(&mut self) {
+        if !self.markers.is_empty() {
+            let len = self.markers.len();
+            self.selected = Some(self.selected.map_or(len - 1, |i| (i + len - 1) % len));
+        }
+    }
+
+    /// 選択中の構造物を表示中心にする
+    pub fn center_on_selected(&mut self) {
+        if let Some(m) = self.selected_marker() {
+            (self.center_x, self.center_z) = (m.x, m.z);
+        }
+    }
+
+    pub fn selected_marker(&self) -> Option<&Marker> {
+        self.selected.map(|i| &self.markers[i])
+    }
+
+    /// キーが押されるたびと構造物の検索が終わったときに再描画する（q または Esc で終了）
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if self.search.is_some() && !event::poll(POLL_INTERVAL)? {
+                self.receive_markers();
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Left | KeyCode::Char('h') => self.pan(-PAN_CELLS, 0),
+                KeyCode::Right | KeyCode::Char('l') => self.pan(PAN_CELLS, 0),
+                KeyCode::Up | KeyCode::Char('k') => self.pan(0, -PAN_CELLS),
+                KeyCode::Down | KeyCode::Char('j') => self.pan(0, PAN_CELLS),
+                KeyCode::Char('+') | KeyCode::Char('=') => self.zoom_in(),
+                KeyCode::Char('-') => self.zoom_out(),
+                KeyCode::Tab | KeyCode::Char('n') => self.select_next(),
+                KeyCode::BackTab | KeyCode::Char('p') => self.select_prev(),
+                KeyCode::Enter | KeyCode::Char('c') => self.center_on_selected(),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [map_area, details_area] =
+            Layout::horizontal([Constraint::Min(10), Constraint::Length(DETAILS_WIDTH)]).areas(frame.area());
+
+        let map_block = Block::bordered().title(" 🗺️ 地図 ");
+        let inner = map_block.inner(map_area);
+        // 1セルを2文字幅にして縦横比をそろえる
+        let (cols, rows) = (inner.width as i32 / 2, inner.height as i32);
+        self.refresh(cols, rows);
+
+        frame.render_widget(Paragraph::new(self.map_lines(cols, rows)).block(map_block), map_area);
+        self.draw_details(frame, details_area);
+    }
+
+    fn map_lines(&self, cols: i32, rows: i32) -> Vec<Line<'static>> {
+        let mut symbols = vec![vec![None; cols as usize]; rows as usize];
+        for (i, m) in self.markers.iter().enumerate() {
+            let (col, row) = self.cell_of(m.x, m.z, cols, rows);
+            // 検索中は前の表示範囲の構造物が範囲外にある
+            if !(0..cols).contains(&col) || !(0..rows).contains(&row) {
+                continue;
+            }
+            let cell = &mut symbols[row as usize][col as usize];
+            // 同じセルに複数ある場合は選択中のものを優先
+            if cell.is_none() || self.selected == Some(i) {
+                *cell = Some((marker_symbol(m.id), self.selected == Some(i)));
+            }
+        }
+
+        (0..rows as usize)
+            .map(|row| {
+                let spans: Vec<Span> = (0..cols as usize)
+                    .map(|col| {
+                        let bg = biome_color(self.biomes[row][col]);
+                        let is_center = col as i32 == cols / 2 && row as i32 == rows / 2;
+                        match symbols[row][col] {
+                            Some((symbol, true)) => Span::styled(
+                                format!("{} ", symbol),
+                                Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+                            ),
+                            Some((symbol, false)) => Span::styled(
+                                format!("{} ", symbol),
+                                Style::default().fg(Color::White).bg(bg).add_modifier(Modifier::BOLD),
+                            ),
+                            None if is_center => Span::styled("+ ", Style::default().fg(Color::Red).bg(bg)),
+                            None => Span::styled("  ", Style::default().bg(bg)),
+                        }
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let center_biome = get_biome_at(self.seed, BlockPos::new(self.center_x, self.center_z));
+        let mut lines = vec![
+            Line::from(format!("シード: {}", self.seed)),
+            Line::from(format!("バージョン: {}", self.mc_version)),
+            Line::from(format!("中心: X={}, Z={}", self.center_x, self.center_z)),
+            Line::from(format!("中心のバイオーム: {}", center_biome.display_name())),
+            Line::from(format!("縮尺: 1マス = {}ブロック", self.zoom())),
+            Line::from(format!(
+                "表示中の構造物: {}件{}",
+                self.markers.len(),
+                if self.search.is_some() { "（検索中）" } else { "" }
+            )),
+            Line::from(""),
+        ];
+
+        match self.selected_marker() {
+            Some(m) => {
+                let biome = get_biome_at(self.seed, BlockPos::new(m.x, m.z));
+                lines.extend([
+                    Line::styled(format!("▶ {}", m.name), Style::default().add_modifier(Modifier::BOLD)),
+                    Line::from(format!("  座標: X={}, Z={}", m.x, m.z)),
+                    Line::from(format!(
+                        "  中心から: {:.0}ブロック",
+                        distance_2d(self.center_x, self.center_z, m.x, m.z)
+                    )),
+                    Line::from(format!("  ネザー: X={}, Z={}", overworld_to_nether(m.x), overworld_to_nether(m.z))),
+                    Line::from(format!("  バイオーム: {}", biome.display_name())),
+                ]);
+            }
+            None => lines.push(Line::from("Tab で構造物を選択")),
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled("記号", Style::default().add_modifier(Modifier::BOLD)));
+        for st in StructureType::ALL.iter().filter(|st| st.dimension() == Dimension::Overworld) {
+            lines.push(Line::from(format!("  {} {}", marker_symbol(st.id()), st.display_name())));
+        }
+        lines.push(Line::from(format!("  {} {}", marker_symbol(STRONGHOLD_ID), stronghold_name())));
+        if !custom_structures().is_empty() {
+            lines.push(Line::from("  ? カスタム構造物"));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("←↓↑→/hjkl 移動  +/- 拡大縮小"));
+        lines.push(Line::from("Tab/n・p 選択  Enter/c 中心へ"));
+        lines.push(Line::from("q/Esc 終了"));
+
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" 詳細 ")), area);
+    }
+}
+
+/// 全画面の地図エクスプローラーを起動（終了時に端末を元に戻す）
+pub fn run_explorer(explorer: Explorer) -> io::Result<()> {
+    ratatui::run(|terminal| explorer.run(terminal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::LATEST_VERSION;
+
+    #[test]
+    fn test_cell_mapping_and_zoom() {
+        let mut explorer = Explorer::new(12345, LATEST_VERSION, -100, 50);
+        assert_eq!(explorer.zoom(), 32);
+        let (x, z) = explorer.cell_origin(3, 7, 40, 20);
+        assert_eq!(explorer.cell_of(x, z, 40, 20), (3, 7));
+        assert_eq!(explorer.cell_of(-100, 50, 40, 20), (20, 10));
+
+        explorer.pan(2, -1);
+        assert_eq!((explorer.center_x, explorer.center_z), (-36, 18));
+        for _ in 0..10 {
+            explorer.zoom_in();
+        }
+        assert_eq!(explorer.zoom(), ZOOM_LEVELS[0]);
+    }
+
+    /// 構造物の検索が終わるまで待つ
+    fn wait_markers(explorer: &mut Explorer) {
+        while !explorer.receive_markers() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_markers_and_selection() {
+        let mut explorer = Explorer::new(12345, LATEST_VERSION, 0, 0);
+        explorer.zoom_out();
+        explorer.refresh(40, 20);
+        wait_markers(&mut explorer);
+        assert!(!explorer.markers.is_empty());
+        assert_eq!(explorer.biomes.len(), 20);
+
+        explorer.select_prev();
+        assert_eq!(explorer.selected, Some(explorer.markers.len() - 1));
+        explorer.select_next();
+        assert_eq!(explorer.selected, Some(0));
+
+        // 選択した構造物を中心にしても選択が保たれる
+        let marker = explorer.selected_marker().unwrap().clone();
+        explorer.center_on_selected();
+        explorer.refresh(40, 20);
+        wait_markers(&mut explorer);
+        assert_eq!(explorer.selected_marker(), Some(&marker));
+    }
+}
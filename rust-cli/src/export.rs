@@ -0,0 +1,161 @@
+//! エクスポートモジュール
+//!
+//! 検索結果をゲーム内コマンド等の形式に変換する
+
+use crate::algorithms::height::{estimate_surface_y, HeightGrid};
+use crate::coords::Dimension;
+use crate::structures::StructureHit;
+
+/// ネザーでのテレポート先Y座標（要塞・バスティオンの床付近）
+const NETHER_TP_Y: i32 = 70;
+
+/// エンドでのテレポート先Y座標（外周の島の地表付近）
+const END_TP_Y: i32 = 65;
+
+/// ディメンションのテレポート先Y座標を取得
+pub fn target_y(seed: i64, dimension: Dimension, x: i32, z: i32) -> i32 {
+    match dimension {
+        Dimension::Nether => NETHER_TP_Y,
+        Dimension::End => END_TP_Y,
+        Dimension::Overworld => estimate_surface_y(seed, x, z),
+    }
+}
+
+/// 検索結果を `/tp` と `/execute` コマンドに変換
+///
+/// `dimension` の識別子（overworld, nether, the_end）を `/execute in` に渡す。
+/// 出力は `#` コメント付きで、そのまま .mcfunction としても使える。
+pub fn export_commands(seed: i64, dimension: Dimension, structures: &[StructureHit]) -> String {
+    let mut lines = Vec::new();
+
+    for hit in structures {
+        let (x, z) = (hit.pos.x, hit.pos.z);
+        lines.extend(teleport_lines(hit.kind.display_name(), dimension, x, target_y(seed, dimension, x, z), z));
+    }
+
+    lines.join("\n")
+}
+
+/// 1地点分のコメント行と `/tp`・`/execute` コマンド
+pub fn teleport_lines(name: &str, dimension: Dimension, x: i32, y: i32, z: i32) -> [String; 3] {
+    [
+        format!("# {} X={}, Z={}", name, x, z),
+        format!("/tp @s {} {} {}", x, y, z),
+        format!("/execute in {} run tp @s {} {} {}", dimension.id(), x, y, z),
+    ]
+}
+
+/// --copy で指定できる形式
+pub const COPY_FORMATS: &[&str] = &["coords", "tp", "execute"];
+
+/// クリップボードにコピーする1地点分のテキスト
+///
+/// coords は "X Y Z"、tp と execute はそれぞれのコマンド。不明な形式は None。
+pub fn copy_text(format: &str, seed: i64, dimension: Dimension, x: i32, z: i32) -> Option<String> {
+    let y = target_y(seed, dimension, x, z);
+    match format {
+        "coords" => Some(format!("{} {} {}", x, y, z)),
+        "tp" => Some(format!("/tp @s {} {} {}", x, y, z)),
+        "execute" => Some(format!("/execute in {} run tp @s {} {} {}", dimension.id(), x, y, z)),
+        _ => None,
+    }
+}
+
+/// 検索結果を Amulet Editor の「Go To」に貼り付ける座標の一覧に変換
+///
+/// 1地点ごとに `#` のコメント行（表示名と座標）と "X Y Z" の行を出力する。Amulet の「Go To」は
+/// "X Y Z" の形式の座標を貼り付けて移動できる。先頭のコメントにディメンションを Amulet の表記
+/// （minecraft:the_nether 等）で書くので、移動の前に Amulet でそのディメンションに切り替える。
+pub fn export_amulet(seed: i64, dimension: Dimension, structures: &[StructureHit]) -> String {
+    let amulet_dimension = match dimension {
+        Dimension::Overworld => "minecraft:overworld",
+        Dimension::Nether => "minecraft:the_nether",
+        Dimension::End => "minecraft:the_end",
+    };
+
+    let mut lines = vec![format!("# Amulet Editor の「Go To」に貼り付ける座標（ディメンション: {}）", amulet_dimension)];
+    for hit in structures {
+        let (x, z) = (hit.pos.x, hit.pos.z);
+        lines.push(format!("# {} X={}, Z={}", hit.kind.display_name(), x, z));
+        lines.push(format!("{} {} {}", x, target_y(seed, dimension, x, z), z));
+    }
+
+    lines.join("\n")
+}
+
+/// 高さマップの1辺の最大ピクセル数
+pub const MAX_HEIGHTMAP_SIZE: usize = 4096;
+
+/// 高さの格子をCSVに変換
+///
+/// 1行目は各列のX座標、2行目以降は先頭がZ座標でその後に各列の高さが並ぶ。
+pub fn heightmap_csv(grid: &HeightGrid) -> String {
+    let mut lines = Vec::with_capacity(grid.height + 1);
+    let header: Vec<String> = (0..grid.width).map(|col| grid.x_at(col).to_string()).collect();
+    lines.push(format!("z\\x,{}", header.join(",")));
+
+    for row in 0..grid.height {
+        let heights: Vec<String> = (0..grid.width).map(|col| grid.get(col, row).to_string()).collect();
+        lines.push(format!("{},{}", grid.z_at(row), heights.join(",")));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// 高さの格子をグレースケールの画素に変換（範囲内の最低を黒、最高を白にする）
+pub fn heightmap_pixels(grid: &HeightGrid) -> Vec<u8> {
+    let (min, max) = grid.range();
+    let span = (max - min).max(1) as f64;
+    grid.heights
+        .iter()
+        .map(|&y| ((y - min) as f64 / span * 255.0).round() as u8)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::structures::{StructureKind, StructureType};
+
+    #[test]
+    fn test_export_nether_commands() {
+        let structures = vec![StructureHit::new(StructureKind::Builtin(StructureType::NetherFortress), BlockPos::new(120, -340))];
+        let output = export_commands(12345, Dimension::Nether, &structures);
+        assert!(output.contains("/tp @s 120 70 -340"));
+        assert!(output.contains("/execute in nether run tp @s 120 70 -340"));
+    }
+
+    #[test]
+    fn test_export_amulet_goto() {
+        let structures = vec![StructureHit::new(StructureKind::Builtin(StructureType::BastionRemnant), BlockPos::new(-20, 40))];
+        let output = export_amulet(12345, Dimension::Nether, &structures);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with('#') && lines[0].contains("minecraft:the_nether"));
+        assert_eq!(lines[1], format!("# {} X=-20, Z=40", StructureType::BastionRemnant.display_name()));
+        // コメント以外の行は「Go To」に貼り付ける "X Y Z"
+        assert_eq!(lines[2], "-20 70 40");
+    }
+
+    #[test]
+    fn test_copy_text() {
+        assert_eq!(copy_text("coords", 12345, Dimension::Nether, 120, -340).unwrap(), "120 70 -340");
+        assert_eq!(copy_text("tp", 12345, Dimension::Nether, 120, -340).unwrap(), "/tp @s 120 70 -340");
+        assert!(copy_text("waypoint", 12345, Dimension::Nether, 120, -340).is_none());
+    }
+
+    #[test]
+    fn test_heightmap_csv() {
+        let grid = HeightGrid {
+            min_x: -16,
+            min_z: 32,
+            step: 16,
+            width: 2,
+            height: 2,
+            heights: vec![60, 70, 80, 90],
+        };
+        assert_eq!(heightmap_csv(&grid), "z\\x,-16,0\n32,60,70\n48,80,90\n");
+        assert_eq!(heightmap_pixels(&grid), vec![0, 85, 170, 255]);
+    }
+}
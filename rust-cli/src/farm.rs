@@ -0,0 +1,329 @@
+//! ファームの候補地の評価モジュール
+//!
+//! ファーム（トラップタワー）を作る構造物の候補を周辺の地形やバイオーム、他の構造物との位置関係で
+//! 0〜100点で採点し、点数の高い順に並べる。最寄りの構造物ではなく作りやすい構造物を選ぶためのもの。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::biome::{get_biome_at, BiomeType};
+use crate::algorithms::height::sample_heights;
+use crate::coords::BlockPos;
+use crate::error::SearchError;
+use crate::i18n::is_english;
+use crate::structures::{find_structures, StructureHit, StructureType};
+use crate::travel::distance_2d;
+
+/// バイオームを調べる間隔（ブロック）
+const SAMPLE_STEP: i32 = 16;
+
+/// 海底神殿の周りの深海の割合を調べる半径（神殿と、湧き潰しの範囲の海）
+const MONUMENT_AREA_RADIUS: i32 = 64;
+
+/// 陸を探す最大の距離。これより離れていれば満点（待機場所から128ブロック以内の陸は湧き枠を奪う）
+const LAND_DISTANCE_FULL: i32 = 128;
+
+/// 待機場所をまとめられる、近くの海底神殿の距離
+const NEIGHBOR_DISTANCE: i32 = 256;
+
+/// 前哨基地の周りの地形の平らさを調べる半径と間隔（襲撃者の湧く範囲と処理層の広さ）
+const OUTPOST_AREA_RADIUS: i32 = 48;
+const OUTPOST_HEIGHT_STEP: i32 = 8;
+
+/// 高さの標準偏差がこれ以上なら平らさの点数は0点
+const FLATNESS_STDDEV_ZERO: f64 = 8.0;
+
+/// 村からこれ以上離れていれば満点（近いと不吉な予感を持ったまま村に入り襲撃が起きやすい）
+const VILLAGE_DISTANCE_FULL: i32 = 512;
+
+/// ファームの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FarmKind {
+    /// ガーディアントラップ（海底神殿）
+    Guardian,
+    /// 襲撃者トラップ（ピリジャー前哨基地）
+    Raid,
+}
+
+impl FarmKind {
+    pub const ALL: [FarmKind; 2] = [FarmKind::Guardian, FarmKind::Raid];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            FarmKind::Guardian => "guardian",
+            FarmKind::Raid => "raid",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<FarmKind> {
+        FarmKind::ALL.into_iter().find(|k| k.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                FarmKind::Guardian => "Guardian farm",
+                FarmKind::Raid => "Raid farm",
+            };
+        }
+        match self {
+            FarmKind::Guardian => "ガーディアントラップ",
+            FarmKind::Raid => "襲撃者トラップ",
+        }
+    }
+
+    /// 候補になる構造物
+    pub fn structure(&self) -> StructureType {
+        match self {
+            FarmKind::Guardian => StructureType::OceanMonument,
+            FarmKind::Raid => StructureType::PillagerOutpost,
+        }
+    }
+
+    /// 採点に使う周りの構造物の種類と、候補から数える距離
+    fn neighbor(&self) -> (StructureType, i32) {
+        match self {
+            FarmKind::Guardian => (StructureType::OceanMonument, NEIGHBOR_DISTANCE),
+            FarmKind::Raid => (StructureType::Village, VILLAGE_DISTANCE_FULL),
+        }
+    }
+}
+
+/// 採点の項目
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FarmFactor {
+    pub id: String,
+    /// 項目の値（割合は0〜1、距離はブロック、件数）
+    pub value: f64,
+    pub points: u32,
+    pub max_points: u32,
+    /// 値の説明（テキスト出力用）
+    pub description: String,
+}
+
+impl FarmFactor {
+    /// `ratio`（0〜1）に応じて最大 `max_points` 点を与える
+    fn new(id: &str, value: f64, ratio: f64, max_points: u32, description: String) -> FarmFactor {
+        FarmFactor {
+            id: id.to_string(),
+            value,
+            points: (ratio.clamp(0.0, 1.0) * max_points as f64).round() as u32,
+            max_points,
+            description,
+        }
+    }
+}
+
+/// 評価したファームの候補地
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FarmSite {
+    pub structure_type: String,
+    pub x: i32,
+    pub z: i32,
+    /// 検索中心からの距離
+    pub distance: f64,
+    /// 0〜100点（項目の点数の合計）
+    pub score: u32,
+    pub factors: Vec<FarmFactor>,
+}
+
+/// 中心から radius ブロック以内の候補を評価し、点数の高い順（同じ点数は中心に近い順）に並べる
+pub fn evaluate_farm_sites(
+    seed: i64,
+    kind: FarmKind,
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+) -> Result<Vec<FarmSite>, SearchError> {
+    let st = kind.structure();
+    let hits = find_structures(seed, BlockPos::new(center_x, center_z), radius, st)?;
+    // 範囲の端の候補の近くにある範囲外の構造物も数えるため広めに探す
+    let (neighbor, distance) = kind.neighbor();
+    let around = find_structures(seed, BlockPos::new(center_x, center_z), radius.saturating_add(distance), neighbor)?;
+
+    let mut sites: Vec<FarmSite> = hits
+        .iter()
+        .map(|hit| {
+            let (x, z) = (hit.pos.x, hit.pos.z);
+            let factors = match kind {
+                FarmKind::Guardian => guardian_factors(seed, x, z, &around),
+                FarmKind::Raid => raid_factors(seed, x, z, &around),
+            };
+            FarmSite {
+                structure_type: hit.kind.display_name().to_string(),
+                x,
+                z,
+                distance: distance_2d(center_x, center_z, x, z),
+                score: factors.iter().map(|f| f.points).sum(),
+                factors,
+            }
+        })
+        .collect();
+    sites.sort_by(|a, b| b.score.cmp(&a.score).then(a.distance.total_cmp(&b.distance)));
+    Ok(sites)
+}
+
+/// ガーディアントラップの採点（深海の割合50点、陸までの距離35点、近くの海底神殿15点）
+fn guardian_factors(seed: i64, x: i32, z: i32, monuments: &[StructureHit]) -> Vec<FarmFactor> {
+    // 神殿の周りの深海の割合（浅い海や陸が混ざると水抜きと湧き潰しが増える）
+    let mut samples = 0;
+    let mut deep = 0;
+    for dx in (-MONUMENT_AREA_RADIUS..=MONUMENT_AREA_RADIUS).step_by(SAMPLE_STEP as usize) {
+        for dz in (-MONUMENT_AREA_RADIUS..=MONUMENT_AREA_RADIUS).step_by(SAMPLE_STEP as usize) {
+            if dx * dx + dz * dz > MONUMENT_AREA_RADIUS * MONUMENT_AREA_RADIUS {
+                continue;
+            }
+            samples += 1;
+            if get_biome_at(seed, BlockPos::new(x + dx, z + dz)) == BiomeType::DeepOcean {
+                deep += 1;
+            }
+        }
+    }
+    let coverage = deep as f64 / samples as f64;
+
+    // 最も近い陸（海以外のバイオーム）までの距離
+    // 正方形の輪の角は辺の中央より遠いため、次の輪の最も近い点が見つけた陸より遠くなるまで調べる
+    let mut land: Option<f64> = None;
+    for ring in 1..=LAND_DISTANCE_FULL / SAMPLE_STEP {
+        if land.is_some_and(|best| (ring * SAMPLE_STEP) as f64 > best) {
+            break;
+        }
+        let nearest = (-ring..=ring)
+            .flat_map(|i| [(i, -ring), (i, ring), (-ring, i), (ring, i)])
+            .map(|(i, j)| (x + i * SAMPLE_STEP, z + j * SAMPLE_STEP))
+            .filter(|&(px, pz)| !get_biome_at(seed, BlockPos::new(px, pz)).is_ocean())
+            .map(|(px, pz)| distance_2d(x, z, px, pz))
+            .min_by(f64::total_cmp);
+        land = match (land, nearest) {
+            (Some(best), Some(d)) => Some(best.min(d)),
+            (best, d) => best.or(d),
+        };
+    }
+    let land_distance = land.unwrap_or(LAND_DISTANCE_FULL as f64).min(LAND_DISTANCE_FULL as f64);
+
+    // 待機場所を共有できる近くの海底神殿
+    let neighbors = monuments
+        .iter()
+        .filter(|m| (m.pos.x, m.pos.z) != (x, z) && distance_2d(x, z, m.pos.x, m.pos.z) <= NEIGHBOR_DISTANCE as f64)
+        .count();
+
+    vec![
+        FarmFactor::new(
+            "deep_ocean_coverage",
+            coverage,
+            coverage,
+            50,
+            format!("周囲{}ブロックの深海の割合: {:.0}%", MONUMENT_AREA_RADIUS, coverage * 100.0),
+        ),
+        FarmFactor::new(
+            "land_distance",
+            land_distance,
+            land_distance / LAND_DISTANCE_FULL as f64,
+            35,
+            match land {
+                Some(d) if d < LAND_DISTANCE_FULL as f64 => format!("最も近い陸: {:.0}ブロック", d),
+                _ => format!("{}ブロック以内に陸なし", LAND_DISTANCE_FULL),
+            },
+        ),
+        FarmFactor::new(
+            "nearby_monuments",
+            neighbors as f64,
+            neighbors.min(1) as f64,
+            15,
+            format!("{}ブロック以内の他の海底神殿: {}件", NEIGHBOR_DISTANCE, neighbors),
+        ),
+    ]
+}
+
+/// 襲撃者トラップの採点（地形の平らさ40点、バイオーム25点、村からの距離35点）
+fn raid_factors(seed: i64, x: i32, z: i32, villages: &[StructureHit]) -> Vec<FarmFactor> {
+    // 周りの高さの標準偏差（起伏が大きいと整地と湧き潰しが増える）
+    let stddev = sample_heights(seed, x, z, OUTPOST_AREA_RADIUS, OUTPOST_HEIGHT_STEP).stddev();
+
+    // 木や氷の少ない開けたバイオームほど湧き潰しと整地が楽
+    let biome = get_biome_at(seed, BlockPos::new(x, z));
+    let openness = match biome {
+        BiomeType::Plains | BiomeType::Desert | BiomeType::Savanna | BiomeType::IceSpikes => 1.0,
+        BiomeType::Taiga | BiomeType::SnowyTaiga | BiomeType::CherryGrove | BiomeType::Forest => 0.5,
+        _ => 0.0,
+    };
+
+    // 最も近い村までの距離
+    let village = villages.iter().map(|v| distance_2d(x, z, v.pos.x, v.pos.z)).min_by(f64::total_cmp);
+    let village_distance = village.unwrap_or(VILLAGE_DISTANCE_FULL as f64).min(VILLAGE_DISTANCE_FULL as f64);
+
+    vec![
+        FarmFactor::new(
+            "terrain_flatness",
+            stddev,
+            1.0 - stddev / FLATNESS_STDDEV_ZERO,
+            40,
+            format!("周囲{}ブロックの高さの標準偏差: {:.1}ブロック", OUTPOST_AREA_RADIUS, stddev),
+        ),
+        FarmFactor::new("biome", openness, openness, 25, format!("バイオーム: {}", biome.display_name())),
+        FarmFactor::new(
+            "village_distance",
+            village_distance,
+            village_distance / VILLAGE_DISTANCE_FULL as f64,
+            35,
+            match village {
+                Some(d) if d < VILLAGE_DISTANCE_FULL as f64 => format!("最も近い村: {:.0}ブロック", d),
+                _ => format!("{}ブロック以内に村なし", VILLAGE_DISTANCE_FULL),
+            },
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guardian_sites() {
+        let sites = evaluate_farm_sites(12345, FarmKind::Guardian, 0, 0, 5000).unwrap();
+        assert!(!sites.is_empty());
+        for pair in sites.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+        }
+        for site in &sites {
+            assert_eq!(site.factors.len(), 3);
+            assert!(site.score <= 100);
+            assert_eq!(site.score, site.factors.iter().map(|f| f.points).sum::<u32>());
+            assert!(site.factors.iter().all(|f| f.points <= f.max_points));
+        }
+        assert_eq!(FarmKind::from_id("guardian"), Some(FarmKind::Guardian));
+
+        // 陸までの距離は範囲内の全ての地点から最も近い陸（輪の角より近い次の輪の地点も含む）
+        let steps = LAND_DISTANCE_FULL / SAMPLE_STEP;
+        for site in &sites {
+            let nearest = (-steps..=steps)
+                .flat_map(|i| (-steps..=steps).map(move |j| (i, j)))
+                .filter(|&(i, j)| (i, j) != (0, 0))
+                .map(|(i, j)| (site.x + i * SAMPLE_STEP, site.z + j * SAMPLE_STEP))
+                .filter(|&(px, pz)| !get_biome_at(12345, BlockPos::new(px, pz)).is_ocean())
+                .map(|(px, pz)| distance_2d(site.x, site.z, px, pz))
+                .fold(LAND_DISTANCE_FULL as f64, f64::min);
+            let land = &site.factors[1];
+            assert_eq!(land.id, "land_distance");
+            assert_eq!(land.value, nearest);
+            assert_eq!(land.points, (nearest / LAND_DISTANCE_FULL as f64 * 35.0).round() as u32);
+        }
+        assert!(evaluate_farm_sites(12345, FarmKind::Guardian, 0, 0, -1).is_err());
+    }
+
+    #[test]
+    fn test_raid_sites() {
+        let sites = evaluate_farm_sites(12345, FarmKind::Raid, 0, 0, 3000).unwrap();
+        assert!(!sites.is_empty());
+        for pair in sites.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+        }
+        for site in &sites {
+            let ids: Vec<&str> = site.factors.iter().map(|f| f.id.as_str()).collect();
+            assert_eq!(ids, ["terrain_flatness", "biome", "village_distance"]);
+            assert!(site.score <= 100);
+            assert!(site.factors[0].value >= 0.0);
+        }
+        assert_eq!(FarmKind::from_id("raid"), Some(FarmKind::Raid));
+    }
+}
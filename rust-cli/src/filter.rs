@@ -0,0 +1,153 @@
+//! スクリプトフィルタモジュール
+//!
+//! `--filter script.rhai` で指定したRhaiスクリプトを検索結果の候補ごとに評価し、
+//! 真を返した候補だけを残す。スクリプトからは `hit` で候補を参照できる。
+//!
+//! ```text
+//! // 平原の村のうち、1000ブロック以内のものだけ残す
+//! hit.type == "village" && hit.biome == "plains" && hit.distance < 1000.0
+//! ```
+//!
+//! `hit` のフィールド: `type`（識別子）, `name`（表示名）, `x`, `z`, `distance`,
+//! `biome`（オーバーワールドのみ。ネザーとエンドでは `()`）, `seed`, `dimension`
+
+#[cfg(feature = "script")]
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+
+#[cfg(feature = "script")]
+use crate::algorithms::biome::get_biome_at;
+#[cfg(feature = "script")]
+use crate::coords::BlockPos;
+use crate::coords::Dimension;
+use crate::structures::StructureHit;
+
+/// 1候補の評価で実行できる演算の上限（無限ループ対策）
+#[cfg(feature = "script")]
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// コンパイル済みのフィルタスクリプト
+#[cfg(feature = "script")]
+pub struct ScriptFilter {
+    engine: Engine,
+    ast: AST,
+}
+
+#[cfg(feature = "script")]
+impl ScriptFilter {
+    /// スクリプトファイルを読み込む
+    pub fn load(path: &str) -> Result<ScriptFilter, String> {
+        let source =
+            std::fs::read_to_string(path).map_err(|e| format!("ファイルを読み込めません: {} ({})", path, e))?;
+        ScriptFilter::from_source(&source).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// スクリプトをコンパイル
+    pub fn from_source(source: &str) -> Result<ScriptFilter, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine.compile(source).map_err(|e| format!("スクリプトの構文エラー: {}", e))?;
+        Ok(ScriptFilter { engine, ast })
+    }
+
+    /// 候補を残すか判定
+    pub fn keep(&self, hit: Map) -> Result<bool, String> {
+        let mut scope = Scope::new();
+        scope.push_constant("hit", hit);
+        self.engine
+            .eval_ast_with_scope::<bool>(&mut scope, &self.ast)
+            .map_err(|e| format!("スクリプトの実行エラー: {}", e))
+    }
+
+    /// 構造物の検索結果を絞り込む
+    pub fn apply(
+        &self,
+        seed: i64,
+        dimension: Dimension,
+        center_x: i32,
+        center_z: i32,
+        structures: Vec<StructureHit>,
+    ) -> Result<Vec<StructureHit>, String> {
+        let mut kept = Vec::new();
+        for hit in structures {
+            if self.keep(hit_map(seed, dimension, center_x, center_z, &hit))? {
+                kept.push(hit);
+            } else {
+                tracing::debug!(id = hit.kind.id(), x = hit.pos.x, z = hit.pos.z, "フィルタで除外");
+            }
+        }
+        Ok(kept)
+    }
+}
+
+/// script 機能なしでビルドした場合のフィルタ（スクリプトを読み込めないため値を作れない）
+#[cfg(not(feature = "script"))]
+pub enum ScriptFilter {}
+
+#[cfg(not(feature = "script"))]
+impl ScriptFilter {
+    pub fn load(_path: &str) -> Result<ScriptFilter, String> {
+        Err("--filter には script 機能を有効にしたビルドが必要です（cargo build --features script）".to_string())
+    }
+
+    pub fn apply(
+        &self,
+        _seed: i64,
+        _dimension: Dimension,
+        _center_x: i32,
+        _center_z: i32,
+        _structures: Vec<StructureHit>,
+    ) -> Result<Vec<StructureHit>, String> {
+        match *self {}
+    }
+}
+
+/// スクリプトに渡す候補
+#[cfg(feature = "script")]
+fn hit_map(seed: i64, dimension: Dimension, center_x: i32, center_z: i32, structure: &StructureHit) -> Map {
+    let BlockPos { x, z } = structure.pos;
+    let distance = (((x - center_x) as f64).powi(2) + ((z - center_z) as f64).powi(2)).sqrt();
+    let biome = match dimension {
+        Dimension::Overworld => Dynamic::from(get_biome_at(seed, BlockPos::new(x, z)).id().to_string()),
+        _ => Dynamic::UNIT,
+    };
+
+    let mut hit = Map::new();
+    hit.insert("type".into(), Dynamic::from(structure.kind.id().to_string()));
+    hit.insert("name".into(), Dynamic::from(structure.kind.display_name().to_string()));
+    hit.insert("x".into(), Dynamic::from(x as i64));
+    hit.insert("z".into(), Dynamic::from(z as i64));
+    hit.insert("distance".into(), Dynamic::from(distance));
+    hit.insert("biome".into(), biome);
+    hit.insert("seed".into(), Dynamic::from(seed));
+    hit.insert("dimension".into(), Dynamic::from(dimension.id().to_string()));
+    hit
+}
+
+#[cfg(all(test, feature = "script"))]
+mod tests {
+    use super::*;
+    use crate::structures::{StructureKind, StructureType};
+
+    #[test]
+    fn test_apply_filter() {
+        let village = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::Village), BlockPos::new(x, z));
+        let monument = StructureHit::new(StructureKind::Builtin(StructureType::OceanMonument), BlockPos::new(200, 0));
+        let structures = vec![village(100, 0), village(3000, 0), monument];
+
+        let filter = ScriptFilter::from_source(r#"hit.type == "village" && hit.distance < 1000.0"#).unwrap();
+        let kept = filter.apply(12345, Dimension::Overworld, 0, 0, structures).unwrap();
+        assert_eq!(kept, vec![village(100, 0)]);
+    }
+
+    #[test]
+    fn test_invalid_scripts() {
+        assert!(ScriptFilter::from_source("hit.x >").is_err());
+
+        let filter = ScriptFilter::from_source("hit.x").unwrap();
+        let hit = StructureHit::new(StructureKind::Custom("x".to_string()), BlockPos::new(1, 1));
+        assert!(filter.keep(hit_map(1, Dimension::Nether, 0, 0, &hit)).is_err());
+
+        let filter = ScriptFilter::from_source("loop {}").unwrap();
+        assert!(filter.keep(Map::new()).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! 生成データモジュール
+//!
+//! 構造物の配置（間隔・分離距離・ソルト）とバージョンごとのバイオーム生成のパラメータの表を、
+//! バイナリに埋め込んだJSON（`data/`）から読み込む。`--data-dir`（設定ファイルの `data_dir`）で
+//! 指定したディレクトリに同じ名前のファイルを置くと、新しいバージョンに合わせた修正を
+//! バイナリを更新せずに使える。
+//!
+//! - `structures.json`: 識別子ごとの配置。書いた構造物だけを差し替える
+//! - `biome_params.json`: バイオーム生成のパラメータ（古い順）。表全体を差し替える
+//!
+//! 正式版（retail）とは別に、プレビュー版（`--channel preview`）で変わった値だけを `data/preview/` に置き、
+//! 正式版の表に重ねる（構造物は識別子ごと、バイオーム生成のパラメータは `since` ごとに差し替える）。
+//!
+//! 表は実行するコマンドごとにスレッドに設定するため、batch・repl の行ごとに別のチャンネルやディレクトリを使える。
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::algorithms::biome::BiomeParams;
+use crate::structures::{GridPlacement, StructureType};
+use crate::version::{McVersion, LATEST_VERSION, PREVIEW_VERSION};
+
+/// 構造物の配置のファイル名
+pub const STRUCTURES_FILE: &str = "structures.json";
+
+/// バイオーム生成のパラメータのファイル名
+pub const BIOME_PARAMS_FILE: &str = "biome_params.json";
+
+const EMBEDDED_STRUCTURES: &str = include_str!("../data/structures.json");
+const EMBEDDED_BIOME_PARAMS: &str = include_str!("../data/biome_params.json");
+const PREVIEW_STRUCTURES: &str = include_str!("../data/preview/structures.json");
+const PREVIEW_BIOME_PARAMS: &str = include_str!("../data/preview/biome_params.json");
+
+thread_local! {
+    static GAME_DATA: RefCell<Arc<GameData>> = RefCell::new(Arc::new(embedded(Channel::Retail)));
+}
+
+/// Bedrock Editionの配信チャンネル
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// 正式版（既定）
+    Retail,
+    /// プレビュー版（ベータ）
+    Preview,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 2] = [Channel::Retail, Channel::Preview];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Channel::Retail => "retail",
+            Channel::Preview => "preview",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Channel> {
+        Channel::ALL.into_iter().find(|c| c.id() == id)
+    }
+
+    /// バージョン未指定の場合に対象にするバージョン
+    pub fn default_version(&self) -> McVersion {
+        match self {
+            Channel::Retail => LATEST_VERSION,
+            Channel::Preview => PREVIEW_VERSION,
+        }
+    }
+}
+
+/// 生成データの表
+#[derive(Debug, Clone)]
+pub struct GameData {
+    /// 表の配信チャンネル
+    pub channel: Channel,
+    /// 組み込みの構造物の配置（StructureType::ALL の順）
+    structures: Vec<GridPlacement>,
+    /// バイオーム生成のパラメータ（古い順）
+    pub biome_params: Vec<BiomeParams>,
+}
+
+impl GameData {
+    /// 組み込みの構造物の配置
+    pub fn structure(&self, structure_type: StructureType) -> GridPlacement {
+        let index = StructureType::ALL.iter().position(|st| st.id() == structure_type.id()).unwrap_or(0);
+        self.structures[index]
+    }
+
+    /// 構造物の配置のJSONで表を差し替える（書いた構造物だけ）
+    fn apply_structures(&mut self, text: &str) -> Result<(), String> {
+        for (id, placement) in parse_structures(text)? {
+            if let Some(index) = StructureType::ALL.iter().position(|st| st.id() == id) {
+                self.structures[index] = placement;
+            }
+        }
+        Ok(())
+    }
+
+    /// バイオーム生成のパラメータのJSONを表に重ねる（同じ since のものは差し替え、ほかは追加）
+    fn merge_biome_params(&mut self, text: &str) -> Result<(), String> {
+        let params: Vec<BiomeParams> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        for p in params {
+            match self.biome_params.iter_mut().find(|q| q.since == p.since) {
+                Some(q) => *q = p,
+                None => self.biome_params.push(p),
+            }
+        }
+        self.biome_params.sort_by_key(|p| p.since);
+        Ok(())
+    }
+}
+
+/// チャンネルの埋め込みのデータ（プレビュー版は正式版の表に違いを重ねる）
+fn embedded(channel: Channel) -> GameData {
+    let structures = parse_structures(EMBEDDED_STRUCTURES).expect("埋め込みの構造物の配置が不正");
+    let biome_params = parse_biome_params(EMBEDDED_BIOME_PARAMS).expect("埋め込みのバイオームのパラメータが不正");
+    let structures = StructureType::ALL
+        .iter()
+        .map(|st| *structures.get(st.id()).expect("埋め込みの構造物の配置が不足"))
+        .collect();
+    let mut data = GameData {
+        channel,
+        structures,
+        biome_params,
+    };
+    if channel == Channel::Preview {
+        data.apply_structures(PREVIEW_STRUCTURES).expect("埋め込みのプレビュー版の構造物の配置が不正");
+        data.merge_biome_params(PREVIEW_BIOME_PARAMS).expect("埋め込みのプレビュー版のバイオームのパラメータが不正");
+    }
+    data
+}
+
+/// 構造物の配置のJSONを解釈
+fn parse_structures(text: &str) -> Result<BTreeMap<String, GridPlacement>, String> {
+    let structures: BTreeMap<String, GridPlacement> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    for (id, placement) in &structures {
+        if !StructureType::ALL.iter().any(|st| st.id() == id) {
+            return Err(format!("不明な構造物タイプ: {}（組み込みの構造物のみ）", id));
+        }
+        if placement.separation <= 0 || placement.spacing <= placement.separation {
+            return Err(format!("{}: spacing は separation より大きくしてください", id));
+        }
+    }
+    Ok(structures)
+}
+
+/// バイオーム生成のパラメータのJSONを解釈
+fn parse_biome_params(text: &str) -> Result<Vec<BiomeParams>, String> {
+    let params: Vec<BiomeParams> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if params.is_empty() {
+        return Err("パラメータが1つもありません".to_string());
+    }
+    if params.windows(2).any(|pair| pair[0].since >= pair[1].since) {
+        return Err("パラメータは since の古い順に並べてください".to_string());
+    }
+    Ok(params)
+}
+
+/// チャンネルの埋め込みのデータにディレクトリのファイルを重ねて読み込む（ディレクトリ未指定の場合は埋め込みのデータ）
+pub fn load_game_data(channel: Channel, dir: Option<&str>) -> Result<GameData, String> {
+    let mut data = embedded(channel);
+    let Some(dir) = dir else {
+        return Ok(data);
+    };
+    let dir = Path::new(dir);
+    if !dir.is_dir() {
+        return Err(format!("生成データのディレクトリがありません: {}", dir.display()));
+    }
+
+    let read = |name: &str| -> Result<Option<String>, String> {
+        let path = dir.join(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        tracing::info!("生成データ: {}", path.display());
+        std::fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| format!("生成データを読み込めません: {} ({})", path.display(), e))
+    };
+    let invalid = |name: &str, e: String| format!("生成データの形式が不正です: {} ({})", dir.join(name).display(), e);
+
+    if let Some(text) = read(STRUCTURES_FILE)? {
+        data.apply_structures(&text).map_err(|e| invalid(STRUCTURES_FILE, e))?;
+    }
+    if let Some(text) = read(BIOME_PARAMS_FILE)? {
+        data.biome_params = parse_biome_params(&text).map_err(|e| invalid(BIOME_PARAMS_FILE, e))?;
+    }
+    Ok(data)
+}
+
+/// 実行するコマンドの生成データを設定
+pub fn set_game_data(data: Arc<GameData>) {
+    GAME_DATA.with(|d| *d.borrow_mut() = data);
+}
+
+/// 現在の生成データ（未設定の場合は正式版の埋め込みのデータ）
+pub fn game_data() -> Arc<GameData> {
+    GAME_DATA.with(|d| d.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_game_data() {
+        let data = load_game_data(Channel::Retail, None).unwrap();
+        assert_eq!(data.structure(StructureType::Village).spacing, 32);
+        assert_eq!(data.structure(StructureType::AncientCity).salt, 20083232);
+        assert_eq!(data.biome_params.len(), 3);
+        assert_eq!(Channel::from_id("preview"), Some(Channel::Preview));
+        let preview = load_game_data(Channel::Preview, None).unwrap();
+        assert_eq!(preview.channel, Channel::Preview);
+        assert_eq!(preview.biome_params.len(), data.biome_params.len());
+
+        // プレビュー版の違いは since ごとに差し替えるか追加する
+        let mut merged = data.clone();
+        merged
+            .merge_biome_params(r#"[{ "since": "1.21.10", "ocean": -0.25, "deep_ocean": -0.5, "shore": 0.0, "cold": 0.2, "warm": 0.6, "deep_dark": false }]"#)
+            .unwrap();
+        assert_eq!(merged.biome_params.len(), 4);
+        assert_eq!(merged.biome_params[3].ocean, -0.25);
+        merged.merge_biome_params(r#"[{ "since": "1.18.0", "ocean": -0.3, "deep_ocean": -0.5, "shore": 0.0, "cold": 0.2, "warm": 0.6, "deep_dark": false }]"#).unwrap();
+        assert_eq!((merged.biome_params.len(), merged.biome_params[0].ocean), (4, -0.3));
+
+        set_game_data(Arc::new(preview));
+        assert_eq!(game_data().channel, Channel::Preview);
+        set_game_data(Arc::new(data.clone()));
+        assert_eq!(game_data().channel, Channel::Retail);
+
+        let dir = std::env::temp_dir().join(format!("bedrockmate-data-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(STRUCTURES_FILE), r#"{ "village": { "spacing": 34, "separation": 8, "salt": 10387312 } }"#).unwrap();
+        let data = load_game_data(Channel::Retail, dir.to_str()).unwrap();
+        assert_eq!(data.structure(StructureType::Village).spacing, 34);
+        assert_eq!(data.structure(StructureType::PillagerOutpost).spacing, 80);
+        assert_eq!(data.biome_params.len(), 3);
+
+        std::fs::write(dir.join(BIOME_PARAMS_FILE), "[]").unwrap();
+        assert!(load_game_data(Channel::Retail, dir.to_str()).is_err());
+        std::fs::write(dir.join(STRUCTURES_FILE), r#"{ "trail_ruins": { "spacing": 34, "separation": 8, "salt": 1 } }"#).unwrap();
+        assert!(load_game_data(Channel::Retail, dir.to_str()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(load_game_data(Channel::Retail, dir.to_str()).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! 検索履歴モジュール
+//!
+//! 設定ファイルで `history = true` にすると、各コマンドの引数を
+//! `~/.config/bedrockmate/history.db` に記録する。記録した引数はそのまま再実行できる。
+
+#[cfg(feature = "db")]
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[cfg(feature = "db")]
+use crate::db::open_db;
+
+#[cfg(feature = "db")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    args TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+)";
+
+/// 記録した実行履歴
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// プログラム名を除いたコマンドライン引数
+    pub args: Vec<String>,
+    pub created_at: String,
+}
+
+/// SQLiteの履歴保存先
+#[cfg(feature = "db")]
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+#[cfg(feature = "db")]
+impl HistoryStore {
+    /// 履歴ファイルを開く（省略時は ~/.config/bedrockmate/history.db。存在しない場合は作成）
+    pub fn open(path: Option<&str>) -> Result<HistoryStore, String> {
+        HistoryStore::init(open_db(path, "history.db")?)
+    }
+
+    fn init(conn: Connection) -> Result<HistoryStore, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("履歴ファイルを初期化できません: {}", e))?;
+        Ok(HistoryStore { conn })
+    }
+
+    /// 実行した引数を記録
+    pub fn record(&self, args: &[String]) -> Result<i64, String> {
+        self.conn
+            .execute(
+                "INSERT INTO history (args) VALUES (?1)",
+                params![serde_json::to_string(args).unwrap()],
+            )
+            .map_err(|e| format!("履歴を記録できません: {}", e))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 新しい順に最大 `limit` 件の履歴を取得
+    pub fn list(&self, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, args, created_at FROM history ORDER BY id DESC LIMIT ?1")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![limit as i64], entry_from_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("履歴を読み込めません: {}", e))
+    }
+
+    /// IDを指定して履歴を取得
+    pub fn get(&self, id: i64) -> Result<Option<HistoryEntry>, String> {
+        self.conn
+            .query_row(
+                "SELECT id, args, created_at FROM history WHERE id = ?1",
+                params![id],
+                entry_from_row,
+            )
+            .optional()
+            .map_err(|e| format!("履歴を読み込めません: {}", e))
+    }
+}
+
+#[cfg(feature = "db")]
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let args: String = row.get(1)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        args: serde_json::from_str(&args).unwrap_or_default(),
+        created_at: row.get(2)?,
+    })
+}
+
+/// 引数のシード指定（-s, --seed）を差し替える
+///
+/// 元の引数にシード指定がない場合は末尾に追加する。
+#[cfg(feature = "db")]
+pub fn replace_seed(args: &[String], seed: i64) -> Vec<String> {
+    let mut replaced = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-s" || arg == "--seed" {
+            iter.next();
+        } else if !(arg.starts_with("--seed=") || (arg.starts_with("-s") && arg[2..].parse::<i64>().is_ok())) {
+            replaced.push(arg.clone());
+        }
+    }
+    replaced.push("--seed".to_string());
+    replaced.push(seed.to_string());
+    replaced
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_record_and_list() {
+        let store = HistoryStore::init(Connection::open_in_memory().unwrap()).unwrap();
+        store.record(&args("structures --seed 1 -t village")).unwrap();
+        let id = store.record(&args("biome -s 2 -t jungle")).unwrap();
+
+        let entries = store.list(10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, id);
+        assert_eq!(store.get(id).unwrap().unwrap().args, args("biome -s 2 -t jungle"));
+        assert!(store.get(id + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_replace_seed() {
+        assert_eq!(replace_seed(&args("structures --seed 1 -r 3000"), 9), args("structures -r 3000 --seed 9"));
+        assert_eq!(replace_seed(&args("biome -s -5 -t jungle"), 9), args("biome -t jungle --seed 9"));
+        assert_eq!(replace_seed(&args("spawn --seed=1"), 9), args("spawn --seed 9"));
+        assert_eq!(replace_seed(&args("nether"), 9), args("nether --seed 9"));
+    }
+}
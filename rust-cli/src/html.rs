@@ -0,0 +1,219 @@
+//! HTML地図モジュール
+//!
+//! バイオームの地図と構造物の位置を1つのHTMLファイルにまとめる。
+//! 地図の画像はタイルに分けて埋め込むため、サーバーなしでブラウザで開ける。
+//! ドラッグで移動、ホイールで拡大縮小、構造物のアイコンに重ねると名前と座標を表示する。
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::icons::structure_icon;
+use crate::render::{Image, MapView};
+use crate::structures::StructureHit;
+
+/// 埋め込むタイルの1辺のピクセル数
+const TILE_SIZE: usize = 256;
+
+/// 地図の見出し
+pub struct MapHeader {
+    pub seed: i64,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// 構造物のアイコンの大きさ（ピクセル数）
+    pub marker_size: u32,
+}
+
+/// 地図と構造物の位置をHTML文書に変換（地図のタイルはPNGで埋め込むため map 機能が必要）
+pub fn render_html_map(
+    header: &MapHeader,
+    view: &MapView,
+    image: &Image,
+    structures: &[StructureHit],
+) -> std::io::Result<String> {
+    let mut tiles = Vec::new();
+    for y in (0..image.height).step_by(TILE_SIZE) {
+        for x in (0..image.width).step_by(TILE_SIZE) {
+            let tile = image.crop(x, y, TILE_SIZE, TILE_SIZE);
+            tiles.push(format!(
+                r#"<img src="data:image/png;base64,{}" style="left:{}px;top:{}px;width:{}px;height:{}px">"#,
+                STANDARD.encode(tile.to_png()?),
+                x,
+                y,
+                tile.width,
+                tile.height
+            ));
+        }
+    }
+
+    // アイコンは構造物の種類ごとにCSSのクラスにして1回だけ埋め込む
+    let mut icon_ids: Vec<&str> = Vec::new();
+    let mut markers = Vec::new();
+    for hit in structures {
+        let id = hit.kind.id();
+        let class = match icon_ids.iter().position(|i| *i == id) {
+            Some(i) => i,
+            None => {
+                icon_ids.push(id);
+                icon_ids.len() - 1
+            }
+        };
+        let (px, py) = view.pixel_of(hit.pos.x, hit.pos.z);
+        markers.push(format!(
+            r#"<div class="marker icon-{}" style="left:{:.1}px;top:{:.1}px" title="{} X={}, Z={}"></div>"#,
+            class,
+            px,
+            py,
+            escape_html(hit.kind.display_name()),
+            hit.pos.x,
+            hit.pos.z
+        ));
+    }
+    let icons: Vec<String> = icon_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            format!(
+                r#".icon-{} {{ background-image: url("data:image/svg+xml;base64,{}"); }}"#,
+                i,
+                STANDARD.encode(structure_icon(id).to_svg())
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        r##"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<title>BedrockMate シード {seed}</title>
+<style>
+html, body {{ margin: 0; height: 100%; overflow: hidden; background: #202020; font-family: sans-serif; }}
+#viewport {{ position: absolute; inset: 0; cursor: grab; }}
+#map {{ position: absolute; transform-origin: 0 0; --zoom: 1; }}
+#map img {{ position: absolute; image-rendering: pixelated; }}
+.marker {{ position: absolute; width: {marker_size}px; height: {marker_size}px; margin: -{marker_half}px 0 0 -{marker_half}px;
+  background-size: contain; transform: scale(calc(1 / var(--zoom))); }}
+{icons}
+#info {{ position: fixed; top: 8px; left: 8px; padding: 6px 10px; background: rgba(0, 0, 0, 0.7); color: #fff; font-size: 13px; }}
+</style>
+</head>
+<body>
+<div id="viewport">
+<div id="map" style="width:{width}px;height:{height}px">
+{tiles}
+{markers}
+</div>
+</div>
+<div id="info">シード: {seed} ／ 中心: X={center_x}, Z={center_z} ／ 半径: {radius}ブロック ／ 構造物: {count}件<br><span id="cursor"></span></div>
+<script>
+const view = {{ minX: {min_x}, minZ: {min_z}, blocksPerPixel: {blocks_per_pixel}, width: {width}, height: {height} }};
+const viewport = document.getElementById("viewport");
+const map = document.getElementById("map");
+const cursor = document.getElementById("cursor");
+let zoom = Math.min(innerWidth / view.width, innerHeight / view.height);
+let left = (innerWidth - view.width * zoom) / 2;
+let top_ = (innerHeight - view.height * zoom) / 2;
+let drag = null;
+
+function apply() {{
+  map.style.transform = `translate(${{left}}px, ${{top_}}px) scale(${{zoom}})`;
+  map.style.setProperty("--zoom", zoom);
+}}
+
+viewport.addEventListener("mousedown", (e) => {{
+  drag = {{ x: e.clientX - left, y: e.clientY - top_ }};
+  viewport.style.cursor = "grabbing";
+}});
+addEventListener("mouseup", () => {{
+  drag = null;
+  viewport.style.cursor = "grab";
+}});
+addEventListener("mousemove", (e) => {{
+  if (drag) {{
+    left = e.clientX - drag.x;
+    top_ = e.clientY - drag.y;
+    apply();
+  }}
+  const x = Math.floor(view.minX + (e.clientX - left) / zoom * view.blocksPerPixel);
+  const z = Math.floor(view.minZ + (e.clientY - top_) / zoom * view.blocksPerPixel);
+  cursor.textContent = `カーソル: X=${{x}}, Z=${{z}}`;
+}});
+viewport.addEventListener("wheel", (e) => {{
+  e.preventDefault();
+  const factor = e.deltaY < 0 ? 1.25 : 0.8;
+  left = e.clientX - (e.clientX - left) * factor;
+  top_ = e.clientY - (e.clientY - top_) * factor;
+  zoom *= factor;
+  apply();
+}}, {{ passive: false }});
+apply();
+</script>
+</body>
+</html>
+"##,
+        seed = header.seed,
+        center_x = header.center_x,
+        center_z = header.center_z,
+        radius = header.radius,
+        count = structures.len(),
+        width = image.width,
+        height = image.height,
+        min_x = view.min_x,
+        min_z = view.min_z,
+        blocks_per_pixel = view.blocks_per_pixel,
+        tiles = tiles.join("\n"),
+        markers = markers.join("\n"),
+        marker_size = header.marker_size,
+        marker_half = header.marker_size as f64 / 2.0,
+        icons = icons.join("\n"),
+    ))
+}
+
+/// HTMLの属性値に埋め込む文字列の特殊文字をエスケープ
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(all(test, feature = "map"))]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::palette::Palette;
+    use crate::render::render_biome_map;
+    use crate::structures::StructureKind;
+
+    #[test]
+    fn test_render_html_map() {
+        let header = MapHeader {
+            seed: 12345,
+            center_x: 0,
+            center_z: 0,
+            radius: 300,
+            marker_size: 12,
+        };
+        let view = MapView::around(0, 0, 300, 1);
+        let image = render_biome_map(12345, &view, &Palette::default());
+        // 未登録のカスタム構造物は識別子をそのまま表示名にする
+        let structures = vec![StructureHit::new(StructureKind::Custom("<村>".to_string()), BlockPos::new(100, -50))];
+        let html = render_html_map(&header, &view, &image, &structures).unwrap();
+
+        // 600ピクセル四方は 3×3 のタイル
+        assert_eq!(html.matches("data:image/png;base64,").count(), 9);
+        assert!(html.contains("left:400.0px;top:250.0px"));
+        assert!(html.contains("&lt;村&gt; X=100, Z=-50"));
+        assert_eq!(html.matches(".icon-0 {").count(), 1);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+    }
+}
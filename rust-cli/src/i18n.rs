@@ -0,0 +1,24 @@
+//! 表示言語モジュール
+//!
+//! 構造物・バイオーム名の表示言語を保持する（ja: 日本語、en: 英語）
+
+use std::sync::OnceLock;
+
+static LANG: OnceLock<String> = OnceLock::new();
+
+/// 対応している言語
+pub const SUPPORTED_LANGS: [&str; 2] = ["ja", "en"];
+
+/// 表示言語を設定（起動時に一度だけ）。未対応の言語の場合は偽を返す
+pub fn set_lang(lang: &str) -> bool {
+    if !SUPPORTED_LANGS.contains(&lang) {
+        return false;
+    }
+    let _ = LANG.set(lang.to_string());
+    true
+}
+
+/// 英語表示か判定（未設定の場合は日本語）
+pub fn is_english() -> bool {
+    LANG.get().is_some_and(|lang| lang == "en")
+}
@@ -0,0 +1,118 @@
+//! 構造物のアイコンモジュール
+//!
+//! 書き出す地図（PNG画像・HTML）で構造物の位置に描く8×8ドットのアイコン。
+//! 構造物ごとに形と色を変え、アイコンのない構造物は白い丸で描く。
+
+use crate::render::{Image, BLACK};
+use crate::structures::STRONGHOLD_ID;
+
+/// アイコンの1辺のドット数
+const ICON_DOTS: usize = 8;
+
+/// 既定のアイコンの大きさ（ピクセル数）
+pub const DEFAULT_MARKER_SIZE: u32 = 12;
+
+/// 構造物のアイコン（上の行から1行1バイト、上位ビットが左）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Icon {
+    pub rows: [u8; ICON_DOTS],
+    pub color: [u8; 3],
+}
+
+impl Icon {
+    fn is_set(&self, col: usize, row: usize) -> bool {
+        self.rows[row] & (0x80 >> col) != 0
+    }
+
+    /// 中心を (cx, cy) として大きさ size ピクセルで描く（黒で縁取る）
+    pub fn draw(&self, image: &mut Image, cx: i64, cy: i64, size: u32) {
+        let dot = (size as f64 / ICON_DOTS as f64).max(1.0);
+        let left = cx as f64 - dot * ICON_DOTS as f64 / 2.0;
+        let top = cy as f64 - dot * ICON_DOTS as f64 / 2.0;
+        let dot_rect = |col: usize, row: usize| {
+            let x0 = (left + col as f64 * dot).round() as i64;
+            let y0 = (top + row as f64 * dot).round() as i64;
+            let x1 = (left + (col + 1) as f64 * dot).round() as i64;
+            let y1 = (top + (row + 1) as f64 * dot).round() as i64;
+            (x0, y0, x1 - x0, y1 - y0)
+        };
+
+        for (color, grow) in [(BLACK, 1), (self.color, 0)] {
+            for row in 0..ICON_DOTS {
+                for col in (0..ICON_DOTS).filter(|&col| self.is_set(col, row)) {
+                    let (x, y, w, h) = dot_rect(col, row);
+                    image.fill_rect(x - grow, y - grow, w + grow * 2, h + grow * 2, color);
+                }
+            }
+        }
+    }
+
+    /// HTMLに埋め込むSVG（1ドットを1単位とし、縁取りの分だけ広げた10×10の画像）
+    pub fn to_svg(self) -> String {
+        let mut rects = Vec::new();
+        for (color, grow) in [(BLACK, 1.0), (self.color, 0.0)] {
+            let fill = format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2]);
+            for row in 0..ICON_DOTS {
+                for col in (0..ICON_DOTS).filter(|&col| self.is_set(col, row)) {
+                    rects.push(format!(
+                        "<rect x='{}' y='{}' width='{}' height='{}' fill='{}'/>",
+                        col as f64 + 1.0 - grow * 0.5,
+                        row as f64 + 1.0 - grow * 0.5,
+                        1.0 + grow,
+                        1.0 + grow,
+                        fill
+                    ));
+                }
+            }
+        }
+        format!(
+            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 10 10' shape-rendering='crispEdges'>{}</svg>",
+            rects.join("")
+        )
+    }
+}
+
+/// 構造物の識別子のアイコン
+pub fn structure_icon(id: &str) -> Icon {
+    let (rows, color) = match id {
+        "village" => ([0x18, 0x3C, 0x7E, 0xFF, 0x66, 0x66, 0x7E, 0x7E], [176, 110, 50]),
+        "outpost" => ([0x5A, 0x7E, 0x3C, 0x3C, 0x3C, 0x3C, 0x3C, 0x7E], [130, 130, 130]),
+        "monument" => ([0x00, 0x18, 0x18, 0x3C, 0x3C, 0x7E, 0x7E, 0xFF], [70, 180, 170]),
+        "mansion" => ([0x24, 0x7E, 0xFF, 0xFF, 0xDB, 0xFF, 0xDB, 0xFF], [110, 70, 35]),
+        "fortress" => ([0xA5, 0xFF, 0x7E, 0x66, 0x66, 0x66, 0x66, 0xE7], [120, 25, 25]),
+        "bastion" => ([0xDB, 0xFF, 0xFF, 0xE7, 0xE7, 0xFF, 0xFF, 0xFF], [60, 60, 70]),
+        "igloo" => ([0x00, 0x00, 0x3C, 0x7E, 0xFF, 0xFF, 0xE7, 0xE7], [235, 240, 255]),
+        "witch_hut" => ([0x18, 0x18, 0x3C, 0x7E, 0xFF, 0x7E, 0x42, 0x42], [130, 60, 160]),
+        "shipwreck" => ([0x10, 0x18, 0x1C, 0x10, 0xFF, 0x7E, 0x3C, 0x00], [160, 110, 60]),
+        "buried_treasure" => ([0xC3, 0xE7, 0x7E, 0x3C, 0x3C, 0x7E, 0xE7, 0xC3], [230, 30, 30]),
+        "ruined_portal" => ([0xFF, 0x81, 0xBD, 0xBD, 0xBD, 0xBD, 0x81, 0xFF], [140, 50, 200]),
+        "end_city" => ([0x18, 0x3C, 0x18, 0x3C, 0x7E, 0x3C, 0x3C, 0x7E], [230, 210, 240]),
+        "trial_chamber" => ([0xFF, 0x99, 0xFF, 0x99, 0xFF, 0x99, 0xFF, 0x00], [210, 120, 60]),
+        "mineshaft" => ([0x7E, 0xFF, 0x99, 0x18, 0x18, 0x18, 0x18, 0x18], [200, 170, 110]),
+        "ancient_city" => ([0x81, 0xC3, 0xFF, 0xDB, 0xFF, 0xC3, 0xC3, 0xC3], [0, 110, 120]),
+        STRONGHOLD_ID => ([0x00, 0x3C, 0x7E, 0xDB, 0xDB, 0x7E, 0x3C, 0x00], [40, 170, 90]),
+        _ => ([0x3C, 0x7E, 0xFF, 0xFF, 0xFF, 0xFF, 0x7E, 0x3C], [255, 255, 255]),
+    };
+    Icon { rows, color }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structure_icons() {
+        assert_ne!(structure_icon("village"), structure_icon("outpost"));
+        assert_eq!(structure_icon("trail_ruins"), structure_icon("unknown"));
+
+        let mut image = Image::new(20, 20);
+        structure_icon("village").draw(&mut image, 10, 10, 16);
+        // 屋根の頂点（上から1行目の中央）と縁取り
+        assert_eq!(image.crop(9, 3, 1, 1), {
+            let mut expected = Image::new(1, 1);
+            expected.fill_rect(0, 0, 1, 1, [176, 110, 50]);
+            expected
+        });
+        assert!(structure_icon("village").to_svg().contains("fill='#b06e32'"));
+    }
+}
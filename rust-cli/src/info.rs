@@ -0,0 +1,205 @@
+//! 生成ルール説明モジュール
+//!
+//! 構造物・バイオームについて、このツールが使っている生成ルールを説明する
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::biome::BiomeType;
+use crate::coords::Dimension;
+use crate::custom::{find_custom_structure, CustomStructure};
+use crate::locator::CHUNK_PERMILLE;
+use crate::structures::{stronghold_name, PlacementModel, StructureType, STRONGHOLD_ID, STRONGHOLD_SINCE_VERSION};
+
+/// 生成ルールの説明
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GenerationInfo {
+    pub id: String,
+    pub name: String,
+    /// structure または biome
+    pub kind: String,
+    pub placement: Option<String>,
+    /// リージョンの一辺（チャンク）
+    pub spacing: Option<i32>,
+    pub separation: Option<i32>,
+    pub salt: Option<i64>,
+    /// 生成条件となるバイオーム
+    pub biomes: Vec<String>,
+    pub versions: String,
+    /// ルールの説明文
+    pub rules: Vec<String>,
+}
+
+/// 構造物またはバイオームの生成ルールを取得
+pub fn generation_info(id: &str) -> Option<GenerationInfo> {
+    if id == STRONGHOLD_ID {
+        return Some(stronghold_info());
+    }
+    if let Ok(st) = id.parse::<StructureType>() {
+        return Some(structure_info(st));
+    }
+    if let Some(custom) = find_custom_structure(id) {
+        return Some(custom_info(custom));
+    }
+    BiomeType::from_str(id).map(biome_info)
+}
+
+fn structure_info(st: StructureType) -> GenerationInfo {
+    let spacing = st.spacing();
+    let separation = st.separation();
+
+    let mut rules = match st.placement() {
+        PlacementModel::NetherQuadrant => vec![
+            "ネザーを480×480ブロックの区画（quadrant）に分割し、各区画にネザー要塞かバスティオンのどちらか一方だけが生成される".to_string(),
+            "区画ごとの乱数で約33%がネザー要塞、約67%がバスティオンになる".to_string(),
+            "区画内の位置はオフセット100〜379ブロックの範囲で決まる".to_string(),
+            format!("乱数のソルト値は {}（ネザー要塞・バスティオン共通）", st.salt()),
+        ],
+        PlacementModel::Chunk => vec![
+            format!(
+                "リージョンではなくチャンクごとに判定し、各チャンクで約{}%の確率で生成される",
+                CHUNK_PERMILLE as f64 / 10.0
+            ),
+            "生成されるチャンクでは中心付近に配置される".to_string(),
+            format!("乱数のソルト値は {}", st.salt()),
+        ],
+        PlacementModel::Grid => grid_rules(spacing, separation, st.salt()),
+    };
+
+    let biomes: Vec<String> = st.allowed_biomes().iter().map(|b| b.id().to_string()).collect();
+    if st.dimension() == Dimension::End {
+        rules.push("エンドの構造物のためオーバーワールドのバイオーム条件はない".to_string());
+    } else if biomes.is_empty() {
+        rules.push("ネザー構造物のためオーバーワールドのバイオーム条件はない".to_string());
+    } else {
+        rules.push(format!(
+            "生成できるバイオーム: {}（このツールの座標計算ではバイオーム判定を行わないため、実際には生成されない候補も含まれる）",
+            biomes.join(", ")
+        ));
+    }
+    if !st.version_notes().is_empty() {
+        rules.push(st.version_notes().to_string());
+    }
+    if let Some(since) = st.experimental_since() {
+        rules.push(experimental_rule(since));
+    }
+
+    GenerationInfo {
+        id: st.id().to_string(),
+        name: st.display_name().to_string(),
+        kind: "structure".to_string(),
+        placement: Some(st.placement().id().to_string()),
+        spacing: Some(spacing),
+        separation: Some(separation),
+        salt: Some(st.salt()),
+        biomes,
+        versions: format!("{}+", st.since_version()),
+        rules,
+    }
+}
+
+fn grid_rules(spacing: i32, separation: i32, salt: i64) -> Vec<String> {
+    vec![
+        format!(
+            "ワールドを {}×{} チャンク（{}ブロック四方）のリージョンに分割し、各リージョンに1つずつ候補を置く",
+            spacing,
+            spacing,
+            spacing * 16
+        ),
+        format!(
+            "候補のチャンクはリージョン内のオフセット 0〜{} チャンクの範囲から選ばれる（分離距離 {} チャンク）",
+            spacing - separation - 1,
+            separation
+        ),
+        format!("リージョンごとの乱数はワールドシードとソルト値 {} から決まる", salt),
+    ]
+}
+
+fn custom_info(custom: &CustomStructure) -> GenerationInfo {
+    let mut rules = grid_rules(custom.spacing, custom.separation, custom.salt);
+    rules.push(format!("カスタム構造物の定義ファイルで追加された{}の構造物", custom.dimension.id()));
+    if !custom.biomes.is_empty() {
+        rules.push(format!(
+            "生成できるバイオーム: {}（このツールの座標計算ではバイオーム判定を行わないため、実際には生成されない候補も含まれる）",
+            custom.biomes.join(", ")
+        ));
+    }
+
+    GenerationInfo {
+        id: custom.id.clone(),
+        name: custom.display_name().to_string(),
+        kind: "structure".to_string(),
+        placement: Some(PlacementModel::Grid.id().to_string()),
+        spacing: Some(custom.spacing),
+        separation: Some(custom.separation),
+        salt: Some(custom.salt),
+        biomes: custom.biomes.clone(),
+        versions: "custom".to_string(),
+        rules,
+    }
+}
+
+fn stronghold_info() -> GenerationInfo {
+    GenerationInfo {
+        id: STRONGHOLD_ID.to_string(),
+        name: stronghold_name().to_string(),
+        kind: "structure".to_string(),
+        placement: Some("ring".to_string()),
+        spacing: None,
+        separation: None,
+        salt: None,
+        biomes: Vec::new(),
+        versions: format!("{}+", STRONGHOLD_SINCE_VERSION),
+        rules: vec![
+            "原点を中心とした同心円状のリングに等間隔で配置される（リング配置モデル）".to_string(),
+            "1つ目のリングは3個、原点から約1280〜2816ブロック。以降3072ブロックごとに外側のリングがある".to_string(),
+            "リングの開始角度と各要塞の距離のばらつきはシードから決まる".to_string(),
+        ],
+    }
+}
+
+/// 実験的機能として生成されるバージョンの説明
+fn experimental_rule(since: &str) -> String {
+    format!("{} 以降はゲーム内の「実験」の設定で生成される（--experimental で検索できる）", since)
+}
+
+fn biome_info(biome: BiomeType) -> GenerationInfo {
+    let mut rules = vec![
+        "温度・湿度・大陸性のノイズからバイオームを決める、1.18以降のマルチノイズ生成の簡易近似".to_string(),
+        "1.18より前のバージョンは、大陸・気候・バイオーム・川のレイヤーを拡大しながら重ねるレイヤー方式の近似".to_string(),
+        format!("希少度 {:.2}", biome.rarity()),
+        format!("検索時は {}ブロック間隔でサンプリングする", biome.sampling_step()),
+    ];
+    if let Some(since) = biome.experimental_since() {
+        rules.push(experimental_rule(since));
+    }
+    GenerationInfo {
+        id: biome.id().to_string(),
+        name: biome.display_name().to_string(),
+        kind: "biome".to_string(),
+        placement: None,
+        spacing: None,
+        separation: None,
+        salt: None,
+        biomes: Vec::new(),
+        versions: format!("{}+", biome.since_version()),
+        rules,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_info() {
+        let village = generation_info("village").unwrap();
+        assert_eq!(village.spacing, Some(32));
+        assert!(village.biomes.contains(&"plains".to_string()));
+
+        assert_eq!(generation_info("stronghold").unwrap().placement.as_deref(), Some("ring"));
+        assert_eq!(generation_info("badlands").unwrap().kind, "biome");
+        assert!(generation_info("end_city").unwrap().biomes.is_empty());
+        assert!(generation_info("sky_castle").is_none());
+    }
+}
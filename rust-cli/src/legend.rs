@@ -0,0 +1,161 @@
+//! 凡例モジュール
+//!
+//! 書き出す地図の左上に、シード・バージョン・中心座標と、構造物・道順・色分け・バイオームの凡例を描く。
+//! 画像だけを共有しても何の地図かが分かるようにする。文字は地図の字形にある英数字で書く。
+
+use crate::algorithms::biome::{get_biome_at, BiomeType};
+use crate::coords::BlockPos;
+use crate::icons::structure_icon;
+use crate::palette::Palette;
+use crate::render::{heat_color, Image, MapView, BLACK, GLYPH_ADVANCE, GLYPH_HEIGHT, ROUTE_COLOR, WHITE};
+use crate::version::McVersion;
+
+/// 凡例の行の高さ（ピクセル数）
+const ROW_HEIGHT: i64 = GLYPH_HEIGHT + 5;
+
+/// 凡例の余白（ピクセル数）
+const PADDING: i64 = 8;
+
+/// 見本（色・記号）の幅（ピクセル数）
+const SWATCH_WIDTH: i64 = 16;
+
+/// 凡例の構造物のアイコンの大きさ（ピクセル数）
+const LEGEND_ICON_SIZE: u32 = 12;
+
+/// 凡例を描かない画像の幅の下限（ピクセル数）
+const MIN_IMAGE_WIDTH: usize = 240;
+
+/// 地図の凡例の内容
+pub struct Legend<'a> {
+    pub seed: i64,
+    pub version: McVersion,
+    pub center_x: i32,
+    pub center_z: i32,
+    pub blocks_per_pixel: i32,
+    /// 重ねた構造物の識別子
+    pub structures: Vec<String>,
+    pub palette: &'a Palette,
+    /// 地図に含まれるバイオーム
+    pub biomes: Vec<BiomeType>,
+    /// 色分けした場合の区画の構造物の数の最大
+    pub density_max: Option<usize>,
+    /// 道順を重ねたか
+    pub route: bool,
+}
+
+/// 凡例の1行
+enum Row {
+    Text(String),
+    Marker(String),
+    Route,
+    Density(usize),
+    Biome(BiomeType),
+}
+
+/// 地図に含まれるバイオーム（4ピクセルごとに調べ、BiomeType::ALL の順）
+pub fn biomes_in_view(seed: i64, view: &MapView) -> Vec<BiomeType> {
+    let mut found = Vec::new();
+    for py in (0..view.height).step_by(4) {
+        for px in (0..view.width).step_by(4) {
+            let (x, z) = view.block_at(px, py);
+            let biome = get_biome_at(seed, BlockPos::new(x, z));
+            if !found.contains(&biome) {
+                found.push(biome);
+            }
+        }
+    }
+    BiomeType::ALL.into_iter().filter(|b| found.contains(b)).collect()
+}
+
+/// 地図の左上に凡例を描く（小さすぎる画像には描かない）
+pub fn draw_legend(image: &mut Image, legend: &Legend) {
+    if image.width < MIN_IMAGE_WIDTH {
+        return;
+    }
+
+    let mut rows = vec![
+        Row::Text(format!("SEED {}", legend.seed)),
+        Row::Text(format!("BEDROCK {}", legend.version)),
+        Row::Text(format!("CENTER X={} Z={}", legend.center_x, legend.center_z)),
+        Row::Text(format!("1PX = {} BLOCKS", legend.blocks_per_pixel)),
+    ];
+    rows.extend(legend.structures.iter().map(|id| Row::Marker(id.clone())));
+    if legend.route {
+        rows.push(Row::Route);
+    }
+    if let Some(max) = legend.density_max {
+        rows.push(Row::Density(max));
+    }
+    rows.extend(legend.biomes.iter().map(|b| Row::Biome(*b)));
+
+    let label_width = rows.iter().map(|row| row_label(row).len() as i64).max().unwrap_or(0) * GLYPH_ADVANCE;
+    let width = PADDING * 2 + SWATCH_WIDTH + label_width;
+    let height = PADDING * 2 + rows.len() as i64 * ROW_HEIGHT;
+    image.blend_rect(0, 0, width, height, BLACK, 0.7);
+
+    for (i, row) in rows.iter().enumerate() {
+        let top = PADDING + i as i64 * ROW_HEIGHT;
+        let swatch_x = PADDING;
+        let text_x = match row {
+            Row::Text(_) => PADDING,
+            _ => PADDING + SWATCH_WIDTH,
+        };
+        match row {
+            Row::Text(_) => {}
+            Row::Marker(id) => structure_icon(id).draw(image, swatch_x + 5, top + GLYPH_HEIGHT / 2, LEGEND_ICON_SIZE),
+            Row::Route => image.fill_rect(swatch_x, top + GLYPH_HEIGHT / 2 - 1, 11, 3, ROUTE_COLOR),
+            Row::Density(_) => {
+                for dx in 0..11 {
+                    let [r, g, b] = heat_color(dx as f64 / 10.0);
+                    image.fill_rect(swatch_x + dx, top, 1, GLYPH_HEIGHT, [r as u8, g as u8, b as u8]);
+                }
+            }
+            Row::Biome(biome) => {
+                image.fill_rect(swatch_x - 1, top - 1, 12, GLYPH_HEIGHT + 2, WHITE);
+                image.fill_rect(swatch_x, top, 10, GLYPH_HEIGHT, legend.palette.color(*biome));
+            }
+        }
+        image.draw_text(text_x, top, &row_label(row), WHITE);
+    }
+}
+
+fn row_label(row: &Row) -> String {
+    match row {
+        Row::Text(text) | Row::Marker(text) => text.clone(),
+        Row::Route => "ROUTE".to_string(),
+        Row::Density(max) => format!("DENSITY 0-{}", max),
+        Row::Biome(biome) => biome.id().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::render_biome_map;
+
+    #[test]
+    fn test_draw_legend() {
+        let palette = Palette::default();
+        let view = MapView::around(0, 0, 400, 2);
+        let mut image = render_biome_map(12345, &view, &palette);
+        let before = image.clone();
+        let biomes = biomes_in_view(12345, &view);
+        assert!(!biomes.is_empty());
+
+        let legend = Legend {
+            seed: 12345,
+            version: McVersion(1, 21, 0),
+            center_x: 0,
+            center_z: 0,
+            blocks_per_pixel: 2,
+            structures: vec!["village".to_string()],
+            palette: &palette,
+            biomes,
+            density_max: None,
+            route: false,
+        };
+        draw_legend(&mut image, &legend);
+        assert_ne!(image.crop(0, 0, 40, 40), before.crop(0, 0, 40, 40));
+        assert_eq!(image.crop(399, 399, 1, 1), before.crop(399, 399, 1, 1));
+    }
+}
@@ -0,0 +1,229 @@
+//! ワールドの保存データモジュール
+//!
+//! Bedrock Editionのワールドのフォルダにある `level.dat`（8バイトのヘッダーと
+//! リトルエンディアンのNBT）から、最後に開いたゲームのバージョン（`lastOpenedWithVersion`）と
+//! 保存形式のバージョン（`StorageVersion`）を読み、計算に使うバージョンを決める。
+
+use std::path::{Path, PathBuf};
+
+use crate::version::McVersion;
+
+/// level.dat のヘッダーのバイト数（保存形式のバージョンとNBTの長さ）
+const HEADER_SIZE: usize = 8;
+
+/// 保存形式のバージョンごとの、その形式を使い始めたゲームのバージョン（新しい順。lastOpenedWithVersion がない古いワールド用の近似）
+const STORAGE_VERSIONS: [(i32, McVersion); 4] = [
+    (10, McVersion(1, 19, 0)),
+    (9, McVersion(1, 18, 30)),
+    (8, McVersion(1, 10, 0)),
+    (5, McVersion(1, 0, 0)),
+];
+
+/// level.dat から読んだ情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelInfo {
+    /// 最後に開いたゲームのバージョン
+    pub last_opened: Option<McVersion>,
+    /// 保存形式のバージョン
+    pub storage_version: Option<i32>,
+}
+
+impl LevelInfo {
+    /// 計算に使うバージョン（最後に開いたバージョン、なければ保存形式から推定）
+    pub fn game_version(&self) -> Option<McVersion> {
+        self.last_opened.or_else(|| {
+            let storage = self.storage_version?;
+            STORAGE_VERSIONS.iter().find(|(s, _)| storage >= *s).map(|(_, v)| *v)
+        })
+    }
+}
+
+/// ワールドのフォルダ（または level.dat）のパスから level.dat のパス
+fn level_dat_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_dir() {
+        path.join("level.dat")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// ワールドの level.dat を読み込む
+pub fn read_level_info(path: &str) -> Result<LevelInfo, String> {
+    let path = level_dat_path(path);
+    let bytes = std::fs::read(&path).map_err(|e| format!("ワールドを読み込めません: {} ({})", path.display(), e))?;
+    parse_level_dat(&bytes).map_err(|e| format!("level.dat の形式が不正です: {} ({})", path.display(), e))
+}
+
+/// level.dat の内容を解釈
+pub fn parse_level_dat(bytes: &[u8]) -> Result<LevelInfo, String> {
+    let mut reader = NbtReader {
+        bytes,
+        pos: HEADER_SIZE.min(bytes.len()),
+    };
+    if bytes.len() < HEADER_SIZE || reader.u8()? != TAG_COMPOUND {
+        return Err("NBTのルートが見つかりません".to_string());
+    }
+    reader.string()?;
+
+    let mut info = LevelInfo {
+        last_opened: None,
+        storage_version: None,
+    };
+    loop {
+        let tag = reader.u8()?;
+        if tag == TAG_END {
+            break;
+        }
+        let name = reader.string()?;
+        match (name.as_str(), tag) {
+            ("StorageVersion", TAG_INT) => info.storage_version = Some(reader.i32()?),
+            ("lastOpenedWithVersion", TAG_LIST) => {
+                let parts = reader.int_list()?;
+                if let [major, minor, patch, ..] = parts[..] {
+                    info.last_opened = Some(McVersion(major.max(0) as u32, minor.max(0) as u32, patch.max(0) as u32));
+                }
+            }
+            _ => reader.skip(tag)?,
+        }
+    }
+    Ok(info)
+}
+
+const TAG_END: u8 = 0;
+const TAG_INT: u8 = 3;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+
+/// リトルエンディアンのNBTの読み取り
+struct NbtReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl NbtReader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len()).ok_or("データが途中で終わっています")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn length(&mut self) -> Result<usize, String> {
+        usize::try_from(self.i32()?).map_err(|_| "長さが負です".to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_le_bytes(self.take(2)?.try_into().unwrap()) as usize;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    /// TAG_Int のリスト（それ以外の要素のリストは空）
+    fn int_list(&mut self) -> Result<Vec<i32>, String> {
+        let element = self.u8()?;
+        let len = self.length()?;
+        if element != TAG_INT {
+            for _ in 0..len {
+                self.skip(element)?;
+            }
+            return Ok(Vec::new());
+        }
+        (0..len).map(|_| self.i32()).collect()
+    }
+
+    /// タグの値を読み飛ばす
+    fn skip(&mut self, tag: u8) -> Result<(), String> {
+        match tag {
+            1 => self.take(1).map(drop),
+            2 => self.take(2).map(drop),
+            3 | 5 => self.take(4).map(drop),
+            4 | 6 => self.take(8).map(drop),
+            7 => {
+                let len = self.length()?;
+                self.take(len).map(drop)
+            }
+            8 => self.string().map(drop),
+            TAG_LIST => {
+                let element = self.u8()?;
+                for _ in 0..self.length()? {
+                    self.skip(element)?;
+                }
+                Ok(())
+            }
+            TAG_COMPOUND => loop {
+                let tag = self.u8()?;
+                if tag == TAG_END {
+                    return Ok(());
+                }
+                self.string()?;
+                self.skip(tag)?;
+            },
+            11 => {
+                let len = self.length()?;
+                self.take(len.checked_mul(4).ok_or("長さが大きすぎます")?).map(drop)
+            }
+            12 => {
+                let len = self.length()?;
+                self.take(len.checked_mul(8).ok_or("長さが大きすぎます")?).map(drop)
+            }
+            other => Err(format!("不明なタグ: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(tag: u8, name: &str) -> Vec<u8> {
+        let mut bytes = vec![tag];
+        bytes.extend((name.len() as u16).to_le_bytes());
+        bytes.extend(name.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_parse_level_dat() {
+        let mut nbt = named(TAG_COMPOUND, "");
+        nbt.extend(named(8, "LevelName"));
+        nbt.extend(5u16.to_le_bytes());
+        nbt.extend(b"World");
+        nbt.extend(named(TAG_COMPOUND, "abilities"));
+        nbt.extend(named(1, "flying"));
+        nbt.push(0);
+        nbt.push(TAG_END);
+        nbt.extend(named(TAG_INT, "StorageVersion"));
+        nbt.extend(10i32.to_le_bytes());
+        nbt.extend(named(TAG_LIST, "lastOpenedWithVersion"));
+        nbt.push(TAG_INT);
+        nbt.extend(5i32.to_le_bytes());
+        for part in [1i32, 20, 81, 1, 0] {
+            nbt.extend(part.to_le_bytes());
+        }
+        nbt.push(TAG_END);
+
+        let mut bytes = 10i32.to_le_bytes().to_vec();
+        bytes.extend((nbt.len() as i32).to_le_bytes());
+        bytes.extend(&nbt);
+        let info = parse_level_dat(&bytes).unwrap();
+        assert_eq!(info.last_opened, Some(McVersion(1, 20, 81)));
+        assert_eq!(info.storage_version, Some(10));
+        assert_eq!(info.game_version(), Some(McVersion(1, 20, 81)));
+
+        let old = LevelInfo {
+            last_opened: None,
+            storage_version: Some(8),
+        };
+        assert_eq!(old.game_version(), Some(McVersion(1, 10, 0)));
+        assert!(parse_level_dat(&bytes[..bytes.len() - 3]).is_err());
+        assert!(parse_level_dat(&[0; 4]).is_err());
+    }
+}
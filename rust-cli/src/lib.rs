@@ -0,0 +1,14 @@
+//! BedrockMate の決定的な内部処理
+//!
+//! CLI の機能はすべてバイナリ（bedrockmate）にある。`internals` 機能を有効にすると、構造物の配置や
+//! バイオームの近似の土台になる乱数・構造物シード・ノイズの基本処理をこのライブラリから使える。
+//! 外部からファジングやプロパティテストを行ったり、他の言語で互換の実装を作って結果を突き合わせたり
+//! するためのもので、各関数の説明に結果が満たす条件を書いている。
+//! 計算方法を変えた場合は `structures::ALGORITHM_REVISION` 等の改訂番号と同じく結果が変わる。
+
+#[cfg(feature = "internals")]
+pub mod rng;
+
+#[cfg(feature = "internals")]
+#[path = "algorithms/noise.rs"]
+pub mod noise;
@@ -0,0 +1,377 @@
+//! 構造物の配置モデルモジュール
+//!
+//! 構造物の配置の計算を配置モデルごとに `StructureLocator` として実装する。
+//! 配置モデルは候補の生成（区画ごとの候補、またはリング上の候補）と、候補が実際に生成されるかの確認だけを受け持ち、
+//! 検索範囲の確認やワールドの座標への変換、最寄りの検索は配置モデルによらず共通の処理で行う。
+//!
+//! - grid: リージョン格子（リージョンごとに1つの候補）
+//! - nether_quadrant: ネザーの480ブロック区画（区画ごとに要塞かバスティオンの一方）
+//! - chunk: チャンクごとの確率
+//! - ring: 要塞の同心円状のリング
+
+use crate::budget;
+use crate::coords::{check_search_area, BlockPos, ChunkPos, Dimension, RegionPos, CHUNK_SIZE};
+use crate::custom::find_custom_structure;
+use crate::error::SearchError;
+use crate::progress;
+use crate::rng::{get_structure_seed, next_int};
+use crate::structures::{GridPlacement, PlacementModel, StructureHit, StructureKind, StructureType};
+
+/// 構造物の配置モデル
+pub trait StructureLocator {
+    /// ログと進捗表示に使う名前
+    fn name(&self) -> &str;
+
+    /// 生成されるディメンション
+    fn dimension(&self) -> Dimension;
+
+    /// 候補を1つずつ置く区画の1辺のチャンク数（区画に分けない配置は None）
+    fn cell_chunks(&self) -> Option<i32> {
+        None
+    }
+
+    /// 区画の候補（生成の座標。候補のない区画は None）
+    fn cell_candidate(&self, _seed: i64, _cell: RegionPos) -> Option<StructureHit> {
+        None
+    }
+
+    /// 区画の候補を検索範囲の結果に含めるか（既定では候補の位置が範囲内か）
+    fn in_range(&self, _cell: RegionPos, candidate: &StructureHit, center: BlockPos, radius: i32) -> bool {
+        candidate.pos.distance_sq(center) <= (radius as i64).pow(2)
+    }
+
+    /// 中心（生成の座標）から radius ブロックの範囲の候補
+    ///
+    /// 既定では範囲に掛かる区画を中心の列から外側へ走査する。
+    fn candidates(&self, seed: i64, center: BlockPos, radius: i32) -> Vec<StructureHit> {
+        let Some(chunks) = self.cell_chunks() else {
+            return Vec::new();
+        };
+        let min_cell = BlockPos::new(center.x - radius, center.z - radius).region(chunks);
+        let max_cell = BlockPos::new(center.x + radius, center.z + radius).region(chunks);
+
+        let cells_per_row = (max_cell.z - min_cell.z + 1) as u64;
+        let cells = (max_cell.x - min_cell.x + 1) as u64 * cells_per_row;
+        let bar = progress::bar(cells, &format!("{} 区画", self.name()));
+
+        let mut results = Vec::new();
+        for cell_x in budget::outward(min_cell.x, max_cell.x, center.region(chunks).x) {
+            if budget::expired() {
+                break;
+            }
+            bar.inc(cells_per_row);
+            for cell_z in min_cell.z..=max_cell.z {
+                let cell = RegionPos::new(cell_x, cell_z);
+                match self.cell_candidate(seed, cell) {
+                    Some(candidate) if self.in_range(cell, &candidate, center, radius) => results.push(candidate),
+                    Some(candidate) => {
+                        tracing::trace!(
+                            cell_x,
+                            cell_z,
+                            block_x = candidate.pos.x,
+                            block_z = candidate.pos.z,
+                            "範囲外の候補を除外"
+                        );
+                    }
+                    None => {}
+                }
+            }
+        }
+        bar.finish_and_clear();
+
+        tracing::info!(structure = self.name(), cells, hits = results.len(), "区画を走査");
+        tracing::debug!(
+            structure = self.name(),
+            rejected = cells - results.len() as u64,
+            "範囲外・候補のない区画"
+        );
+        results
+    }
+
+    /// 候補が実際に生成されるか（既定ではすべて）
+    fn validate(&self, _seed: i64, _candidate: &StructureHit) -> bool {
+        true
+    }
+}
+
+/// 配置モデルで検索範囲の構造物を検索（座標はワールドの座標。半径や範囲が不正な場合はエラー）
+pub fn locate(
+    locator: &dyn StructureLocator,
+    seed: i64,
+    center: BlockPos,
+    radius: i32,
+) -> Result<Vec<StructureHit>, SearchError> {
+    check_search_area(center.x, center.z, radius)?;
+    let dimension = locator.dimension();
+    let center = center.to_generation(dimension);
+    Ok(locator
+        .candidates(seed, center, radius)
+        .into_iter()
+        .filter(|candidate| locator.validate(seed, candidate))
+        .map(|candidate| StructureHit::new(candidate.kind, candidate.pos.to_world(dimension)))
+        .collect())
+}
+
+/// 組み込みの構造物の配置モデル
+pub fn locator_for(st: StructureType) -> Box<dyn StructureLocator> {
+    match st.placement() {
+        PlacementModel::NetherQuadrant => Box::new(NetherQuadrantLocator { types: vec![st] }),
+        PlacementModel::Chunk => Box::new(ChunkLocator {
+            kind: StructureKind::Builtin(st),
+            salt: st.salt(),
+            permille: CHUNK_PERMILLE,
+        }),
+        PlacementModel::Grid => Box::new(GridLocator {
+            kind: StructureKind::Builtin(st),
+            placement: GridPlacement {
+                spacing: st.spacing(),
+                separation: st.separation(),
+                salt: st.salt(),
+            },
+        }),
+    }
+}
+
+/// 構造物の種類の配置モデル（組み込みの構造物、要塞、カスタム構造物。登録されていないカスタム構造物は None）
+pub fn locator_for_kind(kind: &StructureKind) -> Option<Box<dyn StructureLocator>> {
+    match kind {
+        StructureKind::Builtin(st) => Some(locator_for(*st)),
+        StructureKind::Stronghold => Some(Box::new(StrongholdRingLocator)),
+        StructureKind::Custom(id) => {
+            let custom = find_custom_structure(id)?;
+            Some(Box::new(GridLocator { kind: kind.clone(), placement: custom.placement() }))
+        }
+    }
+}
+
+/// リージョン格子の配置（組み込みの構造物とカスタム構造物で共通）
+pub struct GridLocator {
+    pub kind: StructureKind,
+    pub placement: GridPlacement,
+}
+
+/// エンドの外周の島が始まる、原点からの距離
+const END_OUTER_ISLANDS_DISTANCE: i64 = 1000;
+
+impl StructureLocator for GridLocator {
+    fn name(&self) -> &str {
+        self.kind.id()
+    }
+
+    fn dimension(&self) -> Dimension {
+        self.kind.dimension()
+    }
+
+    fn cell_chunks(&self) -> Option<i32> {
+        Some(self.placement.spacing)
+    }
+
+    /// リージョンの構造物のブロック座標（チャンク中心）
+    fn cell_candidate(&self, seed: i64, cell: RegionPos) -> Option<StructureHit> {
+        let GridPlacement {
+            spacing,
+            separation,
+            salt,
+        } = self.placement;
+        let mut struct_seed = get_structure_seed(seed, cell.x, cell.z, salt);
+
+        // リージョン内のオフセットを計算
+        let offset_range = spacing - separation;
+        let offset_x = next_int(&mut struct_seed, offset_range);
+        let offset_z = next_int(&mut struct_seed, offset_range);
+
+        // 構造物のチャンク座標
+        let origin = cell.min_chunk(spacing);
+        let pos = ChunkPos::new(origin.x + offset_x, origin.z + offset_z).center();
+        Some(StructureHit::new(self.kind.clone(), pos))
+    }
+
+    fn validate(&self, _seed: i64, candidate: &StructureHit) -> bool {
+        // エンドシティは中央の島とその周囲の空白地帯には生成されない
+        self.kind != StructureKind::Builtin(StructureType::EndCity)
+            || candidate.pos.distance_sq(BlockPos::new(0, 0)) >= END_OUTER_ISLANDS_DISTANCE.pow(2)
+    }
+}
+
+/// ネザーの構造物の区画の1辺のチャンク数（480ブロック）
+pub const QUADRANT_CHUNKS: i32 = 30;
+
+/// ネザーの区画の配置（480x480 quadrant algorithm）
+///
+/// Bedrock Editionでは、ネザー要塞とバスティオンは480x480ブロックの
+/// 領域（quadrant）ごとに、どちらか一方のみが生成される。
+/// - ネザー要塞: 約33%
+/// - バスティオン: 約67%
+pub struct NetherQuadrantLocator {
+    /// 結果に含める種類（区画を1度だけ走査して種類で絞り込む）
+    pub types: Vec<StructureType>,
+}
+
+/// ネザーの区画の構造物の種類とブロック座標
+pub fn nether_quadrant(seed: i64, quadrant: RegionPos) -> (StructureType, BlockPos) {
+    let mut quadrant_seed = get_structure_seed(seed, quadrant.x, quadrant.z, 30084232);
+    let structure_roll = next_int(&mut quadrant_seed, 100);
+
+    // 33% = ネザー要塞, 67% = バスティオン
+    let structure_type = if structure_roll < 33 {
+        StructureType::NetherFortress
+    } else {
+        StructureType::BastionRemnant
+    };
+
+    // 構造物の実際の位置を計算
+    let origin = quadrant.min_chunk(QUADRANT_CHUNKS).min_block();
+    let offset_x = next_int(&mut quadrant_seed, 280) + 100;
+    let offset_z = next_int(&mut quadrant_seed, 280) + 100;
+    (structure_type, BlockPos::new(origin.x + offset_x, origin.z + offset_z))
+}
+
+impl StructureLocator for NetherQuadrantLocator {
+    fn name(&self) -> &str {
+        "nether"
+    }
+
+    fn dimension(&self) -> Dimension {
+        Dimension::Nether
+    }
+
+    fn cell_chunks(&self) -> Option<i32> {
+        Some(QUADRANT_CHUNKS)
+    }
+
+    fn cell_candidate(&self, seed: i64, cell: RegionPos) -> Option<StructureHit> {
+        let (structure_type, pos) = nether_quadrant(seed, cell);
+        Some(StructureHit::new(StructureKind::Builtin(structure_type), pos))
+    }
+
+    /// 区画内のチェックポイント（100, 200, 300のオフセット）のいずれかが範囲内なら、その区画の構造物を含める（1 quadrant = 1構造物）
+    fn in_range(&self, cell: RegionPos, _candidate: &StructureHit, center: BlockPos, radius: i32) -> bool {
+        let origin = cell.min_chunk(QUADRANT_CHUNKS).min_block();
+        let check_points = [100, 200, 300];
+        check_points.iter().any(|offset_x| {
+            check_points.iter().any(|offset_z| {
+                let point = BlockPos::new(origin.x + offset_x, origin.z + offset_z);
+                point.distance_sq(center) <= (radius as i64).pow(2)
+            })
+        })
+    }
+
+    fn validate(&self, _seed: i64, candidate: &StructureHit) -> bool {
+        matches!(candidate.kind, StructureKind::Builtin(st) if self.types.contains(&st))
+    }
+}
+
+/// チャンクごとの確率で生成される構造物の、チャンクあたりの確率（‰）
+pub const CHUNK_PERMILLE: i32 = 4;
+
+/// チャンクごとの確率の配置
+pub struct ChunkLocator {
+    pub kind: StructureKind,
+    pub salt: i64,
+    /// チャンクあたりの確率（‰）
+    pub permille: i32,
+}
+
+impl StructureLocator for ChunkLocator {
+    fn name(&self) -> &str {
+        self.kind.id()
+    }
+
+    fn dimension(&self) -> Dimension {
+        self.kind.dimension()
+    }
+
+    fn cell_chunks(&self) -> Option<i32> {
+        Some(1)
+    }
+
+    fn cell_candidate(&self, seed: i64, cell: RegionPos) -> Option<StructureHit> {
+        let mut chunk_seed = get_structure_seed(seed, cell.x, cell.z, self.salt);
+        (next_int(&mut chunk_seed, 1000) < self.permille)
+            .then(|| StructureHit::new(self.kind.clone(), ChunkPos::new(cell.x, cell.z).center()))
+    }
+}
+
+/// 要塞（ストロングホールド）のリングごとの個数
+const STRONGHOLD_RING_COUNTS: [i32; 8] = [3, 6, 10, 15, 21, 28, 36, 9];
+
+/// 要塞のソルト値
+const STRONGHOLD_SALT: i64 = 97858791;
+
+/// 要塞のリング配置
+///
+/// 原点を中心とした同心円状のリングに等間隔で配置され、
+/// リングごとの開始角度と距離のばらつきをシードから決める。
+/// 1つ目のリングは原点から約1280〜2816ブロック、以降3072ブロックごとに外側へ広がる。
+pub struct StrongholdRingLocator;
+
+impl StrongholdRingLocator {
+    /// すべての要塞（生成の座標）
+    pub fn all(&self, seed: i64) -> Vec<StructureHit> {
+        let mut results = Vec::new();
+
+        for (ring, &count) in STRONGHOLD_RING_COUNTS.iter().enumerate() {
+            let ring = ring as i32;
+            let mut ring_seed = get_structure_seed(seed, ring, 0, STRONGHOLD_SALT);
+            let start_angle = next_int(&mut ring_seed, 3600) as f64 / 3600.0 * std::f64::consts::TAU;
+
+            for i in 0..count {
+                let mut struct_seed = get_structure_seed(seed, ring, i + 1, STRONGHOLD_SALT);
+                let angle = start_angle + std::f64::consts::TAU * i as f64 / count as f64;
+                let distance = 1280.0 + ring as f64 * 3072.0 + next_int(&mut struct_seed, 1536) as f64;
+
+                // チャンク中心に揃える
+                let chunk_x = (angle.cos() * distance / CHUNK_SIZE as f64).round() as i32;
+                let chunk_z = (angle.sin() * distance / CHUNK_SIZE as f64).round() as i32;
+                results.push(StructureHit::new(
+                    StructureKind::Stronghold,
+                    ChunkPos::new(chunk_x, chunk_z).center(),
+                ));
+            }
+        }
+
+        results
+    }
+}
+
+impl StructureLocator for StrongholdRingLocator {
+    fn name(&self) -> &str {
+        "stronghold"
+    }
+
+    fn dimension(&self) -> Dimension {
+        Dimension::Overworld
+    }
+
+    fn candidates(&self, seed: i64, center: BlockPos, radius: i32) -> Vec<StructureHit> {
+        let mut results = self.all(seed);
+        results.retain(|hit| hit.pos.distance_sq(center) <= (radius as i64).pow(2));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_locator() {
+        let locator = locator_for(StructureType::Mineshaft);
+        assert_eq!(locator.cell_chunks(), Some(1));
+        let hits = locate(&*locator, 12345, BlockPos::new(0, 0), 3000).unwrap();
+        // チャンクあたり0.4%（半径3000ブロックの円は約11万チャンク）
+        assert!((200..700).contains(&hits.len()), "{}", hits.len());
+        assert!(hits
+            .iter()
+            .all(|hit| hit.pos.x.rem_euclid(CHUNK_SIZE) == 8 && hit.pos.z.rem_euclid(CHUNK_SIZE) == 8));
+    }
+
+    #[test]
+    fn test_stronghold_ring_locator() {
+        let all = StrongholdRingLocator.all(12345);
+        assert_eq!(all.len(), 128);
+        let near = locate(&StrongholdRingLocator, 12345, BlockPos::new(0, 0), 3000).unwrap();
+        assert_eq!(near.len(), 3);
+        assert!(near.iter().all(|hit| all.contains(hit)));
+    }
+}
@@ -0,0 +1,26 @@
+//! 診断ログモジュール
+//!
+//! `-v`（詳細）・`--quiet`（エラーのみ）に応じたログを標準エラー出力に書き出す。
+//! -v で検索ごとの走査件数、-vv で除外した候補の件数やキャッシュの利用、-vvv で候補ごとの判定を表示する。
+
+use std::io::IsTerminal;
+
+use tracing::Level;
+
+/// 指定された詳細度のログ出力を設定（2回目以降の呼び出しは無視される）
+pub fn init(verbose: u8, quiet: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => Level::ERROR,
+        (false, 0) => Level::WARN,
+        (false, 1) => Level::INFO,
+        (false, 2) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    };
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false)
+        .without_time()
+        .try_init();
+}
@@ -4,13 +4,131 @@
 
 mod structures;
 mod algorithms;
+mod areas;
+mod batch;
+mod bookmarks;
+mod budget;
+mod clearance;
+mod clipboard;
+mod config;
+mod coords;
+mod custom;
+#[cfg(feature = "db")]
+mod db;
+mod diffmap;
+mod doctor;
+mod documents;
+mod error;
+#[cfg(feature = "tui")]
+mod explore;
+mod export;
+mod farm;
+mod gamedata;
+mod filter;
+mod history;
+mod html;
+mod icons;
+mod i18n;
+mod info;
+mod locator;
+mod legend;
+#[cfg(feature = "world")]
+mod level;
+mod logging;
+mod meta;
+mod output;
+mod palette;
+mod portal;
+mod progress;
+mod proximity;
+mod quickcheck;
+mod render;
+mod repl;
+mod rng;
+mod route;
+mod schema;
+mod seeds;
+mod site;
+mod speedrun;
+#[cfg(feature = "tui")]
+mod task;
+mod template;
+mod travel;
+mod triangulate;
+mod version;
+mod versiondiff;
+mod world;
 
-use clap::{Parser, Subcommand};
-use serde::Serialize;
-use std::io::{self, Write};
+use std::io::{BufRead, BufReader, IsTerminal};
+use std::sync::Arc;
+use std::time::Duration;
 
-use structures::{StructureType, find_structures, find_nether_structures};
-use algorithms::biome::find_nearest_biome;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+
+use structures::{
+    PlacementModel, StructureHit, StructureKind, StructureSelection, StructureSelectionParser, StructureType, default_types, find_in_dimension, find_kind, find_nearest_any, find_strongholds, sort_nearest_first, fortress_area, structure_bbox, structure_biome, structure_confidence, Confidence,
+    STRONGHOLD_ID, stronghold_name, STRONGHOLD_SINCE_VERSION,
+};
+use algorithms::biome::{self, find_nearest_biome, BiomeType, MULTI_NOISE_SINCE_VERSION};
+use algorithms::column::biome_column;
+use algorithms::deep_dark::find_deep_dark;
+use algorithms::dungeon::{find_dungeons, SpawnerMob};
+use algorithms::ore::{find_ore_blobs, find_ore_veins, OreType, VeinType};
+use algorithms::underground::{compass_direction, find_underground_features, UndergroundFeature, RAVINE_DEEPSLATE_Y};
+use algorithms::height::sample_heights;
+use algorithms::loot::{predict_loot, ChestLoot, StructureLoot};
+use algorithms::spawn::estimate_spawn;
+use algorithms::slime::{find_slime_chunks, find_slime_clusters, render_slime_map};
+use areas::{ExcludeArea, SearchArea, Sector};
+use batch::{record_to_args, BatchResult};
+#[cfg(feature = "db")]
+use bookmarks::{export_bookmark_commands, export_waypoints, BookmarkStore, NewBookmark};
+use config::Config;
+use coords::{check_search_area, convert, overworld_to_nether, parse_xz, BlockPos, Dimension, NETHER_SCALE, WORLD_OFFSET_UNIT, XZ};
+use custom::{custom_structures, find_custom_structure, load_custom_structures, register_custom_structures};
+use diffmap::{render_diff_map, structure_diff, DiffMode, DiffSide};
+use doctor::run_checks;
+use documents::{
+    BiomeEntry, BiomeResult, ColumnResult, DeepDarkResult, DiffMapResult, DoctorResult, DungeonResult, BuildSiteResult, CorridorResult, FarmResult, LocationScoreResult, NetherClearResult, WoodResult, HeightmapResult, HubEntry, HubResult, ListResult, MapResult, OreResult, PortalResult, SearchResult, SlimeResult, SpawnResult,
+    StructureEntry, StructureResult, StrongholdMatch, TriangulateResult, UndergroundResult, VeinResult, VersionDiffResult, StructureChangeEntry, BiomeChangeEntry,
+};
+use error::{CliError, Outcome, SearchError};
+use versiondiff::{biome_change, structure_changes, StructureChange};
+#[cfg(feature = "tui")]
+use explore::{run_explorer, Explorer};
+use clearance::{find_build_areas, find_clear_areas, MAX_BUILD_SIZE};
+use clipboard::set_clipboard;
+use farm::{evaluate_farm_sites, FarmKind};
+use export::{copy_text, export_amulet, export_commands, heightmap_csv, heightmap_pixels, COPY_FORMATS, MAX_HEIGHTMAP_SIZE};
+#[cfg(feature = "db")]
+use export::target_y;
+use filter::ScriptFilter;
+use gamedata::{load_game_data, set_game_data, Channel};
+#[cfg(feature = "db")]
+use history::{replace_seed, HistoryStore};
+use html::{render_html_map, MapHeader};
+use icons::DEFAULT_MARKER_SIZE;
+use info::generation_info;
+use legend::{biomes_in_view, draw_legend, Legend};
+#[cfg(feature = "world")]
+use level::read_level_info;
+use output::{capture, emit_json, select_format};
+use palette::Palette;
+use portal::{predict_link, suggest_placement};
+use proximity::{apply_near, find_hubs, NearConstraint};
+use quickcheck::{run_quickcheck, QuickCheck, Target};
+use render::{density_cells, draw_markers, draw_route, render_map, write_gray_png, write_png, zoom_preset, Georeference, Image, MapStyle, MapView, MAX_MAP_SIZE, ZOOM_PRESETS};
+use repl::{run_repl, Session};
+use route::{plan_route, route_path_from_json, waypoints_from_json, Waypoint};
+use schema::output_schemas;
+use seeds::read_seeds_file;
+use site::{land_corridors, score_location, wood_availability, Criterion};
+use speedrun::plan_speedrun;
+use template::OutputTemplate;
+use travel::{distance_2d, format_duration, mode_display_name, travel_report};
+use triangulate::{triangulate, Throw};
+use version::McVersion;
+use world::WorldType;
 
 /// BedrockMate CLI - Minecraft Bedrock Edition 構造物ファインダー
 #[derive(Parser)]
@@ -21,202 +139,3709 @@ use algorithms::biome::find_nearest_biome;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// 設定ファイル（省略時は ~/.config/bedrockmate/config.toml）
+    #[arg(long, global = true)]
+    config: Option<String>,
+
+    /// 対象のゲームバージョン（例: 1.21.0。省略時は --world のワールドのバージョン、それもなければ最新）
+    #[arg(long, global = true)]
+    mc_version: Option<String>,
+
+    /// ワールドのフォルダ（または level.dat）。最後に開いたゲームのバージョンを対象にする
+    #[arg(long, global = true)]
+    world: Option<String>,
+
+    /// 構造物・バイオーム名の表示言語（ja, en）
+    #[arg(long, global = true)]
+    lang: Option<String>,
+
+    /// 診断ログを表示（-v: 走査件数、-vv: 除外した候補、-vvv: 候補ごとの判定）
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// 警告と進捗表示を出さない（エラーのみ表示）
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// 複数シードの検索と batch で、すべての検索で結果が見つかった場合だけ終了コード0にする（既定ではいずれか1つ）
+    #[arg(long, global = true)]
+    fail_if_empty: bool,
+
+    /// JSON出力と同じ結果にHandlebarsテンプレートを適用して出力（Discord用Markdown等）
+    #[arg(long, global = true)]
+    format_template: Option<String>,
+
+    /// 検索の制限時間（秒）。過ぎた場合はそれまでの結果を返し、JSON出力に truncated: true を付ける
+    #[arg(long, global = true)]
+    timeout: Option<f64>,
+
+    /// ワールドの種類（infinite, old, flat。old は256×256ブロックの旧形式で範囲外の結果を除き、flat はオーバーワールドの構造物を除く）
+    #[arg(long, global = true)]
+    world_type: Option<String>,
+
+    /// ワールドのずれ（dx,dz。移行や中心の付け替えで原点がずれたワールド用。入力と出力の座標すべてに適用。128の倍数）
+    #[arg(long, global = true, allow_hyphen_values = true)]
+    world_offset: Option<String>,
+
+    /// ゲーム内の「実験」の設定やベータ版でだけ生成される構造物・バイオームも対象にする
+    #[arg(long, global = true)]
+    experimental: bool,
+
+    /// 配信チャンネル（retail, preview。preview はプレビュー版の表を使い、既定の対象バージョンもプレビュー版にする）
+    #[arg(long, global = true)]
+    channel: Option<String>,
+
+    /// 構造物の配置・バイオームのパラメータの表を差し替えるディレクトリ（structures.json, biome_params.json）
+    #[arg(long, global = true)]
+    data_dir: Option<String>,
+}
+
+/// 検索コマンド（structures, nether, biome）に共通の引数
+#[derive(Args)]
+struct SearchOptions {
+    /// ワールドシード値（省略時は設定ファイルの値）
+    #[arg(short, long)]
+    seed: Option<i64>,
+
+    /// 比較する複数のシード値（カンマ区切り。結果をシードごとにまとめて出力）
+    #[arg(long, value_delimiter = ',', allow_hyphen_values = true, conflicts_with = "seed")]
+    seeds: Vec<i64>,
+
+    /// 比較するシード値を1行に1つ書いたファイル（# 以降はコメント）
+    #[arg(long, conflicts_with_all = ["seed", "seeds"])]
+    seeds_file: Option<String>,
+
+    /// 検索中心X座標（nether ではネザー座標）
+    #[arg(short = 'x', long, default_value = "0")]
+    center_x: i32,
+
+    /// 検索中心Z座標（nether ではネザー座標）
+    #[arg(short = 'z', long, default_value = "0")]
+    center_z: i32,
+
+    /// 検索半径（ブロック単位、既定: structures 5000, nether 1000, biome 10000）
+    #[arg(short, long)]
+    radius: Option<i32>,
+
+    /// 検索中心を推定スポーン地点にする（-x/-zより優先。nether ではスポーン地点のネザー座標）
+    #[arg(long)]
+    near_spawn: bool,
+
+    /// 出力形式（json, text, msgpack, cbor。既定: text。structures は html でバイオームの地図に結果を重ねた1つのHTMLファイル）
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// 最寄りの結果をクリップボードにコピー（coords: "X Y Z", tp: /tp コマンド, execute: /execute in コマンド。既定: coords）
+    #[arg(long, num_args = 0..=1, default_missing_value = "coords", conflicts_with_all = ["seeds", "seeds_file"])]
+    copy: Option<String>,
+}
+
+impl SearchOptions {
+    /// 検索半径（コマンドライン > 設定ファイル > コマンドごとの既定値）
+    fn radius(&self, config: &Config, default: i32) -> i32 {
+        config.radius(self.radius, default)
+    }
+
+    /// 出力形式（コマンドライン > 設定ファイル > text）
+    fn output(&self, config: &Config) -> String {
+        select_format(config.output(self.output.clone()))
+    }
+
+    /// シードの検索中心（--near-spawn の場合は推定スポーン地点をディメンションの座標に直す）
+    fn center(&self, seed: i64, dimension: Dimension) -> (i32, i32) {
+        let (x, z) = resolve_center(seed, self.center_x, self.center_z, self.near_spawn);
+        match dimension {
+            Dimension::Nether if self.near_spawn => (overworld_to_nether(x), overworld_to_nether(z)),
+            _ => (x, z),
+        }
+    }
+
+    /// 指定したシード（--seeds, --seeds-file の場合はそれぞれ）で検索を実行
+    fn for_each_seed(
+        &self,
+        config: &Config,
+        output: &str,
+        require_all: bool,
+        mut search: impl FnMut(i64) -> Result<Outcome, CliError>,
+    ) -> Result<Outcome, CliError> {
+        match seed_list(self.seeds.clone(), self.seeds_file.clone())? {
+            Some(seeds) => search_seeds(&seeds, output, require_all, search),
+            None => search(config.seed(self.seed).ok_or(CliError::MissingSeed)?),
+        }
+    }
+}
+
+/// 構造物の検索結果の絞り込みと書き出しに共通の引数（structures, nether）
+#[derive(Args)]
+struct ResultOptions {
+    /// 候補ごとに評価して絞り込むRhaiスクリプト（hit.type, hit.x, hit.z, hit.biome, hit.distance 等を参照できる）
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// 結果から除く円の範囲（"x,z,r"。nether ではネザー座標。探索済みの場所等。複数指定可）
+    #[arg(long, allow_hyphen_values = true)]
+    exclude: Vec<String>,
+
+    /// 検索中心から見た方角（n, ne, e, se, s, sw, w, nw）を中心とした90°の範囲の結果だけを残す
+    #[arg(long, conflicts_with = "quadrant")]
+    direction: Option<String>,
+
+    /// 検索中心からX・Z軸で区切った象限（+x+z, +x-z, -x+z, -x-z。北は -z、東は +x）の結果だけを残す
+    #[arg(long, allow_hyphen_values = true)]
+    quadrant: Option<String>,
+
+    /// 構造物の形とチェストの中身を予測して表示（埋蔵金、難破船、荒廃したポータル、エンドシティ、試練の間、廃坑、ネザー要塞、バスティオン。バスティオンはピグステップ等が入る確率も表示）
+    #[arg(long)]
+    loot: bool,
+
+    /// エクスポート形式（commands, amulet）。指定時は出力形式より優先
+    #[arg(short, long, conflicts_with_all = ["seeds", "seeds_file"])]
+    export: Option<String>,
+}
+
+impl ResultOptions {
+    /// --filter のスクリプトを読み込む
+    fn script_filter(&self) -> Result<Option<ScriptFilter>, CliError> {
+        self.filter.as_deref().map(ScriptFilter::load).transpose().map_err(CliError::Usage)
+    }
+
+    /// --exclude / --direction / --quadrant から結果を残す範囲を得る
+    fn area(&self) -> Result<SearchArea, CliError> {
+        let sector = match (&self.direction, &self.quadrant) {
+            (Some(name), _) => Some(Sector::direction(name).map_err(CliError::Usage)?),
+            (None, Some(spec)) => Some(Sector::quadrant(spec).map_err(CliError::Usage)?),
+            (None, None) => None,
+        };
+        let exclude = self.exclude.iter().map(|spec| ExcludeArea::parse(spec)).collect::<Result<_, _>>().map_err(CliError::Usage)?;
+        Ok(SearchArea { sector, exclude })
+    }
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// 構造物を検索
-    Structures {
-        /// ワールドシード値
-        #[arg(short, long)]
-        seed: i64,
+#[derive(Subcommand)]
+enum Commands {
+    /// 構造物を検索
+    Structures {
+        #[command(flatten)]
+        options: SearchOptions,
+
+        /// 検索する構造物タイプ（woodland_mansion 等の別名も可。end_city はエンドの座標。any:fortress,bastion で複数の種類のうち最寄りの1件。カスタム構造物の識別子も可）
+        #[arg(short = 't', long, default_value = "all", value_parser = StructureSelectionParser)]
+        structure_type: StructureSelection,
+
+        /// 検索するディメンション（overworld, nether, end。省略時は構造物タイプから決める。座標はそのディメンションの座標。all はそのディメンションの主な構造物）
+        #[arg(long, value_enum)]
+        dimension: Option<Dimension>,
+
+        #[command(flatten)]
+        results: ResultOptions,
+
+        /// 近さの条件で絞り込む（"village<=300:outpost" で300ブロック以内に前哨基地がある村。複数指定ですべてを満たすもの）
+        #[arg(long)]
+        near: Vec<String>,
+    },
+
+    /// バイオームを検索
+    Biome {
+        #[command(flatten)]
+        options: SearchOptions,
+
+        /// 検索するバイオーム（jungle, mesa, mushroom, ice_spikes, cherry_grove等）
+        #[arg(short = 't', long)]
+        target: String,
+    },
+
+    /// ネザー構造物を検索（要塞、バスティオン。座標はネザー座標）
+    Nether {
+        #[command(flatten)]
+        options: SearchOptions,
+
+        #[command(flatten)]
+        results: ResultOptions,
+    },
+
+    /// バスティオン（指定すればネザー要塞も）が入らないネザーの範囲を検索（nether コマンドの逆。金トラップの場所選びに）
+    NetherClear {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標（ネザー座標）
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標（ネザー座標）
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ネザーのブロック数、既定: 1000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 構造物が入らない円の半径（ネザーのブロック数）
+        #[arg(long, default_value = "64")]
+        clearance: i32,
+
+        /// ネザー要塞も避ける
+        #[arg(long)]
+        no_fortress: bool,
+
+        /// 表示する範囲の最大数
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 座標を変換（オーバーワールド⇔ネザー、ブロック⇔チャンク⇔リージョン）
+    Convert {
+        /// X座標
+        #[arg(short = 'x', long, allow_negative_numbers = true)]
+        x: i32,
+
+        /// Z座標
+        #[arg(short = 'z', long, allow_negative_numbers = true)]
+        z: i32,
+
+        /// 入力座標の種類（block, nether, chunk, region）
+        #[arg(short, long, default_value = "block")]
+        from: String,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 2点間の距離と移動手段ごとの所要時間を計算
+    Distance {
+        /// 出発地X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        from_x: i32,
+
+        /// 出発地Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        from_z: i32,
+
+        /// 目的地X座標
+        #[arg(long, allow_negative_numbers = true)]
+        to_x: i32,
+
+        /// 目的地Z座標
+        #[arg(long, allow_negative_numbers = true)]
+        to_z: i32,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 1.18 以降の鉄・銅の大鉱脈を含むサブチャンクを検索
+    Veins {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 200）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 大鉱脈の種類で絞り込む（iron, copper）
+        #[arg(long)]
+        vein: Option<String>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 地表の溶岩湖や洞窟の入口、渓谷、帯水層、鍾乳石の群生、繁茂した洞窟の目印を予測
+    Underground {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 300）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 検索する地形（all, lava_lake, cave_entrance, ravine, aquifer, dripstone_cluster, azalea_tree）
+        #[arg(long, default_value = "all")]
+        feature: String,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// スライムチャンクを検索（シード不要）
+    Slime {
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 160）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 出力形式（json, text, map, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// ディープダークの地域と、その中の古代都市を予測
+    Deepdark {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 3000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// ディープダークの中の古代都市だけを表示
+        #[arg(long)]
+        only_deep_dark: bool,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// ダンジョン（モンスタールーム）とスポナーのモブを予測
+    Dungeon {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 300）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// スポナーのモブで絞り込む（zombie, skeleton, spider）
+        #[arg(long)]
+        mob: Option<String>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// チャンクの鉱脈の位置と高さを予測（古代の残骸はネザーの座標）
+    Ore {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 鉱石の種類（diamond, ancient_debris）
+        #[arg(long, default_value = "diamond")]
+        ore: String,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 0 = 検索中心のチャンクだけ）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 座標の Y=-64〜320 のバイオームを高さの帯ごとに表示（地下の洞窟のバイオームを確認）
+    Column {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// X座標
+        #[arg(short = 'x', long, allow_negative_numbers = true)]
+        x: i32,
+
+        /// Z座標
+        #[arg(short = 'z', long, allow_negative_numbers = true)]
+        z: i32,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 定番の質問に既定の範囲で1回で答える（mushroom-near-spawn: スポーン地点の近くにキノコ島はある？ 等）
+    Quickcheck {
+        /// 質問のプリセット（mushroom-near-spawn, cherry-near-spawn, village-near-spawn）
+        preset: String,
+
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 拠点の候補地の周りで手に入る木材の種類と、手に入らない木材までの距離を表示
+    Wood {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 拠点のX座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 拠点のZ座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 手に入るとみなす半径（ブロック単位、既定: 1000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 範囲内にない木材を探す半径（ブロック）
+        #[arg(long, default_value = "5000")]
+        search_radius: i32,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 8方位それぞれの海に出ずに進める距離を調べ、陸続きの道が長い方角を表示（陸路の探索や線路の向き選びに）
+    Corridor {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 出発地点のX座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 出発地点のZ座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 調べる距離（ブロック単位、既定: 5000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 座標を拠点の場所として基準ごとに採点し、重み付きの合計点を表示
+    ScoreLocation {
+        /// X座標
+        #[arg(allow_negative_numbers = true)]
+        x: i32,
+
+        /// Z座標
+        #[arg(allow_negative_numbers = true)]
+        z: i32,
+
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 基準の重み（"基準=重み"。基準は biome_diversity, river_access, terrain_mix, village, fortress。
+        /// 指定しない基準の重みは1、0で合計から除く。複数指定可）
+        #[arg(short, long)]
+        weight: Vec<String>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 地上の構造物がなく起伏の小さい正方形の範囲を検索（巨大建築や配布ワールドの土台選びに）
+    BuildSite {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 2000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 範囲の一辺（ブロック、16〜1024）
+        #[arg(long, default_value = "128")]
+        size: i32,
+
+        /// 許容する地表の高さの標準偏差（ブロック）
+        #[arg(long, default_value = "3.0")]
+        max_stddev: f64,
+
+        /// 表示する範囲の最大数
+        #[arg(long, default_value = "3")]
+        limit: usize,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// ファームの候補地の構造物を作りやすさで採点して並べる
+    Farm {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// ファームの種類（guardian: 海底神殿のガーディアントラップ、raid: 前哨基地の襲撃者トラップ）
+        #[arg(short, long, default_value = "guardian")]
+        kind: String,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 3000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 表示する候補地の最大数
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 指定した種類の構造物の多くに近い地点（拠点の候補）を検索
+    Hub {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 検索中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 検索中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索半径（ブロック単位、既定: 3000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 構造物タイプ（カンマ区切り。stronghold も指定可）
+        #[arg(short = 't', long, value_delimiter = ',', default_value = "village,outpost,monument,mansion")]
+        structure_type: Vec<String>,
+
+        /// 地点から構造物までの最大の距離（ブロック単位）
+        #[arg(short, long, default_value = "500")]
+        distance: i32,
+
+        /// 距離以内にある必要がある構造物の種類の数（既定: 指定したすべての種類）
+        #[arg(long)]
+        min_types: Option<usize>,
+
+        /// 表示する地点の最大数
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// バイオームの地図に構造物の位置を重ねてPNG画像やHTMLに書き出す
+    Map {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 地図の半径（ブロック単位、既定: 2000。縮尺だけを指定した場合は1024ピクセル四方の範囲）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 重ねる構造物タイプ（カンマ区切り。stronghold も指定可）
+        #[arg(short = 't', long, value_delimiter = ',', default_value = "village,outpost,monument,mansion")]
+        structure_type: Vec<String>,
+
+        /// 1ピクセルあたりのブロック数（既定: 範囲が1024ピクセル四方程度に収まる縮尺）
+        #[arg(long, conflicts_with = "zoom")]
+        blocks_per_pixel: Option<i32>,
+
+        /// 縮尺のプリセット（base: 1, town: 4, region: 16, continent: 64 ブロック/ピクセル）
+        #[arg(long)]
+        zoom: Option<String>,
+
+        /// バイオームの配色（default, vanilla, high_contrast, colorblind か、配色のTOML・JSONファイル）
+        #[arg(long, default_value = "default")]
+        palette: String,
+
+        /// 地表の推定の高さから起伏の陰影を付ける
+        #[arg(long)]
+        hillshade: bool,
+
+        /// 区画ごとの構造物の数で色分けする（区画の1辺のブロック数。既定: 1000）
+        #[arg(long, num_args = 0..=1, default_missing_value = "1000")]
+        heatmap: Option<i32>,
+
+        /// 道順を重ねる（route コマンドのJSON出力、地点の配列または検索結果のJSONのファイル）
+        #[arg(long)]
+        route: Option<String>,
+
+        /// 構造物のアイコンの大きさ（ピクセル数）
+        #[arg(long, default_value_t = DEFAULT_MARKER_SIZE)]
+        marker_size: u32,
+
+        /// シード・バージョン・中心座標と凡例を画像に描かない
+        #[arg(long)]
+        no_legend: bool,
+
+        /// PNG画像の書き出し先
+        #[arg(long)]
+        png: Option<String>,
+
+        /// 出力形式（json, text, html, msgpack, cbor。既定: text。html は地図をHTMLとして標準出力に書き出す）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 同じ範囲を2つのシード（またはバージョン）で描いた地図を比較するPNG画像を書き出す
+    Diffmap {
+        /// 比較元（A）のワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 比較先（B）のワールドシード値（省略時はAと同じ）
+        #[arg(long, required_unless_present = "other_version")]
+        other_seed: Option<i64>,
+
+        /// 比較先（B）のバージョン（省略時はAと同じ。例: 1.20.0）
+        #[arg(long)]
+        other_version: Option<String>,
+
+        /// 中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 地図の半径（ブロック単位、既定: 2000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 重ねる構造物タイプ（カンマ区切り。stronghold も指定可）
+        #[arg(short = 't', long, value_delimiter = ',', default_value = "village,outpost,monument,mansion")]
+        structure_type: Vec<String>,
+
+        /// 1ピクセルあたりのブロック数（既定: 範囲が1024ピクセル四方程度に収まる縮尺）
+        #[arg(long)]
+        blocks_per_pixel: Option<i32>,
+
+        /// 描き方（overlay: Aの地図に違いを重ねる, side: 左右に並べる）
+        #[arg(long, default_value = "overlay")]
+        mode: String,
+
+        /// 構造物のアイコンの大きさ（ピクセル数）
+        #[arg(long, default_value_t = DEFAULT_MARKER_SIZE)]
+        marker_size: u32,
+
+        /// PNG画像の書き出し先
+        #[arg(long)]
+        png: String,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 同じ検索を2つのバージョンで実行し、移動・出現・消滅する構造物とバイオームを表示（ワールドを更新するかの判断に）
+    VersionDiff {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 比較元のバージョン（省略時は対象バージョン。例: 1.20.0）
+        #[arg(long)]
+        from: Option<String>,
+
+        /// 比較先のバージョン（例: 1.21.0）
+        #[arg(long)]
+        to: String,
+
+        /// 中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 検索範囲（中心からのブロック数、既定: 2000）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 比較する構造物タイプ（カンマ区切り。stronghold も指定可。既定: オーバーワールドのすべて）
+        #[arg(short = 't', long, value_delimiter = ',')]
+        structure_type: Vec<String>,
+
+        /// 比較するバイオーム（カンマ区切り。既定: すべて）
+        #[arg(short, long, value_delimiter = ',')]
+        biome: Vec<String>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 範囲の地表の推定の高さをPNG画像やCSVに書き出す
+    Heightmap {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 範囲の半径（ブロック単位、既定: 256）
+        #[arg(short, long)]
+        radius: Option<i32>,
+
+        /// 1ピクセル（CSVの1マス）あたりのブロック数
+        #[arg(long, default_value = "1")]
+        step: i32,
+
+        /// グレースケールのPNG画像の書き出し先（低い所が黒、高い所が白）
+        #[arg(long, required_unless_present = "csv")]
+        png: Option<String>,
+
+        /// CSVの書き出し先（1行目がX座標、各行の先頭がZ座標）
+        #[arg(long)]
+        csv: Option<String>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// ワールドスポーン地点を推定
+    Spawn {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// エンダーアイの投擲地点と向きから要塞の位置を三角測量
+    Triangulate {
+        /// 投擲記録 "X,Z,向き(度)"（2回以上指定）
+        #[arg(short, long = "throw", required = true, allow_hyphen_values = true)]
+        throws: Vec<String>,
+
+        /// ワールドシード値（指定すると最寄りの予測要塞に合わせる）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 対応している構造物とバイオームの一覧を表示
+    List {
+        /// 表示する種類（all, structures, biomes）
+        #[arg(short, long, default_value = "all")]
+        kind: String,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 構造物・バイオームの生成ルールを説明
+    Info {
+        /// 構造物またはバイオームの識別子（village, stronghold, jungle等）
+        name: String,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 複数の地点を巡る短いルートを計画
+    Route {
+        /// 出発地X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 出発地Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 経由地点 "X,Z" または "X,Z,名前"（複数指定可）
+        #[arg(short, long = "point", allow_hyphen_values = true)]
+        points: Vec<String>,
+
+        /// 経由地点を読み込む検索結果JSONファイル（structures -o json の出力）
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// ネザー経由の座標と距離も表示
+        #[arg(long)]
+        nether: bool,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// シードのスピードラン向けのルートを提案（スポーン→村か難破船→荒廃したポータル→ネザー要塞とバスティオン→要塞）
+    SpeedrunPlan {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// オーバーワールドの各区間の検索半径（ブロック）
+        #[arg(short, long, default_value = "2000")]
+        radius: i32,
+
+        /// ネザーの入口からネザー要塞・バスティオンを探す半径（ネザーのブロック数）
+        #[arg(long, default_value = "1000")]
+        nether_radius: i32,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 新しいネザーポータルの接続先を予測
+    Portal {
+        /// 新しいポータルのX座標
+        #[arg(short = 'x', long, allow_negative_numbers = true)]
+        x: i32,
+
+        /// 新しいポータルのZ座標
+        #[arg(short = 'z', long, allow_negative_numbers = true)]
+        z: i32,
+
+        /// 新しいポータルを置くディメンション（overworld, nether）
+        #[arg(short, long, default_value = "overworld")]
+        from: String,
+
+        /// 既存のオーバーワールド側ポータル "X,Z"（複数指定可）
+        #[arg(long = "overworld-portal", allow_hyphen_values = true)]
+        overworld_portals: Vec<String>,
+
+        /// 既存のネザー側ポータル "X,Z"（複数指定可）
+        #[arg(long = "nether-portal", allow_hyphen_values = true)]
+        nether_portals: Vec<String>,
+
+        /// つなげたい移動先ポータル "X,Z"（指定すると設置場所を提案）
+        #[arg(short, long, allow_hyphen_values = true)]
+        target: Option<String>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 各コマンドのJSON出力のJSON Schemaを表示
+    Schema {
+        /// コマンド名（structures, biome 等。省略時はすべてのコマンド）
+        command: Option<String>,
+    },
+
+    /// 組み込みデータの整合性と各アルゴリズムの動作を自己診断
+    Doctor {
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 気になった地点をメモ付きで保存・一覧・書き出し
+    Bookmark {
+        /// ブックマークファイル（省略時は ~/.config/bedrockmate/bookmarks.db）
+        #[arg(long)]
+        db: Option<String>,
+
+        #[command(subcommand)]
+        action: BookmarkAction,
+    },
+
+    /// バイオーム地図と構造物を全画面で表示（矢印キーで移動、+/- で拡大縮小）
+    Explore {
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 表示中心X座標
+        #[arg(short = 'x', long, default_value = "0", allow_negative_numbers = true)]
+        center_x: i32,
+
+        /// 表示中心Z座標
+        #[arg(short = 'z', long, default_value = "0", allow_negative_numbers = true)]
+        center_z: i32,
+
+        /// 表示中心を推定スポーン地点にする（-x/-zより優先）
+        #[arg(long)]
+        near_spawn: bool,
+    },
+
+    /// JSONLの検索条件を1行ずつ実行し、結果をJSONLで出力
+    Batch {
+        /// 入力ファイル（省略時または "-" の場合は標準入力）
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+
+    /// シード・バージョン・検索中心を保持したまま短いコマンドで検索する対話モード
+    Repl {
+        /// ワールドシード値（省略時は設定ファイルの値。対話中に seed で変更可）
+        #[arg(short, long)]
+        seed: Option<i64>,
+    },
+
+    /// 記録した実行履歴の表示・再実行（設定ファイルで history = true にすると記録）
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// 実行履歴を新しい順に表示
+    List {
+        /// 表示する件数
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 履歴のコマンドを再実行
+    Rerun {
+        /// 履歴ID（history list で確認）
+        id: i64,
+
+        /// シード値を差し替えて実行
+        #[arg(short, long)]
+        seed: Option<i64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BookmarkAction {
+    /// 地点を保存
+    Add {
+        /// 地点の名前
+        #[arg(long)]
+        name: String,
+
+        /// X座標
+        #[arg(short = 'x', long, allow_negative_numbers = true)]
+        x: i32,
+
+        /// Z座標
+        #[arg(short = 'z', long, allow_negative_numbers = true)]
+        z: i32,
+
+        /// Y座標（省略時はシードから推定した地表の高さ）
+        #[arg(short = 'y', long, allow_negative_numbers = true)]
+        y: Option<i32>,
+
+        /// ディメンション（overworld, nether）
+        #[arg(short, long, default_value = "overworld")]
+        dimension: String,
+
+        /// ワールドシード値（省略時は設定ファイルの値）
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 分類（base, farm 等の自由な文字列）
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// メモ（「拠点予定地」「襲撃トラップ」等）
+        #[arg(short, long)]
+        notes: Option<String>,
+    },
+
+    /// 保存した地点を一覧表示
+    List {
+        /// このシードのブックマークだけ表示
+        #[arg(short, long)]
+        seed: Option<i64>,
+
+        /// 出力形式（json, text, msgpack, cbor。既定: text）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 保存した地点を書き出し
+    Export {
+        /// 書き出し形式（waypoints: route --input 用JSON, commands: テレポートコマンド）
+        #[arg(short, long, default_value = "waypoints")]
+        format: String,
+
+        /// このシードのブックマークだけ書き出す
+        #[arg(short, long)]
+        seed: Option<i64>,
+    },
+
+    /// 保存した地点を削除
+    Remove {
+        /// ブックマークID（list で確認）
+        id: i64,
+    },
+}
+
+fn main() {
+    let code = match run(std::env::args().collect()) {
+        Ok(outcome) => outcome.exit_code(),
+        Err(e) => {
+            eprintln!("{}", e);
+            e.exit_code()
+        }
+    };
+    std::process::exit(code);
+}
+
+/// コマンドライン引数（先頭はプログラム名）を解釈して実行
+///
+/// 引数の誤りは clap が使い方を表示して終了コード2で終了する。
+fn run(args: Vec<String>) -> Result<Outcome, CliError> {
+    execute(Cli::parse_from(&args), &args)
+}
+
+/// 解釈済みのコマンドを実行
+fn execute(cli: Cli, args: &[String]) -> Result<Outcome, CliError> {
+    logging::init(cli.verbose, cli.quiet);
+
+    let mut config = Config::load(cli.config.as_deref()).map_err(CliError::Config)?;
+
+    let template = match &cli.format_template {
+        Some(path) => Some(OutputTemplate::load(path).map_err(CliError::Usage)?),
+        None => None,
+    };
+    if template.is_some() {
+        // テンプレートはJSON出力と同じ結果の文書に適用する
+        config.force_output("json");
+    }
+    output::set_template(template);
+
+    let lang = cli.lang.or_else(|| config.lang.clone()).unwrap_or_else(|| "ja".to_string());
+    if !i18n::set_lang(&lang) {
+        return Err(CliError::Usage(format!("不明な言語: {}（{}）", lang, i18n::SUPPORTED_LANGS.join(", "))));
+    }
+
+    register_custom_structures(load_custom_structures(config.structures_file.as_deref()).map_err(CliError::Config)?);
+    let channel = match cli.channel.or_else(|| config.channel.clone()) {
+        Some(id) => Channel::from_id(&id).ok_or_else(|| {
+            let ids: Vec<_> = Channel::ALL.iter().map(|c| c.id()).collect();
+            CliError::Usage(format!("不明なチャンネル: {}（{}）", id, ids.join(", ")))
+        })?,
+        None => Channel::Retail,
+    };
+    let data_dir = cli.data_dir.or_else(|| config.data_dir.clone());
+    set_game_data(Arc::new(load_game_data(channel, data_dir.as_deref()).map_err(CliError::Config)?));
+
+    let parse_version =
+        |v: &str| McVersion::parse(v).ok_or_else(|| CliError::Usage(format!("不正なバージョン: {}（例: 1.21.0）", v)));
+    let world_version = match &cli.world {
+        Some(path) => Some(world_game_version(path)?),
+        None => None,
+    };
+    let mc_version = match (cli.mc_version.as_deref().map(parse_version).transpose()?, world_version) {
+        (Some(explicit), Some(detected)) => {
+            if explicit != detected {
+                tracing::warn!("--mc-version {} はワールドのバージョン {} と異なります（--mc-version を使います）", explicit, detected);
+            }
+            explicit
+        }
+        (Some(explicit), None) => explicit,
+        (None, Some(detected)) => detected,
+        (None, None) => match &config.version {
+            Some(v) => parse_version(v)?,
+            None => channel.default_version(),
+        },
+    };
+    let experimental = cli.experimental || config.experimental.unwrap_or(false);
+    version::set_experimental(experimental);
+    meta::set_mc_version(mc_version);
+    biome::set_version(mc_version, experimental);
+
+    let world = match cli.world_type.or_else(|| config.world_type.clone()) {
+        Some(id) => WorldType::from_id(&id).ok_or_else(|| {
+            let ids: Vec<_> = WorldType::ALL.iter().map(|w| w.id()).collect();
+            CliError::Usage(format!("不明なワールドの種類: {}（{}）", id, ids.join(", ")))
+        })?,
+        None => WorldType::Infinite,
+    };
+    if world == WorldType::Flat {
+        warn_flat_world(&cli.command);
+    }
+    // batch・repl では前の行のずれが残らないよう、指定がなければ0に戻す
+    let (dx, dz) = match cli.world_offset.or_else(|| config.world_offset.clone()) {
+        Some(offset) => {
+            let (dx, dz) = parse_xz(&offset)
+                .ok_or_else(|| CliError::Usage(format!("不正なワールドのずれ: {}（例: 1024,-2048）", offset)))?;
+            if dx % WORLD_OFFSET_UNIT != 0 || dz % WORLD_OFFSET_UNIT != 0 {
+                return Err(CliError::Usage(format!(
+                    "--world-offset は {} の倍数で指定してください（ネザーでは1/8になるため）: {}",
+                    WORLD_OFFSET_UNIT, offset
+                )));
+            }
+            (dx, dz)
+        }
+        None => (0, 0),
+    };
+    coords::set_world_offset(dx, dz);
+
+    if let Some(seconds) = cli.timeout.filter(|t| !(*t > 0.0 && t.is_finite())) {
+        return Err(CliError::Usage(format!("制限時間は正の秒数で指定してください: {}", seconds)));
+    }
+    budget::set_timeout(cli.timeout.map(Duration::from_secs_f64));
+
+    // 全画面・対話モードの表示を崩さないよう、進捗表示は単発の検索コマンドだけにする
+    progress::set_enabled(
+        !cli.quiet
+            && matches!(
+                cli.command,
+                Commands::Structures { .. } | Commands::Biome { .. } | Commands::Nether { .. }
+            ),
+    );
+
+    let replayable = !matches!(
+        cli.command,
+        Commands::History { .. } | Commands::Repl { .. } | Commands::Batch { .. }
+    );
+    if config.history == Some(true) && replayable {
+        record_history(&args[1..]);
+    }
+
+    match cli.command {
+        Commands::Structures {
+            options,
+            structure_type,
+            dimension,
+            results,
+            near,
+        } => {
+            let radius = options.radius(&config, 5000);
+            let output = options.output(&config);
+            let copy = &options.copy;
+            check_copy_format(copy.as_deref())?;
+            // any: は指定した種類のうち最も近い1件だけを探す
+            let any_types = match &structure_type {
+                StructureSelection::Any(types) => Some(available_any_types(types, mc_version)?),
+                _ => None,
+            };
+            if any_types.is_some() && (results.filter.is_some() || !near.is_empty()) {
+                return Err(CliError::Usage("any: は --filter や --near と同時に指定できません".to_string()));
+            }
+            let structure_types: Vec<StructureType> = match &structure_type {
+                StructureSelection::Any(_) => vec![],
+                // 対象バージョンで生成されない構造物は除外
+                StructureSelection::All => default_types(dimension.unwrap_or(Dimension::Overworld))
+                    .iter()
+                    .copied()
+                    .filter(|st| st.is_available(mc_version))
+                    .collect(),
+                StructureSelection::Stronghold if !mc_version.is_at_least(STRONGHOLD_SINCE_VERSION) => {
+                    return Err(CliError::Usage(format!("{} は {} 以降で追加された構造物です", STRONGHOLD_ID, STRONGHOLD_SINCE_VERSION)));
+                }
+                StructureSelection::Stronghold => vec![],
+                StructureSelection::Builtin(st) if !st.is_available(mc_version) => {
+                    return Err(structure_not_available(st, mc_version));
+                }
+                StructureSelection::Builtin(st) => vec![*st],
+                StructureSelection::Custom(id) => match find_custom_structure(id) {
+                    Some(_) => vec![],
+                    None => return Err(CliError::Usage(format!("不明な構造物タイプ: {}", id))),
+                },
+            };
+            // 構造物タイプのディメンション（ネザー構造物はネザー、エンドシティはエンドの座標）
+            let typed = any_types
+                .iter()
+                .flatten()
+                .chain(&structure_types)
+                .next()
+                .map(|st| st.dimension())
+                .or_else(|| match &structure_type {
+                    StructureSelection::Custom(id) => find_custom_structure(id).map(|c| c.dimension),
+                    StructureSelection::Stronghold => Some(Dimension::Overworld),
+                    _ => None,
+                });
+            let dimension = match (dimension, typed) {
+                (Some(d), Some(t)) if d != t => {
+                    return Err(SearchError::WrongDimension { structure: structure_type.id(), dimension: d }.into());
+                }
+                (Some(d), _) | (None, Some(d)) => d,
+                (None, None) => Dimension::Overworld,
+            };
+            // 組み込みの構造物タイプ以外の種類（all の場合はそのディメンションの登録済みのカスタム構造物もすべて検索する）
+            let kinds: Vec<StructureKind> = match &structure_type {
+                StructureSelection::All => custom_structures()
+                    .iter()
+                    .filter(|c| c.dimension == dimension)
+                    .map(|c| StructureKind::Custom(c.id.clone()))
+                    .collect(),
+                StructureSelection::Stronghold => vec![StructureKind::Stronghold],
+                StructureSelection::Custom(id) => vec![StructureKind::Custom(id.clone())],
+                _ => vec![],
+            };
+
+            if output == "html" {
+                if !options.seeds.is_empty() || options.seeds_file.is_some() {
+                    return Err(CliError::Usage("html 形式は複数のシードの比較には使えません".to_string()));
+                }
+                if dimension != Dimension::Overworld {
+                    return Err(CliError::Usage("html 形式はオーバーワールドの構造物のみ対応しています".to_string()));
+                }
+            }
+
+            let requested: Vec<String> = match &any_types {
+                Some(types) => types.iter().map(|st| st.id().to_string()).collect(),
+                None if structure_type == StructureSelection::All => {
+                    structure_types.iter().map(|st| st.id().to_string()).collect()
+                }
+                None => vec![structure_type.id()],
+            };
+            let missing: Vec<String> = requested.into_iter().filter(|id| !world.is_generated(id)).collect();
+            if !missing.is_empty() {
+                tracing::warn!("{} は {} のワールドでは生成されません", missing.join(", "), world.id());
+            }
+            let filter = results.script_filter()?;
+            let near: Vec<NearConstraint> =
+                near.iter().map(|spec| NearConstraint::parse(spec)).collect::<Result<_, _>>().map_err(CliError::Usage)?;
+            let area = results.area()?;
+            let search = |seed: i64| {
+                let (center_x, center_z) = options.center(seed, dimension);
+                warn_outside_world(world, dimension, center_x, center_z, radius);
+                let center = BlockPos::new(center_x, center_z);
+                let mut all_structures = find_in_dimension(seed, dimension, center, radius, &structure_types)?;
+                for kind in &kinds {
+                    all_structures.extend(find_kind(seed, kind, center, radius)?);
+                }
+                if let Some(types) = &any_types {
+                    let accept = |pos: BlockPos| area.accepts(center_x, center_z, pos.x, pos.z) && world.contains(dimension, pos.x, pos.z);
+                    all_structures.extend(find_nearest_any(seed, center, radius, types, accept)?);
+                }
+                if area.is_restricted() {
+                    all_structures = area.apply(center_x, center_z, all_structures);
+                }
+                all_structures = world.retain(all_structures);
+                if !near.is_empty() {
+                    all_structures = apply_near(&near, seed, center_x, center_z, radius, all_structures)?;
+                }
+
+                if let Some(filter) = &filter {
+                    all_structures = filter
+                        .apply(seed, dimension, center_x, center_z, all_structures)
+                        .map_err(CliError::Usage)?;
+                }
+
+                sort_nearest_first(&mut all_structures, BlockPos::new(center_x, center_z));
+
+                match &results.export {
+                    Some(format) => export_results(format, seed, dimension, &all_structures)?,
+                    None if output == "html" => {
+                        let view = MapView::around(center_x, center_z, radius, MapView::auto_scale(radius));
+                        let image = render_map(seed, &view, &MapStyle::default(), &[], center_x, center_z);
+                        let header = MapHeader {
+                            seed,
+                            center_x,
+                            center_z,
+                            radius,
+                            marker_size: DEFAULT_MARKER_SIZE,
+                        };
+                        output_html_map(&header, &view, &image, &all_structures)?
+                    }
+                    None => output_results(&output, seed, center_x, center_z, radius, &all_structures, results.loot)?,
+                }
+                if let (Some(format), Some(nearest)) = (&copy, all_structures.first()) {
+                    copy_nearest(format, seed, dimension, nearest.pos.x, nearest.pos.z);
+                }
+                Ok(Outcome::from_found(!all_structures.is_empty()))
+            };
+            options.for_each_seed(&config, &output, cli.fail_if_empty, search)
+        }
+
+        Commands::Nether { options, results } => {
+            let radius = options.radius(&config, 1000);
+            let output = options.output(&config);
+            let copy = &options.copy;
+            check_copy_format(copy.as_deref())?;
+            let filter = results.script_filter()?;
+            let area = results.area()?;
+            let search = |seed: i64| {
+                let (center_x, center_z) = options.center(seed, Dimension::Nether);
+                warn_outside_world(world, Dimension::Nether, center_x, center_z, radius);
+                let mut structures =
+                    find_in_dimension(seed, Dimension::Nether, BlockPos::new(center_x, center_z), radius, default_types(Dimension::Nether))?;
+                for c in custom_structures().iter().filter(|c| c.dimension == Dimension::Nether) {
+                    structures.extend(find_kind(seed, &StructureKind::Custom(c.id.clone()), BlockPos::new(center_x, center_z), radius)?);
+                }
+                structures = world.retain(structures);
+                if area.is_restricted() {
+                    structures = area.apply(center_x, center_z, structures);
+                }
+                if let Some(filter) = &filter {
+                    structures = filter.apply(seed, Dimension::Nether, center_x, center_z, structures).map_err(CliError::Usage)?;
+                }
+                sort_nearest_first(&mut structures, BlockPos::new(center_x, center_z));
+                match &results.export {
+                    Some(format) => export_results(format, seed, Dimension::Nether, &structures)?,
+                    None => output_results(&output, seed, center_x, center_z, radius, &structures, results.loot)?,
+                }
+                let nearest = structures.iter().min_by_key(|hit| hit.pos.distance_sq(BlockPos::new(center_x, center_z)));
+                if let (Some(format), Some(nearest)) = (&copy, nearest) {
+                    copy_nearest(format, seed, Dimension::Nether, nearest.pos.x, nearest.pos.z);
+                }
+                Ok(Outcome::from_found(!structures.is_empty()))
+            };
+            options.for_each_seed(&config, &output, cli.fail_if_empty, search)
+        }
+
+        Commands::Biome { options, target } => {
+            let radius = options.radius(&config, 10000);
+            let output = options.output(&config);
+            let copy = &options.copy;
+            check_copy_format(copy.as_deref())?;
+            if mc_version < MULTI_NOISE_SINCE_VERSION {
+                tracing::info!("{}より前のバージョンのため、レイヤー方式のバイオーム生成の近似で検索します", MULTI_NOISE_SINCE_VERSION);
+            }
+            match BiomeType::from_str(&target) {
+                None => return Err(SearchError::UnknownBiome(target).into()),
+                Some(b) if !b.is_available(mc_version) => {
+                    return Err(not_available(b.id(), "バイオーム", b.since_version(), b.experimental_since(), mc_version));
+                }
+                Some(_) => {}
+            }
+            let search = |seed: i64| {
+                let (center_x, center_z) = options.center(seed, Dimension::Overworld);
+                warn_outside_world(world, Dimension::Overworld, center_x, center_z, radius);
+                // 範囲の限られたワールドでは範囲の外まで探さない
+                let radius = world.farthest(Dimension::Overworld, center_x, center_z).map_or(radius, |far| radius.min(far));
+                let nearest =
+                    find_nearest_biome(seed, BlockPos::new(center_x, center_z), radius, &target, |pos| world.contains(Dimension::Overworld, pos.x, pos.z))?;
+                match nearest {
+                    Some((BlockPos { x, z }, distance)) => {
+                        if output == "json" {
+                            let result = BiomeResult {
+                                seed,
+                                target_biome: target.clone(),
+                                found: true,
+                                x: Some(x),
+                                z: Some(z),
+                                distance: Some(distance),
+                                confidence: Confidence::Approximate,
+                                truncated: budget::truncated(),
+                            };
+                            emit_json(&result)?;
+                        } else {
+                            println!("🌴 最寄りの{}バイオーム", target);
+                            println!("   座標: X={}, Z={}", x, z);
+                            println!("   距離: {:.0}ブロック", distance);
+                            print_truncated_notice();
+                        }
+                        if let Some(format) = &copy {
+                            copy_nearest(format, seed, Dimension::Overworld, x, z);
+                        }
+                    }
+                    None => {
+                        if output == "json" {
+                            let result = BiomeResult {
+                                seed,
+                                target_biome: target.clone(),
+                                found: false,
+                                x: None,
+                                z: None,
+                                distance: None,
+                                confidence: Confidence::Approximate,
+                                truncated: budget::truncated(),
+                            };
+                            emit_json(&result)?;
+                        } else {
+                            println!("❌ {}バイオームが見つかりませんでした（範囲: {}ブロック）", target, radius);
+                            print_truncated_notice();
+                        }
+                    }
+                }
+                Ok(Outcome::from_found(nearest.is_some()))
+            };
+            options.for_each_seed(&config, &output, cli.fail_if_empty, search)
+        }
+
+        Commands::Convert { x, z, from, output } => {
+            let output = select_format(config.output(output));
+            let conversion = convert(x, z, &from)?;
+
+            if output == "json" {
+                emit_json(&conversion)?;
+            } else {
+                let range = &conversion.block_range;
+                println!("📍 座標変換結果（入力: {} X={}, Z={}）", from, x, z);
+                println!("   ブロック範囲: X={}〜{}, Z={}〜{}", range.min_x, range.max_x, range.min_z, range.max_z);
+                println!("   オーバーワールド: X={}, Z={}", conversion.overworld.x, conversion.overworld.z);
+                println!("   ネザー: X={}, Z={}", conversion.nether.x, conversion.nether.z);
+                println!("   チャンク: {}, {}", conversion.chunk.x, conversion.chunk.z);
+                println!("   チャンク内座標: ({}, {})", conversion.chunk_local.x, conversion.chunk_local.z);
+                println!("   リージョン: {}, {} ({})", conversion.region.x, conversion.region.z, conversion.region_file);
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Distance {
+            from_x,
+            from_z,
+            to_x,
+            to_z,
+            output,
+        } => {
+            let output = select_format(config.output(output));
+            let report = travel_report(from_x, from_z, to_x, to_z);
+
+            if output == "json" {
+                emit_json(&report)?;
+            } else {
+                println!("📏 X={}, Z={} → X={}, Z={}", from_x, from_z, to_x, to_z);
+                println!("   距離: {:.0}ブロック（ネザー側: {:.0}ブロック）", report.distance, report.nether_distance);
+                for time in &report.times {
+                    println!("   {}: {}", mode_display_name(&time.mode), format_duration(time.seconds));
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Slime {
+            center_x,
+            center_z,
+            radius,
+            output,
+        } => {
+            let radius = config.radius(radius, 160);
+            let output = select_format(config.output(output));
+            let chunks = find_slime_chunks(center_x, center_z, radius)?;
+            let clusters = find_slime_clusters(&chunks);
+
+            match output.as_str() {
+                "json" => {
+                    let result = SlimeResult {
+                        center_x,
+                        center_z,
+                        radius,
+                        slime_chunks: chunks.clone(),
+                        clusters,
+                    };
+                    emit_json(&result)?;
+                }
+                "map" => {
+                    println!("{}", render_slime_map(center_x, center_z, radius));
+                    println!("🟩 スライムチャンク  ⬛ 通常チャンク  ⭐ 中心");
+                }
+                _ => {
+                    println!("🟢 スライムチャンク検索結果");
+                    println!("   検索中心: X={}, Z={}", center_x, center_z);
+                    println!("   検索半径: {}ブロック", radius);
+                    println!();
+
+                    if chunks.is_empty() {
+                        println!("   スライムチャンクが見つかりませんでした");
+                    }
+                    for chunk in &chunks {
+                        println!(
+                            "   チャンク({}, {}) X={}, Z={} (距離: {:.0})",
+                            chunk.chunk_x, chunk.chunk_z, chunk.block_x, chunk.block_z, chunk.distance
+                        );
+                    }
+
+                    if !clusters.is_empty() {
+                        println!();
+                        println!("⭐ 隣接するスライムチャンク（トラップ向き）");
+                        for cluster in &clusters {
+                            println!("   {}チャンク 中心 X={}, Z={}", cluster.size, cluster.center_x, cluster.center_z);
+                        }
+                    }
+                }
+            }
+            Ok(Outcome::from_found(!chunks.is_empty()))
+        }
+
+        Commands::Deepdark {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            only_deep_dark,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 3000);
+            let output = select_format(config.output(output));
+            let mut report = find_deep_dark(seed, center_x, center_z, radius)?;
+            if only_deep_dark {
+                report.cities.retain(|c| c.in_deep_dark);
+            }
+
+            if output == "json" {
+                let result = DeepDarkResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    regions: report.regions.clone(),
+                    cities: report.cities.clone(),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🌑 ディープダークと古代都市の予測");
+                println!("   シード: {}", seed);
+                println!("   検索中心: X={}, Z={}", center_x, center_z);
+                println!("   検索半径: {}ブロック", radius);
+                println!();
+
+                println!("   ディープダークの地域: {}か所", report.regions.len());
+                for (i, region) in report.regions.iter().enumerate() {
+                    println!(
+                        "   #{} 中心 X={}, Z={} (約{}ブロック², 高地 {:.0}%, 距離: {:.0})",
+                        i,
+                        region.x,
+                        region.z,
+                        region.area,
+                        region.highlands * 100.0,
+                        region.distance
+                    );
+                }
+                println!();
+
+                if report.cities.is_empty() {
+                    println!("   古代都市が見つかりませんでした");
+                }
+                for city in &report.cities {
+                    let status = match (city.in_deep_dark, city.under_highlands) {
+                        (true, true) => "✅ ディープダーク・高地の下",
+                        (true, false) => "🟡 ディープダーク",
+                        (false, _) => "❌ ディープダークの外（生成されない可能性が高い）",
+                    };
+                    let region = city.region.map(|r| format!(" 地域#{}", r)).unwrap_or_default();
+                    println!(
+                        "   🏛️ X={}, Z={} (距離: {:.0}) {}{}",
+                        city.x, city.z, city.distance, status, region
+                    );
+                }
+            }
+            Ok(Outcome::from_found(report.cities.iter().any(|c| c.in_deep_dark)))
+        }
+
+        Commands::Dungeon {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            mob,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 300);
+            let output = select_format(config.output(output));
+            let mob = match mob {
+                Some(id) => Some(SpawnerMob::from_id(&id).ok_or_else(|| {
+                    CliError::Usage(format!("不明なモブ: {}（zombie, skeleton, spider）", id))
+                })?),
+                None => None,
+            };
+            let mut dungeons = find_dungeons(seed, center_x, center_z, radius)?;
+            if let Some(mob) = mob {
+                dungeons.retain(|d| d.mob == mob);
+            }
+
+            if output == "json" {
+                let result = DungeonResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    dungeons: dungeons.clone(),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🕳️ ダンジョン検索結果");
+                println!("   シード: {}", seed);
+                println!("   検索中心: X={}, Z={}", center_x, center_z);
+                println!("   検索半径: {}ブロック", radius);
+                println!();
+
+                if dungeons.is_empty() {
+                    println!("   ダンジョンが見つかりませんでした");
+                }
+                for dungeon in &dungeons {
+                    println!(
+                        "   {} X={}, Y={}, Z={} (距離: {:.0})",
+                        dungeon.mob.display_name(),
+                        dungeon.x,
+                        dungeon.y,
+                        dungeon.z,
+                        dungeon.distance
+                    );
+                }
+            }
+            Ok(Outcome::from_found(!dungeons.is_empty()))
+        }
+
+        Commands::Ore {
+            seed,
+            ore,
+            center_x,
+            center_z,
+            radius,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            // 鉱脈はチャンク単位で見るため、設定ファイルの検索半径は使わない
+            let radius = radius.unwrap_or(0);
+            let output = select_format(config.output(output));
+            let ore = OreType::from_id(&ore).ok_or_else(|| {
+                let ids: Vec<&str> = OreType::ALL.iter().map(|o| o.id()).collect();
+                CliError::Usage(format!("不明な鉱石: {}（{}）", ore, ids.join(", ")))
+            })?;
+            let blobs = find_ore_blobs(seed, ore, center_x, center_z, radius)?;
+
+            if output == "json" {
+                let result = OreResult {
+                    seed,
+                    ore,
+                    center_x,
+                    center_z,
+                    radius,
+                    blobs: blobs.clone(),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("⛏️ {} の鉱脈の予測", ore.display_name());
+                println!("   シード: {}", seed);
+                if ore.dimension() == Dimension::Nether {
+                    println!("   検索中心（ネザー座標）: X={}, Z={}", center_x, center_z);
+                } else {
+                    println!("   検索中心: X={}, Z={}", center_x, center_z);
+                }
+                println!("   検索半径: {}ブロック", radius);
+                println!("   ※ 鉱脈を置こうとする位置です。周りが石でない場合や空気に触れる場合は置かれないことがあります");
+                println!();
+
+                if blobs.is_empty() {
+                    println!("   鉱脈が見つかりませんでした");
+                }
+                for blob in &blobs {
+                    println!(
+                        "   [{}, {}] {} X={}, Y={}, Z={} (最大{}個, 距離: {:.0})",
+                        blob.chunk_x, blob.chunk_z, blob.feature, blob.x, blob.y, blob.z, blob.size, blob.distance
+                    );
+                }
+            }
+            Ok(Outcome::from_found(!blobs.is_empty()))
+        }
+
+        Commands::Veins {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            vein,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 200);
+            let output = select_format(config.output(output));
+            let vein = match vein {
+                Some(id) => Some(
+                    VeinType::from_id(&id)
+                        .ok_or_else(|| CliError::Usage(format!("不明な大鉱脈: {}（iron, copper）", id)))?,
+                ),
+                None => None,
+            };
+            let mut veins = find_ore_veins(seed, center_x, center_z, radius)?;
+            if let Some(vein) = vein {
+                veins.retain(|v| v.vein == vein);
+            }
+
+            if output == "json" {
+                let result = VeinResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    veins: veins.clone(),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("⛏️ 大鉱脈の検索結果");
+                println!("   シード: {}", seed);
+                println!("   検索中心: X={}, Z={}", center_x, center_z);
+                println!("   検索半径: {}ブロック", radius);
+                println!();
+
+                if veins.is_empty() {
+                    println!("   大鉱脈が見つかりませんでした");
+                }
+                for found in &veins {
+                    println!(
+                        "   {} X={}, Y={}, Z={} (濃さ: {:.0}%, 距離: {:.0})",
+                        found.vein.display_name(),
+                        found.x,
+                        found.y,
+                        found.z,
+                        found.density * 100.0,
+                        found.distance
+                    );
+                }
+            }
+            Ok(Outcome::from_found(!veins.is_empty()))
+        }
+
+        Commands::Underground {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            feature,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 300);
+            let output = select_format(config.output(output));
+            let features = if feature == "all" {
+                UndergroundFeature::ALL.to_vec()
+            } else {
+                let feature = UndergroundFeature::from_id(&feature).ok_or_else(|| {
+                    let ids: Vec<&str> = UndergroundFeature::ALL.iter().map(|f| f.id()).collect();
+                    CliError::Usage(format!("不明な地形: {}（all, {}）", feature, ids.join(", ")))
+                })?;
+                vec![feature]
+            };
+            let sites = find_underground_features(seed, &features, center_x, center_z, radius)?;
+
+            if output == "json" {
+                let result = UndergroundResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    sites: sites.clone(),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🪨 地形の予測結果");
+                println!("   シード: {}", seed);
+                println!("   検索中心: X={}, Z={}", center_x, center_z);
+                println!("   検索半径: {}ブロック", radius);
+                println!("   ※ 高さは地表の推定です（渓谷は掘り始めの高さ、帯水層は水位）");
+                println!();
+
+                if sites.is_empty() {
+                    println!("   見つかりませんでした");
+                }
+                for site in &sites {
+                    print!(
+                        "   {} X={}, Y={}, Z={} (距離: {:.0})",
+                        site.feature.display_name(),
+                        site.x,
+                        site.y,
+                        site.z,
+                        site.distance
+                    );
+                    if let Some(fluid) = site.fluid {
+                        print!(" {}", fluid.display_name());
+                    }
+                    if let Some(cave_y) = site.cave_y {
+                        print!(" 洞窟 Y={}付近", cave_y);
+                    }
+                    if let (Some(yaw), Some(length)) = (site.yaw, site.length) {
+                        print!(" {}〜{}に 計{}ブロック", compass_direction(yaw), compass_direction(yaw + 180.0), length);
+                        if site.y < RAVINE_DEEPSLATE_Y {
+                            print!(" 深層岩まで届く");
+                        }
+                    }
+                    println!();
+                }
+            }
+            Ok(Outcome::from_found(!sites.is_empty()))
+        }
+
+        Commands::Column { seed, x, z, output } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let (surface_y, bands) = biome_column(seed, x, z);
+
+            if output == "json" {
+                let result = ColumnResult {
+                    seed,
+                    x,
+                    z,
+                    surface_y,
+                    bands,
+                };
+                emit_json(&result)?;
+            } else {
+                println!("📏 バイオームの縦の断面");
+                println!("   シード: {}", seed);
+                println!("   座標: X={}, Z={}", x, z);
+                println!("   地表の推定: Y={}", surface_y);
+                println!();
+
+                // 上から順に表示
+                for band in bands.iter().rev() {
+                    let marker = if band.cave { "🕳️" } else { "🌍" };
+                    println!("   Y={:>4}〜{:>4} {} {}", band.min_y, band.max_y, marker, band.name);
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::NetherClear {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            clearance,
+            no_fortress,
+            limit,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 1000);
+            let output = select_format(config.output(output));
+            let mut avoid = vec![StructureType::BastionRemnant];
+            if no_fortress {
+                avoid.push(StructureType::NetherFortress);
+            }
+            if clearance < 1 {
+                return Err(CliError::Usage("--clearance は1以上を指定してください".to_string()));
+            }
+
+            let mut areas = find_clear_areas(seed, center_x, center_z, radius, clearance, &avoid)?;
+            areas.truncate(limit);
+
+            if output == "json" {
+                let found = !areas.is_empty();
+                let result = NetherClearResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    clearance,
+                    avoid: avoid.iter().map(|st| st.id().to_string()).collect(),
+                    areas,
+                };
+                emit_json(&result)?;
+                return Ok(Outcome::from_found(found));
+            }
+            let names: Vec<&str> = avoid.iter().map(|st| st.display_name()).collect();
+            println!("🔥 構造物のないネザーの範囲（半径{}ブロック）", clearance);
+            println!("   シード: {}", seed);
+            println!("   検索中心: X={}, Z={}（ネザー座標）", center_x, center_z);
+            println!("   検索半径: {}ブロック", radius);
+            println!("   避ける構造物: {}", names.join("、"));
+            println!();
+            if areas.is_empty() {
+                println!("   検索範囲内に条件を満たす範囲は見つかりませんでした");
+            }
+            for (i, area) in areas.iter().enumerate() {
+                println!(
+                    "   {}. ネザー X={}, Z={}（オーバーワールド X={}, Z={}） 中心から{:.0}ブロック",
+                    i + 1,
+                    area.x,
+                    area.z,
+                    area.overworld_x,
+                    area.overworld_z,
+                    area.distance
+                );
+                if let (Some(id), Some(distance)) = (&area.nearest_type, area.nearest_distance) {
+                    let name = id.parse::<StructureType>().map_or(id.as_str(), |st| st.display_name());
+                    println!("      最も近い{}: {:.0}ブロック", name, distance);
+                }
+            }
+            Ok(Outcome::from_found(!areas.is_empty()))
+        }
+
+        Commands::Quickcheck { preset, seed, output } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let check = QuickCheck::from_id(&preset).ok_or_else(|| {
+                let names: Vec<&str> = QuickCheck::ALL.iter().map(|q| q.id()).collect();
+                CliError::Usage(format!("不明なプリセット: {}（{}）", preset, names.join(", ")))
+            })?;
+            match check.target() {
+                Target::Biome(b) if !b.is_available(mc_version) => {
+                    return Err(not_available(b.id(), "バイオーム", b.since_version(), b.experimental_since(), mc_version));
+                }
+                Target::Structure(st) if !st.is_available(mc_version) => return Err(structure_not_available(&st, mc_version)),
+                _ => {}
+            }
+
+            let result = run_quickcheck(seed, check, |pos| world.contains(Dimension::Overworld, pos.x, pos.z))?;
+
+            if output == "json" {
+                emit_json(&result)?;
+                return Ok(Outcome::from_found(result.found));
+            }
+            println!("❓ {}", result.question);
+            println!("   シード: {}", seed);
+            println!("   スポーン地点（推定）: X={}, Z={}", result.spawn.x, result.spawn.z);
+            match (result.x, result.z, result.distance) {
+                (Some(x), Some(z), Some(distance)) => {
+                    println!("   ✅ あります: X={}, Z={}（スポーン地点から{:.0}ブロック）", x, z, distance)
+                }
+                _ => println!("   ❌ ありません"),
+            }
+            Ok(Outcome::from_found(result.found))
+        }
+
+        Commands::Wood {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            search_radius,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 1000);
+            let output = select_format(config.output(output));
+
+            let woods = wood_availability(seed, center_x, center_z, radius, search_radius)?;
+            let complete = woods.iter().all(|w| w.available);
+
+            if output == "json" {
+                let result = WoodResult { seed, center_x, center_z, radius, search_radius, woods };
+                emit_json(&result)?;
+                return Ok(Outcome::from_found(complete));
+            }
+            println!("🪵 X={}, Z={} の周りで手に入る木材（半径{}ブロック）", center_x, center_z, radius);
+            println!("   シード: {}", seed);
+            println!();
+            for wood in &woods {
+                match (wood.x, wood.z, wood.distance) {
+                    (Some(x), Some(z), Some(distance)) => println!(
+                        "   {} {}: X={}, Z={}（{:.0}ブロック）",
+                        if wood.available { "✅" } else { "⚠️" },
+                        wood.name,
+                        x,
+                        z,
+                        distance
+                    ),
+                    _ => println!("   ❌ {}: {}ブロック以内に見つかりません", wood.name, search_radius.max(radius)),
+                }
+            }
+            Ok(Outcome::from_found(complete))
+        }
+
+        Commands::Corridor {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 5000);
+            let output = select_format(config.output(output));
+
+            let corridors = land_corridors(seed, center_x, center_z, radius)?;
+            let found = corridors.first().is_some_and(|c| c.length > 0.0);
+
+            if output == "json" {
+                let result = CorridorResult { seed, center_x, center_z, radius, corridors };
+                emit_json(&result)?;
+                return Ok(Outcome::from_found(found));
+            }
+            println!("🧭 X={}, Z={} から海に出ずに進める距離（最大{}ブロック）", center_x, center_z, radius);
+            println!("   シード: {}", seed);
+            println!();
+            if !found {
+                println!("   出発地点が海です");
+            }
+            for corridor in &corridors {
+                let rivers = if corridor.rivers > 0 { format!("、川を{}回渡る", corridor.rivers) } else { String::new() };
+                println!(
+                    "   {}: {:.0}ブロック{}（X={}, Z={}まで{}）",
+                    corridor.name,
+                    corridor.length,
+                    if corridor.open { "以上" } else { "" },
+                    corridor.end_x,
+                    corridor.end_z,
+                    rivers
+                );
+            }
+            Ok(Outcome::from_found(found))
+        }
+
+        Commands::ScoreLocation { x, z, seed, weight, output } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let mut weights: Vec<(Criterion, f64)> = Criterion::ALL.iter().map(|&c| (c, 1.0)).collect();
+            for spec in &weight {
+                let (criterion, value) = parse_weight(spec)?;
+                if let Some(entry) = weights.iter_mut().find(|(c, _)| *c == criterion) {
+                    entry.1 = value;
+                }
+            }
+
+            let score = score_location(seed, x, z, &weights)?;
+
+            if output == "json" {
+                let result = LocationScoreResult { seed, x, z, total: score.total, components: score.components };
+                emit_json(&result)?;
+                return Ok(Outcome::Found);
+            }
+            println!("📋 X={}, Z={} の拠点としての評価: {:.0}点", x, z, score.total);
+            println!("   シード: {}", seed);
+            println!();
+            for component in &score.components {
+                println!(
+                    "   {}: {:.0}点（重み {}） {}",
+                    component.name, component.score, component.weight, component.description
+                );
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::BuildSite {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            size,
+            max_stddev,
+            limit,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 2000);
+            let output = select_format(config.output(output));
+            if !(16..=MAX_BUILD_SIZE).contains(&size) {
+                return Err(CliError::Usage(format!(
+                    "範囲の一辺は16〜{}ブロックで指定してください: {}",
+                    MAX_BUILD_SIZE, size
+                )));
+            }
+
+            let areas = find_build_areas(seed, center_x, center_z, radius, size, max_stddev, limit)?;
+
+            if output == "json" {
+                let found = !areas.is_empty();
+                let result = BuildSiteResult { seed, center_x, center_z, radius, size, max_stddev, areas };
+                emit_json(&result)?;
+                return Ok(Outcome::from_found(found));
+            }
+            println!("🏗️ 構造物がなく平らな{}x{}ブロックの範囲", size, size);
+            println!("   シード: {}", seed);
+            println!("   検索中心: X={}, Z={}", center_x, center_z);
+            println!("   検索半径: {}ブロック", radius);
+            println!();
+            if areas.is_empty() {
+                println!("   検索範囲内に高さの標準偏差が{}ブロック以下の範囲は見つかりませんでした", max_stddev);
+            }
+            for (i, area) in areas.iter().enumerate() {
+                println!(
+                    "   {}. 中心 X={}, Z={}（中心から{:.0}ブロック） 範囲: X={}〜{}, Z={}〜{}",
+                    i + 1,
+                    area.x,
+                    area.z,
+                    area.distance,
+                    area.min_x,
+                    area.max_x,
+                    area.min_z,
+                    area.max_z
+                );
+                println!("      地表の高さ: Y={}〜{}（標準偏差 {:.1}ブロック）", area.min_y, area.max_y, area.stddev);
+            }
+            Ok(Outcome::from_found(!areas.is_empty()))
+        }
+
+        Commands::Farm {
+            seed,
+            kind,
+            center_x,
+            center_z,
+            radius,
+            limit,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 3000);
+            let output = select_format(config.output(output));
+            let kind = FarmKind::from_id(&kind).ok_or_else(|| {
+                let names: Vec<&str> = FarmKind::ALL.iter().map(|k| k.id()).collect();
+                CliError::Usage(format!("不明なファームの種類: {}（{}）", kind, names.join(", ")))
+            })?;
+            if !kind.structure().is_available(mc_version) {
+                return Err(structure_not_available(&kind.structure(), mc_version));
+            }
+
+            let mut sites = evaluate_farm_sites(seed, kind, center_x, center_z, radius)?;
+            sites.truncate(limit);
+
+            if output == "json" {
+                let found = !sites.is_empty();
+                let result = FarmResult {
+                    seed,
+                    kind: kind.id().to_string(),
+                    center_x,
+                    center_z,
+                    radius,
+                    sites,
+                };
+                emit_json(&result)?;
+                return Ok(Outcome::from_found(found));
+            }
+            println!("🧰 {}の候補地", kind.display_name());
+            println!("   シード: {}", seed);
+            println!("   検索中心: X={}, Z={}", center_x, center_z);
+            println!("   検索半径: {}ブロック", radius);
+            println!();
+            if sites.is_empty() {
+                println!("   検索範囲内に{}は見つかりませんでした", kind.structure().display_name());
+            }
+            for (i, site) in sites.iter().enumerate() {
+                println!(
+                    "   {}. {} X={}, Z={}（中心から{:.0}ブロック） {}点",
+                    i + 1,
+                    site.structure_type,
+                    site.x,
+                    site.z,
+                    site.distance,
+                    site.score
+                );
+                for factor in &site.factors {
+                    println!("      {}（{}/{}点）", factor.description, factor.points, factor.max_points);
+                }
+            }
+            Ok(Outcome::from_found(!sites.is_empty()))
+        }
+
+        Commands::Hub {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            structure_type,
+            distance,
+            min_types,
+            limit,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 3000);
+            let output = select_format(config.output(output));
+            let min_types = min_types.unwrap_or(structure_type.len());
+            if distance < 1 {
+                return Err(CliError::Usage("--distance は1以上を指定してください".to_string()));
+            }
+            if min_types < 1 || min_types > structure_type.len() {
+                return Err(CliError::Usage(format!("--min-types は1〜{}を指定してください", structure_type.len())));
+            }
+            if let Some(st) = StructureType::ALL
+                .iter()
+                .find(|st| structure_type.contains(&st.id().to_string()) && !st.is_available(mc_version))
+            {
+                return Err(structure_not_available(st, mc_version));
+            }
+
+            let mut hubs = find_hubs(seed, center_x, center_z, radius, &structure_type, distance, min_types).map_err(CliError::Usage)?;
+            hubs.truncate(limit);
+
+            if output == "json" {
+                let result = HubResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    distance,
+                    min_types,
+                    hubs: hubs
+                        .iter()
+                        .map(|hub| HubEntry {
+                            x: hub.x,
+                            z: hub.z,
+                            distance: distance_2d(center_x, center_z, hub.x, hub.z),
+                            structures: hub
+                                .members
+                                .iter()
+                                .map(|hit| structure_result(seed, hit, hub.x, hub.z, None))
+                                .collect(),
+                        })
+                        .collect(),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🏠 拠点の候補");
+                println!("   シード: {}", seed);
+                println!("   検索中心: X={}, Z={}", center_x, center_z);
+                println!("   条件: {}種類のうち{}種類以上が{}ブロック以内", structure_type.len(), min_types, distance);
+                println!();
+                if hubs.is_empty() {
+                    println!("   条件を満たす地点は見つかりませんでした");
+                }
+                for (i, hub) in hubs.iter().enumerate() {
+                    println!(
+                        "   {}. X={}, Z={}（中心から{:.0}ブロック、{}種類）",
+                        i + 1,
+                        hub.x,
+                        hub.z,
+                        distance_2d(center_x, center_z, hub.x, hub.z),
+                        hub.members.len()
+                    );
+                    for hit in &hub.members {
+                        let BlockPos { x, z } = hit.pos;
+                        println!("      {} X={}, Z={}（{:.0}ブロック）", hit.kind.display_name(), x, z, distance_2d(hub.x, hub.z, x, z));
+                    }
+                }
+            }
+            Ok(Outcome::from_found(!hubs.is_empty()))
+        }
+
+        Commands::Map {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            structure_type,
+            blocks_per_pixel,
+            zoom,
+            palette,
+            hillshade,
+            heatmap,
+            route,
+            marker_size,
+            no_legend,
+            png,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            if output != "html" && png.is_none() {
+                return Err(CliError::Usage("--png で書き出し先を指定するか、--output html を指定してください".to_string()));
+            }
+            let blocks_per_pixel = match zoom {
+                Some(name) => Some(zoom_preset(&name).ok_or_else(|| {
+                    let names: Vec<&str> = ZOOM_PRESETS.iter().map(|(n, _)| *n).collect();
+                    CliError::Usage(format!("不明な縮尺のプリセット: {}（{}）", name, names.join(", ")))
+                })?),
+                None => blocks_per_pixel,
+            };
+            if blocks_per_pixel.is_some_and(|bpp| bpp < 1) {
+                return Err(CliError::Usage("--blocks-per-pixel は1以上を指定してください".to_string()));
+            }
+            let style = MapStyle {
+                palette: Palette::load(&palette).map_err(CliError::Usage)?,
+                hillshade,
+            };
+            let radius = match (radius, blocks_per_pixel) {
+                (None, Some(bpp)) => MapView::default_radius(bpp),
+                _ => config.radius(radius, 2000),
+            };
+            let view = MapView::around(center_x, center_z, radius, blocks_per_pixel.unwrap_or_else(|| MapView::auto_scale(radius)));
+            if view.width > MAX_MAP_SIZE {
+                return Err(CliError::Usage(format!(
+                    "地図が大きすぎます（1辺 {} ピクセルまで）。--blocks-per-pixel を大きくしてください",
+                    MAX_MAP_SIZE
+                )));
+            }
+
+            if heatmap.is_some_and(|size| size < 1) {
+                return Err(CliError::Usage("--heatmap の区画は1ブロック以上を指定してください".to_string()));
+            }
+
+            // 地図の四隅（色分けする場合は地図に掛かる区画の全体）まで含むように探す
+            let extent = radius + heatmap.unwrap_or(0);
+            let found = map_structures(seed, mc_version, center_x, center_z, extent, &structure_type)?;
+            let density = match heatmap {
+                Some(size) => density_cells(&view, size, &found.iter().map(|hit| (hit.pos.x, hit.pos.z)).collect::<Vec<_>>()),
+                None => Vec::new(),
+            };
+            let mut structures: Vec<_> = found
+                .into_iter()
+                .filter(|hit| (hit.pos.x - center_x).abs() <= radius && (hit.pos.z - center_z).abs() <= radius)
+                .collect();
+            sort_nearest_first(&mut structures, BlockPos::new(center_x, center_z));
+
+            let path = match &route {
+                Some(file) => std::fs::read_to_string(file)
+                    .ok()
+                    .and_then(|json| route_path_from_json(&json))
+                    .ok_or_else(|| CliError::Usage(format!("道順のファイルを読み込めません: {}", file)))?,
+                None => Vec::new(),
+            };
+
+            let mut image = render_map(seed, &view, &style, &density, center_x, center_z);
+            draw_route(&mut image, &view, &path);
+
+            // HTMLでは構造物をマーカーの要素として重ねる
+            if output != "html" {
+                let markers: Vec<(&str, i32, i32)> =
+                    structures.iter().map(|hit| (hit.kind.id(), hit.pos.x, hit.pos.z)).collect();
+                draw_markers(&mut image, &view, &markers, marker_size);
+            }
+            if !no_legend {
+                let legend = Legend {
+                    seed,
+                    version: mc_version,
+                    center_x,
+                    center_z,
+                    blocks_per_pixel: view.blocks_per_pixel,
+                    structures: structure_type.clone(),
+                    palette: &style.palette,
+                    biomes: biomes_in_view(seed, &view),
+                    density_max: density.iter().map(|c| c.count).max(),
+                    route: path.len() > 1,
+                };
+                draw_legend(&mut image, &legend);
+            }
+            if output == "html" {
+                let header = MapHeader {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    marker_size,
+                };
+                output_html_map(&header, &view, &image, &structures)?;
+                return Ok(Outcome::Found);
+            }
+
+            let file = png.unwrap_or_default();
+            write_png(&file, &image)?;
+            let georeference = view.georeference(&file);
+            let sidecars = georeference.write_sidecars()?;
+
+            if output == "json" {
+                let result = MapResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    blocks_per_pixel: view.blocks_per_pixel,
+                    width: view.width,
+                    height: view.height,
+                    file,
+                    georeference,
+                    structures: structures.iter().map(|hit| structure_result(seed, hit, center_x, center_z, None)).collect(),
+                    density,
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🗺️ 地図を書き出しました");
+                println!("   シード: {}", seed);
+                println!("   中心: X={}, Z={}", center_x, center_z);
+                println!("   大きさ: {}×{}（{}ブロック/ピクセル）", view.width, view.height, view.blocks_per_pixel);
+                println!("   構造物: {}件", structures.len());
+                if path.len() > 1 {
+                    let total: f64 = path.windows(2).map(|p| distance_2d(p[0].0, p[0].1, p[1].0, p[1].1)).sum();
+                    println!("   道順: {}地点（総距離: {:.0}ブロック）", path.len(), total);
+                }
+                println!("   📁 {}", file);
+                for sidecar in &sidecars {
+                    println!("   📁 {}（座標の対応）", sidecar);
+                }
+
+                let mut densest: Vec<_> = density.iter().filter(|c| c.count > 0).collect();
+                densest.sort_by_key(|c| std::cmp::Reverse(c.count));
+                if !densest.is_empty() {
+                    println!();
+                    println!("   🔥 構造物の多い区画");
+                }
+                for cell in densest.iter().take(5) {
+                    println!("   X={}〜{}, Z={}〜{}: {}件", cell.x, cell.x + cell.size, cell.z, cell.z + cell.size, cell.count);
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Diffmap {
+            seed,
+            other_seed,
+            other_version,
+            center_x,
+            center_z,
+            radius,
+            structure_type,
+            blocks_per_pixel,
+            mode,
+            marker_size,
+            png,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let other_seed = other_seed.unwrap_or(seed);
+            let other_version = match other_version {
+                Some(v) => McVersion::parse(&v).ok_or_else(|| CliError::Usage(format!("不正なバージョン: {}（例: 1.21.0）", v)))?,
+                None => mc_version,
+            };
+            let mode = DiffMode::from_id(&mode).ok_or_else(|| {
+                let names: Vec<&str> = DiffMode::ALL.iter().map(|m| m.id()).collect();
+                CliError::Usage(format!("不明な描き方: {}（{}）", mode, names.join(", ")))
+            })?;
+            if blocks_per_pixel.is_some_and(|bpp| bpp < 1) {
+                return Err(CliError::Usage("--blocks-per-pixel は1以上を指定してください".to_string()));
+            }
+            let radius = config.radius(radius, 2000);
+            let view = MapView::around(center_x, center_z, radius, blocks_per_pixel.unwrap_or_else(|| MapView::auto_scale(radius)));
+            if view.width > MAX_MAP_SIZE {
+                return Err(CliError::Usage(format!(
+                    "地図が大きすぎます（1辺 {} ピクセルまで）。--blocks-per-pixel を大きくしてください",
+                    MAX_MAP_SIZE
+                )));
+            }
+
+            let structures_a = map_structures(seed, mc_version, center_x, center_z, radius, &structure_type)?;
+            let structures_b = map_structures(other_seed, other_version, center_x, center_z, radius, &structure_type)?;
+            let a = DiffSide {
+                seed,
+                version: mc_version,
+                structures: &structures_a,
+            };
+            let b = DiffSide {
+                seed: other_seed,
+                version: other_version,
+                structures: &structures_b,
+            };
+            let (image, changed) = render_diff_map(&view, mode, &a, &b, marker_size, center_x, center_z);
+            write_png(&png, &image)?;
+
+            let (removed, added) = structure_diff(&structures_a, &structures_b);
+            let mut only_a: Vec<StructureResult> = removed.iter().map(|hit| structure_result(seed, hit, center_x, center_z, None)).collect();
+            let mut only_b: Vec<StructureResult> =
+                added.iter().map(|hit| structure_result(other_seed, hit, center_x, center_z, None)).collect();
+            only_a.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+            only_b.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+            if output == "json" {
+                let result = DiffMapResult {
+                    seed,
+                    other_seed,
+                    version: mc_version.to_string(),
+                    other_version: other_version.to_string(),
+                    center_x,
+                    center_z,
+                    radius,
+                    blocks_per_pixel: view.blocks_per_pixel,
+                    mode: mode.id().to_string(),
+                    width: image.width,
+                    height: image.height,
+                    file: png,
+                    changed_biome_ratio: changed,
+                    only_a,
+                    only_b,
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🔀 比較地図を書き出しました");
+                println!("   A: シード {}（{}）", seed, mc_version);
+                println!("   B: シード {}（{}）", other_seed, other_version);
+                println!("   中心: X={}, Z={}", center_x, center_z);
+                println!("   大きさ: {}×{}（{}ブロック/ピクセル）", image.width, image.height, view.blocks_per_pixel);
+                println!("   バイオームの違い: {:.1}%", changed * 100.0);
+                println!("   構造物: Aだけ {}件 / Bだけ {}件", only_a.len(), only_b.len());
+                println!("   📁 {}", png);
+                for (label, list) in [("Aだけにある構造物", &only_a), ("Bだけにある構造物", &only_b)] {
+                    if list.is_empty() {
+                        continue;
+                    }
+                    println!();
+                    println!("   {}", label);
+                    for s in list.iter().take(10) {
+                        println!("   {} X={}, Z={}（{:.0}ブロック）", s.structure_type, s.x, s.z, s.distance);
+                    }
+                    if list.len() > 10 {
+                        println!("   ...ほか{}件", list.len() - 10);
+                    }
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::VersionDiff {
+            seed,
+            from,
+            to,
+            center_x,
+            center_z,
+            radius,
+            structure_type,
+            biome,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let from_version = match from {
+                Some(v) => parse_version(&v)?,
+                None => mc_version,
+            };
+            let to_version = parse_version(&to)?;
+            let radius = config.radius(radius, 2000);
+            let structure_type = if structure_type.is_empty() {
+                StructureType::ALL
+                    .iter()
+                    .filter(|st| st.dimension() == Dimension::Overworld)
+                    .map(|st| st.id().to_string())
+                    .chain([STRONGHOLD_ID.to_string()])
+                    .collect()
+            } else {
+                structure_type
+            };
+            let biomes = if biome.is_empty() {
+                BiomeType::ALL.to_vec()
+            } else {
+                biome
+                    .iter()
+                    .map(|id| {
+                        BiomeType::from_str(id).ok_or_else(|| SearchError::UnknownBiome(id.clone()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            warn_outside_world(world, Dimension::Overworld, center_x, center_z, radius);
+
+            // バイオームの生成の方式はバージョンごとに切り替え、終わったら対象バージョンに戻す
+            let search = |version: McVersion| {
+                biome::set_version(version, experimental);
+                let structures: Vec<_> = map_structures(seed, version, center_x, center_z, radius, &structure_type)?
+                    .into_iter()
+                    .filter(|hit| world.contains(Dimension::Overworld, hit.pos.x, hit.pos.z))
+                    .collect();
+                let nearest = biomes
+                    .iter()
+                    .map(|b| {
+                        let found = if b.is_available(version) {
+                            find_nearest_biome(seed, BlockPos::new(center_x, center_z), radius, b.id(), |pos| world.contains(Dimension::Overworld, pos.x, pos.z))?
+                        } else {
+                            None
+                        };
+                        Ok(found.map(|(pos, _)| (pos.x, pos.z)))
+                    })
+                    .collect::<Result<Vec<_>, SearchError>>()?;
+                Ok::<_, CliError>((structures, nearest))
+            };
+            let from_result = search(from_version);
+            let to_result = search(to_version);
+            biome::set_version(mc_version, experimental);
+            let (structures_from, biomes_from) = from_result?;
+            let (structures_to, biomes_to) = to_result?;
+
+            let mut changes = structure_changes(&structures_from, &structures_to);
+            changes.sort_by(|a, b| {
+                let distance = |(_, from, to): &StructureChange| {
+                    from.or(*to).map_or(0.0, |hit| distance_2d(center_x, center_z, hit.pos.x, hit.pos.z))
+                };
+                distance(a).total_cmp(&distance(b))
+            });
+            let unchanged_structures = structures_from.len() - changes.iter().filter(|(_, from, _)| from.is_some()).count();
+            let position = |hit: &StructureHit| XZ { x: hit.pos.x, z: hit.pos.z };
+            let structures: Vec<StructureChangeEntry> = changes
+                .iter()
+                .map(|(change, from, to)| StructureChangeEntry {
+                    structure_type: from.or(*to).map_or_else(String::new, |hit| hit.kind.display_name().to_string()),
+                    change: change.id().to_string(),
+                    from: from.map(position),
+                    to: to.map(position),
+                })
+                .collect();
+            let mut unchanged_biomes = 0;
+            let mut biome_changes = Vec::new();
+            for ((b, from), to) in biomes.iter().zip(biomes_from).zip(biomes_to) {
+                match biome_change(from, to) {
+                    Some(change) => biome_changes.push((*b, change, from, to)),
+                    None if from.is_some() => unchanged_biomes += 1,
+                    None => {}
+                }
+            }
+            let found = !structures.is_empty() || !biome_changes.is_empty();
+
+            if output == "json" {
+                let xz = |p: Option<(i32, i32)>| p.map(|(x, z)| XZ { x, z });
+                let result = VersionDiffResult {
+                    seed,
+                    from_version: from_version.to_string(),
+                    to_version: to_version.to_string(),
+                    center_x,
+                    center_z,
+                    radius,
+                    structures,
+                    unchanged_structures,
+                    biomes: biome_changes
+                        .iter()
+                        .map(|(b, change, from, to)| BiomeChangeEntry {
+                            biome: b.id().to_string(),
+                            change: change.id().to_string(),
+                            from: xz(*from),
+                            to: xz(*to),
+                        })
+                        .collect(),
+                    unchanged_biomes,
+                };
+                emit_json(&result)?;
+            } else {
+                let describe = |p: Option<(i32, i32)>| p.map_or_else(|| "-".to_string(), |(x, z)| format!("X={}, Z={}", x, z));
+                println!("🔄 バージョンによる変化");
+                println!("   シード: {}", seed);
+                println!("   比較: {} → {}", from_version, to_version);
+                println!("   検索中心: X={}, Z={}", center_x, center_z);
+                println!("   検索範囲: {}ブロック", radius);
+                println!("   構造物: 変化 {}件 / 変化なし {}件", structures.len(), unchanged_structures);
+                println!("   バイオーム: 変化 {}件 / 変化なし {}件", biome_changes.len(), unchanged_biomes);
+                if !structures.is_empty() {
+                    println!();
+                    println!("   構造物");
+                    for (change, from, to) in changes.iter().take(10) {
+                        let name = from.or(*to).map_or("", |hit| hit.kind.display_name());
+                        let from = from.map(|hit| (hit.pos.x, hit.pos.z));
+                        let to = to.map(|hit| (hit.pos.x, hit.pos.z));
+                        println!("   {} {} {} → {}", change.label(), name, describe(from), describe(to));
+                    }
+                    if changes.len() > 10 {
+                        println!("   ...ほか{}件（近い順。すべては --output json）", changes.len() - 10);
+                    }
+                }
+                if !biome_changes.is_empty() {
+                    println!();
+                    println!("   バイオーム（最寄りの位置）");
+                    for (b, change, from, to) in &biome_changes {
+                        println!("   {} {} {} → {}", change.label(), b.display_name(), describe(*from), describe(*to));
+                    }
+                }
+                if !found {
+                    println!();
+                    println!("   ✅ この範囲では変化はありません");
+                }
+            }
+            Ok(Outcome::from_found(found))
+        }
+        Commands::Heightmap {
+            seed,
+            center_x,
+            center_z,
+            radius,
+            step,
+            png,
+            csv,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let radius = config.radius(radius, 256);
+            let output = select_format(config.output(output));
+            if step < 1 {
+                return Err(CliError::Usage("--step は1以上を指定してください".to_string()));
+            }
+            check_search_area(center_x, center_z, radius)?;
+            if radius as i64 * 2 / step as i64 > MAX_HEIGHTMAP_SIZE as i64 {
+                return Err(CliError::Usage(format!(
+                    "範囲が広すぎます（1辺 {} ピクセルまで）。--step を大きくしてください",
+                    MAX_HEIGHTMAP_SIZE
+                )));
+            }
+
+            let grid = sample_heights(seed, center_x, center_z, radius, step);
+            let (min_y, max_y) = grid.range();
+            let mut files = Vec::new();
+            if let Some(path) = png {
+                write_gray_png(&path, grid.width, grid.height, &heightmap_pixels(&grid))?;
+                let georef = Georeference::new(&path, grid.min_x, grid.min_z, grid.step, grid.width, grid.height);
+                files.push(path);
+                files.extend(georef.write_sidecars()?);
+            }
+            if let Some(path) = csv {
+                std::fs::write(&path, heightmap_csv(&grid))?;
+                files.push(path);
+            }
+
+            if output == "json" {
+                let result = HeightmapResult {
+                    seed,
+                    center_x,
+                    center_z,
+                    radius,
+                    step,
+                    width: grid.width,
+                    height: grid.height,
+                    min_y,
+                    max_y,
+                    files,
+                };
+                emit_json(&result)?;
+            } else {
+                println!("⛰️ 高さマップを書き出しました");
+                println!("   シード: {}", seed);
+                println!("   範囲: X={}〜{}, Z={}〜{}", grid.min_x, grid.x_at(grid.width - 1), grid.min_z, grid.z_at(grid.height - 1));
+                println!("   大きさ: {}×{}（{}ブロックごと）", grid.width, grid.height, step);
+                println!("   高さ: Y={}〜{}", min_y, max_y);
+                for file in &files {
+                    println!("   📁 {}", file);
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Spawn { seed, output } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let spawn = estimate_spawn(seed);
+
+            if output == "json" {
+                let result = SpawnResult { seed, spawn };
+                emit_json(&result)?;
+            } else {
+                println!("🏠 推定スポーン地点");
+                println!("   シード: {}", seed);
+                println!("   座標: X={}, Y={}, Z={}", spawn.x, spawn.y, spawn.z);
+                println!("   バイオーム: {}", spawn.biome);
+                if spawn.fallback {
+                    println!("   ⚠️ 周辺にスポーン可能なバイオームが見つからないため原点を表示しています");
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Triangulate {
+            throws,
+            seed,
+            output,
+        } => {
+            let seed = config.seed(seed);
+            let output = select_format(config.output(output));
+            let mut parsed = Vec::new();
+            for throw in &throws {
+                let t = Throw::parse(throw)
+                    .ok_or_else(|| CliError::Usage(format!("投擲記録の形式が不正です（X,Z,向き）: {}", throw)))?;
+                parsed.push(t);
+            }
+
+            let estimate = triangulate(&parsed).ok_or_else(|| {
+                CliError::Usage("交点を計算できません（2回以上の平行でない投擲が必要です。ほぼ平行な場合は交点が遠すぎます）".to_string())
+            })?;
+
+            // シード指定時は推定地点に最も近い予測要塞を探す
+            let stronghold = seed.and_then(|seed| {
+                find_strongholds(seed)
+                    .into_iter()
+                    .map(|hit| {
+                        let BlockPos { x, z } = hit.pos;
+                        (x, z, distance_2d(estimate.x, estimate.z, x, z))
+                    })
+                    .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
+            });
+
+            if output == "json" {
+                let result = TriangulateResult {
+                    throws: parsed,
+                    estimate,
+                    stronghold: stronghold.map(|(x, z, distance)| StrongholdMatch {
+                        x,
+                        z,
+                        distance_from_estimate: distance,
+                    }),
+                };
+                emit_json(&result)?;
+            } else {
+                println!("👁️ 三角測量結果（投擲{}回）", parsed.len());
+                println!("   推定地点: X={}, Z={} (誤差: 約{:.0}ブロック)", estimate.x, estimate.z, estimate.error);
+                println!("   ネザー座標: X={}, Z={}", overworld_to_nether(estimate.x), overworld_to_nether(estimate.z));
+                if let Some((x, z, distance)) = stronghold {
+                    println!("   最寄りの予測要塞: X={}, Z={} (推定地点から{:.0}ブロック)", x, z, distance);
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::List { kind, output } => {
+            let output = select_format(config.output(output));
+            let show_structures = kind == "all" || kind == "structures";
+            let show_biomes = kind == "all" || kind == "biomes";
+            if !show_structures && !show_biomes {
+                return Err(CliError::Usage(format!("不明な種類: {}", kind)));
+            }
+
+            let mut structures: Vec<StructureEntry> = StructureType::ALL
+                .iter()
+                .map(|st| StructureEntry {
+                    id: st.id().to_string(),
+                    name: st.display_name().to_string(),
+                    aliases: st.aliases().iter().map(|a| a.to_string()).collect(),
+                    placement: st.placement().id().to_string(),
+                    spacing: Some(st.spacing()),
+                    separation: Some(st.separation()),
+                    salt: Some(st.salt()),
+                    versions: format!("{}+", st.since_version()),
+                    experimental_versions: st.experimental_since().map(|v| format!("{}+", v)),
+                    available: st.is_available(mc_version),
+                })
+                .collect();
+            structures.push(StructureEntry {
+                id: STRONGHOLD_ID.to_string(),
+                name: stronghold_name().to_string(),
+                aliases: Vec::new(),
+                placement: "ring".to_string(),
+                spacing: None,
+                separation: None,
+                salt: None,
+                versions: format!("{}+", STRONGHOLD_SINCE_VERSION),
+                experimental_versions: None,
+                available: mc_version.is_at_least(STRONGHOLD_SINCE_VERSION),
+            });
+            structures.extend(custom_structures().iter().map(|c| StructureEntry {
+                id: c.id.clone(),
+                name: c.display_name().to_string(),
+                aliases: Vec::new(),
+                placement: PlacementModel::Grid.id().to_string(),
+                spacing: Some(c.spacing),
+                separation: Some(c.separation),
+                salt: Some(c.salt),
+                versions: "custom".to_string(),
+                experimental_versions: None,
+                available: true,
+            }));
+
+            let biomes: Vec<BiomeEntry> = BiomeType::ALL
+                .iter()
+                .map(|b| BiomeEntry {
+                    id: b.id().to_string(),
+                    name: b.display_name().to_string(),
+                    rarity: b.rarity(),
+                    versions: format!("{}+", b.since_version()),
+                    experimental_versions: b.experimental_since().map(|v| format!("{}+", v)),
+                    available: b.is_available(mc_version),
+                })
+                .collect();
+
+            if output == "json" {
+                let result = ListResult {
+                    structures: show_structures.then_some(structures),
+                    biomes: show_biomes.then_some(biomes),
+                };
+                emit_json(&result)?;
+            } else {
+                if show_structures {
+                    println!("🏛️ 構造物（structures -t に指定できる識別子。nether_quadrant は nether コマンドで検索）");
+                    for st in &structures {
+                        let value = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+                        let unavailable = if st.available {
+                            String::new()
+                        } else {
+                            format!("（{}では生成されない）", mc_version)
+                        };
+                        let aliases = if st.aliases.is_empty() {
+                            String::new()
+                        } else {
+                            format!("  別名: {}", st.aliases.join(", "))
+                        };
+                        println!(
+                            "   {:<16} {}{}  配置: {}  間隔: {}  分離: {}  ソルト: {}  対応: {}{}{}",
+                            st.id,
+                            st.name,
+                            aliases,
+                            st.placement,
+                            value(st.spacing.map(|v| v.to_string())),
+                            value(st.separation.map(|v| v.to_string())),
+                            value(st.salt.map(|v| v.to_string())),
+                            st.versions,
+                            experimental_note(st.experimental_versions.as_deref()),
+                            unavailable
+                        );
+                    }
+                }
+                if show_structures && show_biomes {
+                    println!();
+                }
+                if show_biomes {
+                    println!("🌳 バイオーム（biome -t に指定できる識別子）");
+                    for b in &biomes {
+                        let unavailable = if b.available {
+                            String::new()
+                        } else {
+                            format!("（{}では生成されない）", mc_version)
+                        };
+                        println!(
+                            "   {:<16} {}  希少度: {:.2}  対応: {}{}{}",
+                            b.id,
+                            b.name,
+                            b.rarity,
+                            b.versions,
+                            experimental_note(b.experimental_versions.as_deref()),
+                            unavailable
+                        );
+                    }
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Info { name, output } => {
+            let output = select_format(config.output(output));
+            let info = generation_info(&name)
+                .ok_or_else(|| CliError::Usage(format!("不明な構造物・バイオーム: {}（一覧は list コマンド）", name)))?;
+
+            if output == "json" {
+                emit_json(&info)?;
+            } else {
+                println!("📖 {} ({})", info.name, info.id);
+                println!("   対応バージョン: {}", info.versions);
+                if let Some(placement) = &info.placement {
+                    println!("   配置モデル: {}", placement);
+                }
+                for rule in &info.rules {
+                    println!("   ・{}", rule);
+                }
+            }
+            Ok(Outcome::Found)
+        }
+
+        Commands::Route {
+            center_x,
+            center_z,
+            points,
+            input,
+            nether,
+            output,
+        } => {
+            let output = select_format(config.output(output));
+            let mut waypoints = Vec::new();
+            for point in &points {
+                let w = Waypoint::parse(point)
+                    .ok_or_else(|| CliError::Usage(format!("地点の形式が不正です（X,Z または X,Z,名前）: {}", point)))?;
+                waypoints.push(w);
+            }
+            if let Some(path) = &input {
+                let loaded = std::fs::read_to_string(path)
+                    .ok()
+                    .and_then(|json| waypoints_from_json(&json))
+                    .ok_or_else(|| CliError::Usage(format!("検索結果ファイルを読み込めません: {}", path)))?;
+                waypoints.extend(loaded);
+            }
+            if waypoints.is_empty() {
+                return Err(CliError::Usage("経由地点を --point または --input で指定してください".to_string()));
+            }
 
-        /// 検索中心X座標
-        #[arg(short = 'x', long, default_value = "0")]
-        center_x: i32,
+            let route = plan_route(center_x, center_z, waypoints);
 
-        /// 検索中心Z座標
-        #[arg(short = 'z', long, default_value = "0")]
-        center_z: i32,
+            if output == "json" {
+                emit_json(&route)?;
+            } else {
+                println!("🧭 巡回ルート（{}地点）", route.legs.len());
+                println!("   出発: X={}, Z={}", route.start_x, route.start_z);
+                for (i, leg) in route.legs.iter().enumerate() {
+                    if nether {
+                        println!(
+                            "   {}. {} X={}, Z={} (ネザー: X={}, Z={}) +{:.0} (ネザー側 +{:.0})",
+                            i + 1,
+                            leg.name,
+                            leg.x,
+                            leg.z,
+                            leg.nether_x,
+                            leg.nether_z,
+                            leg.distance,
+                            leg.distance / NETHER_SCALE as f64
+                        );
+                    } else {
+                        println!("   {}. {} X={}, Z={} +{:.0}", i + 1, leg.name, leg.x, leg.z, leg.distance);
+                    }
+                }
+                println!("   総距離: {:.0}ブロック", route.total_distance);
+                if nether {
+                    println!("   ネザー経由の総距離: {:.0}ブロック", route.nether_total_distance);
+                }
+            }
+            Ok(Outcome::Found)
+        }
 
-        /// 検索半径（ブロック単位）
-        #[arg(short, long, default_value = "5000")]
-        radius: i32,
+        Commands::SpeedrunPlan {
+            seed,
+            radius,
+            nether_radius,
+            output,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let output = select_format(config.output(output));
+            let plan = plan_speedrun(seed, radius, nether_radius)?;
 
-        /// 検索する構造物タイプ（all, village, fortress, bastion, monument, mansion, outpost）
-        #[arg(short = 't', long, default_value = "all")]
-        structure_type: String,
+            if output == "json" {
+                emit_json(&plan)?;
+            } else {
+                println!("🏃 スピードランのルート（シード: {}）", seed);
+                let mut entered = false;
+                for (i, leg) in plan.legs.iter().enumerate() {
+                    if leg.dimension == "nether" {
+                        if !entered {
+                            println!("   🔥 ネザーへ（ネザー側の座標で移動）");
+                            entered = true;
+                        }
+                        println!(
+                            "   {}. {} ネザー: X={}, Z={} (オーバーワールド: X={}, Z={}) +{:.0}",
+                            i + 1,
+                            leg.name,
+                            leg.nether_x,
+                            leg.nether_z,
+                            leg.x,
+                            leg.z,
+                            leg.distance
+                        );
+                    } else {
+                        if entered {
+                            println!("   🌍 オーバーワールドへ戻る");
+                            entered = false;
+                        }
+                        println!(
+                            "   {}. {} X={}, Z={} (ネザー: X={}, Z={}) +{:.0}",
+                            i + 1,
+                            leg.name,
+                            leg.x,
+                            leg.z,
+                            leg.nether_x,
+                            leg.nether_z,
+                            leg.distance
+                        );
+                    }
+                }
+                println!("   オーバーワールドの移動: {:.0}ブロック", plan.overworld_distance);
+                println!("   ネザーの移動: {:.0}ブロック", plan.nether_distance);
+                if !plan.missing.is_empty() {
+                    println!("   ⚠️ 検索範囲内に見つからず飛ばした構造物: {}", plan.missing.join(", "));
+                }
+            }
+            Ok(Outcome::from_found(plan.missing.is_empty()))
+        }
 
-        /// 出力形式（json, text）
-        #[arg(short, long, default_value = "text")]
-        output: String,
-    },
+        Commands::Portal {
+            x,
+            z,
+            from,
+            overworld_portals,
+            nether_portals,
+            target,
+            output,
+        } => {
+            let output = select_format(config.output(output));
+            let mut parsed = Vec::new();
+            for list in [&overworld_portals, &nether_portals] {
+                let mut portals = Vec::new();
+                for p in list {
+                    let xz = parse_xz(p).ok_or_else(|| CliError::Usage(format!("ポータル座標の形式が不正です（X,Z）: {}", p)))?;
+                    portals.push(xz);
+                }
+                parsed.push(portals);
+            }
+            let (overworld_portals, nether_portals) = (&parsed[0], &parsed[1]);
+            let (mut source_portals, destination_portals) = match from.as_str() {
+                "overworld" => (overworld_portals.clone(), nether_portals.clone()),
+                "nether" => (nether_portals.clone(), overworld_portals.clone()),
+                _ => {
+                    return Err(CliError::Usage(format!("不明なディメンション: {}（overworld, nether）", from)));
+                }
+            };
 
-    /// バイオームを検索
-    Biome {
-        /// ワールドシード値
-        #[arg(short, long)]
-        seed: i64,
+            let link = predict_link(&from, x, z, &destination_portals)?;
 
-        /// 検索中心X座標
-        #[arg(short = 'x', long, default_value = "0")]
-        center_x: i32,
+            // 戻りの接続（移動先のポータルから新しいポータルに戻れるか）
+            source_portals.push((x, z));
+            let (arrive_x, arrive_z) = link
+                .linked
+                .map(|l| (l.x, l.z))
+                .unwrap_or((link.target_x, link.target_z));
+            let back = predict_link(&link.target_dimension, arrive_x, arrive_z, &source_portals)?;
+            let returns_here = back.linked.map(|l| (l.x, l.z)) == Some((x, z));
 
-        /// 検索中心Z座標
-        #[arg(short = 'z', long, default_value = "0")]
-        center_z: i32,
+            let suggestion = match &target {
+                Some(t) => {
+                    let desired = parse_xz(t)
+                        .ok_or_else(|| CliError::Usage(format!("移動先ポータル座標の形式が不正です（X,Z）: {}", t)))?;
+                    Some(suggest_placement(&from, desired, &destination_portals)?)
+                }
+                None => None,
+            };
 
-        /// 検索半径（ブロック単位）
-        #[arg(short, long, default_value = "10000")]
-        radius: i32,
+            if output == "json" {
+                let result = PortalResult {
+                    link,
+                    return_link: back,
+                    returns_to_new_portal: returns_here,
+                    suggestion,
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🌀 ポータル接続予測（{} X={}, Z={}）", from, x, z);
+                println!("   移動先の基準座標: {} X={}, Z={}", link.target_dimension, link.target_x, link.target_z);
+                match &link.linked {
+                    Some(l) => println!("   接続先: 既存ポータル X={}, Z={} (基準座標から{:.0}ブロック)", l.x, l.z, l.distance),
+                    None => println!("   接続先: 既存ポータルなし（基準座標付近に新しいポータルが生成されます）"),
+                }
+                if returns_here {
+                    println!("   戻り: ✅ このポータルに戻ります");
+                } else {
+                    match &back.linked {
+                        Some(l) => println!("   戻り: ⚠️ 別のポータル X={}, Z={} につながります", l.x, l.z),
+                        None => println!("   戻り: ⚠️ 新しいポータルが生成されます"),
+                    }
+                }
+                if let Some(s) = &suggestion {
+                    println!();
+                    println!("📍 狙ったポータルにつなぐ設置場所（{}）", s.dimension);
+                    println!("   推奨座標: X={}, Z={}", s.x, s.z);
+                    println!("   設置可能範囲: X={}〜{}, Z={}〜{}", s.min_x, s.max_x, s.min_z, s.max_z);
+                    for (cx, cz) in &s.conflicts {
+                        println!("   ⚠️ 範囲の端では X={}, Z={} のポータルに接続が奪われる可能性があります", cx, cz);
+                    }
+                }
+            }
+            Ok(Outcome::Found)
+        }
 
-        /// 検索するバイオーム（jungle, mesa, mushroom, ice_spikes等）
-        #[arg(short = 't', long)]
-        target: String,
+        Commands::Schema { command } => {
+            let schemas = output_schemas();
+            match command {
+                Some(name) => {
+                    let (_, schema) = schemas.iter().find(|(n, _)| *n == name).ok_or_else(|| {
+                        let names: Vec<&str> = schemas.iter().map(|(n, _)| *n).collect();
+                        CliError::Usage(format!("不明なコマンド: {}（{}）", name, names.join(", ")))
+                    })?;
+                    emit_json(schema)?;
+                }
+                None => {
+                    let all: serde_json::Map<String, serde_json::Value> =
+                        schemas.into_iter().map(|(n, schema)| (n.to_string(), schema.to_value())).collect();
+                    emit_json(&all)?;
+                }
+            }
+            Ok(Outcome::Found)
+        }
 
-        /// 出力形式（json, text）
-        #[arg(short, long, default_value = "text")]
-        output: String,
-    },
+        Commands::Doctor { output } => {
+            let output = select_format(config.output(output));
+            let checks = run_checks();
+            let failed = checks.iter().filter(|c| !c.passed).count();
 
-    /// ネザー構造物を検索（要塞、バスティオン）
-    Nether {
-        /// ワールドシード値
-        #[arg(short, long)]
-        seed: i64,
+            if output == "json" {
+                let result = DoctorResult {
+                    passed: failed == 0,
+                    checks,
+                };
+                emit_json(&result)?;
+            } else {
+                println!("🩺 自己診断");
+                for c in &checks {
+                    if c.passed {
+                        println!("   ✅ {}", c.name);
+                    } else {
+                        println!("   ❌ {}: {}", c.name, c.detail);
+                    }
+                }
+                println!();
+                println!("   {}/{} 項目が成功", checks.len() - failed, checks.len());
+            }
 
-        /// 検索中心X座標（ネザー座標）
-        #[arg(short = 'x', long, default_value = "0")]
-        center_x: i32,
+            if failed > 0 {
+                return Err(CliError::Internal(format!("{}項目の自己診断に失敗しました", failed)));
+            }
+            Ok(Outcome::Found)
+        }
 
-        /// 検索中心Z座標（ネザー座標）
-        #[arg(short = 'z', long, default_value = "0")]
-        center_z: i32,
+        Commands::Bookmark { db, action } => run_bookmark(db.as_deref(), &config, action),
 
-        /// 検索半径（ブロック単位）
-        #[arg(short, long, default_value = "1000")]
-        radius: i32,
+        Commands::Explore {
+            seed,
+            center_x,
+            center_z,
+            near_spawn,
+        } => {
+            let seed = config.seed(seed).ok_or(CliError::MissingSeed)?;
+            let (center_x, center_z) = resolve_center(seed, center_x, center_z, near_spawn);
+            start_explorer(seed, mc_version, center_x, center_z)?;
+            Ok(Outcome::Found)
+        }
 
-        /// 出力形式（json, text）
-        #[arg(short, long, default_value = "text")]
-        output: String,
-    },
+        Commands::Batch { input } => {
+            let reader: Box<dyn BufRead> = match input.as_deref() {
+                None | Some("-") => Box::new(std::io::stdin().lock()),
+                Some(path) => {
+                    let file = std::fs::File::open(path)
+                        .map_err(|e| CliError::Usage(format!("入力ファイルを読み込めません: {} ({})", path, e)))?;
+                    Box::new(BufReader::new(file))
+                }
+            };
+            let mut all_found = true;
+            for (i, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let result = run_batch_line(&args[0], i + 1, &line);
+                all_found &= result.exit_code == error::EXIT_FOUND;
+                println!("{}", serde_json::to_string(&result).unwrap());
+            }
+            // 各検索の成否は exit_code で返すため、既定ではすべて実行できれば成功
+            Ok(Outcome::from_found(all_found || !cli.fail_if_empty))
+        }
+
+        Commands::Repl { seed } => {
+            let session = Session {
+                seed: config.seed(seed),
+                version: Some(mc_version),
+                ..Session::default()
+            };
+            // 入力の誤りで対話モードごと終了しないよう、解釈エラーは表示だけにする
+            run_repl(session, |line_args| {
+                let line_args: Vec<String> = std::iter::once(args[0].clone()).chain(line_args).collect();
+                match Cli::try_parse_from(&line_args) {
+                    Ok(cli) => {
+                        if let Err(e) = execute(cli, &line_args) {
+                            eprintln!("{}", e);
+                        }
+                    }
+                    Err(e) => {
+                        let _ = e.print();
+                    }
+                }
+            });
+            Ok(Outcome::Found)
+        }
+
+        Commands::History { action } => run_history(&config, action, args),
+    }
 }
 
-#[derive(Serialize)]
-struct StructureResult {
-    structure_type: String,
-    x: i32,
-    z: i32,
-    distance: f64,
+/// 実行した引数を履歴に記録（記録できない場合は警告のみ）
+#[cfg(feature = "db")]
+fn record_history(args: &[String]) {
+    if let Err(e) = HistoryStore::open(None).and_then(|store| store.record(args)) {
+        tracing::warn!("{}", e);
+    }
 }
 
-#[derive(Serialize)]
-struct SearchResult {
-    seed: i64,
-    center_x: i32,
-    center_z: i32,
-    radius: i32,
-    structures: Vec<StructureResult>,
+/// db 機能なしでビルドした場合は履歴を記録できない
+#[cfg(not(feature = "db"))]
+fn record_history(_args: &[String]) {
+    tracing::warn!("履歴の記録には db 機能を有効にしたビルドが必要です（cargo build --features db）");
 }
 
-fn main() {
-    let cli = Cli::parse();
+/// history サブコマンドを実行
+#[cfg(feature = "db")]
+fn run_history(config: &Config, action: HistoryAction, args: &[String]) -> Result<Outcome, CliError> {
+    let store = HistoryStore::open(None).map_err(CliError::Storage)?;
+    match action {
+        HistoryAction::List { limit, output } => {
+            let output = select_format(config.output(output));
+            let entries = store.list(limit).map_err(CliError::Storage)?;
 
-    match cli.command {
-        Commands::Structures {
-            seed,
-            center_x,
-            center_z,
-            radius,
-            structure_type,
-            output,
-        } => {
-            let structure_types = match structure_type.as_str() {
-                "all" => vec![
-                    StructureType::Village,
-                    StructureType::PillagerOutpost,
-                    StructureType::OceanMonument,
-                    StructureType::WoodlandMansion,
-                ],
-                "village" => vec![StructureType::Village],
-                "outpost" => vec![StructureType::PillagerOutpost],
-                "monument" => vec![StructureType::OceanMonument],
-                "mansion" => vec![StructureType::WoodlandMansion],
-                _ => {
-                    eprintln!("不明な構造物タイプ: {}", structure_type);
-                    return;
+            if output == "json" {
+                emit_json(&entries)?;
+            } else {
+                println!("🕘 実行履歴（新しい順、{}件）", entries.len());
+                if config.history != Some(true) {
+                    println!("   ※ 記録するには設定ファイルに history = true を書いてください");
+                }
+                for e in &entries {
+                    println!("   #{} {} bedrockmate {}", e.id, e.created_at, e.args.join(" "));
                 }
+            }
+            Ok(Outcome::from_found(!entries.is_empty()))
+        }
+
+        HistoryAction::Rerun { id, seed } => {
+            let entry = store
+                .get(id)
+                .map_err(CliError::Storage)?
+                .ok_or_else(|| CliError::Usage(format!("履歴 #{} は存在しません", id)))?;
+            let replay = match seed {
+                Some(seed) => replace_seed(&entry.args, seed),
+                None => entry.args,
             };
+            eprintln!("🔁 bedrockmate {}", replay.join(" "));
+            run(std::iter::once(args[0].clone()).chain(replay).collect())
+        }
+    }
+}
 
-            let mut all_structures = Vec::new();
+/// db 機能なしでビルドした場合は履歴を読めない
+#[cfg(not(feature = "db"))]
+fn run_history(_config: &Config, _action: HistoryAction, _args: &[String]) -> Result<Outcome, CliError> {
+    Err(CliError::Usage("history には db 機能を有効にしたビルドが必要です（cargo build --features db）".to_string()))
+}
 
-            for st in structure_types {
-                let structures = find_structures(seed, center_x, center_z, radius, st);
-                all_structures.extend(structures);
+/// バッチ入力の1行を実行して結果を返す
+fn run_batch_line(program: &str, line: usize, text: &str) -> BatchResult {
+    let record: serde_json::Value = match serde_json::from_str(text) {
+        Ok(r) => r,
+        Err(e) => {
+            return BatchResult {
+                id: None,
+                line,
+                exit_code: error::EXIT_USAGE,
+                result: None,
+                error: Some(format!("JSONとして解釈できません: {}", e)),
             }
+        }
+    };
+    let id = record.get("id").cloned();
+    let failure = |exit_code: i32, error: String| BatchResult {
+        id: id.clone(),
+        line,
+        exit_code,
+        result: None,
+        error: Some(error),
+    };
 
-            // 距離順にソート
-            all_structures.sort_by(|a, b| {
-                let dist_a = ((a.1 - center_x) as f64).powi(2) + ((a.2 - center_z) as f64).powi(2);
-                let dist_b = ((b.1 - center_x) as f64).powi(2) + ((b.2 - center_z) as f64).powi(2);
-                dist_a.partial_cmp(&dist_b).unwrap()
-            });
+    let mut args = match record_to_args(&record) {
+        Ok(a) => a,
+        Err(e) => return failure(error::EXIT_USAGE, e),
+    };
+    // 結果をJSONで受け取れるよう、--output に対応したコマンドだけ実行する
+    if !accepts_output(&args) {
+        return failure(error::EXIT_USAGE, format!("{} はJSON出力に対応していません", args.join(" ")));
+    }
+    args.insert(0, program.to_string());
+    args.push("--output=json".to_string());
 
-            output_results(&output, seed, center_x, center_z, radius, &all_structures);
+    let cli = match Cli::try_parse_from(&args) {
+        Ok(c) => c,
+        Err(e) => {
+            let message = e.to_string();
+            let first_line = message.lines().next().unwrap_or_default();
+            return failure(error::EXIT_USAGE, first_line.trim_start_matches("error: ").to_string());
         }
+    };
+    match capture(|| execute(cli, &args)) {
+        (Ok(outcome), documents) => BatchResult {
+            id,
+            line,
+            exit_code: outcome.exit_code(),
+            result: documents.into_iter().next(),
+            error: None,
+        },
+        (Err(e), _) => failure(e.exit_code(), e.to_string()),
+    }
+}
 
-        Commands::Nether {
-            seed,
-            center_x,
-            center_z,
-            radius,
-            output,
-        } => {
-            let structures = find_nether_structures(seed, center_x, center_z, radius);
-            output_results(&output, seed, center_x, center_z, radius, &structures);
+/// サブコマンド（`bookmark list` 等の入れ子を含む）が --output を受け付けるか
+fn accepts_output(args: &[String]) -> bool {
+    let mut command = Cli::command();
+    for name in args {
+        match command.find_subcommand(name) {
+            Some(sub) => command = sub.clone(),
+            None => break,
         }
+    }
+    let accepts = command.get_arguments().any(|a| a.get_id() == "output");
+    accepts
+}
 
-        Commands::Biome {
+/// bookmark サブコマンドを実行
+#[cfg(feature = "db")]
+fn run_bookmark(db: Option<&str>, config: &Config, action: BookmarkAction) -> Result<Outcome, CliError> {
+    let store = BookmarkStore::open(db).map_err(CliError::Storage)?;
+    match action {
+        BookmarkAction::Add {
+            name,
+            x,
+            z,
+            y,
+            dimension,
             seed,
-            center_x,
-            center_z,
-            radius,
-            target,
-            output,
+            category,
+            notes,
         } => {
-            match find_nearest_biome(seed, center_x, center_z, radius, &target) {
-                Some((x, z, distance)) => {
-                    if output == "json" {
-                        let result = serde_json::json!({
-                            "seed": seed,
-                            "target_biome": target,
-                            "found": true,
-                            "x": x,
-                            "z": z,
-                            "distance": distance
-                        });
-                        println!("{}", serde_json::to_string_pretty(&result).unwrap());
-                    } else {
-                        println!("🌴 最寄りの{}バイオーム", target);
-                        println!("   座標: X={}, Z={}", x, z);
-                        println!("   距離: {:.0}ブロック", distance);
-                    }
-                }
-                None => {
-                    if output == "json" {
-                        let result = serde_json::json!({
-                            "seed": seed,
-                            "target_biome": target,
-                            "found": false
-                        });
-                        println!("{}", serde_json::to_string_pretty(&result).unwrap());
-                    } else {
-                        println!("❌ {}バイオームが見つかりませんでした（範囲: {}ブロック）", target, radius);
+            let dim = match Dimension::from_id(&dimension) {
+                Some(d @ (Dimension::Overworld | Dimension::Nether)) => d,
+                _ => return Err(CliError::Usage(format!("不明なディメンション: {}（overworld, nether）", dimension))),
+            };
+            let seed = config.seed(seed);
+            let y = match (y, seed) {
+                (Some(y), _) => y,
+                (None, Some(seed)) => target_y(seed, dim, x, z),
+                // ネザーの高さはシードによらない。オーバーワールドはサーバー側の既定値に合わせる
+                (None, None) if dim == Dimension::Nether => target_y(0, dim, x, z),
+                (None, None) => 64,
+            };
+            let bookmark = NewBookmark {
+                seed,
+                name,
+                x,
+                y,
+                z,
+                dimension,
+                category,
+                notes,
+            };
+            let id = store.add(&bookmark).map_err(CliError::Storage)?;
+            println!("📌 ブックマーク #{} を保存しました: {} X={}, Y={}, Z={}", id, bookmark.name, x, y, z);
+            Ok(Outcome::Found)
+        }
+
+        BookmarkAction::List { seed, output } => {
+            let output = select_format(config.output(output));
+            let bookmarks = store.list(seed).map_err(CliError::Storage)?;
+
+            if output == "json" {
+                emit_json(&bookmarks)?;
+            } else {
+                println!("📌 ブックマーク（{}件）", bookmarks.len());
+                for b in &bookmarks {
+                    let category = b.category.as_deref().map(|c| format!(" [{}]", c)).unwrap_or_default();
+                    println!("   #{} {}{} {} X={}, Y={}, Z={}", b.id, b.name, category, b.dimension, b.x, b.y, b.z);
+                    if let Some(notes) = &b.notes {
+                        println!("      📝 {}", notes);
                     }
                 }
             }
+            Ok(Outcome::from_found(!bookmarks.is_empty()))
+        }
+
+        BookmarkAction::Export { format, seed } => {
+            let bookmarks = store.list(seed).map_err(CliError::Storage)?;
+            match format.as_str() {
+                "waypoints" => println!("{}", export_waypoints(&bookmarks)),
+                "commands" => println!("{}", export_bookmark_commands(&bookmarks)),
+                _ => return Err(CliError::Usage(format!("不明なエクスポート形式: {}", format))),
+            }
+            Ok(Outcome::from_found(!bookmarks.is_empty()))
+        }
+
+        BookmarkAction::Remove { id } => {
+            if !store.remove(id).map_err(CliError::Storage)? {
+                return Err(CliError::Usage(format!("ブックマーク #{} は存在しません", id)));
+            }
+            println!("🗑️ ブックマーク #{} を削除しました", id);
+            Ok(Outcome::Found)
+        }
+    }
+}
+
+/// db 機能なしでビルドした場合はブックマークを保存できない
+#[cfg(not(feature = "db"))]
+fn run_bookmark(_db: Option<&str>, _config: &Config, _action: BookmarkAction) -> Result<Outcome, CliError> {
+    Err(CliError::Usage("bookmark には db 機能を有効にしたビルドが必要です（cargo build --features db）".to_string()))
+}
+
+/// --seeds / --seeds-file で指定された複数のシード値（どちらも未指定の場合は None）
+fn seed_list(seeds: Vec<i64>, seeds_file: Option<String>) -> Result<Option<Vec<i64>>, CliError> {
+    match seeds_file {
+        Some(path) => read_seeds_file(&path).map(Some).map_err(CliError::Usage),
+        None if seeds.is_empty() => Ok(None),
+        None => Ok(Some(seeds)),
+    }
+}
+
+/// シードごとに検索を実行し、結果をシード別にまとめて出力
+///
+/// JSON出力ではシードごとの結果を配列にまとめる。いずれかのシードで見つかれば Found
+/// （`require_all` の場合はすべてのシードで見つかった場合だけ Found）。
+fn search_seeds(
+    seeds: &[i64],
+    output: &str,
+    require_all: bool,
+    mut search: impl FnMut(i64) -> Result<Outcome, CliError>,
+) -> Result<Outcome, CliError> {
+    // テキスト結果を端末に順次表示する場合は進捗バーが崩れるため、シードごとの進捗表示に任せる
+    let bar = if output != "json" && std::io::stdout().is_terminal() {
+        indicatif::ProgressBar::hidden()
+    } else {
+        progress::seed_bar(seeds.len() as u64)
+    };
+    let mut found_count = 0;
+    let mut documents = Vec::new();
+    for (i, &seed) in seeds.iter().enumerate() {
+        let outcome = if output == "json" {
+            let (outcome, captured) = capture(|| search(seed));
+            documents.extend(captured);
+            outcome?
+        } else {
+            if i > 0 {
+                println!();
+            }
+            println!("🌱 シード {} ({}/{})", seed, i + 1, seeds.len());
+            search(seed)?
+        };
+        if outcome == Outcome::Found {
+            found_count += 1;
+        }
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    if output == "json" {
+        emit_json(&documents)?;
+    }
+    let found = if require_all { found_count == seeds.len() } else { found_count > 0 };
+    Ok(Outcome::from_found(found))
+}
+
+/// 検索中心を決定（スポーン基準の場合は推定スポーン地点）
+fn resolve_center(seed: i64, center_x: i32, center_z: i32, near_spawn: bool) -> (i32, i32) {
+    if near_spawn {
+        let spawn = estimate_spawn(seed);
+        (spawn.x, spawn.z)
+    } else {
+        (center_x, center_z)
+    }
+}
+
+/// "基準=重み" の形式の重みを解釈
+fn parse_weight(spec: &str) -> Result<(Criterion, f64), CliError> {
+    let (name, value) = spec
+        .split_once('=')
+        .ok_or_else(|| CliError::Usage(format!("重みの形式が不正です: {}（例: village=2）", spec)))?;
+    let criterion = Criterion::from_id(name.trim()).ok_or_else(|| {
+        let names: Vec<&str> = Criterion::ALL.iter().map(|c| c.id()).collect();
+        CliError::Usage(format!("不明な基準: {}（{}）", name, names.join(", ")))
+    })?;
+    match value.trim().parse::<f64>() {
+        Ok(value) if value >= 0.0 && value.is_finite() => Ok((criterion, value)),
+        _ => Err(CliError::Usage(format!("重みは0以上の数を指定してください: {}", spec))),
+    }
+}
+
+/// 対象バージョンで生成されない構造物・バイオームのエラー
+///
+/// 実験的機能としてなら生成されるバージョンの場合は --experimental を案内する。
+fn not_available(id: &str, kind: &str, since: &str, experimental_since: Option<&str>, mc_version: McVersion) -> CliError {
+    let hint = match experimental_since {
+        Some(e) if mc_version.is_at_least(e) => format!("（{} 以降は --experimental で実験的機能として検索できます）", e),
+        _ => String::new(),
+    };
+    CliError::Usage(format!("{} は {} 以降で追加された{}です{}", id, since, kind, hint))
+}
+
+/// 対象バージョンで生成されない構造物のエラー
+fn structure_not_available(st: &StructureType, mc_version: McVersion) -> CliError {
+    not_available(st.id(), "構造物", st.since_version(), st.experimental_since(), mc_version)
+}
+
+/// list の「対応」に添える実験的機能のバージョン
+fn experimental_note(versions: Option<&str>) -> String {
+    versions.map(|v| format!("（実験的機能: {}）", v)).unwrap_or_default()
+}
+
+/// -t any: の種類がすべて対象バージョンで生成されるか確かめる
+fn available_any_types(types: &[StructureType], mc_version: McVersion) -> Result<Vec<StructureType>, CliError> {
+    match types.iter().find(|st| !st.is_available(mc_version)) {
+        Some(st) => Err(structure_not_available(st, mc_version)),
+        None => Ok(types.to_vec()),
+    }
+}
+
+/// 中心から東西南北に extent ブロックの正方形の範囲にある構造物（地図に重ねる構造物）
+fn map_structures(
+    seed: i64,
+    mc_version: McVersion,
+    center_x: i32,
+    center_z: i32,
+    extent: i32,
+    structure_type: &[String],
+) -> Result<Vec<StructureHit>, CliError> {
+    let search_radius = (extent as f64 * std::f64::consts::SQRT_2).ceil() as i32;
+    let within = |hit: &StructureHit| (hit.pos.x - center_x).abs() <= extent && (hit.pos.z - center_z).abs() <= extent;
+    let mut found = Vec::new();
+    for id in structure_type {
+        let kind = StructureKind::from_id(id).ok_or_else(|| CliError::Usage(format!("不明な構造物タイプ: {}", id)))?;
+        if kind.dimension() != Dimension::Overworld {
+            return Err(CliError::Usage(format!("{} はオーバーワールドの構造物ではありません", id)));
         }
+        if kind.is_available(mc_version) {
+            found.extend(find_kind(seed, &kind, BlockPos::new(center_x, center_z), search_radius)?.into_iter().filter(within));
+        }
+    }
+    Ok(found)
+}
+
+/// 検索結果を地図の画像に重ねたHTMLを出力
+fn output_html_map(header: &MapHeader, view: &MapView, image: &Image, structures: &[StructureHit]) -> Result<(), CliError> {
+    print!("{}", render_html_map(header, view, image, structures)?);
+    Ok(())
+}
+
+/// 検索結果の構造物をJSONの1件に変換（距離は from_x, from_z から測る）
+fn structure_result(seed: i64, hit: &StructureHit, from_x: i32, from_z: i32, loot: Option<StructureLoot>) -> StructureResult {
+    let BlockPos { x, z } = hit.pos;
+    StructureResult {
+        structure_type: hit.kind.display_name().to_string(),
+        x,
+        z,
+        distance: distance_2d(from_x, from_z, x, z),
+        confidence: structure_confidence(&hit.kind),
+        bbox: structure_bbox(&hit.kind, hit.pos),
+        biome: structure_biome(seed, &hit.kind, hit.pos).map(str::to_string),
+        fortress_area: fortress_area(&hit.kind, hit.pos),
+        loot,
     }
 }
 
@@ -226,21 +3851,20 @@ fn output_results(
     center_x: i32,
     center_z: i32,
     radius: i32,
-    structures: &[(String, i32, i32)],
-) {
+    structures: &[StructureHit],
+    loot: bool,
+) -> Result<(), CliError> {
+    // ルート予測は指定時のみ（対応していない構造物は None）
+    let predict = |hit: &StructureHit| -> Option<StructureLoot> {
+        if !loot {
+            return None;
+        }
+        predict_loot(seed, &hit.kind, hit.pos.x, hit.pos.z)
+    };
+
     if format == "json" {
-        let results: Vec<StructureResult> = structures
-            .iter()
-            .map(|(name, x, z)| {
-                let distance = (((x - center_x) as f64).powi(2) + ((z - center_z) as f64).powi(2)).sqrt();
-                StructureResult {
-                    structure_type: name.clone(),
-                    x: *x,
-                    z: *z,
-                    distance,
-                }
-            })
-            .collect();
+        let results: Vec<StructureResult> =
+            structures.iter().map(|hit| structure_result(seed, hit, center_x, center_z, predict(hit))).collect();
 
         let result = SearchResult {
             seed,
@@ -248,9 +3872,10 @@ fn output_results(
             center_z,
             radius,
             structures: results,
+            truncated: budget::truncated(),
         };
 
-        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+        emit_json(&result)?;
     } else {
         println!("🗺️  構造物検索結果");
         println!("   シード: {}", seed);
@@ -261,10 +3886,203 @@ fn output_results(
         if structures.is_empty() {
             println!("   構造物が見つかりませんでした");
         } else {
-            for (name, x, z) in structures {
-                let distance = (((x - center_x) as f64).powi(2) + ((z - center_z) as f64).powi(2)).sqrt();
-                println!("   {} X={}, Z={} (距離: {:.0})", name, x, z, distance);
+            for hit in structures {
+                let BlockPos { x, z } = hit.pos;
+                let distance = distance_2d(center_x, center_z, x, z);
+                let approximate = if structure_confidence(&hit.kind) == Confidence::Approximate { " ≈近似" } else { "" };
+                println!("   {} X={}, Z={} (距離: {:.0}){}", hit.kind.display_name(), x, z, distance, approximate);
+                if let Some(area) = fortress_area(&hit.kind, hit.pos) {
+                    let (extent, interior) = (area.extent, area.interior);
+                    println!(
+                        "      推定範囲: X={}〜{}, Z={}〜{}（区画内: X={}〜{}, Z={}〜{}）",
+                        extent.min_x, extent.max_x, extent.min_z, extent.max_z,
+                        interior.min_x, interior.max_x, interior.min_z, interior.max_z
+                    );
+                }
+                if let Some(loot) = predict(hit) {
+                    print_loot(&loot);
+                }
             }
         }
+        print_truncated_notice();
+    }
+    Ok(())
+}
+
+/// フラットワールドで意味のないコマンドを警告する
+fn warn_flat_world(command: &Commands) {
+    let missing = match command {
+        Commands::Biome { .. }
+        | Commands::Column { .. }
+        | Commands::Map { .. }
+        | Commands::Diffmap { .. }
+        | Commands::VersionDiff { .. }
+        | Commands::Heightmap { .. }
+        | Commands::Wood { .. }
+        | Commands::Corridor { .. }
+        | Commands::ScoreLocation { .. } => "バイオームと地形",
+        Commands::Deepdark { .. }
+        | Commands::Dungeon { .. }
+        | Commands::Ore { .. }
+        | Commands::Veins { .. }
+        | Commands::Underground { .. } => "洞窟・鉱石・地下の構造物",
+        Commands::Hub { .. } | Commands::Farm { .. } | Commands::NetherClear { .. } | Commands::BuildSite { .. } => {
+            "構造物"
+        }
+        _ => return,
+    };
+    tracing::warn!("フラットワールドのオーバーワールドには{}が生成されないため、このコマンドの結果は実際のワールドと一致しません", missing);
+}
+
+/// 検索範囲がワールドの範囲の外の場合に警告する
+fn warn_outside_world(world: WorldType, dimension: Dimension, center_x: i32, center_z: i32, radius: i32) {
+    if !world.overlaps(dimension, center_x, center_z, radius) {
+        tracing::warn!(
+            "検索範囲が {} のワールドの範囲（{}）の外です",
+            world.id(),
+            world.describe_bounds(dimension)
+        );
+    }
+}
+
+/// ワールドの保存データから対象のゲームバージョンを判定
+#[cfg(feature = "world")]
+fn world_game_version(path: &str) -> Result<McVersion, CliError> {
+    let info = read_level_info(path).map_err(CliError::Usage)?;
+    let version = info
+        .game_version()
+        .ok_or_else(|| CliError::Usage(format!("ワールドのバージョンを判定できません: {}", path)))?;
+    tracing::info!(
+        "ワールドのバージョン: {}（lastOpenedWithVersion: {}、StorageVersion: {}）",
+        version,
+        info.last_opened.map_or_else(|| "-".to_string(), |v| v.to_string()),
+        info.storage_version.map_or_else(|| "-".to_string(), |v| v.to_string())
+    );
+    Ok(version)
+}
+
+/// world 機能なしでビルドした場合はワールドの保存データを読めない
+#[cfg(not(feature = "world"))]
+fn world_game_version(_path: &str) -> Result<McVersion, CliError> {
+    Err(CliError::Usage(
+        "--world には world 機能を有効にしたビルドが必要です（cargo build --features world）".to_string(),
+    ))
+}
+
+/// 地図エクスプローラーを起動
+#[cfg(feature = "tui")]
+fn start_explorer(seed: i64, mc_version: McVersion, center_x: i32, center_z: i32) -> Result<(), CliError> {
+    run_explorer(Explorer::new(seed, mc_version, center_x, center_z))?;
+    Ok(())
+}
+
+/// tui 機能なしでビルドした場合は地図エクスプローラーを使えない
+#[cfg(not(feature = "tui"))]
+fn start_explorer(_seed: i64, _mc_version: McVersion, _center_x: i32, _center_z: i32) -> Result<(), CliError> {
+    Err(CliError::Usage("explore には tui 機能を有効にしたビルドが必要です（cargo build --features tui）".to_string()))
+}
+
+/// 制限時間を過ぎて検索を打ち切った場合に知らせる
+fn print_truncated_notice() {
+    if budget::truncated() {
+        println!();
+        println!("⏱️  制限時間（--timeout）を過ぎたため、途中までの結果です");
+    }
+}
+
+/// 予測した構造物の形とチェストの中身を表示
+fn print_loot(loot: &StructureLoot) {
+    if let Some(variant) = &loot.variant {
+        println!("      🧭 {}", variant);
+    }
+    for chest in &loot.chests {
+        print_chest(chest);
+    }
+    for odds in &loot.odds {
+        println!("      🎲 {} {:.0}%", odds.item, odds.probability * 100.0);
+    }
+}
+
+/// 予測したチェストの中身を表示
+fn print_chest(chest: &ChestLoot) {
+    if chest.rolled_on_open {
+        println!("      🔒 {} ({}, {}, {}): 開けたときに抽選（予測不可）", chest.chest, chest.x, chest.y, chest.z);
+        return;
+    }
+    let items: Vec<String> = chest
+        .items
+        .iter()
+        .map(|s| {
+            let enchantments: Vec<String> = s.enchantments.iter().map(|e| format!("{} {}", e.id, e.level)).collect();
+            if enchantments.is_empty() {
+                format!("{}×{}", s.item, s.count)
+            } else {
+                format!("{}×{} [{}]", s.item, s.count, enchantments.join(", "))
+            }
+        })
+        .collect();
+    println!("      📦 {} ({}, {}, {}): {}", chest.chest, chest.x, chest.y, chest.z, items.join(", "));
+    if !chest.notable.is_empty() {
+        println!("      ⭐ {}", chest.notable.join(", "));
+    }
+}
+
+/// --copy の形式を検証
+fn check_copy_format(format: Option<&str>) -> Result<(), CliError> {
+    match format {
+        Some(f) if !COPY_FORMATS.contains(&f) => Err(CliError::Usage(format!(
+            "不明なコピー形式: {}（{}）",
+            f,
+            COPY_FORMATS.join(", ")
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// 最寄りの結果をクリップボードにコピー（コピーできない環境では警告のみ）
+fn copy_nearest(format: &str, seed: i64, dimension: Dimension, x: i32, z: i32) {
+    let Some(text) = copy_text(format, seed, dimension, x, z) else {
+        return;
+    };
+    match set_clipboard(&text) {
+        Ok(()) => eprintln!("📋 クリップボードにコピーしました: {}", text),
+        Err(e) => tracing::warn!("{}", e),
+    }
+}
+
+fn export_results(format: &str, seed: i64, dimension: Dimension, structures: &[StructureHit]) -> Result<(), CliError> {
+    match format {
+        "commands" => println!("{}", export_commands(seed, dimension, structures)),
+        "amulet" => println!("{}", export_amulet(seed, dimension, structures)),
+        _ => return Err(CliError::Usage(format!("不明なエクスポート形式: {}", format))),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::version::{LATEST_VERSION, PREVIEW_VERSION};
+
+    #[test]
+    fn test_batch_resets_world_offset() {
+        let first = run_batch_line("bedrockmate", 1, r#"{"command": "convert", "x": 100, "z": 100, "world_offset": "1024,0"}"#);
+        let second = run_batch_line("bedrockmate", 2, r#"{"command": "convert", "x": 100, "z": 100}"#);
+        assert_eq!(first.result.unwrap()["meta"]["world_offset"]["x"], 1024);
+        // 前の行のずれは次の行に残らない
+        assert!(second.result.unwrap()["meta"].get("world_offset").is_none());
+        assert_eq!(coords::world_offset(Dimension::Overworld), (0, 0));
+    }
+
+    #[test]
+    fn test_batch_channel_per_line() {
+        let preview = run_batch_line("bedrockmate", 1, r#"{"command": "convert", "x": 1, "z": 1, "channel": "preview"}"#);
+        let retail = run_batch_line("bedrockmate", 2, r#"{"command": "convert", "x": 1, "z": 1}"#);
+        let meta = |r: BatchResult| r.result.unwrap()["meta"].clone();
+        let (preview, retail) = (meta(preview), meta(retail));
+        assert_eq!((&preview["channel"], &preview["mc_version"]), (&json!("preview"), &json!(PREVIEW_VERSION.to_string())));
+        assert_eq!((&retail["channel"], &retail["mc_version"]), (&json!("retail"), &json!(LATEST_VERSION.to_string())));
     }
 }
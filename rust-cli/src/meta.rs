@@ -0,0 +1,123 @@
+//! 出力メタデータモジュール
+//!
+//! JSON出力の各文書に `meta`（ツールのバージョン、アルゴリズムごとの改訂番号、
+//! 対象のゲームバージョン、生成日時）を付け、古い計算結果を利用側で見分けられるようにする。
+
+use std::cell::Cell;
+use std::time::SystemTime;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::algorithms::{biome, deep_dark, dungeon, loot, ore, slime, spawn, underground};
+use crate::coords::{world_offset, Dimension, XZ};
+use crate::gamedata::game_data;
+use crate::structures;
+use crate::version::{self, McVersion};
+
+thread_local! {
+    static MC_VERSION: Cell<Option<McVersion>> = const { Cell::new(None) };
+}
+
+/// 対象のエディション
+pub const EDITION: &str = "bedrock";
+
+/// アルゴリズムごとの改訂番号
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AlgorithmRevisions {
+    pub structures: u32,
+    pub biome: u32,
+    pub spawn: u32,
+    pub slime: u32,
+    pub loot: u32,
+    pub dungeon: u32,
+    pub ore: u32,
+    pub underground: u32,
+    pub deep_dark: u32,
+}
+
+/// JSON文書に付けるメタデータ
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Meta {
+    pub tool_version: String,
+    pub algorithms: AlgorithmRevisions,
+    /// 計算に使ったゲームバージョン
+    pub mc_version: String,
+    /// 実験的機能の構造物・バイオームを対象にしたか（--experimental）
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub experimental: bool,
+    /// 配信チャンネル（retail, preview）
+    pub channel: String,
+    /// ワールドのずれ（--world-offset。ずれがない場合は省略）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub world_offset: Option<XZ>,
+    pub edition: String,
+    /// 生成日時（RFC 3339, UTC）
+    pub generated_at: String,
+}
+
+/// 実行するコマンドの対象バージョンを設定（以降のJSON出力に meta が付く）
+pub fn set_mc_version(version: McVersion) {
+    MC_VERSION.with(|v| v.set(Some(version)));
+}
+
+/// 実行するコマンドの対象バージョン（未設定の場合は None）
+pub fn mc_version() -> Option<McVersion> {
+    MC_VERSION.with(Cell::get)
+}
+
+/// 現在のメタデータ（対象バージョンが未設定の場合は None）
+pub fn current() -> Option<Meta> {
+    let mc_version = mc_version()?;
+    Some(Meta {
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        algorithms: AlgorithmRevisions {
+            structures: structures::ALGORITHM_REVISION,
+            biome: biome::ALGORITHM_REVISION,
+            spawn: spawn::ALGORITHM_REVISION,
+            slime: slime::ALGORITHM_REVISION,
+            loot: loot::ALGORITHM_REVISION,
+            dungeon: dungeon::ALGORITHM_REVISION,
+            ore: ore::ALGORITHM_REVISION,
+            underground: underground::ALGORITHM_REVISION,
+            deep_dark: deep_dark::ALGORITHM_REVISION,
+        },
+        mc_version: mc_version.to_string(),
+        experimental: version::experimental(),
+        channel: game_data().channel.id().to_string(),
+        world_offset: Some(world_offset(Dimension::Overworld))
+            .filter(|offset| *offset != (0, 0))
+            .map(|(x, z)| XZ { x, z }),
+        edition: EDITION.to_string(),
+        generated_at: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+    })
+}
+
+/// JSONオブジェクトの文書に meta を付ける（配列の文書はそのまま）
+pub fn attach(document: &mut Value) {
+    if let (Value::Object(map), Some(meta)) = (document, current()) {
+        map.insert("meta".to_string(), serde_json::to_value(meta).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_meta() {
+        let mut document = serde_json::json!({ "seed": 1 });
+        attach(&mut document);
+        assert!(document.get("meta").is_none());
+
+        set_mc_version(McVersion(1, 18, 0));
+        attach(&mut document);
+        assert_eq!(document["meta"]["mc_version"], "1.18.0");
+        assert_eq!(document["meta"]["edition"], "bedrock");
+
+        let mut list = serde_json::json!([1, 2]);
+        attach(&mut list);
+        assert_eq!(list, serde_json::json!([1, 2]));
+    }
+}
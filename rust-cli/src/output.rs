@@ -0,0 +1,129 @@
+//! 出力モジュール
+//!
+//! 各コマンドのJSON出力を一か所にまとめる。通常は整形したJSONを標準出力に書き、
+//! バッチ実行中は結果を取り込んで呼び出し元に返す。オブジェクトの文書には `meta` を付ける。
+//! `--format-template` 指定時はJSONの代わりにテンプレートを適用した結果を、
+//! `--output msgpack` / `--output cbor` 指定時は同じ文書をバイナリで書く。
+
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::CliError;
+use crate::meta;
+use crate::template::OutputTemplate;
+
+/// JSON出力と同じ文書の書き出し方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<Value>>> = const { RefCell::new(None) };
+    static TEMPLATE: RefCell<Option<OutputTemplate>> = const { RefCell::new(None) };
+    static ENCODING: Cell<Encoding> = const { Cell::new(Encoding::Json) };
+}
+
+/// 出力形式を選ぶ（msgpack, cbor はJSONと同じ文書をバイナリで書き出すため "json" を返す）
+pub fn select_format(format: String) -> String {
+    let encoding = match format.as_str() {
+        "msgpack" => Encoding::MessagePack,
+        "cbor" => Encoding::Cbor,
+        _ => Encoding::Json,
+    };
+    ENCODING.with(|e| e.set(encoding));
+    if encoding == Encoding::Json {
+        format
+    } else {
+        "json".to_string()
+    }
+}
+
+/// 以降のJSON出力に適用するテンプレートを設定（None で通常のJSON出力に戻す）
+pub fn set_template(template: Option<OutputTemplate>) {
+    TEMPLATE.with(|t| *t.borrow_mut() = template);
+}
+
+/// JSON文書を出力（取り込み中は標準出力に書かずに保持する）
+///
+/// テンプレートを設定している場合はJSONの代わりにテンプレートの適用結果を出力する。
+pub fn emit_json<T: Serialize>(value: &T) -> Result<(), CliError> {
+    let mut value = serde_json::to_value(value).unwrap();
+    meta::attach(&mut value);
+    let value = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(documents) => {
+            documents.push(value);
+            None
+        }
+        None => Some(value),
+    });
+    let Some(value) = value else {
+        return Ok(());
+    };
+
+    let rendered = TEMPLATE.with(|t| t.borrow().as_ref().map(|template| template.render(&value)));
+    if let Some(text) = rendered {
+        print!("{}", text.map_err(CliError::Usage)?);
+        return Ok(());
+    }
+    let mut stdout = std::io::stdout().lock();
+    match ENCODING.with(Cell::get) {
+        Encoding::Json => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+        Encoding::MessagePack => stdout.write_all(&encode_msgpack(&value))?,
+        Encoding::Cbor => stdout.write_all(&encode_cbor(&value))?,
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// MessagePackに変換（構造体はフィールド名付きのマップ）
+fn encode_msgpack(value: &Value) -> Vec<u8> {
+    rmp_serde::to_vec_named(value).unwrap()
+}
+
+fn encode_cbor(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+/// `f` の実行中に出力されたJSON文書を取り込んで返す
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Value>) {
+    let previous = CAPTURED.with(|captured| captured.replace(Some(Vec::new())));
+    let result = f();
+    let documents = CAPTURED.with(|captured| captured.replace(previous)).unwrap_or_default();
+    (result, documents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let (result, documents) = capture(|| {
+            emit_json(&serde_json::json!({ "x": 1 })).unwrap();
+            emit_json(&vec![1, 2]).unwrap();
+            42
+        });
+        assert_eq!(result, 42);
+        assert_eq!(documents, vec![serde_json::json!({ "x": 1 }), serde_json::json!([1, 2])]);
+    }
+
+    #[test]
+    fn test_binary_encodings() {
+        assert_eq!(select_format("msgpack".to_string()), "json");
+        assert_eq!(select_format("text".to_string()), "text");
+
+        let value = serde_json::json!({ "seed": 12345, "structures": [{ "x": -8, "distance": 1.5 }] });
+        let decoded: Value = rmp_serde::from_slice(&encode_msgpack(&value)).unwrap();
+        assert_eq!(decoded, value);
+        let decoded: Value = ciborium::from_reader(encode_cbor(&value).as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
@@ -0,0 +1,270 @@
+//! バイオームの配色モジュール
+//!
+//! 地図のバイオームの色を組み込みのプリセットか、ユーザーの配色ファイル（TOML・JSON）から選ぶ。
+//! 配色ファイルはもとにするプリセット（base）とバイオームの識別子ごとの色（"#rrggbb"）を書き、
+//! 書かなかったバイオームはプリセットの色になる。
+//!
+//! ```toml
+//! base = "colorblind"
+//!
+//! [colors]
+//! plains = "#9acd32"
+//! ocean = "#1e3a8a"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::algorithms::biome::BiomeType;
+
+/// 組み込みの配色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PalettePreset {
+    /// エクスプローラーと同じ色
+    Default,
+    /// ゲーム内の地図アイテムに近い色
+    Vanilla,
+    /// 隣り合うバイオームを見分けやすい鮮やかな色
+    HighContrast,
+    /// 色覚の多様性に配慮した色（Okabe-Ito の配色がもと）
+    Colorblind,
+}
+
+impl PalettePreset {
+    pub const ALL: [PalettePreset; 4] = [
+        PalettePreset::Default,
+        PalettePreset::Vanilla,
+        PalettePreset::HighContrast,
+        PalettePreset::Colorblind,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            PalettePreset::Default => "default",
+            PalettePreset::Vanilla => "vanilla",
+            PalettePreset::HighContrast => "high_contrast",
+            PalettePreset::Colorblind => "colorblind",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<PalettePreset> {
+        PalettePreset::ALL.into_iter().find(|p| p.id() == id)
+    }
+
+    /// バイオームの色
+    pub fn color(&self, biome: BiomeType) -> [u8; 3] {
+        match self {
+            PalettePreset::Default => default_color(biome),
+            PalettePreset::Vanilla => vanilla_color(biome),
+            PalettePreset::HighContrast => high_contrast_color(biome),
+            PalettePreset::Colorblind => colorblind_color(biome),
+        }
+    }
+}
+
+fn default_color(biome: BiomeType) -> [u8; 3] {
+    match biome {
+        BiomeType::Plains => [141, 179, 96],
+        BiomeType::Forest => [5, 102, 33],
+        BiomeType::Jungle => [83, 123, 9],
+        BiomeType::Desert => [250, 148, 24],
+        BiomeType::Mesa => [217, 69, 21],
+        BiomeType::Mushroom => [255, 0, 255],
+        BiomeType::IceSpikes => [180, 220, 220],
+        BiomeType::Swamp => [7, 249, 178],
+        BiomeType::Savanna => [189, 178, 95],
+        BiomeType::Taiga => [11, 102, 89],
+        BiomeType::SnowyTaiga => [49, 85, 74],
+        BiomeType::Ocean => [0, 0, 112],
+        BiomeType::DeepOcean => [0, 0, 48],
+        BiomeType::Beach => [250, 222, 85],
+        BiomeType::River => [0, 0, 255],
+        BiomeType::Mountain => [96, 96, 96],
+        BiomeType::MangroveSwamp => [103, 112, 4],
+        BiomeType::CherryGrove => [255, 183, 197],
+        BiomeType::Unknown => [0, 0, 0],
+    }
+}
+
+fn vanilla_color(biome: BiomeType) -> [u8; 3] {
+    match biome {
+        BiomeType::Plains => [127, 178, 56],
+        BiomeType::Forest => [0, 124, 0],
+        BiomeType::Jungle => [0, 140, 20],
+        BiomeType::Desert => [247, 233, 163],
+        BiomeType::Mesa => [216, 127, 51],
+        BiomeType::Mushroom => [127, 63, 178],
+        BiomeType::IceSpikes => [160, 160, 255],
+        BiomeType::Swamp => [102, 127, 51],
+        BiomeType::Savanna => [183, 170, 90],
+        BiomeType::Taiga => [0, 100, 40],
+        BiomeType::SnowyTaiga => [255, 255, 255],
+        BiomeType::Ocean => [64, 64, 255],
+        BiomeType::DeepOcean => [44, 44, 180],
+        BiomeType::Beach => [229, 216, 150],
+        BiomeType::River => [64, 64, 255],
+        BiomeType::Mountain => [112, 112, 112],
+        BiomeType::MangroveSwamp => [76, 104, 54],
+        BiomeType::CherryGrove => [242, 127, 165],
+        BiomeType::Unknown => [0, 0, 0],
+    }
+}
+
+fn high_contrast_color(biome: BiomeType) -> [u8; 3] {
+    match biome {
+        BiomeType::Plains => [144, 238, 0],
+        BiomeType::Forest => [0, 110, 0],
+        BiomeType::Jungle => [0, 200, 80],
+        BiomeType::Desert => [255, 230, 0],
+        BiomeType::Mesa => [255, 80, 0],
+        BiomeType::Mushroom => [255, 0, 200],
+        BiomeType::IceSpikes => [150, 255, 255],
+        BiomeType::Swamp => [100, 80, 0],
+        BiomeType::Savanna => [200, 160, 60],
+        BiomeType::Taiga => [0, 90, 110],
+        BiomeType::SnowyTaiga => [235, 235, 235],
+        BiomeType::Ocean => [0, 60, 255],
+        BiomeType::DeepOcean => [0, 0, 110],
+        BiomeType::Beach => [255, 255, 150],
+        BiomeType::River => [0, 200, 255],
+        BiomeType::Mountain => [130, 130, 130],
+        BiomeType::MangroveSwamp => [60, 130, 0],
+        BiomeType::CherryGrove => [255, 120, 200],
+        BiomeType::Unknown => [0, 0, 0],
+    }
+}
+
+fn colorblind_color(biome: BiomeType) -> [u8; 3] {
+    match biome {
+        BiomeType::Plains => [150, 210, 170],
+        BiomeType::Forest => [0, 158, 115],
+        BiomeType::Jungle => [0, 110, 80],
+        BiomeType::Desert => [240, 228, 66],
+        BiomeType::Mesa => [213, 94, 0],
+        BiomeType::Mushroom => [204, 121, 167],
+        BiomeType::IceSpikes => [200, 230, 250],
+        BiomeType::Swamp => [90, 90, 40],
+        BiomeType::Savanna => [230, 159, 0],
+        BiomeType::Taiga => [60, 120, 120],
+        BiomeType::SnowyTaiga => [245, 245, 245],
+        BiomeType::Ocean => [0, 114, 178],
+        BiomeType::DeepOcean => [0, 60, 110],
+        BiomeType::Beach => [250, 240, 180],
+        BiomeType::River => [86, 180, 233],
+        BiomeType::Mountain => [140, 140, 140],
+        BiomeType::MangroveSwamp => [70, 110, 60],
+        BiomeType::CherryGrove => [240, 170, 200],
+        BiomeType::Unknown => [0, 0, 0],
+    }
+}
+
+/// 配色ファイルの形式
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PaletteFile {
+    base: Option<String>,
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+}
+
+/// 地図のバイオームの配色
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    base: PalettePreset,
+    /// 配色ファイルで上書きした色
+    overrides: Vec<(BiomeType, [u8; 3])>,
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::preset(PalettePreset::Default)
+    }
+}
+
+impl Palette {
+    pub fn preset(preset: PalettePreset) -> Palette {
+        Palette {
+            base: preset,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// バイオームの色
+    pub fn color(&self, biome: BiomeType) -> [u8; 3] {
+        self.overrides
+            .iter()
+            .find(|(b, _)| *b == biome)
+            .map_or_else(|| self.base.color(biome), |(_, color)| *color)
+    }
+
+    /// --palette の値（プリセットの名前か配色ファイルのパス）から配色を得る
+    pub fn load(spec: &str) -> Result<Palette, String> {
+        if let Some(preset) = PalettePreset::from_id(spec) {
+            return Ok(Palette::preset(preset));
+        }
+        let path = Path::new(spec);
+        if !path.exists() {
+            let names: Vec<&str> = PalettePreset::ALL.iter().map(|p| p.id()).collect();
+            return Err(format!("不明な配色: {}（プリセットは {}、またはTOML・JSONのファイル）", spec, names.join(", ")));
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("配色ファイルを読み込めません: {} ({})", path.display(), e))?;
+        let json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        Palette::parse(&text, json).map_err(|e| format!("配色ファイルの形式が不正です: {} ({})", path.display(), e))
+    }
+
+    /// 配色ファイルの内容を解釈（json が false の場合はTOML）
+    pub fn parse(text: &str, json: bool) -> Result<Palette, String> {
+        let file: PaletteFile = if json {
+            serde_json::from_str(text).map_err(|e| e.to_string())?
+        } else {
+            toml::from_str(text).map_err(|e| e.to_string())?
+        };
+
+        let base = match file.base.as_deref() {
+            Some(id) => PalettePreset::from_id(id).ok_or_else(|| format!("不明なプリセット: {}", id))?,
+            None => PalettePreset::Default,
+        };
+        let mut overrides = Vec::new();
+        for (id, color) in &file.colors {
+            let biome = BiomeType::from_str(id).ok_or_else(|| format!("不明なバイオーム: {}", id))?;
+            let rgb = parse_hex_color(color).ok_or_else(|| format!("{} の色が不正です: {}（\"#rrggbb\" の形式）", id, color))?;
+            overrides.push((biome, rgb));
+        }
+        Ok(Palette { base, overrides })
+    }
+}
+
+/// "#rrggbb" 形式の色を解釈
+fn parse_hex_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_file() {
+        let toml = "base = \"colorblind\"\n[colors]\nplains = \"#0a0B0c\"\n";
+        let palette = Palette::parse(toml, false).unwrap();
+        assert_eq!(palette.color(BiomeType::Plains), [10, 11, 12]);
+        assert_eq!(palette.color(BiomeType::Ocean), PalettePreset::Colorblind.color(BiomeType::Ocean));
+
+        let json = r##"{"colors": {"badlands": "#ff0000"}}"##;
+        let palette = Palette::parse(json, true).unwrap();
+        assert_eq!(palette.color(BiomeType::Mesa), [255, 0, 0]);
+        assert_eq!(palette.color(BiomeType::Plains), [141, 179, 96]);
+
+        assert!(Palette::parse("[colors]\nvolcano = \"#000000\"", false).is_err());
+        assert!(Palette::parse("[colors]\nplains = \"green\"", false).is_err());
+        assert_eq!(Palette::load("vanilla").unwrap(), Palette::preset(PalettePreset::Vanilla));
+    }
+}
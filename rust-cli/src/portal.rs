@@ -0,0 +1,216 @@
+//! ネザーポータル接続予測モジュール
+//!
+//! Bedrock Editionのポータル探索範囲のルールで、新しいポータルの接続先を予測する
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::coords::{nether_to_overworld, overworld_to_nether, NETHER_SCALE, SEARCH_LIMIT};
+use crate::error::SearchError;
+use crate::travel::distance_2d;
+
+/// オーバーワールド側でポータルを探す範囲（ブロック、正方形の半径）
+pub const OVERWORLD_SEARCH_RADIUS: i32 = 128;
+
+/// ネザー側でポータルを探す範囲（ブロック、正方形の半径）
+pub const NETHER_SEARCH_RADIUS: i32 = 16;
+
+/// 接続先のポータル
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+pub struct LinkedPortal {
+    pub x: i32,
+    pub z: i32,
+    /// 移動先の基準座標からの距離
+    pub distance: f64,
+}
+
+/// ポータル接続の予測結果
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PortalLink {
+    pub from_dimension: String,
+    pub x: i32,
+    pub z: i32,
+    pub target_dimension: String,
+    /// 移動先ディメンションでの基準座標
+    pub target_x: i32,
+    pub target_z: i32,
+    /// 既存ポータルにつながる場合はそのポータル（None なら新しく生成される）
+    pub linked: Option<LinkedPortal>,
+}
+
+/// 狙ったポータルにつなぐための設置場所の提案
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlacementSuggestion {
+    /// 設置するディメンション
+    pub dimension: String,
+    /// 最も確実な設置座標
+    pub x: i32,
+    pub z: i32,
+    /// 狙ったポータルが探索範囲に入る設置範囲（両端を含む）
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_z: i32,
+    pub max_z: i32,
+    /// 範囲の端では接続を奪う可能性がある他のポータル
+    pub conflicts: Vec<(i32, i32)>,
+}
+
+/// 移動先ディメンションと探索半径を取得
+fn target_of(from: &str) -> Result<(&'static str, i32), SearchError> {
+    match from {
+        "overworld" => Ok(("nether", NETHER_SEARCH_RADIUS)),
+        "nether" => Ok(("overworld", OVERWORLD_SEARCH_RADIUS)),
+        _ => Err(SearchError::UnknownCoordinateKind(from.to_string())),
+    }
+}
+
+/// ポータルの座標がディメンションの変換と探索範囲の計算で溢れない範囲にあるか確かめる
+///
+/// ネザーの座標はオーバーワールドに変換すると8倍になるため、上限も1/8にする。
+fn check_portal(dimension: &str, x: i32, z: i32) -> Result<(), SearchError> {
+    let limit = if dimension == "nether" { SEARCH_LIMIT / NETHER_SCALE as i64 } else { SEARCH_LIMIT };
+    if (x as i64).abs() <= limit && (z as i64).abs() <= limit {
+        Ok(())
+    } else {
+        Err(SearchError::CoordinateOutOfRange { kind: dimension.to_string(), x, z })
+    }
+}
+
+/// 座標を移動先ディメンションの基準座標に変換
+fn scale_to_target(from: &str, x: i32, z: i32) -> (i32, i32) {
+    if from == "overworld" {
+        (overworld_to_nether(x), overworld_to_nether(z))
+    } else {
+        (nether_to_overworld(x), nether_to_overworld(z))
+    }
+}
+
+/// 新しいポータルの接続先を予測
+///
+/// 移動先の基準座標を中心とした正方形の探索範囲内で、最も近い既存ポータルに接続する。
+/// `from` は overworld または nether、`destination_portals` は移動先ディメンションの既存ポータル。
+pub fn predict_link(
+    from: &str,
+    x: i32,
+    z: i32,
+    destination_portals: &[(i32, i32)],
+) -> Result<PortalLink, SearchError> {
+    let (target_dimension, radius) = target_of(from)?;
+    check_portal(from, x, z)?;
+    for &(px, pz) in destination_portals {
+        check_portal(target_dimension, px, pz)?;
+    }
+    let (target_x, target_z) = scale_to_target(from, x, z);
+
+    let linked = destination_portals
+        .iter()
+        .filter(|(px, pz)| (px - target_x).abs() <= radius && (pz - target_z).abs() <= radius)
+        .map(|&(px, pz)| LinkedPortal {
+            x: px,
+            z: pz,
+            distance: distance_2d(target_x, target_z, px, pz),
+        })
+        .min_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+
+    Ok(PortalLink {
+        from_dimension: from.to_string(),
+        x,
+        z,
+        target_dimension: target_dimension.to_string(),
+        target_x,
+        target_z,
+        linked,
+    })
+}
+
+/// 移動先の `desired` ポータルにつながるポータルの設置場所を提案
+///
+/// `from` は新しいポータルを設置するディメンション。
+pub fn suggest_placement(
+    from: &str,
+    desired: (i32, i32),
+    destination_portals: &[(i32, i32)],
+) -> Result<PlacementSuggestion, SearchError> {
+    let (target_dimension, radius) = target_of(from)?;
+    for &(px, pz) in destination_portals.iter().chain([&desired]) {
+        check_portal(target_dimension, px, pz)?;
+    }
+    let (dx, dz) = desired;
+
+    // 基準座標が desired から探索半径内に入る設置範囲
+    let (x, z, min_x, max_x, min_z, max_z) = if from == "overworld" {
+        (
+            nether_to_overworld(dx),
+            nether_to_overworld(dz),
+            nether_to_overworld(dx - radius),
+            nether_to_overworld(dx + radius) + NETHER_SCALE - 1,
+            nether_to_overworld(dz - radius),
+            nether_to_overworld(dz + radius) + NETHER_SCALE - 1,
+        )
+    } else {
+        (
+            overworld_to_nether(dx),
+            overworld_to_nether(dz),
+            overworld_to_nether(dx - radius + NETHER_SCALE - 1),
+            overworld_to_nether(dx + radius),
+            overworld_to_nether(dz - radius + NETHER_SCALE - 1),
+            overworld_to_nether(dz + radius),
+        )
+    };
+
+    // desired の探索範囲の2倍以内にある他のポータルは、範囲の端で接続を奪う可能性がある
+    let conflicts = destination_portals
+        .iter()
+        .filter(|&&(px, pz)| (px, pz) != desired)
+        .filter(|(px, pz)| (px - dx).abs() <= radius * 2 && (pz - dz).abs() <= radius * 2)
+        .copied()
+        .collect();
+
+    Ok(PlacementSuggestion {
+        dimension: from.to_string(),
+        x,
+        z,
+        min_x,
+        max_x,
+        min_z,
+        max_z,
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predict_link_nearest_in_range() {
+        let nether_portals = [(100, 100), (130, 128), (200, 200)];
+        let link = predict_link("overworld", 1000, 1000, &nether_portals).unwrap();
+        assert_eq!((link.target_x, link.target_z), (125, 125));
+        let linked = link.linked.unwrap();
+        assert_eq!((linked.x, linked.z), (130, 128));
+
+        let link = predict_link("overworld", 8000, 8000, &nether_portals).unwrap();
+        assert!(link.linked.is_none());
+    }
+
+    #[test]
+    fn test_suggest_placement_links_back() {
+        let nether_portals = [(50, -20), (60, -20)];
+        let suggestion = suggest_placement("overworld", (50, -20), &nether_portals).unwrap();
+        assert_eq!((suggestion.x, suggestion.z), (400, -160));
+        let link = predict_link("overworld", suggestion.x, suggestion.z, &nether_portals).unwrap();
+        assert_eq!(link.linked.map(|l| (l.x, l.z)), Some((50, -20)));
+        assert_eq!(suggestion.conflicts, vec![(60, -20)]);
+    }
+
+    #[test]
+    fn test_rejects_out_of_range() {
+        // ネザーの座標は8倍してもオーバーワールドの座標に収まる範囲まで
+        assert!(predict_link("nether", 2_000_000_000, 0, &[]).is_err());
+        assert!(predict_link("overworld", 0, 0, &[(i32::MIN, 0)]).is_err());
+        assert!(suggest_placement("overworld", (300_000_000, 0), &[]).is_err());
+        assert!(predict_link("nether", 30_000_000 / 8, 0, &[]).is_ok());
+        assert!(predict_link("end", 0, 0, &[]).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+//! 進捗表示モジュール
+//!
+//! 広い範囲の検索で、調べたリージョン数・サンプル数と残り時間を標準エラー出力に表示する。
+//! 出力先が端末でない場合（パイプやリダイレクト）と、進捗表示を有効にしていない場合は表示しない。
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// この件数未満の処理はすぐ終わるため表示しない
+const MIN_LEN: u64 = 10_000;
+
+/// 進捗表示の有無を設定（検索系のコマンドでのみ有効にする）
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// `len` 件の処理の進捗バーを作成（表示しない場合は何も描画しないバー）
+pub fn bar(len: u64, message: &str) -> ProgressBar {
+    if len < MIN_LEN {
+        return ProgressBar::hidden();
+    }
+    new_bar(len, message)
+}
+
+/// 複数シードの検索の進捗バーを作成
+///
+/// 1シードごとの検索にも時間がかかるため件数によらず表示し、表示を崩さないよう
+/// 以降に作成するシードごとの進捗バーは表示しない。
+pub fn seed_bar(len: u64) -> ProgressBar {
+    let bar = new_bar(len, "シード");
+    set_enabled(false);
+    bar
+}
+
+fn new_bar(len: u64, message: &str) -> ProgressBar {
+    if !ENABLED.load(Ordering::Relaxed) || !std::io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(len).with_message(message.to_string());
+    bar.set_style(
+        ProgressStyle::with_template("{msg} [{bar:30}] {human_pos}/{human_len} 残り {eta}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar
+}
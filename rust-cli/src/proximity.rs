@@ -0,0 +1,223 @@
+//! 構造物の近さの条件モジュール
+//!
+//! `--near "village<=300:outpost"` の形で「村から300ブロック以内に前哨基地がある」という条件を書き、
+//! 検索結果を条件を満たす構造物に絞り込む。`:` で続けた構造物はすべて距離以内にある必要があり
+//! （`village<=300:outpost:witch_hut`）、条件を複数指定した場合はすべてを満たす必要がある。
+//! 条件の基準（`<=` の左）にならない構造物は結果から除く。
+//!
+//! また、指定した種類の構造物のうち N 種類以上に D ブロック以内で行ける地点（拠点の候補）を探す。
+
+use crate::coords::{BlockPos, Dimension};
+use crate::error::SearchError;
+use crate::structures::{find_kind, StructureHit, StructureKind};
+use crate::travel::distance_2d;
+
+/// 近さの条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearConstraint {
+    /// 基準の構造物の識別子
+    pub anchor: String,
+    /// 最大の距離（ブロック単位）
+    pub distance: i32,
+    /// 基準から距離以内にある必要がある構造物の識別子
+    pub others: Vec<String>,
+}
+
+impl NearConstraint {
+    /// "village<=300:outpost" の形式を解釈
+    pub fn parse(spec: &str) -> Result<NearConstraint, String> {
+        let invalid = || format!("近さの条件の形式が不正です: {}（例: village<=300:outpost）", spec);
+        let (anchor, rest) = spec.split_once("<=").ok_or_else(invalid)?;
+        let mut parts = rest.split(':').map(str::trim);
+        let distance = parts
+            .next()
+            .and_then(|d| d.parse::<i32>().ok())
+            .filter(|d| *d > 0)
+            .ok_or_else(invalid)?;
+        let others: Vec<String> = parts.map(str::to_string).collect();
+        if others.is_empty() || others.iter().any(String::is_empty) {
+            return Err(invalid());
+        }
+
+        let anchor = anchor.trim().to_string();
+        if let Some(id) = std::iter::once(&anchor).chain(&others).find(|id| !is_overworld(id)) {
+            return Err(format!("近さの条件に使えない構造物タイプ: {}（オーバーワールドの構造物のみ）", id));
+        }
+        Ok(NearConstraint { anchor, distance, others })
+    }
+}
+
+/// 拠点の候補の地点
+#[derive(Debug, Clone, PartialEq)]
+pub struct HubSpot {
+    pub x: i32,
+    pub z: i32,
+    /// 地点から距離以内にある構造物（種類ごとに最も近いもの、近い順）
+    pub members: Vec<StructureHit>,
+}
+
+impl HubSpot {
+    /// 構造物までの距離の合計
+    fn total_distance(&self) -> f64 {
+        self.members.iter().map(|hit| distance_2d(self.x, self.z, hit.pos.x, hit.pos.z)).sum()
+    }
+}
+
+/// 構造物のうち min_types 種類以上に distance ブロック以内で行ける地点を探す
+///
+/// 検索範囲を距離の1/4（16ブロック以上）の間隔で調べ、行ける種類が多く、構造物までの距離の合計が短い順に並べる。
+/// 近い候補はまとめ、互いに距離より離れた地点だけを返す。
+pub fn find_hubs(
+    seed: i64,
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+    types: &[String],
+    distance: i32,
+    min_types: usize,
+) -> Result<Vec<HubSpot>, String> {
+    if let Some(id) = types.iter().find(|id| !is_overworld(id)) {
+        return Err(format!("拠点の検索に使えない構造物タイプ: {}（オーバーワールドの構造物のみ）", id));
+    }
+    // 種類ごとの構造物
+    let found: Vec<Vec<StructureHit>> = types
+        .iter()
+        .map(|id| find_by_id(seed, id, center_x, center_z, radius.saturating_add(distance)).map_err(|e| e.to_string()))
+        .collect::<Result<_, String>>()?;
+
+    let step = (distance / 4).max(16);
+    let steps = radius / step;
+    let mut candidates = Vec::new();
+    for i in -steps..=steps {
+        for j in -steps..=steps {
+            let (x, z) = (center_x + i * step, center_z + j * step);
+            if distance_2d(center_x, center_z, x, z) > radius as f64 {
+                continue;
+            }
+            let mut members: Vec<StructureHit> = found
+                .iter()
+                .filter_map(|list| {
+                    list.iter()
+                        .map(|s| (s, distance_2d(x, z, s.pos.x, s.pos.z)))
+                        .filter(|(_, d)| *d <= distance as f64)
+                        .min_by(|a, b| a.1.total_cmp(&b.1))
+                        .map(|(s, _)| s.clone())
+                })
+                .collect();
+            if members.len() >= min_types {
+                members.sort_by(|a, b| distance_2d(x, z, a.pos.x, a.pos.z).total_cmp(&distance_2d(x, z, b.pos.x, b.pos.z)));
+                candidates.push(HubSpot { x, z, members });
+            }
+        }
+    }
+    candidates.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then(a.total_distance().total_cmp(&b.total_distance()))
+    });
+
+    let mut hubs: Vec<HubSpot> = Vec::new();
+    for spot in candidates {
+        if hubs.iter().all(|h| distance_2d(h.x, h.z, spot.x, spot.z) > distance as f64) {
+            hubs.push(spot);
+        }
+    }
+    Ok(hubs)
+}
+
+/// 近さの条件に使える構造物か（オーバーワールドの構造物と要塞）
+fn is_overworld(id: &str) -> bool {
+    StructureKind::from_id(id).is_some_and(|kind| kind.dimension() == Dimension::Overworld)
+}
+
+/// 中心から radius ブロック以内の構造物（識別子で指定）
+fn find_by_id(seed: i64, id: &str, center_x: i32, center_z: i32, radius: i32) -> Result<Vec<StructureHit>, SearchError> {
+    match StructureKind::from_id(id) {
+        Some(kind) => find_kind(seed, &kind, BlockPos::new(center_x, center_z), radius),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 検索結果から近さの条件をすべて満たす構造物だけを残す
+///
+/// 相手の構造物は検索範囲を条件の距離だけ広げて1度だけ探す。
+pub fn apply_near(
+    constraints: &[NearConstraint],
+    seed: i64,
+    center_x: i32,
+    center_z: i32,
+    radius: i32,
+    structures: Vec<StructureHit>,
+) -> Result<Vec<StructureHit>, SearchError> {
+    let reach = constraints.iter().map(|c| c.distance).max().unwrap_or(0);
+    let mut ids: Vec<&str> = constraints.iter().flat_map(|c| c.others.iter().map(String::as_str)).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    // 相手の構造物（識別子、X、Z）
+    let mut partners: Vec<(&str, i32, i32)> = Vec::new();
+    for id in ids {
+        let found = find_by_id(seed, id, center_x, center_z, radius.saturating_add(reach))?;
+        partners.extend(found.into_iter().map(|hit| (id, hit.pos.x, hit.pos.z)));
+    }
+    let has_near = |id: &str, x: i32, z: i32, distance: i32| {
+        partners.iter().any(|&(p, px, pz)| {
+            // 同じ種類の条件では自分自身を数えない
+            p == id && (px, pz) != (x, z) && distance_2d(x, z, px, pz) <= distance as f64
+        })
+    };
+
+    Ok(structures
+        .into_iter()
+        .filter(|hit| {
+            let BlockPos { x, z } = hit.pos;
+            let mut matching = constraints.iter().filter(|c| c.anchor == hit.kind.id()).peekable();
+            matching.peek().is_some() && matching.all(|c| c.others.iter().all(|other| has_near(other, x, z, c.distance)))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{find_structures, StructureType};
+
+    #[test]
+    fn test_near_constraint() {
+        let c = NearConstraint::parse("village<=300:outpost:witch_hut").unwrap();
+        assert_eq!(c.anchor, "village");
+        assert_eq!(c.distance, 300);
+        assert_eq!(c.others, vec!["outpost", "witch_hut"]);
+        assert!(NearConstraint::parse("village<300:outpost").is_err());
+        assert!(NearConstraint::parse("village<=0:outpost").is_err());
+        assert!(NearConstraint::parse("village<=300").is_err());
+        assert!(NearConstraint::parse("village<=300:fortress").is_err());
+
+        let seed = 12345;
+        let villages = find_structures(seed, BlockPos::new(0, 0), 3000, StructureType::Village).unwrap();
+        let outposts = find_structures(seed, BlockPos::new(0, 0), 3600, StructureType::PillagerOutpost).unwrap();
+        let c = NearConstraint::parse("village<=600:outpost").unwrap();
+        let kept = apply_near(&[c], seed, 0, 0, 3000, villages.clone()).unwrap();
+        let expected: Vec<_> = villages
+            .into_iter()
+            .filter(|v| outposts.iter().any(|hit| distance_2d(v.pos.x, v.pos.z, hit.pos.x, hit.pos.z) <= 600.0))
+            .collect();
+        assert_eq!(kept, expected);
+    }
+
+    #[test]
+    fn test_find_hubs() {
+        let types = vec!["village".to_string(), "outpost".to_string(), "monument".to_string()];
+        let hubs = find_hubs(12345, 0, 0, 3000, &types, 800, 2).unwrap();
+        assert!(!hubs.is_empty());
+        for hub in &hubs {
+            assert!(hub.members.len() >= 2);
+            assert!(hub.members.iter().all(|hit| distance_2d(hub.x, hub.z, hit.pos.x, hit.pos.z) <= 800.0));
+        }
+        for pair in hubs.windows(2) {
+            assert!(pair[0].members.len() >= pair[1].members.len());
+            assert!(distance_2d(pair[0].x, pair[0].z, pair[1].x, pair[1].z) > 800.0);
+        }
+        assert!(find_hubs(12345, 0, 0, 3000, &["fortress".to_string()], 800, 1).is_err());
+    }
+}
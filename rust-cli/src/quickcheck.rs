@@ -0,0 +1,157 @@
+//! 定番の質問のプリセットモジュール
+//!
+//! 「スポーン地点の近くにキノコ島はある？」のようなよく聞かれる質問を、スポーン地点の推定と
+//! バイオーム・構造物の検索を既定の範囲で組み合わせて1回で答える。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::biome::{find_nearest_biome, BiomeType};
+use crate::algorithms::spawn::{estimate_spawn, SpawnEstimate};
+use crate::coords::BlockPos;
+use crate::error::SearchError;
+use crate::structures::{find_nearest_any, Confidence, StructureType};
+use crate::travel::distance_2d;
+
+/// 探すもの
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Biome(BiomeType),
+    Structure(StructureType),
+}
+
+impl Target {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Target::Biome(biome) => biome.id(),
+            Target::Structure(st) => st.id(),
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Target::Biome(biome) => biome.display_name(),
+            Target::Structure(st) => st.display_name(),
+        }
+    }
+}
+
+/// 質問のプリセット
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickCheck {
+    /// スポーン地点の近くのキノコ島
+    Mushroom,
+    /// スポーン地点の近くのサクラの林
+    Cherry,
+    /// スポーン地点の近くの村
+    Village,
+}
+
+impl QuickCheck {
+    pub const ALL: [QuickCheck; 3] = [QuickCheck::Mushroom, QuickCheck::Cherry, QuickCheck::Village];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            QuickCheck::Mushroom => "mushroom-near-spawn",
+            QuickCheck::Cherry => "cherry-near-spawn",
+            QuickCheck::Village => "village-near-spawn",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<QuickCheck> {
+        QuickCheck::ALL.into_iter().find(|q| q.id() == id)
+    }
+
+    pub fn target(&self) -> Target {
+        match self {
+            QuickCheck::Mushroom => Target::Biome(BiomeType::Mushroom),
+            QuickCheck::Cherry => Target::Biome(BiomeType::CherryGrove),
+            QuickCheck::Village => Target::Structure(StructureType::Village),
+        }
+    }
+
+    /// スポーン地点から探す半径（珍しいものほど広く）
+    pub fn radius(&self) -> i32 {
+        match self {
+            QuickCheck::Mushroom => 5000,
+            QuickCheck::Cherry => 3000,
+            QuickCheck::Village => 1000,
+        }
+    }
+
+    pub fn question(&self) -> String {
+        format!("スポーン地点から{}ブロック以内に{}はある？", self.radius(), self.target().display_name())
+    }
+}
+
+/// 質問の答え
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct QuickCheckResult {
+    pub seed: i64,
+    pub preset: String,
+    pub question: String,
+    pub spawn: SpawnEstimate,
+    /// 探したバイオームか構造物の識別子
+    pub target: String,
+    pub radius: i32,
+    pub found: bool,
+    pub x: Option<i32>,
+    pub z: Option<i32>,
+    /// スポーン地点からの距離
+    pub distance: Option<f64>,
+    pub confidence: Confidence,
+}
+
+/// プリセットの質問に答える（accept が偽の座標は見つからなかったものとして扱う）
+pub fn run_quickcheck(
+    seed: i64,
+    check: QuickCheck,
+    accept: impl Fn(BlockPos) -> bool,
+) -> Result<QuickCheckResult, SearchError> {
+    let spawn = estimate_spawn(seed);
+    let radius = check.radius();
+    let (nearest, confidence) = match check.target() {
+        Target::Biome(biome) => {
+            (find_nearest_biome(seed, BlockPos::new(spawn.x, spawn.z), radius, biome.id(), accept)?, Confidence::Approximate)
+        }
+        Target::Structure(st) => {
+            let hit = find_nearest_any(seed, BlockPos::new(spawn.x, spawn.z), radius, &[st], accept)?;
+            let nearest = hit.map(|hit| (hit.pos, distance_2d(spawn.x, spawn.z, hit.pos.x, hit.pos.z)));
+            (nearest, st.confidence())
+        }
+    };
+    Ok(QuickCheckResult {
+        seed,
+        preset: check.id().to_string(),
+        question: check.question(),
+        target: check.target().id().to_string(),
+        radius,
+        found: nearest.is_some(),
+        x: nearest.map(|(pos, _)| pos.x),
+        z: nearest.map(|(pos, _)| pos.z),
+        distance: nearest.map(|(_, d)| d),
+        confidence,
+        spawn,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_quickcheck() {
+        for check in QuickCheck::ALL {
+            assert_eq!(QuickCheck::from_id(check.id()), Some(check));
+            let result = run_quickcheck(12345, check, |_| true).unwrap();
+            assert_eq!(result.found, result.distance.is_some());
+            if let Some(distance) = result.distance {
+                assert!(distance <= check.radius() as f64);
+            }
+        }
+        let village = run_quickcheck(12345, QuickCheck::Village, |_| true).unwrap();
+        assert!(village.found);
+        assert_eq!(village.target, "village");
+        assert!(!run_quickcheck(12345, QuickCheck::Village, |_| false).unwrap().found);
+    }
+}
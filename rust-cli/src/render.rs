@@ -0,0 +1,718 @@
+//! 画像出力モジュール
+//!
+//! 推定した地形やバイオームの地図をPNG画像として書き出す
+
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::biome::get_biome_at;
+use crate::algorithms::height::{estimate_terrain_y, SEA_LEVEL};
+use crate::coords::BlockPos;
+use crate::icons::structure_icon;
+use crate::palette::Palette;
+use crate::travel::distance_2d;
+
+/// 地図の画像の1辺の既定のピクセル数の目安
+pub const DEFAULT_MAP_SIZE: i32 = 1024;
+
+/// 地図の画像の1辺の最大ピクセル数
+pub const MAX_MAP_SIZE: usize = 8192;
+
+/// 拡大率のプリセット（名前、1ピクセルあたりのブロック数）
+pub const ZOOM_PRESETS: [(&str, i32); 4] = [("base", 1), ("town", 4), ("region", 16), ("continent", 64)];
+
+/// 地図に書く文字の字形（3×5ドット。上の行から3ビットずつ）
+///
+/// 英字は大文字の字形だけを持ち、小文字は大文字として書く。
+const GLYPHS: [(char, u16); 49] = [
+    ('0', 0b111_101_101_101_111),
+    ('1', 0b010_110_010_010_111),
+    ('2', 0b111_001_111_100_111),
+    ('3', 0b111_001_111_001_111),
+    ('4', 0b101_101_111_001_001),
+    ('5', 0b111_100_111_001_111),
+    ('6', 0b111_100_111_101_111),
+    ('7', 0b111_001_001_001_001),
+    ('8', 0b111_101_111_101_111),
+    ('9', 0b111_101_111_001_111),
+    ('A', 0b010_101_111_101_101),
+    ('B', 0b110_101_110_101_110),
+    ('C', 0b011_100_100_100_011),
+    ('D', 0b110_101_101_101_110),
+    ('E', 0b111_100_110_100_111),
+    ('F', 0b111_100_110_100_100),
+    ('G', 0b011_100_101_101_011),
+    ('H', 0b101_101_111_101_101),
+    ('I', 0b111_010_010_010_111),
+    ('J', 0b001_001_001_101_010),
+    ('K', 0b101_101_110_101_101),
+    ('L', 0b100_100_100_100_111),
+    ('M', 0b101_111_111_101_101),
+    ('N', 0b110_101_101_101_101),
+    ('O', 0b010_101_101_101_010),
+    ('P', 0b110_101_110_100_100),
+    ('Q', 0b010_101_101_110_011),
+    ('R', 0b110_101_110_101_101),
+    ('S', 0b011_100_010_001_110),
+    ('T', 0b111_010_010_010_010),
+    ('U', 0b101_101_101_101_111),
+    ('V', 0b101_101_101_101_010),
+    ('W', 0b101_101_111_111_101),
+    ('X', 0b101_101_010_101_101),
+    ('Y', 0b101_101_010_010_010),
+    ('Z', 0b111_001_010_100_111),
+    (':', 0b000_010_000_010_000),
+    ('=', 0b000_111_000_111_000),
+    ('.', 0b000_000_000_000_010),
+    (',', 0b000_000_000_010_100),
+    ('-', 0b000_000_111_000_000),
+    ('_', 0b000_000_000_000_111),
+    ('+', 0b000_010_111_010_000),
+    ('/', 0b001_001_010_100_100),
+    ('(', 0b010_100_100_100_010),
+    (')', 0b010_001_001_001_010),
+    ('#', 0b101_111_101_111_101),
+    ('?', 0b111_001_010_000_010),
+    (' ', 0),
+];
+
+/// 文字の1ドットのピクセル数
+pub const GLYPH_SCALE: i64 = 2;
+
+/// 文字の送り幅（ピクセル数）
+pub const GLYPH_ADVANCE: i64 = 4 * GLYPH_SCALE;
+
+/// 文字の高さ（ピクセル数）
+pub const GLYPH_HEIGHT: i64 = 5 * GLYPH_SCALE;
+
+/// 起伏の陰影を付ける前に高さをならす範囲（ブロック単位の半径）
+const SMOOTHING_BLOCKS: i32 = 32;
+
+/// 道順の線の色
+pub const ROUTE_COLOR: [u8; 3] = [255, 64, 200];
+
+pub const WHITE: [u8; 3] = [255, 255, 255];
+pub const BLACK: [u8; 3] = [0, 0, 0];
+
+/// RGBの画像
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// 北の行から順に1ピクセル3バイト
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![0; width * height * 3],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> [u8; 3] {
+        let i = (y * self.width + x) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        let i = (y * self.width + x) * 3;
+        self.pixels[i..i + 3].copy_from_slice(&color);
+    }
+
+    /// 矩形を塗る（画像からはみ出す部分は描かない）
+    pub fn fill_rect(&mut self, x: i64, y: i64, width: i64, height: i64, color: [u8; 3]) {
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + width).min(self.width as i64);
+        let y1 = (y + height).min(self.height as i64);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                self.set(px as usize, py as usize, color);
+            }
+        }
+    }
+
+    /// 文字を描く（縁取り付き。字形のない文字は ? として書く）
+    pub fn draw_text(&mut self, x: i64, y: i64, text: &str, color: [u8; 3]) {
+        let outline = if color == BLACK { WHITE } else { BLACK };
+        for pass in [outline, color] {
+            for (i, c) in text.chars().enumerate() {
+                let glyph = glyph(c.to_ascii_uppercase());
+                let left = x + i as i64 * GLYPH_ADVANCE;
+                for bit in 0..15 {
+                    if glyph & (1 << (14 - bit)) == 0 {
+                        continue;
+                    }
+                    let px = left + (bit % 3) * GLYPH_SCALE;
+                    let py = y + (bit / 3) * GLYPH_SCALE;
+                    if pass == outline {
+                        self.fill_rect(px - 1, py - 1, GLYPH_SCALE + 2, GLYPH_SCALE + 2, pass);
+                    } else {
+                        self.fill_rect(px, py, GLYPH_SCALE, GLYPH_SCALE, pass);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 矩形に色を重ねる（alpha は重ねる色の割合）
+    pub fn blend_rect(&mut self, x: i64, y: i64, width: i64, height: i64, color: [u8; 3], alpha: f64) {
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + width).min(self.width as i64);
+        let y1 = (y + height).min(self.height as i64);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                let i = (py as usize * self.width + px as usize) * 3;
+                for (channel, c) in self.pixels[i..i + 3].iter_mut().zip(color) {
+                    *channel = (*channel as f64 * (1.0 - alpha) + c as f64 * alpha).round() as u8;
+                }
+            }
+        }
+    }
+
+    /// 一部を切り出す（はみ出す部分は含まない）
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Image {
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        let mut image = Image::new(width, height);
+        for row in 0..height {
+            let from = ((y + row) * self.width + x) * 3;
+            image.pixels[row * width * 3..(row + 1) * width * 3].copy_from_slice(&self.pixels[from..from + width * 3]);
+        }
+        image
+    }
+
+    /// PNG形式のバイト列に変換（map 機能なしでビルドした場合はエラー）
+    pub fn to_png(&self) -> std::io::Result<Vec<u8>> {
+        encode_png(self.width, self.height, PngColor::Rgb, &self.pixels)
+    }
+}
+
+/// 地図に描く範囲と縮尺
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapView {
+    /// 北西の角の座標
+    pub min_x: i32,
+    pub min_z: i32,
+    /// 1ピクセルあたりのブロック数
+    pub blocks_per_pixel: i32,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl MapView {
+    /// 中心から半径の正方形の範囲を描く地図
+    pub fn around(center_x: i32, center_z: i32, radius: i32, blocks_per_pixel: i32) -> MapView {
+        let blocks_per_pixel = blocks_per_pixel.max(1);
+        let size = ((radius * 2 + blocks_per_pixel - 1) / blocks_per_pixel).max(1) as usize;
+        MapView {
+            min_x: center_x - radius,
+            min_z: center_z - radius,
+            blocks_per_pixel,
+            width: size,
+            height: size,
+        }
+    }
+
+    /// 縮尺を指定しなかった場合の半径（DEFAULT_MAP_SIZE ピクセル四方の範囲）
+    pub fn default_radius(blocks_per_pixel: i32) -> i32 {
+        blocks_per_pixel * DEFAULT_MAP_SIZE / 2
+    }
+
+    /// 半径の範囲が DEFAULT_MAP_SIZE ピクセル程度に収まる縮尺
+    pub fn auto_scale(radius: i32) -> i32 {
+        ((radius * 2 + DEFAULT_MAP_SIZE - 1) / DEFAULT_MAP_SIZE).max(1)
+    }
+
+    /// ピクセルの中心の座標
+    pub fn block_at(&self, px: usize, py: usize) -> (i32, i32) {
+        let half = self.blocks_per_pixel / 2;
+        (
+            self.min_x + px as i32 * self.blocks_per_pixel + half,
+            self.min_z + py as i32 * self.blocks_per_pixel + half,
+        )
+    }
+
+    /// 書き出した画像の座標の対応
+    pub fn georeference(&self, image: &str) -> Georeference {
+        Georeference::new(image, self.min_x, self.min_z, self.blocks_per_pixel, self.width, self.height)
+    }
+
+    /// 座標の地図上の位置（ピクセル単位。範囲外は画像の外になる）
+    pub fn pixel_of(&self, x: i32, z: i32) -> (f64, f64) {
+        (
+            (x - self.min_x) as f64 / self.blocks_per_pixel as f64,
+            (z - self.min_z) as f64 / self.blocks_per_pixel as f64,
+        )
+    }
+}
+
+/// 文字の字形
+fn glyph(c: char) -> u16 {
+    GLYPHS
+        .iter()
+        .find(|(g, _)| *g == c)
+        .or_else(|| GLYPHS.iter().find(|(g, _)| *g == '?'))
+        .map_or(0, |(_, bits)| *bits)
+}
+
+/// 書き出した画像のピクセルとブロック座標の対応
+///
+/// ピクセル (px, py) は X=min_x+px×blocks_per_pixel、Z=min_z+py×blocks_per_pixel から
+/// blocks_per_pixel ブロック四方の範囲を表す（画像の下が南）。
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct Georeference {
+    /// 画像のファイル
+    pub image: String,
+    pub width: usize,
+    pub height: usize,
+    pub blocks_per_pixel: i32,
+    /// 北西の角の座標
+    pub min_x: i32,
+    pub min_z: i32,
+    /// 南東の角の座標（含まない）
+    pub max_x: i32,
+    pub max_z: i32,
+}
+
+impl Georeference {
+    pub fn new(image: &str, min_x: i32, min_z: i32, blocks_per_pixel: i32, width: usize, height: usize) -> Georeference {
+        Georeference {
+            image: image.to_string(),
+            width,
+            height,
+            blocks_per_pixel,
+            min_x,
+            min_z,
+            max_x: min_x + width as i32 * blocks_per_pixel,
+            max_z: min_z + height as i32 * blocks_per_pixel,
+        }
+    }
+
+    /// ワールドファイル（.pgw）の内容
+    ///
+    /// GISの慣習では行が進むと北に向かうが、マインクラフトでは南（Zが増える方向）に向かうため
+    /// 5行目（行あたりの変化）は正の値になる。
+    pub fn world_file(&self) -> String {
+        let bpp = self.blocks_per_pixel as f64;
+        let half = bpp / 2.0;
+        format!(
+            "{:.1}\n0.0\n0.0\n{:.1}\n{:.1}\n{:.1}\n",
+            bpp,
+            bpp,
+            self.min_x as f64 + half,
+            self.min_z as f64 + half
+        )
+    }
+
+    /// 画像の隣にワールドファイル（拡張子 .pgw）とJSON（画像のファイル名 + .json）を書き出す
+    pub fn write_sidecars(&self) -> std::io::Result<Vec<String>> {
+        let world_file = Path::new(&self.image).with_extension("pgw").to_string_lossy().into_owned();
+        let json_file = format!("{}.json", self.image);
+        std::fs::write(&world_file, self.world_file())?;
+        std::fs::write(&json_file, serde_json::to_string_pretty(self).unwrap() + "\n")?;
+        Ok(vec![world_file, json_file])
+    }
+}
+
+/// 拡大率のプリセットの1ピクセルあたりのブロック数
+pub fn zoom_preset(name: &str) -> Option<i32> {
+    ZOOM_PRESETS.iter().find(|(n, _)| *n == name).map(|(_, bpp)| *bpp)
+}
+
+/// 地図の描き方
+#[derive(Debug, Clone, Default)]
+pub struct MapStyle {
+    pub palette: Palette,
+    /// 地表の推定の高さから起伏の陰影を付ける
+    pub hillshade: bool,
+}
+
+/// バイオームの色分け地図を描く
+pub fn render_biome_map(seed: i64, view: &MapView, palette: &Palette) -> Image {
+    let mut image = Image::new(view.width, view.height);
+    for py in 0..view.height {
+        for px in 0..view.width {
+            let (x, z) = view.block_at(px, py);
+            image.set(px, py, palette.color(get_biome_at(seed, BlockPos::new(x, z))));
+        }
+    }
+    image
+}
+
+/// 縮尺と中心の目印を描いた地図を描く
+///
+/// `density` を渡した場合は区画ごとの構造物の数で色分けする。
+pub fn render_map(seed: i64, view: &MapView, style: &MapStyle, density: &[DensityCell], center_x: i32, center_z: i32) -> Image {
+    let mut image = render_biome_map(seed, view, &style.palette);
+    if style.hillshade {
+        apply_hillshade(&mut image, seed, view);
+    }
+    draw_heatmap(&mut image, view, density);
+    draw_decorations(&mut image, view, center_x, center_z);
+    image
+}
+
+/// 中心の十字と縮尺を描く
+pub fn draw_decorations(image: &mut Image, view: &MapView, center_x: i32, center_z: i32) {
+    let (cx, cy) = view.pixel_of(center_x, center_z);
+    draw_crosshair(image, cx as i64, cy as i64);
+    draw_scale_bar(image, view.blocks_per_pixel);
+}
+
+/// 地表の推定の高さから北西の光の陰影と高さの明暗を付ける
+fn apply_hillshade(image: &mut Image, seed: i64, view: &MapView) {
+    let mut heights = Vec::with_capacity(view.width * view.height);
+    for py in 0..view.height {
+        for px in 0..view.width {
+            let (x, z) = view.block_at(px, py);
+            heights.push(estimate_terrain_y(seed, x, z) as f64);
+        }
+    }
+    // 推定の高さは64ブロックごとの段差になるため、ならしてから斜面を求める
+    let blur = (SMOOTHING_BLOCKS / view.blocks_per_pixel).max(1) as usize;
+    let heights = box_blur(&box_blur(&heights, view.width, view.height, blur, true), view.width, view.height, blur, false);
+    let height_at = |px: usize, py: usize| heights[py.min(view.height - 1) * view.width + px.min(view.width - 1)];
+    let distance = view.blocks_per_pixel as f64 * 2.0;
+
+    for py in 0..view.height {
+        for px in 0..view.width {
+            let dx = (height_at(px + 1, py) - height_at(px.saturating_sub(1), py)) / distance;
+            let dz = (height_at(px, py + 1) - height_at(px, py.saturating_sub(1))) / distance;
+            let factor = hillshade_factor(dx, dz) * elevation_factor(height_at(px, py));
+
+            let i = (py * image.width + px) * 3;
+            for channel in &mut image.pixels[i..i + 3] {
+                *channel = (*channel as f64 * factor).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+/// 格子の値を横（horizontal が true）か縦に半径 radius の平均でならす（端は端の値を延ばす）
+fn box_blur(values: &[f64], width: usize, height: usize, radius: usize, horizontal: bool) -> Vec<f64> {
+    let (lines, length) = if horizontal { (height, width) } else { (width, height) };
+    let index = |line: usize, i: usize| if horizontal { line * width + i } else { i * width + line };
+    let mut result = vec![0.0; values.len()];
+    for line in 0..lines {
+        for i in 0..length {
+            let from = i.saturating_sub(radius);
+            let to = (i + radius).min(length - 1);
+            let sum: f64 = (from..=to).map(|j| values[index(line, j)]).sum();
+            result[index(line, i)] = sum / (to - from + 1) as f64;
+        }
+    }
+    result
+}
+
+/// 斜面の明るさの倍率（平地が 1.0。dx, dz は東・南に向かう勾配）
+///
+/// 光は北西の高度45度から当たるものとし、北西向きの斜面が明るく南東向きの斜面が暗くなる。
+pub fn hillshade_factor(dx: f64, dz: f64) -> f64 {
+    let light = [-0.5, -0.5, std::f64::consts::FRAC_1_SQRT_2];
+    let length = (dx * dx + dz * dz + 1.0).sqrt();
+    let dot = (-dx * light[0] - dz * light[1] + light[2]) / length;
+    (dot / light[2]).clamp(0.4, 1.4)
+}
+
+/// 高さの明暗（海面付近を 1.0 とし、高い所ほど明るく、海底ほど暗い）
+fn elevation_factor(y: f64) -> f64 {
+    (1.0 + (y - SEA_LEVEL as f64) / 400.0).clamp(0.85, 1.2)
+}
+
+/// 構造物の位置にアイコンを描く（markers は構造物の識別子と座標、size はアイコンのピクセル数）
+pub fn draw_markers(image: &mut Image, view: &MapView, markers: &[(&str, i32, i32)], size: u32) {
+    for &(id, x, z) in markers {
+        let (px, py) = view.pixel_of(x, z);
+        structure_icon(id).draw(image, px as i64, py as i64, size);
+    }
+}
+
+/// 構造物の密度の区画
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct DensityCell {
+    /// 北西の角の座標
+    pub x: i32,
+    pub z: i32,
+    /// 1辺のブロック数
+    pub size: i32,
+    pub count: usize,
+}
+
+/// 地図に掛かる区画ごとの構造物の数（区画はワールドの座標で size ブロックごとに区切る）
+pub fn density_cells(view: &MapView, size: i32, points: &[(i32, i32)]) -> Vec<DensityCell> {
+    let max_x = view.min_x + view.width as i32 * view.blocks_per_pixel - 1;
+    let max_z = view.min_z + view.height as i32 * view.blocks_per_pixel - 1;
+    let mut cells = Vec::new();
+    for cell_z in view.min_z.div_euclid(size)..=max_z.div_euclid(size) {
+        for cell_x in view.min_x.div_euclid(size)..=max_x.div_euclid(size) {
+            let count = points
+                .iter()
+                .filter(|(x, z)| x.div_euclid(size) == cell_x && z.div_euclid(size) == cell_z)
+                .count();
+            cells.push(DensityCell {
+                x: cell_x * size,
+                z: cell_z * size,
+                size,
+                count,
+            });
+        }
+    }
+    cells
+}
+
+/// 区画の構造物の数で地図を色分けする（少ない所は黄、多い所は赤。0件の区画は暗くする）
+///
+/// `cells` は density_cells の結果（北の行から順の格子）。
+fn draw_heatmap(image: &mut Image, view: &MapView, cells: &[DensityCell]) {
+    let Some(first) = cells.first() else {
+        return;
+    };
+    let columns = cells.iter().filter(|c| c.z == first.z).count();
+    let max = cells.iter().map(|c| c.count).max().unwrap_or(0).max(1);
+    for py in 0..view.height {
+        for px in 0..view.width {
+            let (x, z) = view.block_at(px, py);
+            let column = (x - first.x).div_euclid(first.size) as usize;
+            let row = (z - first.z).div_euclid(first.size) as usize;
+            let Some(cell) = cells.get(row * columns + column.min(columns - 1)) else {
+                continue;
+            };
+
+            let i = (py * image.width + px) * 3;
+            let pixel = &mut image.pixels[i..i + 3];
+            // 下のバイオームは灰色にして色の区別を熱の色だけにする
+            let gray = (pixel[0] as f64 * 0.3 + pixel[1] as f64 * 0.59 + pixel[2] as f64 * 0.11) * 0.6;
+            let (color, alpha) = if cell.count == 0 {
+                ([0.0, 0.0, 0.0], 0.3)
+            } else {
+                (heat_color(cell.count as f64 / max as f64), 0.75)
+            };
+            for (channel, heat) in pixel.iter_mut().zip(color) {
+                *channel = (gray * (1.0 - alpha) + heat * alpha).round() as u8;
+            }
+        }
+    }
+}
+
+/// 0.0〜1.0 の密度の色（黄→橙→赤）
+pub fn heat_color(t: f64) -> [f64; 3] {
+    const STOPS: [[f64; 3]; 3] = [[255.0, 237.0, 160.0], [253.0, 141.0, 60.0], [189.0, 0.0, 38.0]];
+    let t = t.clamp(0.0, 1.0) * 2.0;
+    let (from, to) = if t < 1.0 { (STOPS[0], STOPS[1]) } else { (STOPS[1], STOPS[2]) };
+    let f = if t < 1.0 { t } else { t - 1.0 };
+    [0, 1, 2].map(|i| from[i] + (to[i] - from[i]) * f)
+}
+
+/// 道順の線と区間ごとの距離（ブロック数）を描く
+pub fn draw_route(image: &mut Image, view: &MapView, path: &[(i32, i32)]) {
+    let pixels: Vec<(f64, f64)> = path.iter().map(|&(x, z)| view.pixel_of(x, z)).collect();
+    for (color, width) in [(BLACK, 5), (ROUTE_COLOR, 3)] {
+        for pair in pixels.windows(2) {
+            draw_line(image, pair[0], pair[1], width, color);
+        }
+        for &(px, py) in &pixels {
+            let half = width + 1;
+            image.fill_rect(px as i64 - half, py as i64 - half, half * 2 + 1, half * 2 + 1, color);
+        }
+    }
+    for (pair, points) in pixels.windows(2).zip(path.windows(2)) {
+        let distance = distance_2d(points[0].0, points[0].1, points[1].0, points[1].1);
+        let label = format!("{:.0}", distance);
+        let label_width = label.len() as i64 * GLYPH_ADVANCE;
+        let mx = (pair[0].0 + pair[1].0) / 2.0;
+        let my = (pair[0].1 + pair[1].1) / 2.0;
+        image.draw_text(mx as i64 - label_width / 2, my as i64 - GLYPH_HEIGHT - 4, &label, WHITE);
+    }
+}
+
+/// 太さ width の線を引く
+fn draw_line(image: &mut Image, from: (f64, f64), to: (f64, f64), width: i64, color: [u8; 3]) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as usize;
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let x = (from.0 + (to.0 - from.0) * t) as i64;
+        let y = (from.1 + (to.1 - from.1) * t) as i64;
+        image.fill_rect(x - width / 2, y - width / 2, width, width, color);
+    }
+}
+
+/// 地図の中心の十字
+fn draw_crosshair(image: &mut Image, x: i64, y: i64) {
+    const ARM: i64 = 8;
+    image.fill_rect(x - ARM - 1, y - 2, ARM * 2 + 3, 5, BLACK);
+    image.fill_rect(x - 2, y - ARM - 1, 5, ARM * 2 + 3, BLACK);
+    image.fill_rect(x - ARM, y - 1, ARM * 2 + 1, 3, WHITE);
+    image.fill_rect(x - 1, y - ARM, 3, ARM * 2 + 1, WHITE);
+}
+
+/// 地図の幅の1/5以下で最も長いきりのよい長さ（1, 2, 5 × 10^n ブロック）
+pub fn scale_bar_length(width: usize, blocks_per_pixel: i32) -> i64 {
+    let target = (width as i64 * blocks_per_pixel as i64 / 5).max(1);
+    let mut length = 1;
+    let mut unit = 1;
+    while unit <= target {
+        for step in [1, 2, 5] {
+            if unit * step <= target {
+                length = unit * step;
+            }
+        }
+        unit *= 10;
+    }
+    length
+}
+
+/// 左下の縮尺（ブロック数）
+fn draw_scale_bar(image: &mut Image, blocks_per_pixel: i32) {
+    const MARGIN: i64 = 12;
+    if image.width < 80 || image.height < 40 {
+        return;
+    }
+    let length = scale_bar_length(image.width, blocks_per_pixel);
+    let pixels = length / blocks_per_pixel as i64;
+    let x = MARGIN;
+    let y = image.height as i64 - MARGIN;
+
+    image.fill_rect(x - 1, y - 1, pixels + 2, 6, BLACK);
+    image.fill_rect(x - 1, y - 7, 4, 8, BLACK);
+    image.fill_rect(x + pixels - 2, y - 7, 4, 8, BLACK);
+    image.fill_rect(x, y, pixels, 4, WHITE);
+    image.fill_rect(x, y - 6, 2, 6, WHITE);
+    image.fill_rect(x + pixels - 1, y - 6, 2, 6, WHITE);
+    image.draw_text(x + 6, y - GLYPH_HEIGHT - 4, &length.to_string(), WHITE);
+}
+
+/// RGBのPNG画像を書き出す
+pub fn write_png(path: &str, image: &Image) -> std::io::Result<()> {
+    std::fs::write(path, image.to_png()?)
+}
+
+/// グレースケールのPNG画像を書き出す（`pixels` は北の行から順に1ピクセル1バイト）
+pub fn write_gray_png(path: &str, width: usize, height: usize, pixels: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, encode_png(width, height, PngColor::Grayscale, pixels)?)
+}
+
+/// PNGの色の形式
+#[derive(Debug, Clone, Copy)]
+enum PngColor {
+    Rgb,
+    Grayscale,
+}
+
+#[cfg(feature = "map")]
+fn encode_png(width: usize, height: usize, color: PngColor, data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+    encoder.set_color(match color {
+        PngColor::Rgb => png::ColorType::Rgb,
+        PngColor::Grayscale => png::ColorType::Grayscale,
+    });
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(std::io::Error::other)?;
+    writer.write_image_data(data).map_err(std::io::Error::other)?;
+    writer.finish().map_err(std::io::Error::other)?;
+    Ok(bytes)
+}
+
+/// map 機能なしでビルドした場合はPNGに変換できない（地図の描画そのものは使える）
+#[cfg(not(feature = "map"))]
+fn encode_png(_width: usize, _height: usize, _color: PngColor, _data: &[u8]) -> std::io::Result<Vec<u8>> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "PNG出力には map 機能を有効にしたビルドが必要です（cargo build --features map）",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "map")]
+    fn test_write_gray_png() {
+        let path = std::env::temp_dir().join(format!("bedrockmate-render-{}.png", std::process::id()));
+        let path = path.to_str().unwrap();
+        write_gray_png(path, 2, 2, &[0, 64, 128, 255]).unwrap();
+
+        let bytes = std::fs::read(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(&bytes[1..4], b"PNG");
+    }
+
+    #[test]
+    fn test_map_view() {
+        let view = MapView::around(100, -100, 500, 4);
+        assert_eq!((view.width, view.height), (250, 250));
+        assert_eq!(view.block_at(0, 0), (-398, -598));
+        assert_eq!(view.pixel_of(100, -100), (125.0, 125.0));
+        assert_eq!(MapView::auto_scale(5000), 10);
+        assert_eq!(MapView::auto_scale(100), 1);
+
+        let image = render_biome_map(12345, &MapView::around(0, 0, 64, 8), &Palette::default());
+        let tile = image.crop(10, 12, 8, 8);
+        assert_eq!((tile.width, tile.height), (6, 4));
+        assert_eq!(tile.crop(1, 2, 1, 1), image.crop(11, 14, 1, 1));
+    }
+
+    #[test]
+    fn test_scale_bar() {
+        assert_eq!(scale_bar_length(1000, 1), 200);
+        assert_eq!(scale_bar_length(1000, 10), 2000);
+        assert_eq!(scale_bar_length(512, 16), 1000);
+        assert_eq!(scale_bar_length(300, 1), 50);
+        assert_eq!(zoom_preset("continent"), Some(64));
+        assert_eq!(zoom_preset("planet"), None);
+
+        let view = MapView::around(0, 0, 200, 1);
+        let image = render_map(12345, &view, &MapStyle::default(), &[], 0, 0);
+        assert_eq!(image.crop(200, 200, 1, 1).pixels, WHITE);
+        // 縮尺の棒（50ブロック）
+        assert_eq!(image.crop(40, 389, 1, 1).pixels, WHITE);
+    }
+
+    #[test]
+    fn test_hillshade_factor() {
+        assert!((hillshade_factor(0.0, 0.0) - 1.0).abs() < 1e-9);
+        // 南東に向かって高くなる斜面は北西を向いて明るい
+        assert!(hillshade_factor(0.5, 0.5) > 1.0);
+        assert!(hillshade_factor(-0.5, -0.5) < 1.0);
+        assert_eq!(hillshade_factor(-10.0, -10.0), 0.4);
+    }
+
+    #[test]
+    fn test_density_cells() {
+        let view = MapView::around(0, 0, 1000, 10);
+        let points = [(10, 10), (999, 0), (-1, -1), (1500, 1500)];
+        let cells = density_cells(&view, 1000, &points);
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[0], DensityCell { x: -1000, z: -1000, size: 1000, count: 1 });
+        assert_eq!(cells[3].count, 2);
+
+        let mut image = render_biome_map(12345, &view, &Palette::default());
+        draw_heatmap(&mut image, &view, &cells);
+        let hot = image.crop(150, 150, 1, 1).pixels;
+        assert!(hot[0] > hot[2]);
+    }
+
+    #[test]
+    fn test_draw_route() {
+        let view = MapView::around(0, 0, 100, 1);
+        let mut image = Image::new(view.width, view.height);
+        draw_route(&mut image, &view, &[(-50, 0), (50, 0)]);
+        assert_eq!(image.crop(150, 100, 1, 1).pixels, ROUTE_COLOR);
+        assert_eq!(image.crop(100, 150, 1, 1).pixels, BLACK);
+    }
+
+    #[test]
+    fn test_georeference() {
+        let georef = MapView::around(100, -100, 500, 4).georeference("map.png");
+        assert_eq!((georef.min_x, georef.min_z, georef.max_x, georef.max_z), (-400, -600, 600, 400));
+        assert_eq!(georef.world_file(), "4.0\n0.0\n0.0\n4.0\n-398.0\n-598.0\n");
+    }
+}
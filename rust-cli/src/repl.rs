@@ -0,0 +1,238 @@
+//! 対話モジュール
+//!
+//! シード・バージョン・検索中心をセッションに保持し、`village 3000` や `biome jungle`
+//! のような短いコマンドを通常のコマンドライン引数に変換して実行する
+
+use std::io::{self, BufRead, Write};
+
+use crate::algorithms::spawn::estimate_spawn;
+use crate::custom::find_custom_structure;
+use crate::structures::{StructureType, STRONGHOLD_ID};
+use crate::version::McVersion;
+
+const HELP: &str = "\
+コマンド:
+  seed <シード>          シード値を設定
+  version <バージョン>   対象バージョンを設定（例: 1.21.0）
+  center <X> <Z>         検索中心を設定（center spawn で推定スポーン地点）
+  show                   現在の設定を表示
+  <構造物> [半径]        構造物を検索（village, monument, stronghold, all 等）
+  nether [半径]          ネザー構造物を検索
+  biome <バイオーム> [半径]  バイオームを検索
+  slime [半径]           スライムチャンクを検索
+  spawn                  スポーン地点を推定
+  その他                 通常のサブコマンドとして実行（例: convert -x 100 -z 200）
+  help / exit";
+
+/// 入力1行の処理結果
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    /// コマンドライン引数（プログラム名を除く）として実行
+    Run(Vec<String>),
+    /// メッセージを表示
+    Message(String),
+    Nothing,
+    Quit,
+}
+
+/// 対話セッションの状態
+#[derive(Debug, Default)]
+pub struct Session {
+    pub seed: Option<i64>,
+    pub version: Option<McVersion>,
+    pub center_x: i32,
+    pub center_z: i32,
+}
+
+impl Session {
+    /// 入力1行を解釈してセッションを更新し、実行内容を返す
+    pub fn handle(&mut self, line: &str) -> Result<Action, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, rest)) = words.split_first() else {
+            return Ok(Action::Nothing);
+        };
+
+        match command {
+            "help" => Ok(Action::Message(HELP.to_string())),
+            "exit" | "quit" => Ok(Action::Quit),
+            "show" => Ok(Action::Message(self.describe())),
+            "seed" => {
+                let seed = rest.first().and_then(|s| s.parse().ok()).ok_or("使い方: seed <シード>")?;
+                self.seed = Some(seed);
+                Ok(Action::Message(self.describe()))
+            }
+            "version" => {
+                let version = rest
+                    .first()
+                    .and_then(|v| McVersion::parse(v))
+                    .ok_or("使い方: version <バージョン>（例: 1.21.0）")?;
+                self.version = Some(version);
+                Ok(Action::Message(self.describe()))
+            }
+            "center" => {
+                if rest.first() == Some(&"spawn") {
+                    let seed = self.seed.ok_or("先に seed でシード値を設定してください")?;
+                    let spawn = estimate_spawn(seed);
+                    (self.center_x, self.center_z) = (spawn.x, spawn.z);
+                } else {
+                    let [x, z] = rest else {
+                        return Err("使い方: center <X> <Z> または center spawn".to_string());
+                    };
+                    let (Ok(x), Ok(z)) = (x.parse(), z.parse()) else {
+                        return Err("座標は整数で指定してください".to_string());
+                    };
+                    (self.center_x, self.center_z) = (x, z);
+                }
+                Ok(Action::Message(self.describe()))
+            }
+            "nether" => self.search("nether", radius_arg(rest.first())?, vec![]),
+            "slime" => {
+                let mut args = vec!["slime".to_string()];
+                args.extend(self.center_args());
+                args.extend(radius_arg(rest.first())?);
+                Ok(Action::Run(args))
+            }
+            "biome" => {
+                let target = rest.first().ok_or("使い方: biome <バイオーム> [半径]")?;
+                self.search("biome", radius_arg(rest.get(1))?, vec!["--target".to_string(), target.to_string()])
+            }
+            "spawn" => self.search("spawn", vec![], vec![]),
+            id if id == "all"
+                || id == STRONGHOLD_ID
+                || id.parse::<StructureType>().is_ok()
+                || find_custom_structure(id).is_some() =>
+            {
+                self.search(
+                    "structures",
+                    radius_arg(rest.first())?,
+                    vec!["--structure-type".to_string(), id.to_string()],
+                )
+            }
+            _ => {
+                let mut args: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+                args.extend(self.version_args());
+                Ok(Action::Run(args))
+            }
+        }
+    }
+
+    /// セッションのシード・中心・バージョンを付けた検索コマンド
+    fn search(&self, command: &str, radius: Vec<String>, extra: Vec<String>) -> Result<Action, String> {
+        let mut args = vec![command.to_string()];
+        if let Some(seed) = self.seed {
+            args.push(format!("--seed={}", seed));
+        }
+        if command != "spawn" {
+            args.extend(self.center_args());
+        }
+        args.extend(radius);
+        args.extend(extra);
+        args.extend(self.version_args());
+        Ok(Action::Run(args))
+    }
+
+    fn center_args(&self) -> Vec<String> {
+        // 負の座標も渡せるように "=" で連結する
+        vec![format!("--center-x={}", self.center_x), format!("--center-z={}", self.center_z)]
+    }
+
+    fn version_args(&self) -> Vec<String> {
+        self.version.iter().map(|v| format!("--mc-version={}", v)).collect()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "シード: {}  バージョン: {}  検索中心: X={}, Z={}",
+            self.seed.map(|s| s.to_string()).unwrap_or_else(|| "未設定".to_string()),
+            self.version.map(|v| v.to_string()).unwrap_or_else(|| "既定".to_string()),
+            self.center_x,
+            self.center_z
+        )
+    }
+}
+
+fn radius_arg(word: Option<&&str>) -> Result<Vec<String>, String> {
+    match word {
+        Some(r) => match r.parse::<i32>() {
+            Ok(r) => Ok(vec![format!("--radius={}", r)]),
+            Err(_) => Err(format!("半径は整数で指定してください: {}", r)),
+        },
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 標準入力から1行ずつ読み込んで実行する（exit または入力終端で終了）
+pub fn run_repl(mut session: Session, mut execute: impl FnMut(Vec<String>)) {
+    println!("🧭 BedrockMate 対話モード（help でコマンド一覧、exit で終了）");
+    println!("   {}", session.describe());
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("bedrockmate> ");
+        let _ = io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+
+        match session.handle(&line) {
+            Ok(Action::Run(args)) => execute(args),
+            Ok(Action::Message(message)) => println!("{}", message),
+            Ok(Action::Nothing) => {}
+            Ok(Action::Quit) => break,
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(action: Action) -> Vec<String> {
+        match action {
+            Action::Run(args) => args,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_session_commands() {
+        let mut session = Session::default();
+        session.handle("seed 12345").unwrap();
+        session.handle("center -500 200").unwrap();
+        session.handle("version 1.18").unwrap();
+
+        let args = run_args(session.handle("village 3000").unwrap());
+        assert_eq!(
+            args,
+            [
+                "structures",
+                "--seed=12345",
+                "--center-x=-500",
+                "--center-z=200",
+                "--radius=3000",
+                "--structure-type",
+                "village",
+                "--mc-version=1.18.0"
+            ]
+        );
+
+        let args = run_args(session.handle("biome jungle").unwrap());
+        assert!(args.contains(&"--target".to_string()) && args.contains(&"jungle".to_string()));
+
+        let args = run_args(session.handle("convert -x 10 -z 20").unwrap());
+        assert_eq!(args[..5], ["convert", "-x", "10", "-z", "20"]);
+    }
+
+    #[test]
+    fn test_session_errors() {
+        let mut session = Session::default();
+        assert!(session.handle("seed abc").is_err());
+        assert!(session.handle("village far").is_err());
+        assert!(session.handle("center spawn").is_err());
+        assert_eq!(session.handle("   ").unwrap(), Action::Nothing);
+        assert_eq!(session.handle("exit").unwrap(), Action::Quit);
+    }
+}
@@ -0,0 +1,65 @@
+//! 乱数モジュール
+//!
+//! 構造物や地形の配置の計算に使う決定的な乱数の基本処理。
+//! どちらの関数も同じ引数に対して常に同じ値を返し、プラットフォームや実行ごとに結果が変わらない
+//! （整数の演算はすべてラップアラウンドで、オーバーフローしても panic しない）。
+//! 他の言語で互換の実装を作る場合は、64ビット符号付き整数の2の補数のラップアラウンドと
+//! 算術右シフトをそのまま再現すればよい。`internals` 機能を有効にするとライブラリとして公開される。
+
+/// 構造物シードを計算（ワールドのシード、リージョンの座標、構造物のソルトから決まる）
+///
+/// - 同じ引数からは常に同じ値を返す
+/// - `salt` の差はそのまま結果の差になる（`salt` が1違えば結果も1違う）
+/// - リージョンの座標の係数はどちらも奇数のため、片方の座標だけが違う2つのリージョンは同じ値にならない
+pub fn get_structure_seed(world_seed: i64, region_x: i32, region_z: i32, salt: i64) -> i64 {
+    let a = region_x as i64;
+    let b = region_z as i64;
+
+    // Bedrock Edition algorithm (LCG based)
+    world_seed
+        .wrapping_add(a.wrapping_mul(341873128712))
+        .wrapping_add(b.wrapping_mul(132897987541))
+        .wrapping_add(salt)
+}
+
+/// 擬似乱数ジェネレータ（簡易版。64ビットの線形合同法で `seed` を進めて 0..bound の値を返す）
+///
+/// - `bound` は正の値であること（0 以下の場合は panic するか負の値を返す）
+/// - 返り値は常に `0 <= 値 < bound`
+/// - 返り値は進めた後の `seed` だけで決まる（同じ `seed` から始めれば同じ列になる）
+pub fn next_int(seed: &mut i64, bound: i32) -> i32 {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    let bits = (*seed >> 17) as i32;
+    ((bits as i64).abs() % bound as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_int_range() {
+        for start in [0, 1, -1, 12345, i64::MAX, i64::MIN] {
+            let mut seed = start;
+            for bound in [1, 2, 16, 100, 1000, i32::MAX] {
+                let value = next_int(&mut seed, bound);
+                assert!((0..bound).contains(&value), "seed={} bound={} value={}", start, bound, value);
+            }
+        }
+
+        let (mut a, mut b) = (42, 42);
+        let first: Vec<i32> = (0..8).map(|_| next_int(&mut a, 100)).collect();
+        let second: Vec<i32> = (0..8).map(|_| next_int(&mut b, 100)).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_structure_seed() {
+        assert_eq!(get_structure_seed(12345, 0, 0, 10387312), 12345 + 10387312);
+        assert_eq!(get_structure_seed(12345, 3, -2, 1) - get_structure_seed(12345, 3, -2, 0), 1);
+        assert_ne!(get_structure_seed(12345, 1, 0, 0), get_structure_seed(12345, 0, 0, 0));
+        assert_ne!(get_structure_seed(12345, 0, 1, 0), get_structure_seed(12345, 0, 0, 0));
+        // 極端な座標とシードでも panic しない
+        get_structure_seed(i64::MAX, i32::MAX, i32::MIN, i64::MAX);
+    }
+}
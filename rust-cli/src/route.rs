@@ -0,0 +1,199 @@
+//! ルート計画モジュール
+//!
+//! 複数の地点を巡る短いルートを最近傍法＋2-optで求める
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::coords::{overworld_to_nether, NETHER_SCALE};
+use crate::travel::distance_2d;
+
+/// 経由地点
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Waypoint {
+    #[serde(alias = "structure_type")]
+    pub name: String,
+    pub x: i32,
+    pub z: i32,
+}
+
+impl Waypoint {
+    /// "x,z" または "x,z,名前" 形式の文字列から地点を作成
+    pub fn parse(s: &str) -> Option<Waypoint> {
+        let mut parts = s.splitn(3, ',');
+        let x = parts.next()?.trim().parse().ok()?;
+        let z = parts.next()?.trim().parse().ok()?;
+        let name = parts
+            .next()
+            .map(|n| n.trim().to_string())
+            .unwrap_or_else(|| format!("X={}, Z={}", x, z));
+        Some(Waypoint { name, x, z })
+    }
+}
+
+/// ルートの1区間
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RouteLeg {
+    pub name: String,
+    pub x: i32,
+    pub z: i32,
+    pub nether_x: i32,
+    pub nether_z: i32,
+    /// 直前の地点からの距離
+    pub distance: f64,
+    /// 出発地からの累積距離
+    pub cumulative: f64,
+}
+
+/// 計画したルート
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Route {
+    pub start_x: i32,
+    pub start_z: i32,
+    pub legs: Vec<RouteLeg>,
+    pub total_distance: f64,
+    /// ネザー経由で移動した場合の総距離
+    pub nether_total_distance: f64,
+}
+
+/// 2地点間の距離
+fn leg_length(a: (i32, i32), b: (i32, i32)) -> f64 {
+    distance_2d(a.0, a.1, b.0, b.1)
+}
+
+/// 出発地から全地点を巡るルートを計画
+///
+/// 最近傍法で初期ルートを作り、2-optで交差を解消して短くする。
+/// 出発地には戻らない片道ルートとして扱う。
+pub fn plan_route(start_x: i32, start_z: i32, points: Vec<Waypoint>) -> Route {
+    // 最近傍法
+    let mut remaining = points;
+    let mut order: Vec<Waypoint> = Vec::new();
+    let mut current = (start_x, start_z);
+    while !remaining.is_empty() {
+        let (index, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, distance_2d(current.0, current.1, p.x, p.z)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .unwrap();
+        let next = remaining.remove(index);
+        current = (next.x, next.z);
+        order.push(next);
+    }
+
+    // 2-opt（区間を反転して短くなる限り繰り返す）
+    // 区間 i..=j を反転しても区間の内側の距離は変わらないため、両端の辺の差だけで判定する
+    let start = (start_x, start_z);
+    let pos = |p: &Waypoint| (p.x, p.z);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..order.len() {
+            for j in (i + 1)..order.len() {
+                let prev = if i == 0 { start } else { pos(&order[i - 1]) };
+                let (first, last) = (pos(&order[i]), pos(&order[j]));
+                // 片道ルートのため、区間が終点まで続く場合は後ろの辺がない
+                let (before, after) = match order.get(j + 1).map(pos) {
+                    Some(next) => (
+                        leg_length(prev, first) + leg_length(last, next),
+                        leg_length(prev, last) + leg_length(first, next),
+                    ),
+                    None => (leg_length(prev, first), leg_length(prev, last)),
+                };
+                if after + 1e-9 < before {
+                    order[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    let mut legs = Vec::new();
+    let mut cumulative = 0.0;
+    let mut prev = start;
+    for p in order {
+        let distance = distance_2d(prev.0, prev.1, p.x, p.z);
+        cumulative += distance;
+        prev = (p.x, p.z);
+        legs.push(RouteLeg {
+            nether_x: overworld_to_nether(p.x),
+            nether_z: overworld_to_nether(p.z),
+            name: p.name,
+            x: p.x,
+            z: p.z,
+            distance,
+            cumulative,
+        });
+    }
+
+    Route {
+        start_x,
+        start_z,
+        legs,
+        total_distance: cumulative,
+        nether_total_distance: cumulative / NETHER_SCALE as f64,
+    }
+}
+
+/// 検索結果のJSON（structures コマンドの出力）または地点の配列から経由地点を読み込む
+pub fn waypoints_from_json(json: &str) -> Option<Vec<Waypoint>> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let list = match value.get("structures") {
+        Some(list) => list.clone(),
+        None => value,
+    };
+    serde_json::from_value(list).ok()
+}
+
+/// 地図に描く道順を読み込む
+///
+/// route コマンドのJSON出力は出発地から訪問順に、地点の配列や検索結果のJSONは並んでいる順にたどる。
+pub fn route_path_from_json(json: &str) -> Option<Vec<(i32, i32)>> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    if value.get("legs").is_some() {
+        let start = (value["start_x"].as_i64()? as i32, value["start_z"].as_i64()? as i32);
+        let legs: Vec<Waypoint> = serde_json::from_value(value["legs"].clone()).ok()?;
+        return Some(std::iter::once(start).chain(legs.iter().map(|w| (w.x, w.z))).collect());
+    }
+    let points = waypoints_from_json(json)?;
+    Some(points.iter().map(|w| (w.x, w.z)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_route_visits_all() {
+        let points = vec![
+            Waypoint::parse("1000,0,A").unwrap(),
+            Waypoint::parse("100,0").unwrap(),
+            Waypoint::parse("500,0").unwrap(),
+        ];
+        let route = plan_route(0, 0, points);
+        let xs: Vec<i32> = route.legs.iter().map(|l| l.x).collect();
+        assert_eq!(xs, vec![100, 500, 1000]);
+        assert_eq!(route.total_distance, 1000.0);
+        assert_eq!(route.legs[2].name, "A");
+    }
+
+    #[test]
+    fn test_waypoints_from_search_result() {
+        let json = r#"{"seed": 1, "structures": [{"structure_type": "🏘️ 村", "x": 10, "z": -20, "distance": 22.3}]}"#;
+        let points = waypoints_from_json(json).unwrap();
+        assert_eq!(points[0].name, "🏘️ 村");
+        assert_eq!((points[0].x, points[0].z), (10, -20));
+    }
+
+    #[test]
+    fn test_route_path_from_json() {
+        let route = plan_route(10, 20, vec![Waypoint::parse("500,0").unwrap(), Waypoint::parse("100,0").unwrap()]);
+        let json = serde_json::to_string(&route).unwrap();
+        assert_eq!(route_path_from_json(&json).unwrap(), vec![(10, 20), (100, 0), (500, 0)]);
+
+        let json = r#"[{"name": "A", "x": 1, "z": 2}, {"name": "B", "x": -3, "z": 4}]"#;
+        assert_eq!(route_path_from_json(json).unwrap(), vec![(1, 2), (-3, 4)]);
+        assert!(route_path_from_json("{}").is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! JSON Schemaモジュール
+//!
+//! 各コマンドのJSON出力（`--output json`）のスキーマを出力文書の型から生成する。
+//! 連携するツールで出力を検証したり、型を生成したりするのに使う。
+
+use schemars::{schema_for, Schema};
+use serde_json::{Map, Value};
+
+use crate::batch::BatchResult;
+use crate::bookmarks::Bookmark;
+use crate::coords::Conversion;
+use crate::documents::{
+    BiomeResult, ColumnResult, DeepDarkResult, DiffMapResult, DoctorResult, DungeonResult, BuildSiteResult, CorridorResult, FarmResult, LocationScoreResult, NetherClearResult, WoodResult, HeightmapResult, HubResult, ListResult, MapResult, OreResult, PortalResult, SearchResult, SlimeResult, SpawnResult, TriangulateResult, UndergroundResult, VeinResult, VersionDiffResult,
+};
+use crate::history::HistoryEntry;
+use crate::info::GenerationInfo;
+use crate::meta::Meta;
+use crate::route::Route;
+use crate::quickcheck::QuickCheckResult;
+use crate::speedrun::SpeedrunPlan;
+use crate::travel::TravelReport;
+
+/// コマンドごとの出力文書のスキーマ
+///
+/// structures / nether で --seeds を指定した場合は、`structures` の文書の配列になる。
+/// batch は1行ごとの文書のスキーマ。オブジェクトの文書には `meta` が付く。
+pub fn output_schemas() -> Vec<(&'static str, Schema)> {
+    let schemas = vec![
+        ("structures", schema_for!(SearchResult)),
+        ("nether", schema_for!(SearchResult)),
+        ("biome", schema_for!(BiomeResult)),
+        ("column", schema_for!(ColumnResult)),
+        ("convert", schema_for!(Conversion)),
+        ("distance", schema_for!(TravelReport)),
+        ("hub", schema_for!(HubResult)),
+        ("build-site", schema_for!(BuildSiteResult)),
+        ("farm", schema_for!(FarmResult)),
+        ("wood", schema_for!(WoodResult)),
+        ("corridor", schema_for!(CorridorResult)),
+        ("score-location", schema_for!(LocationScoreResult)),
+        ("nether-clear", schema_for!(NetherClearResult)),
+        ("map", schema_for!(MapResult)),
+        ("diffmap", schema_for!(DiffMapResult)),
+        ("version-diff", schema_for!(VersionDiffResult)),
+        ("heightmap", schema_for!(HeightmapResult)),
+        ("deepdark", schema_for!(DeepDarkResult)),
+        ("dungeon", schema_for!(DungeonResult)),
+        ("ore", schema_for!(OreResult)),
+        ("veins", schema_for!(VeinResult)),
+        ("underground", schema_for!(UndergroundResult)),
+        ("slime", schema_for!(SlimeResult)),
+        ("spawn", schema_for!(SpawnResult)),
+        ("triangulate", schema_for!(TriangulateResult)),
+        ("list", schema_for!(ListResult)),
+        ("info", schema_for!(GenerationInfo)),
+        ("route", schema_for!(Route)),
+        ("speedrun-plan", schema_for!(SpeedrunPlan)),
+        ("quickcheck", schema_for!(QuickCheckResult)),
+        ("portal", schema_for!(PortalResult)),
+        ("doctor", schema_for!(DoctorResult)),
+        ("bookmark", schema_for!(Vec<Bookmark>)),
+        ("history", schema_for!(Vec<HistoryEntry>)),
+        ("batch", schema_for!(BatchResult)),
+    ];
+    schemas
+        .into_iter()
+        .map(|(name, schema)| match name {
+            // batch の各行は取り込んだ結果（result）の側に meta が付く
+            "batch" => (name, schema),
+            _ => (name, with_meta(schema)),
+        })
+        .collect()
+}
+
+/// オブジェクトの文書のスキーマに meta を加える
+fn with_meta(mut schema: Schema) -> Schema {
+    if schema.get("properties").is_none() {
+        return schema;
+    }
+    let mut meta = schema_for!(Meta);
+    meta.remove("$schema");
+    // meta が参照する型の定義は文書側の $defs に移す
+    if let Some(Value::Object(defs)) = meta.remove("$defs") {
+        let root_defs = schema.ensure_object().entry("$defs").or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(root_defs) = root_defs {
+            root_defs.extend(defs);
+        }
+    }
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.insert("meta".to_string(), meta.to_value());
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_schemas() {
+        let schemas = output_schemas();
+        for (name, schema) in &schemas {
+            let value = schema.as_value();
+            assert!(value.get("properties").is_some() || value.get("items").is_some(), "{}", name);
+        }
+
+        let search = schema_for!(SearchResult);
+        let required = search.get("required").unwrap().as_array().unwrap();
+        assert!(required.contains(&serde_json::json!("structures")));
+        assert!(schemas[0].1.get("properties").unwrap().get("meta").is_some());
+        let biome = schema_for!(BiomeResult);
+        assert!(!biome.get("required").unwrap().as_array().unwrap().contains(&serde_json::json!("x")));
+    }
+}
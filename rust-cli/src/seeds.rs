@@ -0,0 +1,42 @@
+//! 複数シードモジュール
+//!
+//! 候補のワールドを比較するため、`--seeds` や `--seeds-file` で指定された複数のシード値を読み込む
+
+/// シード一覧のテキストを解釈（1行に1つ、カンマ区切りも可。`#` 以降はコメント）
+pub fn parse_seeds(text: &str) -> Result<Vec<i64>, String> {
+    let mut seeds = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        for value in line.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            let seed = value
+                .parse()
+                .map_err(|_| format!("{}行目: シード値は整数で指定してください: {}", i + 1, value))?;
+            seeds.push(seed);
+        }
+    }
+    Ok(seeds)
+}
+
+/// シード一覧ファイルを読み込む
+pub fn read_seeds_file(path: &str) -> Result<Vec<i64>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("ファイルを読み込めません: {} ({})", path, e))?;
+    let seeds = parse_seeds(&text).map_err(|e| format!("{}: {}", path, e))?;
+    if seeds.is_empty() {
+        return Err(format!("{} にシード値がありません", path));
+    }
+    Ok(seeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seeds() {
+        let seeds = parse_seeds("# 候補\n12345\n-987, 42  # 海が多い\n\n").unwrap();
+        assert_eq!(seeds, [12345, -987, 42]);
+
+        let err = parse_seeds("1\nabc\n").unwrap_err();
+        assert!(err.starts_with("2行目"));
+    }
+}
@@ -0,0 +1,511 @@
+//! 拠点の候補地の評価モジュール
+//!
+//! 拠点を作る場所の周りで手に入る資源や、陸続きで進める方角を、バイオームのサンプリングで調べる。
+//! 複数の基準の重み付きの点数で場所そのものを採点することもできる。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use std::collections::HashSet;
+
+use crate::algorithms::biome::{get_biome_at, BiomeType};
+use crate::algorithms::height::{estimate_terrain_y, SEA_LEVEL};
+use crate::algorithms::underground::compass_direction;
+use crate::areas::DIRECTIONS;
+use crate::budget;
+use crate::coords::{check_search_area, overworld_to_nether, BlockPos};
+use crate::error::SearchError;
+use crate::i18n::is_english;
+use crate::structures::{find_nearest_any, StructureType};
+use crate::travel::distance_2d;
+
+/// バイオームを調べる間隔（ブロック）
+const SAMPLE_STEP: i32 = 32;
+
+/// 陸続きの道を調べる間隔（ブロック）
+const CORRIDOR_STEP: i32 = 16;
+
+/// 木材の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WoodType {
+    Oak,
+    Spruce,
+    Birch,
+    Jungle,
+    Acacia,
+    DarkOak,
+    Mangrove,
+    Cherry,
+}
+
+impl WoodType {
+    pub const ALL: [WoodType; 8] = [
+        WoodType::Oak,
+        WoodType::Spruce,
+        WoodType::Birch,
+        WoodType::Jungle,
+        WoodType::Acacia,
+        WoodType::DarkOak,
+        WoodType::Mangrove,
+        WoodType::Cherry,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            WoodType::Oak => "oak",
+            WoodType::Spruce => "spruce",
+            WoodType::Birch => "birch",
+            WoodType::Jungle => "jungle",
+            WoodType::Acacia => "acacia",
+            WoodType::DarkOak => "dark_oak",
+            WoodType::Mangrove => "mangrove",
+            WoodType::Cherry => "cherry",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                WoodType::Oak => "Oak",
+                WoodType::Spruce => "Spruce",
+                WoodType::Birch => "Birch",
+                WoodType::Jungle => "Jungle",
+                WoodType::Acacia => "Acacia",
+                WoodType::DarkOak => "Dark Oak",
+                WoodType::Mangrove => "Mangrove",
+                WoodType::Cherry => "Cherry",
+            };
+        }
+        match self {
+            WoodType::Oak => "オーク",
+            WoodType::Spruce => "トウヒ",
+            WoodType::Birch => "シラカバ",
+            WoodType::Jungle => "ジャングル",
+            WoodType::Acacia => "アカシア",
+            WoodType::DarkOak => "ダークオーク",
+            WoodType::Mangrove => "マングローブ",
+            WoodType::Cherry => "サクラ",
+        }
+    }
+
+    /// 木が生えるバイオーム（暗い森は区別していないため、ダークオークは森林で近似）
+    pub fn biomes(&self) -> &'static [BiomeType] {
+        match self {
+            WoodType::Oak => &[
+                BiomeType::Plains,
+                BiomeType::Forest,
+                BiomeType::Jungle,
+                BiomeType::Swamp,
+                BiomeType::Savanna,
+                BiomeType::Mountain,
+            ],
+            WoodType::Spruce => &[BiomeType::Taiga, BiomeType::SnowyTaiga, BiomeType::Mountain],
+            WoodType::Birch | WoodType::DarkOak => &[BiomeType::Forest],
+            WoodType::Jungle => &[BiomeType::Jungle],
+            WoodType::Acacia => &[BiomeType::Savanna],
+            WoodType::Mangrove => &[BiomeType::MangroveSwamp],
+            WoodType::Cherry => &[BiomeType::CherryGrove],
+        }
+    }
+}
+
+/// 木材の手に入る場所
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WoodAvailability {
+    pub wood: String,
+    pub name: String,
+    /// 拠点から radius ブロック以内で手に入るか
+    pub available: bool,
+    /// 最も近い生えるバイオームの座標（search_radius ブロック以内に見つからなければ null）
+    pub x: Option<i32>,
+    pub z: Option<i32>,
+    pub distance: Option<f64>,
+    pub biome: Option<String>,
+}
+
+/// 拠点の周りで手に入る木材を調べる
+///
+/// 中心から外側へ正方形の輪の順にバイオームを調べ、木材ごとに最も近いサンプルを記録する。
+/// radius ブロック以内にない木材は search_radius ブロックまで探して距離を返す。
+pub fn wood_availability(
+    seed: i64,
+    x: i32,
+    z: i32,
+    radius: i32,
+    search_radius: i32,
+) -> Result<Vec<WoodAvailability>, SearchError> {
+    check_search_area(x, z, search_radius.max(radius))?;
+    let mut nearest: Vec<Option<(i32, i32, f64, BiomeType)>> = vec![None; WoodType::ALL.len()];
+    let rings = search_radius.max(radius) / SAMPLE_STEP;
+    for ring in 0..=rings {
+        // 輪の上の点は ring * SAMPLE_STEP ブロック以上離れているため、すべて見つかればそれより近い点はない
+        let ring_distance = (ring * SAMPLE_STEP) as f64;
+        if nearest.iter().all(|n| n.is_some_and(|(_, _, d, _)| d <= ring_distance)) || budget::expired() {
+            break;
+        }
+        let points: Vec<(i32, i32)> = if ring == 0 {
+            vec![(0, 0)]
+        } else {
+            (-ring..ring).flat_map(|i| [(i, -ring), (ring, i), (-i, ring), (-ring, -i)]).collect()
+        };
+        for (i, j) in points {
+            let (px, pz) = (x + i * SAMPLE_STEP, z + j * SAMPLE_STEP);
+            let distance = distance_2d(x, z, px, pz);
+            if distance > search_radius.max(radius) as f64 {
+                continue;
+            }
+            let biome = get_biome_at(seed, BlockPos::new(px, pz));
+            for (k, wood) in WoodType::ALL.iter().enumerate() {
+                if wood.biomes().contains(&biome) && nearest[k].is_none_or(|(_, _, d, _)| distance < d) {
+                    nearest[k] = Some((px, pz, distance, biome));
+                }
+            }
+        }
+    }
+
+    Ok(WoodType::ALL
+        .iter()
+        .zip(nearest)
+        .map(|(wood, found)| WoodAvailability {
+            wood: wood.id().to_string(),
+            name: wood.display_name().to_string(),
+            available: found.is_some_and(|(_, _, d, _)| d <= radius as f64),
+            x: found.map(|(x, _, _, _)| x),
+            z: found.map(|(_, z, _, _)| z),
+            distance: found.map(|(_, _, d, _)| d),
+            biome: found.map(|(_, _, _, b)| b.id().to_string()),
+        })
+        .collect())
+}
+
+/// 1つの方角の陸続きの道
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LandCorridor {
+    /// 方角（n, ne, e, se, s, sw, w, nw）
+    pub direction: String,
+    pub name: String,
+    /// 海に出るまでの距離（ブロック）
+    pub length: f64,
+    /// 調べた距離の端まで海に出なかったか
+    pub open: bool,
+    /// 道の端（海の手前の最後の陸、または調べた距離の端）
+    pub end_x: i32,
+    pub end_z: i32,
+    /// 途中で渡る川の数（線路では橋が要る）
+    pub rivers: u32,
+}
+
+/// 8方位それぞれについて、海に出ずに進める距離を max_distance ブロックまで調べ、長い順に並べる
+///
+/// 川は渡れるものとして数える。出発地点が海の場合はすべての方角が0ブロック。
+pub fn land_corridors(seed: i64, x: i32, z: i32, max_distance: i32) -> Result<Vec<LandCorridor>, SearchError> {
+    check_search_area(x, z, max_distance)?;
+    let mut corridors: Vec<LandCorridor> = DIRECTIONS
+        .iter()
+        .map(|&(id, yaw)| {
+            // Minecraftの yaw は南（+Z）が0°で西（-X）に向かって増える
+            let (dx, dz) = (-yaw.to_radians().sin(), yaw.to_radians().cos());
+            let at = |distance: i32| {
+                (x + (dx * distance as f64).round() as i32, z + (dz * distance as f64).round() as i32)
+            };
+            let mut length = 0;
+            let mut rivers = 0;
+            let mut in_river = false;
+            let mut open = true;
+            for distance in (0..=max_distance).step_by(CORRIDOR_STEP as usize) {
+                let (px, pz) = at(distance);
+                let biome = get_biome_at(seed, BlockPos::new(px, pz));
+                if biome.is_ocean() || budget::expired() {
+                    open = false;
+                    break;
+                }
+                if biome == BiomeType::River && !in_river {
+                    rivers += 1;
+                }
+                in_river = biome == BiomeType::River;
+                length = distance;
+            }
+            let (end_x, end_z) = at(length);
+            LandCorridor {
+                direction: id.to_string(),
+                name: compass_direction(yaw).to_string(),
+                length: length as f64,
+                open,
+                end_x,
+                end_z,
+                rivers,
+            }
+        })
+        .collect();
+    corridors.sort_by(|a, b| b.length.total_cmp(&a.length));
+    Ok(corridors)
+}
+
+/// 場所の周りを調べる半径（ブロック）
+const SCORE_AREA_RADIUS: i32 = 512;
+
+/// この数以上のバイオームがあれば多様さは満点
+const DIVERSITY_FULL: usize = 6;
+
+/// この高さ以上の陸を山とみなす
+const MOUNTAIN_Y: i32 = SEA_LEVEL + 30;
+
+/// 村がこれより遠ければ0点（オーバーワールドのブロック数）
+const VILLAGE_DISTANCE_ZERO: i32 = 1000;
+
+/// ネザー要塞がこれより遠ければ0点（ネザーのブロック数）
+const FORTRESS_DISTANCE_ZERO: i32 = 500;
+
+/// 場所の採点の基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    /// 周りのバイオームの種類の多さ
+    BiomeDiversity,
+    /// 川までの近さ
+    RiverAccess,
+    /// 山と平地が両方ある
+    TerrainMix,
+    /// 村までの近さ
+    Village,
+    /// ネザー要塞までの近さ（ネザー側の距離）
+    Fortress,
+}
+
+impl Criterion {
+    pub const ALL: [Criterion; 5] = [
+        Criterion::BiomeDiversity,
+        Criterion::RiverAccess,
+        Criterion::TerrainMix,
+        Criterion::Village,
+        Criterion::Fortress,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Criterion::BiomeDiversity => "biome_diversity",
+            Criterion::RiverAccess => "river_access",
+            Criterion::TerrainMix => "terrain_mix",
+            Criterion::Village => "village",
+            Criterion::Fortress => "fortress",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Criterion> {
+        Criterion::ALL.into_iter().find(|c| c.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                Criterion::BiomeDiversity => "Biome diversity",
+                Criterion::RiverAccess => "River access",
+                Criterion::TerrainMix => "Mountain and plain mix",
+                Criterion::Village => "Village proximity",
+                Criterion::Fortress => "Nether fortress proximity",
+            };
+        }
+        match self {
+            Criterion::BiomeDiversity => "バイオームの多様さ",
+            Criterion::RiverAccess => "川までの近さ",
+            Criterion::TerrainMix => "山と平地の混ざり具合",
+            Criterion::Village => "村までの近さ",
+            Criterion::Fortress => "ネザー要塞までの近さ",
+        }
+    }
+}
+
+/// 基準ごとの点数
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ScoreComponent {
+    pub criterion: String,
+    pub name: String,
+    /// 基準の値（種類の数、距離、山の割合。距離の範囲内に見つからなければ null）
+    pub value: Option<f64>,
+    /// 0〜100点
+    pub score: f64,
+    pub weight: f64,
+    /// 値の説明（テキスト出力用）
+    pub description: String,
+}
+
+/// 場所の採点の結果
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LocationScore {
+    /// 重み付きの平均（0〜100点）
+    pub total: f64,
+    pub components: Vec<ScoreComponent>,
+}
+
+/// 距離が0で1、zero 以上（見つからない場合を含む）で0の割合
+fn closeness(distance: Option<f64>, zero: i32) -> f64 {
+    distance.map_or(0.0, |d| (1.0 - d / zero as f64).max(0.0))
+}
+
+fn describe_distance(distance: Option<f64>, limit: i32, unit: &str) -> String {
+    match distance {
+        Some(d) => format!("{:.0}{}", d, unit),
+        None => format!("{}{}以内になし", limit, unit),
+    }
+}
+
+/// 座標を基準ごとに採点する（weights にない基準は重み0として点数だけ示す）
+///
+/// バイオームと地形は周囲 `SCORE_AREA_RADIUS` ブロックを `SAMPLE_STEP` 間隔で調べる。
+pub fn score_location(seed: i64, x: i32, z: i32, weights: &[(Criterion, f64)]) -> Result<LocationScore, SearchError> {
+    check_search_area(x, z, SCORE_AREA_RADIUS)?;
+    let mut biomes = HashSet::new();
+    let mut river: Option<f64> = None;
+    let (mut land, mut mountains) = (0, 0);
+    let steps = SCORE_AREA_RADIUS / SAMPLE_STEP;
+    for i in -steps..=steps {
+        for j in -steps..=steps {
+            let (px, pz) = (x + i * SAMPLE_STEP, z + j * SAMPLE_STEP);
+            let distance = distance_2d(x, z, px, pz);
+            if distance > SCORE_AREA_RADIUS as f64 {
+                continue;
+            }
+            let biome = get_biome_at(seed, BlockPos::new(px, pz));
+            biomes.insert(biome.id());
+            if biome == BiomeType::River && river.is_none_or(|d| distance < d) {
+                river = Some(distance);
+            }
+            let y = estimate_terrain_y(seed, px, pz);
+            if y >= SEA_LEVEL {
+                land += 1;
+                if y >= MOUNTAIN_Y {
+                    mountains += 1;
+                }
+            }
+        }
+    }
+    let mountain_ratio = if land > 0 { mountains as f64 / land as f64 } else { 0.0 };
+
+    let village = find_nearest_any(seed, BlockPos::new(x, z), VILLAGE_DISTANCE_ZERO, &[StructureType::Village], |_| true)?
+        .map(|hit| distance_2d(x, z, hit.pos.x, hit.pos.z));
+    let (nx, nz) = (overworld_to_nether(x), overworld_to_nether(z));
+    let fortress =
+        find_nearest_any(seed, BlockPos::new(nx, nz), FORTRESS_DISTANCE_ZERO, &[StructureType::NetherFortress], |_| true)?
+            .map(|hit| distance_2d(nx, nz, hit.pos.x, hit.pos.z));
+
+    let components: Vec<ScoreComponent> = Criterion::ALL
+        .iter()
+        .map(|&criterion| {
+            let (value, ratio, description) = match criterion {
+                Criterion::BiomeDiversity => (
+                    Some(biomes.len() as f64),
+                    (biomes.len() as f64 / DIVERSITY_FULL as f64).min(1.0),
+                    format!("周囲{}ブロックに{}種類", SCORE_AREA_RADIUS, biomes.len()),
+                ),
+                Criterion::RiverAccess => (
+                    river,
+                    closeness(river, SCORE_AREA_RADIUS),
+                    describe_distance(river, SCORE_AREA_RADIUS, "ブロック"),
+                ),
+                // 山の割合が半分で最大になる
+                Criterion::TerrainMix => (
+                    Some(mountain_ratio),
+                    4.0 * mountain_ratio * (1.0 - mountain_ratio),
+                    format!("陸のうち山の割合: {:.0}%", mountain_ratio * 100.0),
+                ),
+                Criterion::Village => (
+                    village,
+                    closeness(village, VILLAGE_DISTANCE_ZERO),
+                    describe_distance(village, VILLAGE_DISTANCE_ZERO, "ブロック"),
+                ),
+                Criterion::Fortress => (
+                    fortress,
+                    closeness(fortress, FORTRESS_DISTANCE_ZERO),
+                    describe_distance(fortress, FORTRESS_DISTANCE_ZERO, "ブロック（ネザー）"),
+                ),
+            };
+            let weight = weights.iter().find(|(c, _)| *c == criterion).map_or(0.0, |(_, w)| *w);
+            ScoreComponent {
+                criterion: criterion.id().to_string(),
+                name: criterion.display_name().to_string(),
+                value,
+                score: ratio * 100.0,
+                weight,
+                description,
+            }
+        })
+        .collect();
+
+    let total_weight: f64 = components.iter().map(|c| c.weight).sum();
+    let total = if total_weight > 0.0 {
+        components.iter().map(|c| c.score * c.weight).sum::<f64>() / total_weight
+    } else {
+        0.0
+    };
+    Ok(LocationScore { total, components })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wood_availability() {
+        let woods = wood_availability(12345, 0, 0, 1000, 5000).unwrap();
+        assert_eq!(woods.len(), WoodType::ALL.len());
+        for wood in &woods {
+            if let (Some(x), Some(z), Some(distance)) = (wood.x, wood.z, wood.distance) {
+                assert_eq!(distance, distance_2d(0, 0, x, z));
+                assert_eq!(wood.available, distance <= 1000.0);
+                let biome = BiomeType::from_str(wood.biome.as_deref().unwrap()).unwrap();
+                assert_eq!(get_biome_at(12345, BlockPos::new(x, z)), biome);
+            } else {
+                assert!(!wood.available);
+            }
+        }
+        // 狭い範囲で手に入るものは広い範囲でも同じ場所
+        let near = wood_availability(12345, 0, 0, 200, 200).unwrap();
+        for (a, b) in near.iter().zip(&woods) {
+            if a.available {
+                assert_eq!((a.x, a.z), (b.x, b.z));
+            }
+        }
+        assert!(wood_availability(12345, 0, 0, -1, -1).is_err());
+    }
+
+    #[test]
+    fn test_land_corridors() {
+        let corridors = land_corridors(12345, -512, 0, 3000).unwrap();
+        assert_eq!(corridors.len(), 8);
+        for pair in corridors.windows(2) {
+            assert!(pair[0].length >= pair[1].length);
+        }
+        for corridor in &corridors {
+            assert!(corridor.length <= 3000.0);
+            assert!(!get_biome_at(12345, BlockPos::new(corridor.end_x, corridor.end_z)).is_ocean());
+            let reach = distance_2d(-512, 0, corridor.end_x, corridor.end_z);
+            assert!((reach - corridor.length).abs() <= 1.0);
+        }
+        let north = corridors.iter().find(|c| c.direction == "n").unwrap();
+        assert_eq!(north.name, "北");
+        assert!(north.end_z <= 0 && north.end_x == -512);
+        assert!(land_corridors(12345, 0, 0, -1).is_err());
+    }
+
+    #[test]
+    fn test_score_location() {
+        let equal: Vec<(Criterion, f64)> = Criterion::ALL.iter().map(|&c| (c, 1.0)).collect();
+        let result = score_location(12345, -512, 0, &equal).unwrap();
+        assert_eq!(result.components.len(), Criterion::ALL.len());
+        assert!((0.0..=100.0).contains(&result.total));
+        let mean = result.components.iter().map(|c| c.score).sum::<f64>() / Criterion::ALL.len() as f64;
+        assert!((result.total - mean).abs() < 1e-9);
+        for component in &result.components {
+            assert!((0.0..=100.0).contains(&component.score));
+            // 見つからない距離は null で、点数は0
+            if component.value.is_none() {
+                assert_eq!(component.score, 0.0);
+            }
+            assert_eq!(Criterion::from_id(&component.criterion).map(|c| c.id()), Some(component.criterion.as_str()));
+        }
+
+        // 1つの基準だけに重みを付けると合計はその基準の点数
+        let village = score_location(12345, -512, 0, &[(Criterion::Village, 3.0)]).unwrap();
+        assert_eq!(village.total, village.components[3].score);
+        assert_eq!(score_location(12345, -512, 0, &[]).unwrap().total, 0.0);
+    }
+}
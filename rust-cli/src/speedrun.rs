@@ -0,0 +1,170 @@
+//! スピードランのルート計画モジュール
+//!
+//! スポーン地点から、村か難破船（食料・道具）→ 荒廃したポータル（ネザーへの入口）→
+//! ネザー要塞とバスティオン（ブレイズロッドとエンダーパール）→ 要塞（エンドポータル）の順に、
+//! 直前の地点から最も近いものを選んでルートを組む。ネザーでは荒廃したポータルのネザー座標から入り、
+//! 最後に訪れたネザー構造物の位置でオーバーワールドに戻る前提で要塞までの距離を求める。
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::algorithms::spawn::{estimate_spawn, SpawnEstimate};
+use crate::coords::{check_nether_search_area, nether_to_overworld, overworld_to_nether, BlockPos};
+use crate::error::SearchError;
+use crate::i18n::is_english;
+use crate::route::{plan_route, Waypoint};
+use crate::structures::{find_nearest_any, find_strongholds, StructureHit, StructureType, STRONGHOLD_ID};
+use crate::travel::distance_2d;
+
+/// ネザーに入る前に寄る構造物（この順に1つずつ）
+const OVERWORLD_STAGES: [&[StructureType]; 2] = [
+    &[StructureType::Village, StructureType::Shipwreck],
+    &[StructureType::RuinedPortal],
+];
+
+/// ネザーで寄る構造物（近い順に巡る）
+const NETHER_STAGES: [StructureType; 2] = [StructureType::NetherFortress, StructureType::BastionRemnant];
+
+/// ルートの1区間
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SpeedrunLeg {
+    /// spawn、構造物の識別子、stronghold
+    pub id: String,
+    pub name: String,
+    /// この地点を訪れるディメンション（overworld, nether）
+    pub dimension: String,
+    /// オーバーワールドの座標（ネザーの地点はネザー座標を8倍した座標）
+    pub x: i32,
+    pub z: i32,
+    pub nether_x: i32,
+    pub nether_z: i32,
+    /// 直前の地点からの距離（訪れるディメンションのブロック数）
+    pub distance: f64,
+}
+
+/// スピードランのルート
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SpeedrunPlan {
+    pub seed: i64,
+    pub spawn: SpawnEstimate,
+    pub legs: Vec<SpeedrunLeg>,
+    /// 検索範囲内に見つからず飛ばした構造物の識別子
+    pub missing: Vec<String>,
+    /// オーバーワールドを移動する距離の合計
+    pub overworld_distance: f64,
+    /// ネザーを移動する距離の合計（ネザーのブロック数）
+    pub nether_distance: f64,
+}
+
+fn overworld_leg(id: &str, name: &str, (x, z): (i32, i32), from: (i32, i32)) -> SpeedrunLeg {
+    SpeedrunLeg {
+        id: id.to_string(),
+        name: name.to_string(),
+        dimension: "overworld".to_string(),
+        x,
+        z,
+        nether_x: overworld_to_nether(x),
+        nether_z: overworld_to_nether(z),
+        distance: distance_2d(from.0, from.1, x, z),
+    }
+}
+
+/// シードのスピードランのルートを計画
+///
+/// オーバーワールドの構造物はそれぞれ直前の地点から `radius` ブロック以内、ネザーの構造物は
+/// ネザーの入口から `nether_radius` ブロック（ネザーのブロック数）以内で探す。
+/// 見つからない段階は飛ばして `missing` に記録する。要塞は範囲によらず最も近いものを選ぶ。
+pub fn plan_speedrun(seed: i64, radius: i32, nether_radius: i32) -> Result<SpeedrunPlan, SearchError> {
+    let spawn = estimate_spawn(seed);
+    let spawn_name = if is_english() { "🏠 World spawn" } else { "🏠 スポーン地点" };
+    let mut here = (spawn.x, spawn.z);
+    let mut legs = vec![overworld_leg("spawn", spawn_name, here, here)];
+    let mut missing = Vec::new();
+
+    for types in OVERWORLD_STAGES {
+        match find_nearest_any(seed, BlockPos::new(here.0, here.1), radius, types, |_| true)? {
+            Some(hit) => {
+                let pos = (hit.pos.x, hit.pos.z);
+                legs.push(overworld_leg(hit.kind.id(), hit.kind.display_name(), pos, here));
+                here = pos;
+            }
+            None => missing.extend(types.iter().map(|st| st.id().to_string())),
+        }
+    }
+
+    // ネザーの構造物は入口から見つけたものを巡回ルートで近い順に並べる
+    let entry = (overworld_to_nether(here.0), overworld_to_nether(here.1));
+    check_nether_search_area(entry.0, entry.1, nether_radius)?;
+    let mut waypoints = Vec::new();
+    for st in NETHER_STAGES {
+        match find_nearest_any(seed, BlockPos::new(entry.0, entry.1), nether_radius, &[st], |_| true)? {
+            Some(StructureHit { pos, .. }) => waypoints.push(Waypoint { name: st.id().to_string(), x: pos.x, z: pos.z }),
+            None => missing.push(st.id().to_string()),
+        }
+    }
+    let mut exit = entry;
+    for leg in plan_route(entry.0, entry.1, waypoints).legs {
+        let name = leg.name.parse::<StructureType>().map_or(leg.name.clone(), |st| st.display_name().to_string());
+        legs.push(SpeedrunLeg {
+            id: leg.name,
+            name,
+            dimension: "nether".to_string(),
+            x: nether_to_overworld(leg.x),
+            z: nether_to_overworld(leg.z),
+            nether_x: leg.x,
+            nether_z: leg.z,
+            distance: leg.distance,
+        });
+        exit = (leg.x, leg.z);
+    }
+
+    // ネザーの出口からオーバーワールドに戻って最寄りの要塞へ
+    let from = (nether_to_overworld(exit.0), nether_to_overworld(exit.1));
+    let stronghold = find_strongholds(seed)
+        .into_iter()
+        .min_by_key(|hit| (hit.pos.x as i64 - from.0 as i64).pow(2) + (hit.pos.z as i64 - from.1 as i64).pow(2));
+    if let Some(hit) = stronghold {
+        legs.push(overworld_leg(STRONGHOLD_ID, hit.kind.display_name(), (hit.pos.x, hit.pos.z), from));
+    }
+
+    let total = |dimension: &str| legs.iter().filter(|l| l.dimension == dimension).map(|l| l.distance).sum();
+    Ok(SpeedrunPlan {
+        seed,
+        overworld_distance: total("overworld"),
+        nether_distance: total("nether"),
+        spawn,
+        legs,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_speedrun() {
+        let plan = plan_speedrun(12345, 3000, 1000).unwrap();
+        let ids: Vec<&str> = plan.legs.iter().map(|l| l.id.as_str()).collect();
+        assert_eq!(ids.first(), Some(&"spawn"));
+        assert_eq!(ids.last(), Some(&STRONGHOLD_ID));
+        assert!(plan.missing.is_empty(), "{:?}", plan.missing);
+        assert!(["village", "shipwreck"].contains(&ids[1]));
+        assert_eq!(ids[2], "ruined_portal");
+        let mut nether: Vec<&str> = ids[3..5].to_vec();
+        nether.sort();
+        assert_eq!(nether, ["bastion", "fortress"]);
+
+        for leg in &plan.legs {
+            assert!(leg.distance >= 0.0);
+            if leg.dimension == "nether" {
+                assert_eq!((leg.x, leg.z), (nether_to_overworld(leg.nether_x), nether_to_overworld(leg.nether_z)));
+            }
+        }
+        let nether_total: f64 = plan.legs[3..5].iter().map(|l| l.distance).sum();
+        assert_eq!(plan.nether_distance, nether_total);
+
+        assert!(plan_speedrun(12345, -1, 1000).is_err());
+        assert!(plan_speedrun(12345, 3000, i32::MAX / 2).is_err());
+    }
+}
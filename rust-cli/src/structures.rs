@@ -1,8 +1,77 @@
 //! 構造物検索モジュール
 //! Minecraft Bedrock Edition の構造物座標計算
 
+use std::ffi::OsStr;
+use std::str::FromStr;
+
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::{Arg, Command, ValueEnum};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::biome::{get_biome_at, BiomeType};
+use crate::budget;
+use crate::coords::{check_search_area, BlockPos, Dimension, RegionPos, CHUNK_SIZE};
+use crate::custom::find_custom_structure;
+use crate::error::SearchError;
+use crate::gamedata::game_data;
+use crate::i18n::is_english;
+use crate::locator::{locate, locator_for, locator_for_kind, NetherQuadrantLocator, StrongholdRingLocator, StructureLocator, QUADRANT_CHUNKS};
+use crate::version::McVersion;
+
+/// 配置計算の改訂番号（結果が変わる修正をしたら上げる）
+pub const ALGORITHM_REVISION: u32 = 2;
+
+/// 結果の座標の信頼度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    /// ゲームと同じ計算で、ブロック単位で一致する
+    Exact,
+    /// 位置はゲームと同じ計算だが、バイオーム等の生成条件は確かめていない（生成されない場合がある）
+    Likely,
+    /// 近似のモデルによる推定（位置がずれる、または実際には生成されない場合がある）
+    Approximate,
+}
+
+/// 構造物のおおよその範囲（ブロック座標。高さは分かる構造物のみ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct BoundingBox {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_z: i32,
+    pub max_z: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_y: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_y: Option<i32>,
+}
+
+/// 構造物の配置モデル
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementModel {
+    /// リージョン格子（リージョンごとに1つ）
+    Grid,
+    /// ネザーの480ブロック区画（区画ごとにネザー要塞かバスティオンのどちらか）
+    NetherQuadrant,
+    /// チャンクごとの確率
+    Chunk,
+}
+
+impl PlacementModel {
+    /// 識別子（list の出力等に使う）
+    pub fn id(&self) -> &'static str {
+        match self {
+            PlacementModel::Grid => "grid",
+            PlacementModel::NetherQuadrant => "nether_quadrant",
+            PlacementModel::Chunk => "chunk",
+        }
+    }
+}
+
 /// 構造物タイプ
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StructureType {
     Village,
     PillagerOutpost,
@@ -14,11 +83,222 @@ pub enum StructureType {
     WitchHut,
     Shipwreck,
     BuriedTreasure,
+    RuinedPortal,
+    EndCity,
+    TrialChamber,
+    Mineshaft,
+    AncientCity,
 }
 
 impl StructureType {
-    /// 構造物の表示名を取得
+    /// すべての構造物タイプ
+    pub const ALL: [StructureType; 15] = [
+        StructureType::Village,
+        StructureType::PillagerOutpost,
+        StructureType::OceanMonument,
+        StructureType::WoodlandMansion,
+        StructureType::NetherFortress,
+        StructureType::BastionRemnant,
+        StructureType::Igloo,
+        StructureType::WitchHut,
+        StructureType::Shipwreck,
+        StructureType::BuriedTreasure,
+        StructureType::RuinedPortal,
+        StructureType::EndCity,
+        StructureType::TrialChamber,
+        StructureType::Mineshaft,
+        StructureType::AncientCity,
+    ];
+
+    /// 構造物の識別子を取得（-t オプションで使う名前）
+    pub fn id(&self) -> &'static str {
+        match self {
+            StructureType::Village => "village",
+            StructureType::PillagerOutpost => "outpost",
+            StructureType::OceanMonument => "monument",
+            StructureType::WoodlandMansion => "mansion",
+            StructureType::NetherFortress => "fortress",
+            StructureType::BastionRemnant => "bastion",
+            StructureType::Igloo => "igloo",
+            StructureType::WitchHut => "witch_hut",
+            StructureType::Shipwreck => "shipwreck",
+            StructureType::BuriedTreasure => "buried_treasure",
+            StructureType::RuinedPortal => "ruined_portal",
+            StructureType::EndCity => "end_city",
+            StructureType::TrialChamber => "trial_chamber",
+            StructureType::Mineshaft => "mineshaft",
+            StructureType::AncientCity => "ancient_city",
+        }
+    }
+
+    /// 識別子の別名（ゲーム内の識別子等。-t オプションで識別子の代わりに使える）
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            StructureType::PillagerOutpost => &["pillager_outpost"],
+            StructureType::OceanMonument => &["ocean_monument"],
+            StructureType::WoodlandMansion => &["woodland_mansion"],
+            StructureType::NetherFortress => &["nether_fortress"],
+            StructureType::BastionRemnant => &["bastion_remnant"],
+            StructureType::WitchHut => &["swamp_hut"],
+            StructureType::TrialChamber => &["trial_chambers"],
+            _ => &[],
+        }
+    }
+
+    /// 構造物が追加されたBedrock Editionのバージョン
+    pub fn since_version(&self) -> &'static str {
+        match self {
+            StructureType::Village => "0.9.0",
+            StructureType::PillagerOutpost => "1.10.0",
+            StructureType::OceanMonument => "0.16.0",
+            StructureType::WoodlandMansion => "1.1.0",
+            StructureType::NetherFortress => "0.12.1",
+            StructureType::BastionRemnant => "1.16.0",
+            StructureType::Igloo => "0.15.0",
+            StructureType::WitchHut => "0.9.0",
+            StructureType::Shipwreck => "1.4.0",
+            StructureType::BuriedTreasure => "1.4.0",
+            StructureType::RuinedPortal => "1.16.0",
+            StructureType::EndCity => "1.0.0",
+            StructureType::TrialChamber => "1.21.0",
+            StructureType::Mineshaft => "0.13.0",
+            StructureType::AncientCity => "1.19.0",
+        }
+    }
+
+    /// 正式な追加より前に実験的機能として生成されるようになったバージョン（None は実験的機能の期間がない）
+    pub fn experimental_since(&self) -> Option<&'static str> {
+        match self {
+            StructureType::TrialChamber => Some("1.20.50"),
+            StructureType::AncientCity => Some("1.18.30"),
+            _ => None,
+        }
+    }
+
+    /// 対象バージョン（`--experimental` を含む）で生成されるか
+    pub fn is_available(&self, version: McVersion) -> bool {
+        version.supports(self.since_version(), self.experimental_since())
+    }
+
+    /// 生成できるバイオーム（ゲーム内の生成条件。ネザー構造物は空）
+    pub fn allowed_biomes(&self) -> &'static [BiomeType] {
+        match self {
+            StructureType::Village => &[
+                BiomeType::Plains,
+                BiomeType::Desert,
+                BiomeType::Savanna,
+                BiomeType::Taiga,
+                BiomeType::SnowyTaiga,
+            ],
+            StructureType::PillagerOutpost => &[
+                BiomeType::Plains,
+                BiomeType::Desert,
+                BiomeType::Savanna,
+                BiomeType::Taiga,
+                BiomeType::SnowyTaiga,
+                BiomeType::Mountain,
+            ],
+            StructureType::OceanMonument => &[BiomeType::DeepOcean],
+            StructureType::WoodlandMansion => &[BiomeType::Forest],
+            StructureType::NetherFortress | StructureType::BastionRemnant | StructureType::EndCity => &[],
+            StructureType::Igloo => &[BiomeType::SnowyTaiga],
+            StructureType::WitchHut => &[BiomeType::Swamp],
+            StructureType::Shipwreck => &[BiomeType::Ocean, BiomeType::DeepOcean, BiomeType::Beach],
+            StructureType::BuriedTreasure => &[BiomeType::Beach],
+            StructureType::RuinedPortal
+            | StructureType::TrialChamber
+            | StructureType::Mineshaft
+            | StructureType::AncientCity => &BiomeType::ALL,
+        }
+    }
+
+    /// バージョンによる違いの補足
+    pub fn version_notes(&self) -> &'static str {
+        match self {
+            StructureType::Village => "1.11.0（Village & Pillage）で建物のデザインが刷新",
+            StructureType::NetherFortress => "1.16.0以降はバスティオンと同じ区画を取り合う",
+            StructureType::BastionRemnant => "1.16.0（ネザーアップデート）で追加",
+            StructureType::Shipwreck | StructureType::BuriedTreasure => "1.4.0（Update Aquatic）で追加",
+            StructureType::RuinedPortal => "1.16.0（ネザーアップデート）で追加。ネザーにも生成されるが、このツールではオーバーワールドのみ検索する",
+            StructureType::EndCity => "エンドの中央の島から1000ブロック以上離れた外周の島にだけ生成される",
+            StructureType::TrialChamber => "1.21.0（Tricky Trials）で追加。地下（Y=-40〜-20付近）に生成される",
+            StructureType::Mineshaft => "チャンクごとに約0.4%の確率で生成される（チャンクの乱数はこのツールの近似）",
+            StructureType::AncientCity => "1.19.0（The Wild Update）で追加。地下深く（Y=-51付近）のディープダークにだけ生成される（deepdark コマンドで判定）",
+            _ => "",
+        }
+    }
+
+    /// 生成されるディメンション（overworld, nether, the_end）
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            StructureType::NetherFortress | StructureType::BastionRemnant => Dimension::Nether,
+            StructureType::EndCity => Dimension::End,
+            _ => Dimension::Overworld,
+        }
+    }
+
+    /// 構造物の中心からのおおよその広がり（ブロック数）と、分かる場合は高さの範囲
+    pub fn footprint(&self) -> (i32, Option<(i32, i32)>) {
+        match self {
+            StructureType::Village => (64, None),
+            StructureType::PillagerOutpost => (24, None),
+            StructureType::OceanMonument => (29, Some((39, 62))),
+            StructureType::WoodlandMansion => (40, None),
+            StructureType::NetherFortress => (64, Some((48, 78))),
+            StructureType::BastionRemnant => (32, Some((32, 80))),
+            StructureType::Igloo => (6, None),
+            StructureType::WitchHut => (5, None),
+            StructureType::Shipwreck => (12, None),
+            StructureType::BuriedTreasure => (1, None),
+            StructureType::RuinedPortal => (8, None),
+            StructureType::EndCity => (40, None),
+            StructureType::TrialChamber => (32, Some((-40, -20))),
+            StructureType::Mineshaft => (80, None),
+            StructureType::AncientCity => (48, Some((-51, -20))),
+        }
+    }
+
+    /// 座標の信頼度（ネザーの区画と廃坑は近似、生成条件のある構造物は位置のみ一致）
+    pub fn confidence(&self) -> Confidence {
+        match self {
+            StructureType::NetherFortress | StructureType::BastionRemnant | StructureType::Mineshaft => {
+                Confidence::Approximate
+            }
+            StructureType::RuinedPortal | StructureType::TrialChamber => Confidence::Exact,
+            _ => Confidence::Likely,
+        }
+    }
+
+    /// 配置モデル
+    pub fn placement(&self) -> PlacementModel {
+        match self {
+            StructureType::NetherFortress | StructureType::BastionRemnant => PlacementModel::NetherQuadrant,
+            StructureType::Mineshaft => PlacementModel::Chunk,
+            _ => PlacementModel::Grid,
+        }
+    }
+
+    /// 構造物の表示名を取得（表示言語に従う）
     pub fn display_name(&self) -> &'static str {
+        if is_english() {
+            return match self {
+                StructureType::Village => "🏘️ Village",
+                StructureType::PillagerOutpost => "⚔️ Pillager Outpost",
+                StructureType::OceanMonument => "🌊 Ocean Monument",
+                StructureType::WoodlandMansion => "🏰 Woodland Mansion",
+                StructureType::NetherFortress => "🔥 Nether Fortress",
+                StructureType::BastionRemnant => "🏚️ Bastion Remnant",
+                StructureType::Igloo => "🧊 Igloo",
+                StructureType::WitchHut => "🧙 Witch Hut",
+                StructureType::Shipwreck => "🚢 Shipwreck",
+                StructureType::BuriedTreasure => "💰 Buried Treasure",
+                StructureType::RuinedPortal => "🌀 Ruined Portal",
+                StructureType::EndCity => "🌃 End City",
+                StructureType::TrialChamber => "🗝️ Trial Chambers",
+                StructureType::Mineshaft => "⛏️ Mineshaft",
+                StructureType::AncientCity => "🏛️ Ancient City",
+            };
+        }
         match self {
             StructureType::Village => "🏘️ 村",
             StructureType::PillagerOutpost => "⚔️ 前哨基地",
@@ -30,223 +310,660 @@ impl StructureType {
             StructureType::WitchHut => "🧙 魔女の家",
             StructureType::Shipwreck => "🚢 難破船",
             StructureType::BuriedTreasure => "💰 埋蔵金",
+            StructureType::RuinedPortal => "🌀 荒廃したポータル",
+            StructureType::EndCity => "🌃 エンドシティ",
+            StructureType::TrialChamber => "🗝️ 試練の間",
+            StructureType::Mineshaft => "⛏️ 廃坑",
+            StructureType::AncientCity => "🏛️ 古代都市",
         }
     }
 
     /// 構造物のグリッドサイズを取得（チャンク単位）
     pub fn spacing(&self) -> i32 {
-        match self {
-            StructureType::Village => 32,
-            StructureType::PillagerOutpost => 80,
-            StructureType::OceanMonument => 32,
-            StructureType::WoodlandMansion => 80,
-            StructureType::NetherFortress => 30, // 480/16 = 30 chunks
-            StructureType::BastionRemnant => 30,
-            StructureType::Igloo => 32,
-            StructureType::WitchHut => 32,
-            StructureType::Shipwreck => 24,
-            StructureType::BuriedTreasure => 8,
-        }
+        game_data().structure(*self).spacing
     }
 
     /// 構造物の分離距離を取得（チャンク単位）
     pub fn separation(&self) -> i32 {
-        match self {
-            StructureType::Village => 8,
-            StructureType::PillagerOutpost => 40,
-            StructureType::OceanMonument => 5,
-            StructureType::WoodlandMansion => 20,
-            StructureType::NetherFortress => 4,
-            StructureType::BastionRemnant => 4,
-            StructureType::Igloo => 8,
-            StructureType::WitchHut => 8,
-            StructureType::Shipwreck => 4,
-            StructureType::BuriedTreasure => 4,
-        }
+        game_data().structure(*self).separation
     }
 
     /// 構造物のソルト値を取得
     pub fn salt(&self) -> i64 {
+        game_data().structure(*self).salt
+    }
+}
+
+/// 識別子か別名から構造物タイプを取得（大文字・小文字、- と _、minecraft: の接頭辞は区別しない）
+impl FromStr for StructureType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<StructureType, String> {
+        let id = s.trim().to_ascii_lowercase().replace('-', "_");
+        let id = id.strip_prefix("minecraft:").unwrap_or(&id);
+        <StructureType as ValueEnum>::from_str(id, false).map_err(|_| {
+            let ids: Vec<_> = StructureType::ALL.iter().map(|st| st.id()).collect();
+            format!("不明な構造物タイプ: {}（{}）", s, ids.join(", "))
+        })
+    }
+}
+
+impl ValueEnum for StructureType {
+    fn value_variants<'a>() -> &'a [StructureType] {
+        &StructureType::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.id()).aliases(self.aliases().iter().copied()))
+    }
+}
+
+/// structures コマンドで検索する構造物の指定（-t）
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructureSelection {
+    /// ディメンションの主な構造物すべて（all）
+    All,
+    /// 要塞（リング配置）
+    Stronghold,
+    /// 同じディメンションの複数の種類のうち最も近い1件（any:fortress,bastion）
+    Any(Vec<StructureType>),
+    Builtin(StructureType),
+    /// カスタム構造物の識別子（定義があるかは読み込んだ後に確かめる）
+    Custom(String),
+}
+
+impl StructureSelection {
+    /// 指定の文字列（メッセージ用）
+    pub fn id(&self) -> String {
         match self {
-            StructureType::Village => 10387312,
-            StructureType::PillagerOutpost => 165745296,
-            StructureType::OceanMonument => 10387313,
-            StructureType::WoodlandMansion => 10387319,
-            StructureType::NetherFortress => 30084232,
-            StructureType::BastionRemnant => 30084232,
-            StructureType::Igloo => 14357618,
-            StructureType::WitchHut => 14357620,
-            StructureType::Shipwreck => 165745295,
-            StructureType::BuriedTreasure => 16842397,
+            StructureSelection::All => "all".to_string(),
+            StructureSelection::Stronghold => STRONGHOLD_ID.to_string(),
+            StructureSelection::Any(types) => {
+                format!("any:{}", types.iter().map(StructureType::id).collect::<Vec<_>>().join(","))
+            }
+            StructureSelection::Builtin(st) => st.id().to_string(),
+            StructureSelection::Custom(id) => id.clone(),
+        }
+    }
+
+    /// any: の後のカンマ区切りの種類を読む（組み込みの同じディメンションの構造物のみ）
+    fn parse_any(ids: &str) -> Result<Vec<StructureType>, String> {
+        let types = ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| id.parse::<StructureType>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("any: に指定できない構造物タイプです（組み込みの構造物のみ）: {}", e))?;
+        match types.first() {
+            None => Err("any: の後に構造物タイプをカンマ区切りで指定してください（例: any:fortress,bastion）".to_string()),
+            Some(first) if types.iter().any(|st| st.dimension() != first.dimension()) => {
+                Err("any: には同じディメンションの構造物を指定してください".to_string())
+            }
+            Some(_) => Ok(types),
+        }
+    }
+}
+
+impl FromStr for StructureSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<StructureSelection, String> {
+        if let Some(ids) = s.strip_prefix("any:") {
+            return StructureSelection::parse_any(ids).map(StructureSelection::Any);
         }
+        Ok(match s {
+            "all" => StructureSelection::All,
+            STRONGHOLD_ID => StructureSelection::Stronghold,
+            _ => match s.parse::<StructureType>() {
+                Ok(st) => StructureSelection::Builtin(st),
+                Err(_) => StructureSelection::Custom(s.to_string()),
+            },
+        })
     }
 }
 
-/// 構造物シードを計算
-fn get_structure_seed(world_seed: i64, region_x: i32, region_z: i32, salt: i64) -> i64 {
-    let a = region_x as i64;
-    let b = region_z as i64;
-    
-    // Bedrock Edition algorithm (LCG based)
-    let seed = world_seed
-        .wrapping_add(a.wrapping_mul(341873128712))
-        .wrapping_add(b.wrapping_mul(132897987541))
-        .wrapping_add(salt);
-    
-    seed
+/// -t の値の読み取り（--help に組み込みの構造物タイプの一覧を出す）
+#[derive(Debug, Clone, Copy)]
+pub struct StructureSelectionParser;
+
+impl TypedValueParser for StructureSelectionParser {
+    type Value = StructureSelection;
+
+    fn parse_ref(&self, cmd: &Command, _arg: Option<&Arg>, value: &OsStr) -> Result<StructureSelection, clap::Error> {
+        let s = value.to_str().ok_or_else(|| clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+        s.parse().map_err(|e| clap::Error::raw(ErrorKind::InvalidValue, format!("{}\n", e)).with_cmd(cmd))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let fixed = [PossibleValue::new("all"), PossibleValue::new(STRONGHOLD_ID)];
+        let builtin = StructureType::value_variants().iter().filter_map(ValueEnum::to_possible_value);
+        Some(Box::new(fixed.into_iter().chain(builtin)))
+    }
 }
 
-/// 擬似乱数ジェネレータ（簡易版）
-fn next_int(seed: &mut i64, bound: i32) -> i32 {
-    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
-    let bits = (*seed >> 17) as i32;
-    ((bits as i64).abs() % bound as i64) as i32
+/// グリッド配置（リージョンごとに1つ）のパラメータ（チャンク単位）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridPlacement {
+    pub spacing: i32,
+    pub separation: i32,
+    pub salt: i64,
 }
 
-/// 構造物を検索
+/// 検索結果の構造物の種類
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructureKind {
+    Builtin(StructureType),
+    /// 要塞（リング配置）
+    Stronghold,
+    /// カスタム構造物（識別子）
+    Custom(String),
+}
+
+impl StructureKind {
+    /// 識別子から種類を取得（組み込みの構造物の別名、要塞、登録されたカスタム構造物）
+    pub fn from_id(id: &str) -> Option<StructureKind> {
+        if id == STRONGHOLD_ID {
+            return Some(StructureKind::Stronghold);
+        }
+        match id.parse::<StructureType>() {
+            Ok(st) => Some(StructureKind::Builtin(st)),
+            Err(_) => find_custom_structure(id).map(|c| StructureKind::Custom(c.id.clone())),
+        }
+    }
+
+    /// 識別子（-t オプションで使う名前）
+    pub fn id(&self) -> &str {
+        match self {
+            StructureKind::Builtin(st) => st.id(),
+            StructureKind::Stronghold => STRONGHOLD_ID,
+            StructureKind::Custom(id) => id,
+        }
+    }
+
+    /// 表示名（表示言語に従う）
+    pub fn display_name(&self) -> &str {
+        match self {
+            StructureKind::Builtin(st) => st.display_name(),
+            StructureKind::Stronghold => stronghold_name(),
+            StructureKind::Custom(id) => find_custom_structure(id).map_or(id.as_str(), |c| c.display_name()),
+        }
+    }
+
+    /// 生成されるディメンション
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            StructureKind::Builtin(st) => st.dimension(),
+            StructureKind::Stronghold => Dimension::Overworld,
+            StructureKind::Custom(id) => find_custom_structure(id).map_or(Dimension::Overworld, |c| c.dimension),
+        }
+    }
+
+    /// 対象バージョンで生成されるか（カスタム構造物はバージョンによらない）
+    pub fn is_available(&self, version: McVersion) -> bool {
+        match self {
+            StructureKind::Builtin(st) => st.is_available(version),
+            StructureKind::Stronghold => version.is_at_least(STRONGHOLD_SINCE_VERSION),
+            StructureKind::Custom(_) => true,
+        }
+    }
+}
+
+/// 構造物の検索結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureHit {
+    pub kind: StructureKind,
+    /// 位置（ワールドの座標）
+    pub pos: BlockPos,
+}
+
+impl StructureHit {
+    pub fn new(kind: StructureKind, pos: BlockPos) -> StructureHit {
+        StructureHit { kind, pos }
+    }
+}
+
+/// 構造物を検索（見つからない場合は空。半径や範囲が不正な場合はエラー）
 pub fn find_structures(
     seed: i64,
-    center_x: i32,
-    center_z: i32,
+    center: BlockPos,
     radius: i32,
     structure_type: StructureType,
-) -> Vec<(String, i32, i32)> {
-    let mut results = Vec::new();
-    
-    let spacing = structure_type.spacing();
-    let separation = structure_type.separation();
-    let salt = structure_type.salt();
-    let name = structure_type.display_name().to_string();
-    
-    // 検索範囲をリージョン単位で計算
-    let spacing_blocks = spacing * 16;
-    let min_region_x = (center_x - radius) / spacing_blocks - 1;
-    let max_region_x = (center_x + radius) / spacing_blocks + 1;
-    let min_region_z = (center_z - radius) / spacing_blocks - 1;
-    let max_region_z = (center_z + radius) / spacing_blocks + 1;
-    
-    for region_x in min_region_x..=max_region_x {
-        for region_z in min_region_z..=max_region_z {
-            let mut struct_seed = get_structure_seed(seed, region_x, region_z, salt);
-            
-            // リージョン内のオフセットを計算
-            let offset_range = spacing - separation;
-            let offset_x = next_int(&mut struct_seed, offset_range);
-            let offset_z = next_int(&mut struct_seed, offset_range);
-            
-            // 構造物のチャンク座標
-            let chunk_x = region_x * spacing + offset_x;
-            let chunk_z = region_z * spacing + offset_z;
-            
-            // ブロック座標に変換（チャンク中心）
-            let block_x = chunk_x * 16 + 8;
-            let block_z = chunk_z * 16 + 8;
-            
-            // 範囲内かチェック
-            let dist_sq = ((block_x - center_x) as i64).pow(2) + ((block_z - center_z) as i64).pow(2);
-            if dist_sq <= (radius as i64).pow(2) {
-                results.push((name.clone(), block_x, block_z));
+) -> Result<Vec<StructureHit>, SearchError> {
+    locate(&*locator_for(structure_type), seed, center, radius)
+}
+
+/// 複数の種類のうち最も近い構造物を1つ検索（-t any:fortress,bastion）
+///
+/// 種類ごとに検索範囲をすべて走査して結果を合わせる代わりに、中心から外側へ区画の輪を
+/// すべての種類で距離の近い順に広げ、見つかった構造物より近い輪が残っていない時点で打ち切る。
+/// 種類はすべて同じディメンションである必要がある。`accept` が偽を返す座標の構造物は数えない。
+/// 範囲内に見つからない場合は `Ok(None)`。
+pub fn find_nearest_any(
+    seed: i64,
+    center: BlockPos,
+    radius: i32,
+    types: &[StructureType],
+    accept: impl Fn(BlockPos) -> bool,
+) -> Result<Option<StructureHit>, SearchError> {
+    check_search_area(center.x, center.z, radius)?;
+    // 種類ごとの走査の状態（配置モデル、区画の1辺のチャンク数、次に調べる輪）
+    let mut scans: Vec<(Box<dyn StructureLocator>, i32, i32)> = types
+        .iter()
+        .map(|st| locator_for(*st))
+        .filter_map(|locator| locator.cell_chunks().map(|chunks| (locator, chunks, 0)))
+        .collect();
+    let mut best: Option<(StructureHit, i64)> = None;
+    let mut cells = 0u64;
+
+    loop {
+        // 輪の内側の最も近い点までの距離（中心の区画からの輪の番号 - 1 区画分）
+        let lower_bound = |chunks: i32, ring: i32| (ring - 1).max(0) as i64 * (chunks * CHUNK_SIZE) as i64;
+        let Some(next) = scans
+            .iter_mut()
+            .filter(|(_, chunks, ring)| lower_bound(*chunks, *ring) <= radius as i64)
+            .min_by_key(|(_, chunks, ring)| lower_bound(*chunks, *ring))
+        else {
+            break;
+        };
+        let (chunks, ring) = (next.1, next.2);
+        if best.as_ref().is_some_and(|b| b.1 < lower_bound(chunks, ring).pow(2)) || budget::expired() {
+            break;
+        }
+        next.2 += 1;
+        let locator = &next.0;
+
+        let dimension = locator.dimension();
+        let origin = center.to_generation(dimension).region(chunks);
+        for rx in origin.x - ring..=origin.x + ring {
+            for rz in origin.z - ring..=origin.z + ring {
+                if (rx - origin.x).abs() != ring && (rz - origin.z).abs() != ring {
+                    continue;
+                }
+                cells += 1;
+                let Some(candidate) = locator.cell_candidate(seed, RegionPos::new(rx, rz)) else {
+                    continue;
+                };
+                if !locator.validate(seed, &candidate) {
+                    continue;
+                }
+                let pos = candidate.pos.to_world(dimension);
+                let dist_sq = pos.distance_sq(center);
+                if dist_sq <= (radius as i64).pow(2) && best.as_ref().is_none_or(|b| dist_sq < b.1) && accept(pos) {
+                    best = Some((StructureHit::new(candidate.kind, pos), dist_sq));
+                }
             }
         }
     }
-    
-    results
+
+    tracing::info!(cells, found = best.is_some(), "最寄りの構造物を走査");
+    Ok(best.map(|(hit, _)| hit))
+}
+
+/// -t all で検索するディメンションの構造物の種類
+pub fn default_types(dimension: Dimension) -> &'static [StructureType] {
+    match dimension {
+        Dimension::Overworld => &[
+            StructureType::Village,
+            StructureType::PillagerOutpost,
+            StructureType::OceanMonument,
+            StructureType::WoodlandMansion,
+        ],
+        Dimension::Nether => &[StructureType::NetherFortress, StructureType::BastionRemnant],
+        Dimension::End => &[StructureType::EndCity],
+    }
 }
 
-/// ネザー構造物を検索（480x480 quadrant algorithm）
-/// 
-/// Bedrock Editionでは、ネザー要塞とバスティオンは480x480ブロックの
-/// 領域（quadrant）ごとに、どちらか一方のみが生成される。
-/// - ネザー要塞: 約33%
-/// - バスティオン: 約67%
-pub fn find_nether_structures(
+/// ディメンションの構造物を検索（座標はそのディメンションの座標）
+///
+/// 種類はすべてそのディメンションの組み込みの構造物である必要がある。見つからない場合は空。
+pub fn find_in_dimension(
     seed: i64,
-    center_x: i32,
-    center_z: i32,
+    dimension: Dimension,
+    center: BlockPos,
     radius: i32,
-) -> Vec<(String, i32, i32)> {
+    types: &[StructureType],
+) -> Result<Vec<StructureHit>, SearchError> {
+    check_search_area(center.x, center.z, radius)?;
+    if let Some(st) = types.iter().find(|st| st.dimension() != dimension) {
+        return Err(SearchError::WrongDimension { structure: st.id().to_string(), dimension });
+    }
+    if dimension == Dimension::Nether {
+        // 要塞とバスティオンは同じ区画を取り合うため、区画を1度だけ走査して種類で絞り込む
+        return locate(&NetherQuadrantLocator { types: types.to_vec() }, seed, center, radius);
+    }
     let mut results = Vec::new();
-    
-    const QUADRANT_SIZE: i32 = 480;
-    
-    // 検索範囲をquadrant単位で計算
-    let min_qx = (center_x - radius) / QUADRANT_SIZE - 1;
-    let max_qx = (center_x + radius) / QUADRANT_SIZE + 1;
-    let min_qz = (center_z - radius) / QUADRANT_SIZE - 1;
-    let max_qz = (center_z + radius) / QUADRANT_SIZE + 1;
-    
-    for qx in min_qx..=max_qx {
-        for qz in min_qz..=max_qz {
-            // Quadrant内のチェックポイント（100, 200, 300のオフセット）
-            let check_points = [100, 200, 300];
-            
-            for &offset_x in &check_points {
-                for &offset_z in &check_points {
-                    let block_x = qx * QUADRANT_SIZE + offset_x;
-                    let block_z = qz * QUADRANT_SIZE + offset_z;
-                    
-                    // 範囲内かチェック
-                    let dist_sq = ((block_x - center_x) as i64).pow(2) + ((block_z - center_z) as i64).pow(2);
-                    if dist_sq > (radius as i64).pow(2) {
-                        continue;
-                    }
-                    
-                    // このquadrantでの構造物判定
-                    let mut quadrant_seed = get_structure_seed(seed, qx, qz, 30084232);
-                    let structure_roll = next_int(&mut quadrant_seed, 100);
-                    
-                    // 33% = ネザー要塞, 67% = バスティオン
-                    let (name, is_valid) = if structure_roll < 33 {
-                        ("🔥 ネザー要塞".to_string(), true)
-                    } else {
-                        ("🏚️ バスティオン".to_string(), true)
-                    };
-                    
-                    if is_valid {
-                        // 最初の有効なチェックポイントのみ追加（1 quadrant = 1構造物）
-                        let already_added = results.iter().any(|(_, x, z)| {
-                            *x / QUADRANT_SIZE == qx && *z / QUADRANT_SIZE == qz
-                        });
-                        
-                        if !already_added {
-                            // 構造物の実際の位置を計算
-                            let offset = next_int(&mut quadrant_seed, 280) + 100;
-                            let final_x = qx * QUADRANT_SIZE + offset;
-                            let offset = next_int(&mut quadrant_seed, 280) + 100;
-                            let final_z = qz * QUADRANT_SIZE + offset;
-                            
-                            results.push((name, final_x, final_z));
-                        }
-                        break;
-                    }
-                }
-            }
-        }
+    for &st in types {
+        results.extend(find_structures(seed, center, radius, st)?);
+    }
+    Ok(results)
+}
+
+/// 種類を指定して検索範囲内の構造物を検索（座標はワールドの座標。登録されていないカスタム構造物は見つからない）
+pub fn find_kind(seed: i64, kind: &StructureKind, center: BlockPos, radius: i32) -> Result<Vec<StructureHit>, SearchError> {
+    match locator_for_kind(kind) {
+        Some(locator) => locate(&*locator, seed, center, radius),
+        None => Ok(Vec::new()),
     }
-    
-    results
+}
+
+/// 要塞（ストロングホールド）の識別子
+pub const STRONGHOLD_ID: &str = "stronghold";
+
+/// 要塞の表示名を取得（表示言語に従う）
+pub fn stronghold_name() -> &'static str {
+    if is_english() {
+        "👁️ Stronghold"
+    } else {
+        "👁️ 要塞"
+    }
+}
+
+/// 検索結果の構造物の座標の信頼度
+///
+/// 要塞のリング配置は近似のモデル、カスタム構造物は生成条件を確かめないため位置のみ一致とする。
+pub fn structure_confidence(kind: &StructureKind) -> Confidence {
+    match kind {
+        StructureKind::Builtin(st) => st.confidence(),
+        StructureKind::Stronghold => Confidence::Approximate,
+        StructureKind::Custom(_) => Confidence::Likely,
+    }
+}
+
+/// 検索結果の構造物の位置のバイオーム（オーバーワールドの構造物のみ）
+pub fn structure_biome(seed: i64, kind: &StructureKind, pos: BlockPos) -> Option<&'static str> {
+    (kind.dimension() == Dimension::Overworld).then(|| get_biome_at(seed, pos).id())
+}
+
+/// 要塞の中心からのおおよその広がり（ブロック数）
+const STRONGHOLD_FOOTPRINT: i32 = 56;
+
+/// 検索結果の構造物のおおよその範囲（カスタム構造物は1チャンク）
+pub fn structure_bbox(kind: &StructureKind, pos: BlockPos) -> BoundingBox {
+    let (half, y) = match kind {
+        StructureKind::Builtin(st) => st.footprint(),
+        StructureKind::Stronghold => (STRONGHOLD_FOOTPRINT, None),
+        StructureKind::Custom(_) => (8, None),
+    };
+    BoundingBox {
+        min_x: pos.x - half,
+        max_x: pos.x + half,
+        min_z: pos.z - half,
+        max_z: pos.z + half,
+        min_y: y.map(|(min, _)| min),
+        max_y: y.map(|(_, max)| max),
+    }
+}
+
+/// ネザー要塞の通路が開始位置から伸びる最大の距離（ブロック数）
+pub const FORTRESS_REACH: i32 = 112;
+
+/// ネザー要塞の推定範囲（湧き場所を探しに行く前の目安）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct FortressArea {
+    /// 通路が伸びうる範囲（開始位置から `FORTRESS_REACH` ブロック）
+    pub extent: BoundingBox,
+    /// 要塞のある480ブロックの区画
+    pub quadrant: BoundingBox,
+    /// 通路が伸びうる範囲のうち区画の内側（通路の交差点の多くはこの中にある）
+    pub interior: BoundingBox,
+}
+
+/// 検索結果の構造物がネザー要塞の場合、その推定範囲
+///
+/// 区画は生成の座標で決まるため、ワールドのずれ（--world-offset）を戻してから求める。
+pub fn fortress_area(kind: &StructureKind, pos: BlockPos) -> Option<FortressArea> {
+    if *kind != StructureKind::Builtin(StructureType::NetherFortress) {
+        return None;
+    }
+    let (min_y, max_y) = StructureType::NetherFortress.footprint().1.unzip();
+    let bbox = |min_x, max_x, min_z, max_z| BoundingBox { min_x, max_x, min_z, max_z, min_y, max_y };
+    let origin = pos
+        .to_generation(Dimension::Nether)
+        .region(QUADRANT_CHUNKS)
+        .min_chunk(QUADRANT_CHUNKS)
+        .min_block()
+        .to_world(Dimension::Nether);
+    let size = QUADRANT_CHUNKS * CHUNK_SIZE;
+    let extent = bbox(pos.x - FORTRESS_REACH, pos.x + FORTRESS_REACH, pos.z - FORTRESS_REACH, pos.z + FORTRESS_REACH);
+    let quadrant = bbox(origin.x, origin.x + size - 1, origin.z, origin.z + size - 1);
+    Some(FortressArea {
+        extent,
+        quadrant,
+        interior: bbox(
+            extent.min_x.max(quadrant.min_x),
+            extent.max_x.min(quadrant.max_x),
+            extent.min_z.max(quadrant.min_z),
+            extent.max_z.min(quadrant.max_z),
+        ),
+    })
+}
+
+/// 検索結果を並べる順序（中心からの距離、構造物の識別子、X、Zの順）
+///
+/// 距離は整数の2乗で比べるため、検索の順序や並列化によらず同じ順序になる。
+pub fn result_order(id: &str, pos: BlockPos, center: BlockPos) -> (i64, String, i32, i32) {
+    (pos.distance_sq(center), id.to_string(), pos.x, pos.z)
+}
+
+/// 検索結果を中心に近い順に並べる（同じ距離は構造物の識別子、X、Zの順）
+pub fn sort_nearest_first(structures: &mut [StructureHit], center: BlockPos) {
+    structures.sort_by_cached_key(|hit| result_order(hit.kind.id(), hit.pos, center));
+}
+
+/// 要塞が追加されたBedrock Editionのバージョン
+pub const STRONGHOLD_SINCE_VERSION: &str = "1.0.0";
+
+/// 要塞を検索（リング配置モデル。座標はワールドの座標）
+pub fn find_strongholds(seed: i64) -> Vec<StructureHit> {
+    StrongholdRingLocator
+        .all(seed)
+        .into_iter()
+        .map(|hit| StructureHit::new(hit.kind, hit.pos.to_world(Dimension::Overworld)))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
+    use crate::locator::nether_quadrant;
 
     #[test]
     fn test_find_villages() {
-        let results = find_structures(12345, 0, 0, 1000, StructureType::Village);
+        let results = find_structures(12345, BlockPos::new(0, 0), 1000, StructureType::Village).unwrap();
         println!("Found {} villages", results.len());
-        for (name, x, z) in &results {
-            println!("{}: X={}, Z={}", name, x, z);
+        for hit in &results {
+            println!("{}: X={}, Z={}", hit.kind.display_name(), hit.pos.x, hit.pos.z);
         }
         assert!(!results.is_empty());
     }
 
     #[test]
     fn test_find_nether_structures() {
-        let results = find_nether_structures(12345, 0, 0, 500);
+        let results = find_in_dimension(12345, Dimension::Nether, BlockPos::new(0, 0), 500, default_types(Dimension::Nether)).unwrap();
         println!("Found {} nether structures", results.len());
-        for (name, x, z) in &results {
-            println!("{}: X={}, Z={}", name, x, z);
+        for hit in &results {
+            println!("{}: X={}, Z={}", hit.kind.display_name(), hit.pos.x, hit.pos.z);
         }
+        // 負の座標の区画も1区画に1つ
+        let regions: HashSet<_> = results.iter().map(|hit| hit.pos.region(QUADRANT_CHUNKS)).collect();
+        assert_eq!(regions.len(), results.len());
+    }
+
+    #[test]
+    fn test_find_in_dimension() {
+        let nether = find_in_dimension(12345, Dimension::Nether, BlockPos::new(0, 0), 1000, default_types(Dimension::Nether)).unwrap();
+        let quadrants = NetherQuadrantLocator { types: default_types(Dimension::Nether).to_vec() };
+        assert_eq!(nether, locate(&quadrants, 12345, BlockPos::new(0, 0), 1000).unwrap());
+        let fortresses = find_in_dimension(12345, Dimension::Nether, BlockPos::new(0, 0), 1000, &[StructureType::NetherFortress]).unwrap();
+        let expected: Vec<_> = nether
+            .into_iter()
+            .filter(|hit| hit.kind == StructureKind::Builtin(StructureType::NetherFortress))
+            .collect();
+        assert_eq!(fortresses, expected);
+
+        let villages = find_in_dimension(12345, Dimension::Overworld, BlockPos::new(0, 0), 1000, &[StructureType::Village]).unwrap();
+        assert_eq!(villages, find_structures(12345, BlockPos::new(0, 0), 1000, StructureType::Village).unwrap());
+        assert_eq!(
+            find_in_dimension(12345, Dimension::End, BlockPos::new(0, 0), 1000, &[StructureType::Village]),
+            Err(SearchError::WrongDimension { structure: "village".to_string(), dimension: Dimension::End })
+        );
+    }
+
+    #[test]
+    fn test_find_nearest_any() {
+        let types = [StructureType::Village, StructureType::PillagerOutpost];
+        let nearest = find_nearest_any(12345, BlockPos::new(300, -700), 5000, &types, |_| true).unwrap().unwrap();
+        let mut all = find_structures(12345, BlockPos::new(300, -700), 5000, StructureType::Village).unwrap();
+        all.extend(find_structures(12345, BlockPos::new(300, -700), 5000, StructureType::PillagerOutpost).unwrap());
+        let dist = |hit: &StructureHit| hit.pos.distance_sq(BlockPos::new(300, -700));
+        assert_eq!(dist(&nearest), all.iter().map(dist).min().unwrap());
+
+        let nether = [StructureType::NetherFortress];
+        let hit = find_nearest_any(12345, BlockPos::new(0, 0), 3000, &nether, |_| true).unwrap().unwrap();
+        assert_eq!(hit.kind, StructureKind::Builtin(StructureType::NetherFortress));
+        let fortresses = (-8..8)
+            .flat_map(|qx| (-8..8).map(move |qz| nether_quadrant(12345, RegionPos::new(qx, qz))))
+            .filter(|(st, _)| matches!(st, StructureType::NetherFortress));
+        let nearest = fortresses.map(|(_, pos)| pos.distance_sq(BlockPos::new(0, 0))).min().unwrap();
+        assert_eq!(hit.pos.distance_sq(BlockPos::new(0, 0)), nearest);
+        assert_eq!(find_nearest_any(12345, BlockPos::new(0, 0), 10, &types, |_| true), Ok(None));
+        assert_eq!(find_nearest_any(12345, BlockPos::new(0, 0), 0, &types, |_| true), Err(SearchError::InvalidRadius(0)));
+    }
+
+    #[test]
+    fn test_sort_nearest_first() {
+        let mut expected: Vec<_> = find_structures(12345, BlockPos::new(0, 0), 3000, StructureType::Village)
+            .unwrap()
+            .into_iter()
+            .chain(find_structures(12345, BlockPos::new(0, 0), 3000, StructureType::PillagerOutpost).unwrap())
+            .collect();
+        // 同じ距離の結果は種類、X、Zの順
+        let monument = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::OceanMonument), BlockPos::new(x, z));
+        let outpost = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::PillagerOutpost), BlockPos::new(x, z));
+        expected.extend([outpost(0, 5000), monument(5000, 0), monument(0, 5000)]);
+        sort_nearest_first(&mut expected, BlockPos::new(0, 0));
+        assert_eq!(&expected[expected.len() - 3..], &[monument(0, 5000), monument(5000, 0), outpost(0, 5000)]);
+
+        // 入力の順序によらず同じ結果になる
+        for shift in [1, 7, expected.len() / 2] {
+            let mut shuffled = expected.clone();
+            shuffled.rotate_left(shift);
+            shuffled.reverse();
+            sort_nearest_first(&mut shuffled, BlockPos::new(0, 0));
+            assert_eq!(shuffled, expected);
+        }
+
+        // 座標の差が i32 に収まらなくてもあふれない
+        assert_eq!(result_order("village", BlockPos::new(i32::MAX, 0), BlockPos::new(-1, 0)).0, 1i64 << 62);
+    }
+
+    #[test]
+    fn test_structure_type_from_str() {
+        assert_eq!("village".parse::<StructureType>().unwrap().id(), "village");
+        assert_eq!("woodland_mansion".parse::<StructureType>().unwrap().id(), "mansion");
+        assert_eq!("minecraft:bastion_remnant".parse::<StructureType>().unwrap().id(), "bastion");
+        assert_eq!("Trial-Chambers".parse::<StructureType>().unwrap().id(), "trial_chamber");
+        assert!("stronghold".parse::<StructureType>().is_err());
+        assert!("sky_castle".parse::<StructureType>().unwrap_err().contains("village"));
+
+        // -t は all・stronghold・any:・カスタム構造物の識別子も受け付ける
+        assert_eq!("all".parse(), Ok(StructureSelection::All));
+        assert_eq!("stronghold".parse(), Ok(StructureSelection::Stronghold));
+        assert_eq!("woodland_mansion".parse(), Ok(StructureSelection::Builtin(StructureType::WoodlandMansion)));
+        assert_eq!(
+            "any:fortress, bastion".parse(),
+            Ok(StructureSelection::Any(vec![StructureType::NetherFortress, StructureType::BastionRemnant]))
+        );
+        assert_eq!("sky_castle".parse(), Ok(StructureSelection::Custom("sky_castle".to_string())));
+        assert!("any:".parse::<StructureSelection>().is_err());
+        assert!("any:village,fortress".parse::<StructureSelection>().is_err());
+        assert!("any:sky_castle".parse::<StructureSelection>().is_err());
+    }
+
+    #[test]
+    fn test_structure_confidence() {
+        assert_eq!(structure_confidence(&StructureKind::Builtin(StructureType::RuinedPortal)), Confidence::Exact);
+        assert_eq!(structure_confidence(&StructureKind::Builtin(StructureType::Village)), Confidence::Likely);
+        assert_eq!(structure_confidence(&StructureKind::Builtin(StructureType::NetherFortress)), Confidence::Approximate);
+        assert_eq!(structure_confidence(&StructureKind::Stronghold), Confidence::Approximate);
+        assert_eq!(structure_confidence(&StructureKind::Custom("sky_castle".to_string())), Confidence::Likely);
+    }
+
+    #[test]
+    fn test_structure_bbox() {
+        let bbox = structure_bbox(&StructureKind::Builtin(StructureType::TrialChamber), BlockPos::new(100, -200));
+        assert_eq!((bbox.min_x, bbox.max_x, bbox.min_z, bbox.max_z), (68, 132, -232, -168));
+        assert_eq!((bbox.min_y, bbox.max_y), (Some(-40), Some(-20)));
+        let bbox = structure_bbox(&StructureKind::Custom("sky_castle".to_string()), BlockPos::new(0, 0));
+        assert_eq!((bbox.min_x, bbox.max_x, bbox.min_y), (-8, 8, None));
+    }
+
+    #[test]
+    fn test_fortress_area() {
+        let area = fortress_area(&StructureKind::Builtin(StructureType::NetherFortress), BlockPos::new(580, -100)).unwrap();
+        assert_eq!((area.extent.min_x, area.extent.max_x, area.extent.min_z, area.extent.max_z), (468, 692, -212, 12));
+        assert_eq!((area.quadrant.min_x, area.quadrant.max_x, area.quadrant.min_z, area.quadrant.max_z), (480, 959, -480, -1));
+        assert_eq!((area.interior.min_x, area.interior.max_x, area.interior.min_z, area.interior.max_z), (480, 692, -212, -1));
+        assert_eq!(area.interior.min_y, Some(48));
+        assert!(fortress_area(&StructureKind::Builtin(StructureType::BastionRemnant), BlockPos::new(0, 0)).is_none());
+
+        // 区画はワールドのずれ（ネザーでは1/8）を戻した生成の座標で決まる
+        crate::coords::set_world_offset(1024, -2048);
+        let area = fortress_area(&StructureKind::Builtin(StructureType::NetherFortress), BlockPos::new(580, -100)).unwrap();
+        crate::coords::set_world_offset(0, 0);
+        assert_eq!((area.quadrant.min_x, area.quadrant.max_x, area.quadrant.min_z, area.quadrant.max_z), (128, 607, -256, 223));
+        assert_eq!((area.interior.min_x, area.interior.max_x, area.interior.min_z, area.interior.max_z), (468, 607, -212, 12));
+    }
+
+    #[test]
+    fn test_structure_biome() {
+        let pos = find_structures(12345, BlockPos::new(0, 0), 1000, StructureType::Village).unwrap().remove(0).pos;
+        let biome = Some(get_biome_at(12345, pos).id());
+        assert_eq!(structure_biome(12345, &StructureKind::Builtin(StructureType::Village), pos), biome);
+        assert_eq!(structure_biome(12345, &StructureKind::Stronghold, pos), biome);
+        assert_eq!(structure_biome(12345, &StructureKind::Builtin(StructureType::BastionRemnant), pos), None);
+        assert_eq!(structure_biome(12345, &StructureKind::Builtin(StructureType::EndCity), pos), None);
+    }
+
+    #[test]
+    fn test_find_strongholds_rings() {
+        let results = find_strongholds(12345);
+        assert_eq!(results.len(), 128);
+        for hit in &results[..3] {
+            assert_eq!(hit.kind, StructureKind::Stronghold);
+            let distance = (hit.pos.distance_sq(BlockPos::new(0, 0)) as f64).sqrt();
+            assert!((1200.0..2900.0).contains(&distance), "distance = {}", distance);
+        }
+    }
+
+    #[test]
+    fn test_find_kind() {
+        let center = BlockPos::new(0, 0);
+        let strongholds = find_kind(12345, &StructureKind::Stronghold, center, 3000).unwrap();
+        let expected: Vec<_> = find_strongholds(12345).into_iter().filter(|hit| hit.pos.distance_sq(center) <= 3000 * 3000).collect();
+        assert_eq!(strongholds, expected);
+        let village = StructureKind::from_id("village").unwrap();
+        assert_eq!(find_kind(12345, &village, center, 1000).unwrap(), find_structures(12345, center, 1000, StructureType::Village).unwrap());
+        assert!(StructureKind::from_id("moon").is_none());
+        assert!(find_kind(12345, &StructureKind::Custom("moon".to_string()), center, 1000).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_world_offset() {
+        let expected = find_structures(12345, BlockPos::new(0, 0), 1000, StructureType::Village).unwrap();
+        let nether = find_in_dimension(12345, Dimension::Nether, BlockPos::new(0, 0), 500, default_types(Dimension::Nether)).unwrap();
+        crate::coords::set_world_offset(1024, -2048);
+        let shifted = find_structures(12345, BlockPos::new(1024, -2048), 1000, StructureType::Village).unwrap();
+        let shifted_nether = find_in_dimension(12345, Dimension::Nether, BlockPos::new(128, -256), 500, default_types(Dimension::Nether)).unwrap();
+        crate::coords::set_world_offset(0, 0);
+
+        let moved: Vec<_> = expected.into_iter().map(|hit| StructureHit::new(hit.kind, BlockPos::new(hit.pos.x + 1024, hit.pos.z - 2048))).collect();
+        assert_eq!(shifted, moved);
+        let moved: Vec<_> = nether.into_iter().map(|hit| StructureHit::new(hit.kind, BlockPos::new(hit.pos.x + 128, hit.pos.z - 256))).collect();
+        assert_eq!(shifted_nether, moved);
     }
 }
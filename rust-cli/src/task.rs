@@ -0,0 +1,221 @@
+//! バックグラウンド検索モジュール
+//!
+//! 時間のかかる検索を別のスレッドで実行し、呼び出し側のスレッドを止めずに結果を受け取る。
+//! 画面を描きながら待つ場合は `poll` で完了を確認し、非同期のランタイムからは `SearchHandle` を
+//! そのまま `await` できる（ランタイムには依存しない）。検索は呼び出し側のスレッドの設定
+//! （ワールドのずれ、生成データ、対象バージョンとバイオーム生成の方式、実験的機能、制限時間）を引き継いで実行する。
+//! `cancel` するか `SearchHandle` を破棄すると、検索は制限時間を過ぎたときと同じく打ち切られる。
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Instant;
+
+use crate::algorithms::biome::{self, BiomeModel};
+use crate::budget;
+use crate::coords::{set_world_offset, world_offset, Dimension};
+use crate::gamedata::{game_data, set_game_data, GameData};
+use crate::meta;
+use crate::version::{experimental, set_experimental, McVersion};
+
+/// 検索スレッドに引き継ぐ呼び出し側のスレッドの設定
+struct Settings {
+    world_offset: (i32, i32),
+    game_data: Arc<GameData>,
+    mc_version: Option<McVersion>,
+    biome_model: BiomeModel,
+    experimental: bool,
+    deadline: Option<Instant>,
+}
+
+impl Settings {
+    fn capture() -> Settings {
+        Settings {
+            world_offset: world_offset(Dimension::Overworld),
+            game_data: game_data(),
+            mc_version: meta::mc_version(),
+            biome_model: biome::model(),
+            experimental: experimental(),
+            deadline: budget::deadline(),
+        }
+    }
+
+    fn apply(&self) {
+        set_world_offset(self.world_offset.0, self.world_offset.1);
+        set_game_data(self.game_data.clone());
+        if let Some(version) = self.mc_version {
+            meta::set_mc_version(version);
+        }
+        biome::set_model(self.biome_model);
+        set_experimental(self.experimental);
+        budget::set_deadline(self.deadline);
+    }
+}
+
+/// 検索の完了を待っているタスク
+type SharedWaker = Arc<Mutex<Option<Waker>>>;
+
+/// 検索スレッドの終了時（異常終了を含む）に待っているタスクを起こす
+struct WakeOnDrop(SharedWaker);
+
+impl Drop for WakeOnDrop {
+    fn drop(&mut self) {
+        if let Some(waker) = self.0.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            waker.wake();
+        }
+    }
+}
+
+/// 別のスレッドで実行中の検索
+pub struct SearchHandle<T> {
+    receiver: Receiver<T>,
+    cancelled: Arc<AtomicBool>,
+    waker: SharedWaker,
+    /// 結果を返し終えたか（以降はスレッドが終了していても異常終了ではない）
+    done: bool,
+}
+
+impl<T: Send + 'static> SearchHandle<T> {
+    /// 検索を別のスレッドで開始
+    pub fn spawn(search: impl FnOnce() -> T + Send + 'static) -> SearchHandle<T> {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let waker = SharedWaker::default();
+        let settings = Settings::capture();
+        let (flag, wake) = (cancelled.clone(), WakeOnDrop(waker.clone()));
+        thread::spawn(move || {
+            let _wake = wake;
+            settings.apply();
+            budget::set_cancel(Some(flag));
+            // 受け取る側が先に破棄された場合は結果を捨てる
+            let _ = sender.send(search());
+        });
+        SearchHandle { receiver, cancelled, waker, done: false }
+    }
+}
+
+impl<T> SearchHandle<T> {
+    /// 完了していれば結果を返す（待たない。結果は1度だけ返し、以降は None）
+    ///
+    /// 結果を返す前に検索のスレッドが終了した場合（検索の異常終了）はパニックする。
+    pub fn poll(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        match self.receiver.try_recv() {
+            Ok(result) => {
+                self.done = true;
+                Some(result)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => panic!("検索のスレッドが異常終了しました"),
+        }
+    }
+
+    /// 検索を取り消す（それまでに見つかった結果で打ち切られる）
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<T> Drop for SearchHandle<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl<T> Future for SearchHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let this = self.get_mut();
+        if let Some(result) = SearchHandle::poll(this) {
+            return Poll::Ready(result);
+        }
+        *this.waker.lock().unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone());
+        // 待つタスクを登録する前に完了していた場合に起こされないままにならないよう確認し直す
+        match SearchHandle::poll(this) {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Wake;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::structures::{find_structures, StructureType};
+
+    /// 完了するまで現在のスレッドを止めて Future を実行する
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct Unpark(thread::Thread);
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn test_search_handle() {
+        let expected = find_structures(12345, BlockPos::new(0, 0), 2000, StructureType::Village).unwrap();
+        let handle = SearchHandle::spawn(|| find_structures(12345, BlockPos::new(0, 0), 2000, StructureType::Village));
+        assert_eq!(block_on(handle).unwrap(), expected);
+
+        let mut handle = SearchHandle::spawn(|| 42);
+        let result = loop {
+            if let Some(result) = handle.poll() {
+                break result;
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(result, 42);
+        // 結果を返した後はスレッドが終了していても None
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(handle.poll(), None);
+    }
+
+    #[test]
+    fn test_settings_and_cancel() {
+        // 呼び出し側のワールドのずれを引き継ぐ
+        set_world_offset(1024, -2048);
+        let handle = SearchHandle::spawn(|| world_offset(Dimension::Overworld));
+        set_world_offset(0, 0);
+        assert_eq!(block_on(handle), (1024, -2048));
+
+        // 対象バージョンとバイオーム生成の方式を引き継ぐ
+        let version = McVersion::parse("1.16").unwrap();
+        meta::set_mc_version(version);
+        biome::set_version(version, false);
+        let handle = SearchHandle::spawn(|| (meta::mc_version(), biome::model()));
+        biome::set_version(crate::version::LATEST_VERSION, false);
+        assert_eq!(block_on(handle), (Some(version), biome::biome_model(version, false)));
+
+        // 取り消すと検索スレッドで budget::expired が真になる
+        let handle = SearchHandle::spawn(|| {
+            while !budget::expired() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            budget::truncated()
+        });
+        handle.cancel();
+        assert!(block_on(handle));
+    }
+}
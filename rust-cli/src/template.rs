@@ -0,0 +1,100 @@
+//! 出力テンプレートモジュール
+//!
+//! `--format-template <file>` で指定したHandlebarsテンプレートを、JSON出力と同じ結果の文書に適用する。
+//! Discord用のMarkdownやサーバーのMOTD、掲示板の表などをJSONの後処理なしで出力できる。
+//!
+//! ```text
+//! **{{seed}}** の構造物
+//! {{#each structures}}
+//! - {{structure_type}}: `{{x}} ~ {{z}}`（{{round distance}}ブロック）
+//! {{/each}}
+//! ```
+//!
+//! 追加のヘルパー: `round`（数値を整数に丸める）、`nether`（オーバーワールド座標をネザー座標に変換）
+
+#[cfg(feature = "template")]
+use handlebars::{handlebars_helper, no_escape, Handlebars};
+use serde_json::Value;
+
+#[cfg(feature = "template")]
+use crate::coords::overworld_to_nether;
+
+#[cfg(feature = "template")]
+const TEMPLATE_NAME: &str = "output";
+
+#[cfg(feature = "template")]
+handlebars_helper!(round: |v: f64| v.round() as i64);
+#[cfg(feature = "template")]
+handlebars_helper!(nether: |v: i64| overworld_to_nether(v as i32));
+
+/// コンパイル済みの出力テンプレート
+#[cfg(feature = "template")]
+pub struct OutputTemplate {
+    registry: Handlebars<'static>,
+}
+
+#[cfg(feature = "template")]
+impl OutputTemplate {
+    /// テンプレートファイルを読み込む
+    pub fn load(path: &str) -> Result<OutputTemplate, String> {
+        let source =
+            std::fs::read_to_string(path).map_err(|e| format!("ファイルを読み込めません: {} ({})", path, e))?;
+        OutputTemplate::from_source(&source).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// テンプレートをコンパイル
+    pub fn from_source(source: &str) -> Result<OutputTemplate, String> {
+        let mut registry = Handlebars::new();
+        // HTMLではないため特殊文字をエスケープしない
+        registry.register_escape_fn(no_escape);
+        registry.register_helper("round", Box::new(round));
+        registry.register_helper("nether", Box::new(nether));
+        registry
+            .register_template_string(TEMPLATE_NAME, source)
+            .map_err(|e| format!("テンプレートの構文エラー: {}", e))?;
+        Ok(OutputTemplate { registry })
+    }
+
+    /// 結果の文書にテンプレートを適用
+    pub fn render(&self, document: &Value) -> Result<String, String> {
+        self.registry
+            .render(TEMPLATE_NAME, document)
+            .map_err(|e| format!("テンプレートを適用できません: {}", e))
+    }
+}
+
+/// template 機能なしでビルドした場合のテンプレート（読み込めないため値を作れない）
+#[cfg(not(feature = "template"))]
+pub enum OutputTemplate {}
+
+#[cfg(not(feature = "template"))]
+impl OutputTemplate {
+    pub fn load(_path: &str) -> Result<OutputTemplate, String> {
+        Err("--format-template には template 機能を有効にしたビルドが必要です（cargo build --features template）".to_string())
+    }
+
+    pub fn render(&self, _document: &Value) -> Result<String, String> {
+        match *self {}
+    }
+}
+
+#[cfg(all(test, feature = "template"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_template() {
+        let template = OutputTemplate::from_source(
+            "{{#each structures}}- {{structure_type}} <{{x}}, {{z}}> {{round distance}} / {{nether x}}\n{{/each}}",
+        )
+        .unwrap();
+        let document = json!({
+            "seed": 1,
+            "structures": [{ "structure_type": "🏘️ 村", "x": 232, "z": -168, "distance": 286.4 }]
+        });
+        assert_eq!(template.render(&document).unwrap(), "- 🏘️ 村 <232, -168> 286 / 29\n");
+
+        assert!(OutputTemplate::from_source("{{#each structures}}").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! 移動距離・所要時間計算モジュール
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::coords::NETHER_SCALE;
+
+/// 移動手段と速度（ブロック/秒）
+pub const TRAVEL_MODES: [(&str, &str, f64); 4] = [
+    ("walk", "🚶 歩き", 4.317),
+    ("sprint", "🏃 ダッシュ", 5.612),
+    ("boat", "🚣 ボート", 8.0),
+    ("elytra", "🪽 エリトラ（滑空）", 33.5),
+];
+
+/// ネザー経由の移動速度（ネザー側をダッシュで移動）
+const NETHER_SPEED: f64 = 5.612;
+
+/// 移動手段ごとの所要時間
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TravelTime {
+    pub mode: String,
+    /// ネザー経由の場合はネザー側の距離
+    pub distance: f64,
+    pub seconds: f64,
+}
+
+/// 2点間の移動レポート
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TravelReport {
+    pub from_x: i32,
+    pub from_z: i32,
+    pub to_x: i32,
+    pub to_z: i32,
+    pub distance: f64,
+    pub nether_distance: f64,
+    pub times: Vec<TravelTime>,
+}
+
+/// 2点間の水平距離を計算
+pub fn distance_2d(x1: i32, z1: i32, x2: i32, z2: i32) -> f64 {
+    ((x2 as f64 - x1 as f64).powi(2) + (z2 as f64 - z1 as f64).powi(2)).sqrt()
+}
+
+/// 2点間の距離と移動手段ごとの所要時間を計算
+pub fn travel_report(from_x: i32, from_z: i32, to_x: i32, to_z: i32) -> TravelReport {
+    let distance = distance_2d(from_x, from_z, to_x, to_z);
+    let nether_distance = distance / NETHER_SCALE as f64;
+
+    let mut times: Vec<TravelTime> = TRAVEL_MODES
+        .iter()
+        .map(|(mode, _, speed)| TravelTime {
+            mode: mode.to_string(),
+            distance,
+            seconds: distance / speed,
+        })
+        .collect();
+
+    times.push(TravelTime {
+        mode: "nether".to_string(),
+        distance: nether_distance,
+        seconds: nether_distance / NETHER_SPEED,
+    });
+
+    TravelReport {
+        from_x,
+        from_z,
+        to_x,
+        to_z,
+        distance,
+        nether_distance,
+        times,
+    }
+}
+
+/// 移動手段の表示名を取得
+pub fn mode_display_name(mode: &str) -> &'static str {
+    TRAVEL_MODES
+        .iter()
+        .find(|(id, _, _)| *id == mode)
+        .map(|(_, name, _)| *name)
+        .unwrap_or("🌀 ネザー経由（ダッシュ）")
+}
+
+/// 秒数を「X時間Y分」「X分Y秒」形式に整形
+pub fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as i64;
+    let minutes = total / 60;
+    if minutes >= 60 {
+        format!("{}時間{}分", minutes / 60, minutes % 60)
+    } else if minutes > 0 {
+        format!("{}分{}秒", minutes, total % 60)
+    } else {
+        format!("{}秒", total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_travel_report() {
+        let report = travel_report(0, 0, 3000, 4000);
+        assert_eq!(report.distance, 5000.0);
+        assert_eq!(report.nether_distance, 625.0);
+        let nether = report.times.iter().find(|t| t.mode == "nether").unwrap();
+        let walk = report.times.iter().find(|t| t.mode == "walk").unwrap();
+        assert!(nether.seconds < walk.seconds);
+
+        // 座標の差が i32 に収まらなくてもあふれない
+        assert_eq!(distance_2d(-i32::MAX, 0, i32::MAX, 0), 2.0 * i32::MAX as f64);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42.4), "42秒");
+        assert_eq!(format_duration(125.0), "2分5秒");
+        assert_eq!(format_duration(7380.0), "2時間3分");
+    }
+}
@@ -0,0 +1,130 @@
+//! エンダーアイ三角測量モジュール
+//!
+//! 複数の投擲地点と向きから、視線の交点（要塞の推定位置）を求める
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::coords::SEARCH_LIMIT;
+
+/// エンダーアイの投擲記録
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+pub struct Throw {
+    pub x: f64,
+    pub z: f64,
+    /// 向き（度）。Minecraftの表記どおり 0 = 南(+Z)、90 = 西(-X)、180 = 北、-90 = 東(+X)
+    pub yaw: f64,
+}
+
+impl Throw {
+    /// "x,z,yaw" 形式の文字列から投擲記録を作成（NaN や無限大を含む場合は None）
+    pub fn parse(s: &str) -> Option<Throw> {
+        let parts: Vec<f64> = s
+            .split(',')
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+        match parts.as_slice() {
+            [x, z, yaw] if parts.iter().all(|v| v.is_finite()) => Some(Throw {
+                x: *x,
+                z: *z,
+                yaw: *yaw,
+            }),
+            _ => None,
+        }
+    }
+
+    /// 視線の単位方向ベクトル（dx, dz）
+    fn direction(&self) -> (f64, f64) {
+        let rad = self.yaw.to_radians();
+        (-rad.sin(), rad.cos())
+    }
+}
+
+/// 三角測量の結果
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Triangulation {
+    pub x: i32,
+    pub z: i32,
+    /// 各視線から推定地点までの距離の二乗平均平方根（ブロック）
+    pub error: f64,
+}
+
+/// 複数の視線の交点を最小二乗法で求める
+///
+/// 視線が2本未満、すべて平行な場合、またはほぼ平行で交点が検索できる座標の上限（`SEARCH_LIMIT`）より遠い場合は
+/// `None` を返す。
+pub fn triangulate(throws: &[Throw]) -> Option<Triangulation> {
+    if throws.len() < 2 {
+        return None;
+    }
+
+    // 各視線への垂直距離の二乗和を最小化: Σ(I - ddᵀ) p = Σ(I - ddᵀ) p_i
+    let (mut a11, mut a12, mut a22, mut b1, mut b2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for throw in throws {
+        let (dx, dz) = throw.direction();
+        let m11 = 1.0 - dx * dx;
+        let m12 = -dx * dz;
+        let m22 = 1.0 - dz * dz;
+        a11 += m11;
+        a12 += m12;
+        a22 += m22;
+        b1 += m11 * throw.x + m12 * throw.z;
+        b2 += m12 * throw.x + m22 * throw.z;
+    }
+
+    let det = a11 * a22 - a12 * a12;
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let x = (a22 * b1 - a12 * b2) / det;
+    let z = (a11 * b2 - a12 * b1) / det;
+    if x.abs() > SEARCH_LIMIT as f64 || z.abs() > SEARCH_LIMIT as f64 {
+        return None;
+    }
+
+    let sum_sq: f64 = throws
+        .iter()
+        .map(|throw| {
+            let (dx, dz) = throw.direction();
+            let cross = (x - throw.x) * dz - (z - throw.z) * dx;
+            cross * cross
+        })
+        .sum();
+
+    Some(Triangulation {
+        x: x.round() as i32,
+        z: z.round() as i32,
+        error: (sum_sq / throws.len() as f64).sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triangulate_two_throws() {
+        // (0,0)から東(+X)へ、(1000,-1000)から南(+Z)へ → 交点 (1000, 0)
+        let throws = [Throw::parse("0,0,-90").unwrap(), Throw::parse("1000,-1000,0").unwrap()];
+        let result = triangulate(&throws).unwrap();
+        assert_eq!((result.x, result.z), (1000, 0));
+        assert!(result.error < 1.0);
+    }
+
+    #[test]
+    fn test_parallel_throws() {
+        let throws = [Throw::parse("0,0,0").unwrap(), Throw::parse("100,0,0").unwrap()];
+        assert!(triangulate(&throws).is_none());
+        assert!(Throw::parse("1,2").is_none());
+        assert!(Throw::parse("0,0,NaN").is_none());
+        assert!(Throw::parse("inf,0,90").is_none());
+    }
+
+    #[test]
+    fn test_nearly_parallel_throws() {
+        // ほぼ平行な視線の交点は座標の上限より遠いため、i32 に丸めずに None とする
+        let throws = [Throw::parse("0,0,180").unwrap(), Throw::parse("20000000,0,179.998").unwrap()];
+        assert!(triangulate(&throws).is_none());
+    }
+}
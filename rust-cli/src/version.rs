@@ -0,0 +1,98 @@
+//! ゲームバージョンモジュール
+
+use std::cell::Cell;
+use std::fmt;
+
+use serde::Deserialize;
+
+/// 既定で対象とするBedrock Editionのバージョン
+pub const LATEST_VERSION: McVersion = McVersion(1, 21, 0);
+
+/// プレビュー版（--channel preview）で既定で対象とするバージョン
+pub const PREVIEW_VERSION: McVersion = McVersion(1, 21, 10);
+
+thread_local! {
+    static EXPERIMENTAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 実行するコマンドで実験的機能（ゲーム内の「実験」の設定やベータ版でだけ生成されるもの）を対象にするか設定
+pub fn set_experimental(enabled: bool) {
+    EXPERIMENTAL.with(|e| e.set(enabled));
+}
+
+/// 実験的機能を対象にしているか
+pub fn experimental() -> bool {
+    EXPERIMENTAL.with(Cell::get)
+}
+
+/// Bedrock Editionのバージョン（メジャー.マイナー.パッチ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub struct McVersion(pub u32, pub u32, pub u32);
+
+impl McVersion {
+    /// "1.21.0" や "1.18" 形式の文字列からバージョンを作成（パッチ省略時は0）
+    pub fn parse(s: &str) -> Option<McVersion> {
+        let parts: Vec<u32> = s
+            .trim()
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+        match parts.as_slice() {
+            [major, minor] => Some(McVersion(*major, *minor, 0)),
+            [major, minor, patch] => Some(McVersion(*major, *minor, *patch)),
+            _ => None,
+        }
+    }
+
+    /// `since` 以降のバージョンか判定（`since` が解釈できない場合は真）
+    pub fn is_at_least(&self, since: &str) -> bool {
+        McVersion::parse(since).is_none_or(|since| *self >= since)
+    }
+
+    /// 正式に追加されたバージョン（`since`）以降か、実験的機能を対象にしていて
+    /// 実験的機能として追加されたバージョン（`experimental_since`）以降か判定
+    pub fn supports(&self, since: &str, experimental_since: Option<&str>) -> bool {
+        self.is_at_least(since) || (experimental() && experimental_since.is_some_and(|e| self.is_at_least(e)))
+    }
+}
+
+impl TryFrom<String> for McVersion {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<McVersion, String> {
+        McVersion::parse(&s).ok_or_else(|| format!("不正なバージョン: {}（例: 1.21.0）", s))
+    }
+}
+
+impl fmt::Display for McVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_compare() {
+        assert_eq!(McVersion::parse("1.18"), Some(McVersion(1, 18, 0)));
+        assert_eq!(McVersion::parse("1.21.30"), Some(McVersion(1, 21, 30)));
+        assert!(McVersion::parse("1.x").is_none());
+        assert!(McVersion(1, 16, 0).is_at_least("1.10.0"));
+        assert!(!McVersion(1, 9, 0).is_at_least("1.10.0"));
+        assert_eq!(LATEST_VERSION.to_string(), "1.21.0");
+    }
+
+    #[test]
+    fn test_supports_experimental() {
+        let version = McVersion(1, 20, 60);
+        assert!(!version.supports("1.21.0", Some("1.20.50")));
+        set_experimental(true);
+        assert!(version.supports("1.21.0", Some("1.20.50")));
+        assert!(!version.supports("1.21.0", Some("1.20.70")));
+        assert!(!version.supports("1.21.0", None));
+        set_experimental(false);
+    }
+}
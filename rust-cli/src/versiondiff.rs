@@ -0,0 +1,124 @@
+//! バージョン比較モジュール
+//!
+//! 同じシード・範囲の検索を2つのバージョンで実行した結果を比べ、移動・出現・消滅した構造物とバイオームを求める。
+//! ワールドを新しいバージョンで開く前に、見つけておいた場所が変わるかを確かめるのに使う。
+
+use crate::diffmap::structure_diff;
+use crate::structures::{StructureHit, StructureKind};
+
+/// リージョンのない構造物（要塞）を移動したとみなす距離（ブロック数）
+const STRONGHOLD_MOVE_DISTANCE: i32 = 1024;
+
+/// 比較元から比較先への変化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// 近くの別の位置に変わった
+    Moved,
+    /// 比較先にだけある
+    Appeared,
+    /// 比較元にだけある
+    Disappeared,
+}
+
+impl Change {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Change::Moved => "moved",
+            Change::Appeared => "appeared",
+            Change::Disappeared => "disappeared",
+        }
+    }
+
+    /// テキスト出力の見出し
+    pub fn label(&self) -> &'static str {
+        match self {
+            Change::Moved => "↔️ 移動",
+            Change::Appeared => "➕ 出現",
+            Change::Disappeared => "➖ 消滅",
+        }
+    }
+}
+
+/// 構造物の変化（変化、比較元の位置、比較先の位置）
+pub type StructureChange<'a> = (Change, Option<&'a StructureHit>, Option<&'a StructureHit>);
+
+/// 同じ種類の構造物を移動したとみなす距離（リージョンの一辺）
+fn move_distance(kind: &StructureKind) -> i32 {
+    match kind {
+        StructureKind::Builtin(st) => st.spacing() * 16,
+        _ => STRONGHOLD_MOVE_DISTANCE,
+    }
+}
+
+/// 2つのバージョンの検索結果の構造物の変化
+///
+/// 比較元にだけある構造物は、比較先にだけある同じ種類の構造物のうちリージョンの一辺以内で最も近いものと組にして移動とする。
+/// 組にならなかったものは消滅・出現とする。両方にある構造物は含めない。
+pub fn structure_changes<'a>(from: &'a [StructureHit], to: &'a [StructureHit]) -> Vec<StructureChange<'a>> {
+    let (removed, added) = structure_diff(from, to);
+    let mut paired = vec![false; added.len()];
+    let mut changes = Vec::new();
+    for old in removed {
+        let limit = move_distance(&old.kind) as f64;
+        let nearest = added
+            .iter()
+            .enumerate()
+            .filter(|(i, other)| !paired[*i] && other.kind == old.kind)
+            .map(|(i, other)| (i, ((other.pos.x - old.pos.x) as f64).hypot((other.pos.z - old.pos.z) as f64)))
+            .filter(|(_, distance)| *distance <= limit)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match nearest {
+            Some((i, _)) => {
+                paired[i] = true;
+                changes.push((Change::Moved, Some(old), Some(added[i])));
+            }
+            None => changes.push((Change::Disappeared, Some(old), None)),
+        }
+    }
+    for (new, paired) in added.into_iter().zip(paired) {
+        if !paired {
+            changes.push((Change::Appeared, None, Some(new)));
+        }
+    }
+    changes
+}
+
+/// 最寄りのバイオームの位置の変化（変わらない場合は None）
+pub fn biome_change(from: Option<(i32, i32)>, to: Option<(i32, i32)>) -> Option<Change> {
+    match (from, to) {
+        (Some(a), Some(b)) if a != b => Some(Change::Moved),
+        (Some(_), None) => Some(Change::Disappeared),
+        (None, Some(_)) => Some(Change::Appeared),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::structures::StructureType;
+
+    #[test]
+    fn test_structure_changes() {
+        let village = |x, z| StructureHit::new(StructureKind::Builtin(StructureType::Village), BlockPos::new(x, z));
+        let city = StructureHit::new(StructureKind::Builtin(StructureType::AncientCity), BlockPos::new(300, 300));
+        let from = vec![village(0, 0), village(2000, 0), village(5000, 0)];
+        let to = vec![village(0, 0), village(2100, 50), city];
+        let changes = structure_changes(&from, &to);
+        assert_eq!(
+            changes,
+            vec![
+                (Change::Moved, Some(&from[1]), Some(&to[1])),
+                (Change::Disappeared, Some(&from[2]), None),
+                (Change::Appeared, None, Some(&to[2])),
+            ]
+        );
+
+        assert_eq!(biome_change(Some((0, 0)), Some((0, 0))), None);
+        assert_eq!(biome_change(Some((0, 0)), Some((16, 0))), Some(Change::Moved));
+        assert_eq!(biome_change(None, Some((16, 0))), Some(Change::Appeared));
+        assert_eq!(biome_change(Some((0, 0)), None), Some(Change::Disappeared));
+        assert_eq!(biome_change(None, None), None);
+    }
+}
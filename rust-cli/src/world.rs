@@ -0,0 +1,148 @@
+//! ワールドの種類モジュール
+//!
+//! 旧形式（Old）のワールドは X・Z とも 0〜255 の 256×256 ブロックに限られ、ネザーは 128×128 ブロック、
+//! エンドはない。範囲外の検索結果を除き、生成されない構造物を検索しようとした場合は警告する。
+//!
+//! フラットワールドのオーバーワールドには構造物・地形・洞窟・鉱石が生成されない（ネザーとエンドは通常どおり）。
+
+use crate::coords::Dimension;
+use crate::custom::find_custom_structure;
+use crate::structures::{StructureHit, StructureType, STRONGHOLD_ID};
+
+/// 旧形式のワールドのオーバーワールドの1辺のブロック数
+const OLD_OVERWORLD_SIZE: i32 = 256;
+
+/// 旧形式のワールドのネザーの1辺のブロック数
+const OLD_NETHER_SIZE: i32 = 128;
+
+/// ワールドの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldType {
+    /// 無限のワールド（既定）
+    Infinite,
+    /// 旧形式（256×256ブロック）のワールド
+    Old,
+    /// フラットワールド
+    Flat,
+}
+
+impl WorldType {
+    pub const ALL: [WorldType; 3] = [WorldType::Infinite, WorldType::Old, WorldType::Flat];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            WorldType::Infinite => "infinite",
+            WorldType::Old => "old",
+            WorldType::Flat => "flat",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<WorldType> {
+        WorldType::ALL.into_iter().find(|w| w.id() == id)
+    }
+
+    /// ディメンションの範囲（X・Zの最小値と最大値。範囲のないディメンションは None、ディメンションがない場合は空の範囲）
+    pub fn bounds(&self, dimension: Dimension) -> Option<(i32, i32)> {
+        match (self, dimension) {
+            (WorldType::Infinite | WorldType::Flat, _) => None,
+            (WorldType::Old, Dimension::Overworld) => Some((0, OLD_OVERWORLD_SIZE - 1)),
+            (WorldType::Old, Dimension::Nether) => Some((0, OLD_NETHER_SIZE - 1)),
+            (WorldType::Old, Dimension::End) => Some((0, -1)),
+        }
+    }
+
+    /// 座標がワールドの範囲内か
+    pub fn contains(&self, dimension: Dimension, x: i32, z: i32) -> bool {
+        self.bounds(dimension)
+            .is_none_or(|(min, max)| (min..=max).contains(&x) && (min..=max).contains(&z))
+    }
+
+    /// 検索範囲（中心と半径の円）がワールドの範囲と重なるか
+    pub fn overlaps(&self, dimension: Dimension, center_x: i32, center_z: i32, radius: i32) -> bool {
+        self.bounds(dimension).is_none_or(|(min, max)| {
+            let nearest = |c: i32| c.clamp(min, max.max(min)) as i64 - c as i64;
+            min <= max && nearest(center_x).pow(2) + nearest(center_z).pow(2) <= (radius as i64).pow(2)
+        })
+    }
+
+    /// 中心からワールドの範囲の最も遠い角までの距離（範囲のないディメンションは None）
+    pub fn farthest(&self, dimension: Dimension, center_x: i32, center_z: i32) -> Option<i32> {
+        let (min, max) = self.bounds(dimension)?;
+        let far = |c: i32| (c as i64 - min as i64).abs().max((c as i64 - max as i64).abs());
+        Some(((far(center_x).pow(2) + far(center_z).pow(2)) as f64).sqrt().ceil() as i32)
+    }
+
+    /// 構造物（識別子）が生成されるか
+    ///
+    /// 旧形式のワールドには要塞とエンドシティがなく、フラットワールドにはオーバーワールドの構造物がない。
+    pub fn is_generated(&self, id: &str) -> bool {
+        match self {
+            WorldType::Infinite => true,
+            WorldType::Old => id != STRONGHOLD_ID && id != StructureType::EndCity.id(),
+            WorldType::Flat => {
+                let dimension = match id.parse::<StructureType>() {
+                    Ok(st) => Some(st.dimension()),
+                    Err(_) => find_custom_structure(id).map(|c| c.dimension),
+                };
+                id != STRONGHOLD_ID && dimension.is_none_or(|d| d != Dimension::Overworld)
+            }
+        }
+    }
+
+    /// 検索結果からワールドに生成されない構造物と範囲外の構造物を除く
+    pub fn retain(&self, structures: Vec<StructureHit>) -> Vec<StructureHit> {
+        if *self == WorldType::Infinite {
+            return structures;
+        }
+        structures
+            .into_iter()
+            .filter(|hit| self.is_generated(hit.kind.id()) && self.contains(hit.kind.dimension(), hit.pos.x, hit.pos.z))
+            .collect()
+    }
+
+    /// 範囲の説明（警告に使う）
+    pub fn describe_bounds(&self, dimension: Dimension) -> String {
+        match self.bounds(dimension) {
+            None => "無限".to_string(),
+            Some((min, max)) if min > max => format!("{}はありません", dimension.id()),
+            Some((min, max)) => format!("X・Z {}〜{}", min, max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::BlockPos;
+    use crate::structures::{find_structures, StructureKind};
+
+    #[test]
+    fn test_old_world() {
+        assert_eq!(WorldType::from_id("old"), Some(WorldType::Old));
+        let old = WorldType::Old;
+        assert!(old.contains(Dimension::Overworld, 0, 255));
+        assert!(!old.contains(Dimension::Overworld, 256, 0));
+        assert!(!old.contains(Dimension::Nether, 0, 128));
+        assert!(!old.contains(Dimension::End, 0, 0));
+        assert!(old.overlaps(Dimension::Overworld, -100, 100, 100));
+        assert!(!old.overlaps(Dimension::Overworld, -101, 100, 100));
+        assert!(!old.overlaps(Dimension::End, 0, 0, 1000));
+        assert_eq!(old.farthest(Dimension::Overworld, 0, 0), Some(361));
+        assert_eq!(WorldType::Infinite.farthest(Dimension::Overworld, 0, 0), None);
+        assert!(!old.is_generated(STRONGHOLD_ID));
+
+        let mut structures = find_structures(12345, BlockPos::new(128, 128), 2000, StructureType::Village).unwrap();
+        structures.push(StructureHit::new(StructureKind::Stronghold, BlockPos::new(100, 100)));
+        let kept = old.retain(structures.clone());
+        assert!(kept.iter().all(|hit| old.contains(Dimension::Overworld, hit.pos.x, hit.pos.z)));
+        assert!(kept.len() < structures.len());
+        assert!(!kept.iter().any(|hit| hit.kind == StructureKind::Stronghold));
+        assert_eq!(WorldType::Infinite.retain(structures.clone()), structures);
+
+        let flat = WorldType::Flat;
+        assert!(flat.retain(structures).is_empty());
+        assert!(flat.is_generated("fortress") && flat.is_generated("end_city"));
+        assert!(!flat.is_generated("village") && !flat.is_generated(STRONGHOLD_ID));
+        assert!(flat.contains(Dimension::Overworld, 100000, -100000));
+    }
+}
@@ -111,7 +111,8 @@ def process_job(job_id: int):
             db.update_job_status(job_id, "running", progress=50)
             
             proc = subprocess.run(cmd, capture_output=True, text=True, timeout=300)
-            if proc.returncode == 0:
+            # 終了コード 0: 見つかった, 1: 見つからなかった（どちらも結果のJSONを出力する）
+            if proc.returncode in (0, 1):
                 result = json.loads(proc.stdout)
             else:
                 raise Exception(f"CLI error: {proc.stderr}")
@@ -137,7 +138,8 @@ def process_job(job_id: int):
             db.update_job_status(job_id, "running", progress=50)
             
             proc = subprocess.run(cmd, capture_output=True, text=True, timeout=300)
-            if proc.returncode == 0:
+            # 終了コード 0: 見つかった, 1: 見つからなかった（どちらも結果のJSONを出力する）
+            if proc.returncode in (0, 1):
                 result = json.loads(proc.stdout)
             else:
                 raise Exception(f"CLI error: {proc.stderr}")